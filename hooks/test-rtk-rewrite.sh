@@ -231,7 +231,7 @@ test_rewrite "find" \
 
 test_rewrite "tree" \
   "tree src/" \
-  "rtk ls src/"
+  "rtk tree src/"
 
 test_rewrite "wget" \
   "wget https://example.com/file" \
@@ -0,0 +1,797 @@
+//! `rtk bench <dir>`: replay built-in filters over recorded raw outputs.
+//!
+//! Each file in the corpus is matched to a filter by its tee metadata
+//! (`<id>.json` / `<epoch>_<slug>.log`), its parent directory name, or its
+//! file name prefix (`cargo_test-failing.txt`). The report gives tokens in/out
+//! per filter and checks must-keep anchors (error messages, `file:line`
+//! references, failing test names, plus literals from a `<file>.keep`
+//! sidecar). With `--baseline`, lost savings or newly dropped anchors are
+//! regressions and the command exits 1.
+
+use crate::exec::ExecResult;
+use crate::tokenizer::Tokenizer;
+use crate::utils::format_tokens;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Corpus files larger than this are skipped (1MB, the tee default).
+const MAX_SAMPLE_BYTES: u64 = 1_048_576;
+/// Anchors of each kind checked per sample.
+const MAX_ANCHORS_PER_KIND: usize = 20;
+/// Error messages are matched on their first N chars (filters may truncate).
+const ERROR_ANCHOR_CHARS: usize = 30;
+
+/// A filter that can be replayed on raw text.
+struct BenchFilter {
+    /// Tee slug (`cargo_test`) or fixture name
+    name: &'static str,
+    apply: fn(&str) -> String,
+}
+
+/// Built-in filters with a pure `raw -> filtered` form.
+const FILTERS: &[BenchFilter] = &[
+    BenchFilter {
+        name: "cargo_build",
+        apply: |raw| crate::cargo_cmd::filter_cargo_build(raw, 0),
+    },
+    BenchFilter {
+        name: "cargo_check",
+        apply: |raw| crate::cargo_cmd::filter_cargo_build(raw, 0),
+    },
+    BenchFilter {
+        name: "cargo_clippy",
+        apply: |raw| crate::cargo_cmd::filter_cargo_clippy(raw, 0),
+    },
+    BenchFilter {
+        name: "cargo_install",
+        apply: crate::cargo_cmd::filter_cargo_install,
+    },
+    BenchFilter {
+        name: "cargo_nextest",
+        apply: crate::cargo_cmd::filter_cargo_nextest,
+    },
+    BenchFilter {
+        name: "cargo_test",
+        apply: |raw| crate::cargo_cmd::filter_cargo_test(raw, 0),
+    },
+    BenchFilter {
+        name: "container",
+        apply: crate::log_cmd::run_stdin_str,
+    },
+    BenchFilter {
+        name: "curl",
+        apply: crate::curl_cmd::filter_curl_output,
+    },
+    BenchFilter {
+        name: "err",
+        apply: crate::runner::filter_errors,
+    },
+    BenchFilter {
+        name: "eslint",
+        apply: crate::lint_cmd::filter_eslint_json,
+    },
+    BenchFilter {
+        name: "git_branch",
+        apply: crate::git::filter_branch_output,
+    },
+    BenchFilter {
+        name: "git_diff",
+        apply: |raw| crate::git::compact_diff(raw, 100),
+    },
+    BenchFilter {
+        name: "git_log",
+        apply: |raw| crate::git::filter_log_output(raw, 10),
+    },
+    BenchFilter {
+        name: "git_status",
+        apply: crate::git::filter_status_with_args,
+    },
+    BenchFilter {
+        name: "go_build",
+        apply: crate::go_cmd::filter_go_build,
+    },
+    BenchFilter {
+        name: "go_test",
+        apply: crate::go_cmd::filter_go_test_json,
+    },
+    BenchFilter {
+        name: "go_vet",
+        apply: crate::go_cmd::filter_go_vet,
+    },
+    BenchFilter {
+        name: "golangci",
+        apply: crate::golangci_cmd::filter_golangci_json,
+    },
+    BenchFilter {
+        name: "grep",
+        apply: |raw| crate::grep_cmd::format_matches(raw, "", ".", 80, 50, false),
+    },
+    BenchFilter {
+        name: "lint",
+        apply: crate::lint_cmd::filter_generic_lint,
+    },
+    BenchFilter {
+        name: "mypy",
+        apply: crate::lint_cmd::filter_mypy_output,
+    },
+    BenchFilter {
+        name: "next_build",
+        apply: crate::next_cmd::filter_next_build,
+    },
+    BenchFilter {
+        name: "npm",
+        apply: crate::npm_cmd::filter_npm_output,
+    },
+    BenchFilter {
+        name: "pip_list",
+        apply: crate::pip_cmd::filter_pip_list,
+    },
+    BenchFilter {
+        name: "playwright",
+        apply: crate::playwright_cmd::filter_playwright_output,
+    },
+    BenchFilter {
+        name: "pnpm_install",
+        apply: crate::pnpm_cmd::filter_pnpm_install,
+    },
+    BenchFilter {
+        name: "prettier",
+        apply: crate::prettier_cmd::filter_prettier_output,
+    },
+    BenchFilter {
+        name: "pylint",
+        apply: crate::lint_cmd::filter_pylint_json,
+    },
+    BenchFilter {
+        name: "pytest",
+        apply: crate::pytest_cmd::filter_pytest_output,
+    },
+    BenchFilter {
+        name: "ruff_check",
+        apply: crate::ruff_cmd::filter_ruff_check_json,
+    },
+    BenchFilter {
+        name: "ruff_format",
+        apply: crate::ruff_cmd::filter_ruff_format,
+    },
+    BenchFilter {
+        name: "test",
+        apply: |raw| crate::runner::extract_test_summary(raw, "test"),
+    },
+    BenchFilter {
+        name: "tsc",
+        apply: crate::tsc_cmd::filter_tsc_output,
+    },
+    BenchFilter {
+        name: "vitest_run",
+        apply: crate::vitest_cmd::filter_vitest_output,
+    },
+];
+
+lazy_static! {
+    static ref TEE_STEM: Regex = Regex::new(r"^\d+_(.+)$").unwrap();
+    static ref ERROR_LINE: Regex =
+        Regex::new(r"^\s*(?:error(?:\[\w+\])?|fatal|Error|ERROR)\s*:\s*(.+)$").unwrap();
+    static ref FILE_REF: Regex =
+        Regex::new(r"(?:^|[\s(\[<'`])((?:[\w.-]+/)*[\w-][\w.-]*\.[A-Za-z]{1,5}):(\d+)").unwrap();
+    static ref FAILED_TEST: Regex = Regex::new(
+        r"(?m)^\s*(?:test (\S+) \.\.\. FAILED|--- FAIL: (\S+)|FAILED (\S+::\S+)|\s*FAIL \[[^\]]*\]\s+\S+\s+(\S+))"
+    )
+    .unwrap();
+}
+
+/// Kind of line a filter must not drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorKind {
+    Error,
+    File,
+    Test,
+    /// Literal from a `.keep` sidecar
+    Keep,
+}
+
+impl AnchorKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            AnchorKind::Error => "error",
+            AnchorKind::File => "file",
+            AnchorKind::Test => "test",
+            AnchorKind::Keep => "keep",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Anchor {
+    pub kind: AnchorKind,
+    pub text: String,
+}
+
+impl std::fmt::Display for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind.as_str(), self.text)
+    }
+}
+
+impl Anchor {
+    fn new(kind: AnchorKind, text: impl Into<String>) -> Self {
+        Anchor {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    /// Filters reformat lines, so each kind is matched on its stable part.
+    fn kept_in(&self, filtered: &str) -> bool {
+        match self.kind {
+            AnchorKind::File => {
+                // `path:line` or `base:line`, never a bare basename and a stray number
+                let (path, line) = self.text.rsplit_once(':').unwrap_or((&self.text, ""));
+                let base = path.rsplit('/').next().unwrap_or(path);
+                let needle = format!("{}:{}", base, line);
+                filtered.match_indices(&needle).any(|(at, _)| {
+                    let before = filtered[..at].chars().next_back();
+                    let after = filtered[at + needle.len()..].chars().next();
+                    !before.is_some_and(|c| c.is_alphanumeric() || "_.-".contains(c))
+                        && !after.is_some_and(|c| c.is_ascii_digit())
+                })
+            }
+            AnchorKind::Test => {
+                let short = self.text.rsplit("::").next().unwrap_or(&self.text);
+                filtered.contains(&self.text) || filtered.contains(short)
+            }
+            AnchorKind::Error | AnchorKind::Keep => filtered.contains(&self.text),
+        }
+    }
+}
+
+/// Must-keep anchors found in a raw output.
+fn extract_anchors(raw: &str) -> Vec<Anchor> {
+    let mut anchors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut push = |anchor: Anchor, anchors: &mut Vec<Anchor>| {
+        let count = anchors.iter().filter(|a| a.kind == anchor.kind).count();
+        if count < MAX_ANCHORS_PER_KIND && seen.insert(anchor.clone()) {
+            anchors.push(anchor);
+        }
+    };
+
+    for line in raw.lines() {
+        if let Some(caps) = ERROR_LINE.captures(line) {
+            let message: String = caps[1].trim().chars().take(ERROR_ANCHOR_CHARS).collect();
+            if message.len() >= 4 {
+                push(Anchor::new(AnchorKind::Error, message), &mut anchors);
+            }
+        }
+        for caps in FILE_REF.captures_iter(line) {
+            push(
+                Anchor::new(AnchorKind::File, format!("{}:{}", &caps[1], &caps[2])),
+                &mut anchors,
+            );
+        }
+    }
+
+    for caps in FAILED_TEST.captures_iter(raw) {
+        if let Some(name) = caps.iter().skip(1).flatten().next() {
+            push(Anchor::new(AnchorKind::Test, name.as_str()), &mut anchors);
+        }
+    }
+
+    anchors
+}
+
+/// One corpus file replayed through its filter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleResult {
+    /// Path relative to the corpus directory
+    pub file: String,
+    pub filter: String,
+    pub tokens_in: usize,
+    pub tokens_out: usize,
+    pub must_keep: usize,
+    pub missing: Vec<Anchor>,
+}
+
+impl SampleResult {
+    fn savings_pct(&self) -> f64 {
+        savings_pct(self.tokens_in, self.tokens_out)
+    }
+}
+
+fn savings_pct(tokens_in: usize, tokens_out: usize) -> f64 {
+    if tokens_in == 0 {
+        0.0
+    } else {
+        tokens_in.saturating_sub(tokens_out) as f64 / tokens_in as f64 * 100.0
+    }
+}
+
+/// Stored as the baseline file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub samples: Vec<SampleResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unmatched: Vec<String>,
+}
+
+/// A sample that got worse than its baseline.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Regression {
+    pub file: String,
+    pub reason: String,
+}
+
+/// Which filter replays `path`, if any.
+fn resolve_filter(path: &Path, corpus: &Path) -> Option<&'static BenchFilter> {
+    let by_name = |name: &str| FILTERS.iter().find(|f| f.name == name);
+    let stem = path.file_stem()?.to_str()?;
+
+    // 1. Tee metadata: <id>.json sidecar, or the older <epoch>_<slug>.log name
+    if let Some(slug) = std::fs::read_to_string(path.with_extension("json"))
+        .ok()
+        .and_then(|json| serde_json::from_str::<crate::tee::TeeCapture>(&json).ok())
+        .map(|capture| capture.command)
+    {
+        return by_name(&slug);
+    }
+    if let Some(caps) = TEE_STEM.captures(stem) {
+        if let Some(filter) = by_name(&caps[1]) {
+            return Some(filter);
+        }
+    }
+
+    // 2. Directory named after the filter, anywhere under the corpus root
+    let in_dir = path
+        .parent()
+        .filter(|dir| dir.starts_with(corpus) && *dir != corpus)
+        .and_then(|dir| dir.file_name()?.to_str())
+        .and_then(by_name);
+    if in_dir.is_some() {
+        return in_dir;
+    }
+
+    // 3. File name prefix, longest name first (cargo_test before test)
+    FILTERS
+        .iter()
+        .filter(|f| {
+            stem.strip_prefix(f.name)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '.', '_']))
+        })
+        .max_by_key(|f| f.name.len())
+}
+
+/// Raw outputs in the corpus: everything except metadata and `.keep` sidecars.
+fn corpus_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let is_sidecar = ext == "keep"
+                || (ext == "json"
+                    && ["log", "txt", "out"]
+                        .iter()
+                        .any(|raw| p.with_extension(raw).exists()));
+            let hidden = p
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            !is_sidecar && !hidden
+        })
+        .filter(|p| std::fs::metadata(p).is_ok_and(|m| m.len() <= MAX_SAMPLE_BYTES))
+        .collect();
+    files.sort();
+    files
+}
+
+/// `<file>.keep`: one literal per line, `#` comments ignored.
+fn keep_anchors(path: &Path) -> Vec<Anchor> {
+    let mut keep = path.as_os_str().to_owned();
+    keep.push(".keep");
+    std::fs::read_to_string(PathBuf::from(keep))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(|l| Anchor::new(AnchorKind::Keep, l))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn run_sample(
+    filter: &BenchFilter,
+    raw: &str,
+    keep: Vec<Anchor>,
+    tokenizer: Tokenizer,
+) -> (usize, usize, Vec<Anchor>, usize) {
+    let filtered = (filter.apply)(raw);
+    let mut anchors = extract_anchors(raw);
+    anchors.extend(keep);
+    let missing: Vec<Anchor> = anchors
+        .iter()
+        .filter(|a| !a.kept_in(&filtered))
+        .cloned()
+        .collect();
+    (
+        tokenizer.count(raw),
+        tokenizer.count(&filtered),
+        missing,
+        anchors.len(),
+    )
+}
+
+/// Replay every matched file in `dir`.
+pub fn bench(dir: &Path, only: Option<&str>, tokenizer: Tokenizer) -> Result<BenchReport> {
+    if !dir.is_dir() {
+        anyhow::bail!("corpus directory not found: {}", dir.display());
+    }
+
+    let mut report = BenchReport::default();
+    for path in corpus_files(dir) {
+        let relative = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        let Some(filter) = resolve_filter(&path, dir) else {
+            report.unmatched.push(relative);
+            continue;
+        };
+        if only.is_some_and(|name| name != filter.name) {
+            continue;
+        }
+
+        let bytes =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let raw = String::from_utf8_lossy(&bytes);
+        let (tokens_in, tokens_out, missing, must_keep) =
+            run_sample(filter, &raw, keep_anchors(&path), tokenizer);
+        report.samples.push(SampleResult {
+            file: relative,
+            filter: filter.name.to_string(),
+            tokens_in,
+            tokens_out,
+            must_keep,
+            missing,
+        });
+    }
+    Ok(report)
+}
+
+/// Samples that lost more than `tolerance` savings points, newly drop an anchor,
+/// or are in the baseline but no longer benchmarked.
+pub fn compare(current: &BenchReport, baseline: &BenchReport, tolerance: f64) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for base in &baseline.samples {
+        let benchmarked = current
+            .samples
+            .iter()
+            .any(|s| s.file == base.file && s.filter == base.filter);
+        if !benchmarked {
+            regressions.push(Regression {
+                file: base.file.clone(),
+                reason: format!("no longer benchmarked by {}", base.filter),
+            });
+        }
+    }
+    for sample in &current.samples {
+        let Some(base) = baseline
+            .samples
+            .iter()
+            .find(|b| b.file == sample.file && b.filter == sample.filter)
+        else {
+            continue;
+        };
+
+        let (was, now) = (base.savings_pct(), sample.savings_pct());
+        if was - now > tolerance {
+            regressions.push(Regression {
+                file: sample.file.clone(),
+                reason: format!(
+                    "savings {:.1}% -> {:.1}% ({} -> {} tokens out)",
+                    was, now, base.tokens_out, sample.tokens_out
+                ),
+            });
+        }
+        for anchor in sample.missing.iter().filter(|a| !base.missing.contains(a)) {
+            regressions.push(Regression {
+                file: sample.file.clone(),
+                reason: format!("drops {}", anchor),
+            });
+        }
+    }
+    regressions
+}
+
+fn format_text(report: &BenchReport, dir: &Path, verbose: u8) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "rtk bench: {} samples in {}",
+        report.samples.len(),
+        dir.display()
+    ));
+    if !report.unmatched.is_empty() {
+        out.push_str(&format!(" ({} unmatched)", report.unmatched.len()));
+    }
+    out.push('\n');
+
+    // filter -> (samples, in, out, anchors, missing)
+    let mut per_filter: BTreeMap<&str, (usize, usize, usize, usize, usize)> = BTreeMap::new();
+    for s in &report.samples {
+        let entry = per_filter.entry(&s.filter).or_default();
+        entry.0 += 1;
+        entry.1 += s.tokens_in;
+        entry.2 += s.tokens_out;
+        entry.3 += s.must_keep;
+        entry.4 += s.missing.len();
+    }
+
+    if !per_filter.is_empty() {
+        out.push_str(&format!(
+            "{:<16} {:>7} {:>10} {:>10} {:>8} {:>10}\n",
+            "Filter", "Samples", "Tokens in", "Tokens out", "Savings", "Must-keep"
+        ));
+        out.push_str(&"-".repeat(66));
+        out.push('\n');
+        let mut total = (0, 0, 0, 0, 0);
+        for (name, (n, tin, tout, keep, missing)) in &per_filter {
+            out.push_str(&format!(
+                "{:<16} {:>7} {:>10} {:>10} {:>7.1}% {:>10}\n",
+                name,
+                n,
+                format_tokens(*tin),
+                format_tokens(*tout),
+                savings_pct(*tin, *tout),
+                format!("{}/{}", keep - missing, keep)
+            ));
+            total = (
+                total.0 + n,
+                total.1 + tin,
+                total.2 + tout,
+                total.3 + keep,
+                total.4 + missing,
+            );
+        }
+        out.push_str(&"-".repeat(66));
+        out.push('\n');
+        out.push_str(&format!(
+            "{:<16} {:>7} {:>10} {:>10} {:>7.1}% {:>10}\n",
+            "Total",
+            total.0,
+            format_tokens(total.1),
+            format_tokens(total.2),
+            savings_pct(total.1, total.2),
+            format!("{}/{}", total.3 - total.4, total.3)
+        ));
+    }
+
+    let missing: Vec<&SampleResult> = report
+        .samples
+        .iter()
+        .filter(|s| !s.missing.is_empty())
+        .collect();
+    if !missing.is_empty() {
+        out.push_str("\nMissing must-keep lines:\n");
+        for s in missing {
+            for anchor in &s.missing {
+                out.push_str(&format!("  {}  {}\n", s.file, anchor));
+            }
+        }
+    }
+
+    if verbose > 0 && !report.unmatched.is_empty() {
+        out.push_str("\nUnmatched (name a file or directory after a filter):\n");
+        for file in &report.unmatched {
+            out.push_str(&format!("  {}\n", file));
+        }
+        let names: Vec<&str> = FILTERS.iter().map(|f| f.name).collect();
+        out.push_str(&format!("Filters: {}\n", names.join(", ")));
+    }
+
+    out
+}
+
+pub fn run(
+    dir: &Path,
+    filter: Option<&str>,
+    baseline: Option<&Path>,
+    update_baseline: bool,
+    tolerance: f64,
+    format: &str,
+    verbose: u8,
+) -> Result<ExecResult> {
+    if let Some(name) = filter {
+        if !FILTERS.iter().any(|f| f.name == name) {
+            let names: Vec<&str> = FILTERS.iter().map(|f| f.name).collect();
+            anyhow::bail!(
+                "unknown filter '{}' (available: {})",
+                name,
+                names.join(", ")
+            );
+        }
+    }
+
+    let report = bench(dir, filter, Tokenizer::from_config())?;
+
+    let regressions = match baseline {
+        Some(path) if !update_baseline => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+            let mut stored: BenchReport = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid baseline {}", path.display()))?;
+            // --filter benches a subset: only its baseline entries can go missing
+            if let Some(name) = filter {
+                stored.samples.retain(|s| s.filter == name);
+            }
+            Some(compare(&report, &stored, tolerance))
+        }
+        _ => None,
+    };
+
+    match format {
+        "json" => {
+            let json = serde_json::json!({
+                "samples": report.samples,
+                "unmatched": report.unmatched,
+                "regressions": regressions,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        _ => {
+            print!("{}", format_text(&report, dir, verbose));
+            if let (Some(path), Some(regressions)) = (baseline, &regressions) {
+                println!(
+                    "\nBaseline {}: {} regression(s)",
+                    path.display(),
+                    regressions.len()
+                );
+                for r in regressions {
+                    println!("  {}  {}", r.file, r.reason);
+                }
+            }
+        }
+    }
+
+    if update_baseline {
+        let path = baseline.context("--update-baseline needs --baseline <file>")?;
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        eprintln!("Baseline written: {}", path.display());
+    }
+
+    if regressions.is_some_and(|r| !r.is_empty()) {
+        return Ok(ExecResult::code(1));
+    }
+    Ok(ExecResult::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, rel: &str, content: &str) -> PathBuf {
+        let path = dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_extract_anchors() {
+        let raw = "error[E0308]: mismatched types\n  --> src/main.rs:12:5\ntest tests::it_works ... FAILED\n--- FAIL: TestParse (0.00s)\nFAILED tests/test_x.py::test_y - assert 1 == 2";
+        let anchors = extract_anchors(raw);
+        assert!(anchors.contains(&Anchor::new(AnchorKind::Error, "mismatched types")));
+        assert!(anchors.contains(&Anchor::new(AnchorKind::File, "src/main.rs:12")));
+        assert!(anchors.contains(&Anchor::new(AnchorKind::Test, "tests::it_works")));
+        assert!(anchors.contains(&Anchor::new(AnchorKind::Test, "TestParse")));
+        assert!(anchors.contains(&Anchor::new(AnchorKind::Test, "tests/test_x.py::test_y")));
+    }
+
+    #[test]
+    fn test_anchor_kept_in_reformatted_output() {
+        let file = Anchor::new(AnchorKind::File, "src/deep/main.rs:12");
+        assert!(file.kept_in("main.rs:12: mismatched types"));
+        assert!(file.kept_in("  --> src/deep/main.rs:12:5"));
+        assert!(!file.kept_in("main.rs:13"));
+        assert!(!file.kept_in("main.rs:120"));
+        assert!(!file.kept_in("domain.rs:12"));
+        assert!(!file.kept_in("src/deep/main.rs\n  L12 mismatched types"));
+
+        // A count next to the basename is not the line reference
+        let lib = Anchor::new(AnchorKind::File, "src/lib.rs:3");
+        assert!(!lib.kept_in("3 errors in lib.rs"));
+        assert!(lib.kept_in("src/lib.rs:3:1: unused import"));
+
+        let test = Anchor::new(AnchorKind::Test, "tests::it_works");
+        assert!(test.kept_in("FAILED: it_works"));
+    }
+
+    #[test]
+    fn test_resolve_filter() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let by_dir = write(root, "git_status/clean.txt", "");
+        let by_name = write(root, "cargo_test-failing.txt", "");
+        let by_tee = write(root, "1700000000_go_test.log", "");
+        let unknown = write(root, "random.txt", "");
+
+        assert_eq!(resolve_filter(&by_dir, root).unwrap().name, "git_status");
+        assert_eq!(resolve_filter(&by_name, root).unwrap().name, "cargo_test");
+        assert_eq!(resolve_filter(&by_tee, root).unwrap().name, "go_test");
+        assert!(resolve_filter(&unknown, root).is_none());
+    }
+
+    #[test]
+    fn test_bench_and_keep_sidecar() {
+        let temp = tempfile::tempdir().unwrap();
+        let raw = "src/lib.rs:3:1: error: expected item\nnoise\n";
+        let path = write(temp.path(), "err/build.txt", raw);
+        write(
+            temp.path(),
+            "err/build.txt.keep",
+            "# literals\nexpected item\nnot in output\n",
+        );
+        write(temp.path(), "notes.md", "unmatched");
+
+        let report = bench(temp.path(), None, Tokenizer::Heuristic).unwrap();
+        assert_eq!(report.unmatched, vec!["notes.md".to_string()]);
+        assert_eq!(report.samples.len(), 1);
+        let sample = &report.samples[0];
+        assert_eq!(sample.filter, "err");
+        assert_eq!(sample.tokens_in, Tokenizer::Heuristic.count(raw));
+        assert_eq!(
+            sample.missing,
+            vec![Anchor::new(AnchorKind::Keep, "not in output")]
+        );
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_compare_baseline() {
+        let sample = |tokens_out: usize, missing: Vec<Anchor>| SampleResult {
+            file: "cargo_test/a.txt".to_string(),
+            filter: "cargo_test".to_string(),
+            tokens_in: 1000,
+            tokens_out,
+            must_keep: 2,
+            missing,
+        };
+        let lost = Anchor::new(AnchorKind::Test, "it_works");
+        let baseline = BenchReport {
+            samples: vec![sample(100, vec![])],
+            unmatched: vec![],
+        };
+
+        let same = BenchReport {
+            samples: vec![sample(105, vec![])],
+            unmatched: vec![],
+        };
+        assert!(compare(&same, &baseline, 1.0).is_empty());
+
+        let worse = BenchReport {
+            samples: vec![sample(200, vec![lost.clone()])],
+            unmatched: vec![],
+        };
+        let regressions = compare(&worse, &baseline, 1.0);
+        assert_eq!(regressions.len(), 2);
+        assert!(regressions[0].reason.starts_with("savings 90.0% -> 80.0%"));
+        assert_eq!(regressions[1].reason, "drops test it_works");
+
+        let missing = BenchReport::default();
+        let regressions = compare(&missing, &baseline, 1.0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].file, "cargo_test/a.txt");
+        assert_eq!(regressions[0].reason, "no longer benchmarked by cargo_test");
+    }
+}
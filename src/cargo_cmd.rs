@@ -1,7 +1,13 @@
+use crate::exec::ExecResult;
+use crate::parser::{
+    emit_degradation_warning, emit_passthrough_warning, truncate_output, BuildOutput, LintIssue,
+    LintResult, LintSeverity, OutputParser, ParseResult, TestFailure, TestResult,
+};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::ffi::OsString;
 use std::process::Command;
 use std::sync::OnceLock;
@@ -16,7 +22,15 @@ pub enum CargoCommand {
     Nextest,
 }
 
-pub fn run(cmd: CargoCommand, args: &[String], verbose: u8) -> Result<()> {
+/// `[cargo]` section of config.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CargoConfig {
+    /// Pass `-Z unstable-options --format json` to libtest on nightly toolchains.
+    /// Off by default: `harness = false` test targets reject the flags.
+    pub libtest_json: bool,
+}
+
+pub fn run(cmd: CargoCommand, args: &[String], verbose: u8) -> Result<ExecResult> {
     match cmd {
         CargoCommand::Build => run_build(args, verbose),
         CargoCommand::Test => run_test(args, verbose),
@@ -28,15 +42,21 @@ pub fn run(cmd: CargoCommand, args: &[String], verbose: u8) -> Result<()> {
 }
 
 /// Generic cargo command runner with filtering
-fn run_cargo_filtered<F>(subcommand: &str, args: &[String], verbose: u8, filter_fn: F) -> Result<()>
+fn run_cargo_filtered<F>(
+    subcommand: &str,
+    args: &[String],
+    verbose: u8,
+    filter_fn: F,
+) -> Result<ExecResult>
 where
     F: Fn(&str) -> String,
 {
     let timer = tracking::TimedExecution::start();
 
+    let libtest_json = subcommand == "test" && libtest_json_enabled();
     let mut cmd = Command::new("cargo");
     cmd.arg(subcommand);
-    for arg in args {
+    for arg in structured_args(subcommand, args, libtest_json) {
         cmd.arg(arg);
     }
 
@@ -44,18 +64,13 @@ where
         eprintln!("Running: cargo {} {}", subcommand, args.join(" "));
     }
 
-    let output = cmd
-        .output()
+    let output = crate::stream::run_streaming(&mut cmd, live_cargo_line)
         .with_context(|| format!("Failed to run cargo {}", subcommand))?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
+    let raw = format!("{}\n{}", output.stdout, output.stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let filtered = filter_fn(&raw);
+    let exit_code = output.exit_code;
+    let filtered = output.dedupe(&filter_fn(&raw));
+    let raw = humanize_json_lines(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, &format!("cargo_{}", subcommand), exit_code)
     {
@@ -64,41 +79,140 @@ where
         println!("{}", filtered);
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("cargo {} {}", subcommand, args.join(" ")),
         &format!("rtk cargo {} {}", subcommand, args.join(" ")),
         &raw,
-        &filtered,
+        &output.printed(&filtered),
+        exit_code,
     );
 
-    if !output.status.success() {
-        std::process::exit(exit_code);
+    Ok(ExecResult::code(exit_code))
+}
+
+/// Compile errors and test failures, printed as soon as cargo reports them
+fn live_cargo_line(line: &str) -> Option<String> {
+    let trimmed = line.trim();
+    if trimmed.starts_with('{') {
+        if let Ok(msg) = serde_json::from_str::<CargoMessage>(trimmed) {
+            if msg.reason != "compiler-message" {
+                return None;
+            }
+            let issue = msg.message.and_then(diagnostic_to_issue)?;
+            if issue.severity != LintSeverity::Error {
+                return None;
+            }
+            let code = if issue.rule_id == issue.message {
+                "error".to_string()
+            } else {
+                format!("error[{}]", issue.rule_id)
+            };
+            return Some(if issue.file_path.is_empty() {
+                format!("{}: {}", code, issue.message)
+            } else {
+                format!(
+                    "{}: {} ({}:{}:{})",
+                    code, issue.message, issue.file_path, issue.line, issue.column
+                )
+            });
+        }
+        if let Ok(event) = serde_json::from_str::<LibtestEvent>(trimmed) {
+            return (event.kind == "test" && event.event == "failed")
+                .then(|| format!("test {} ... FAILED", event.name.unwrap_or_default()));
+        }
+        return None;
+    }
+
+    // Text output (user-chosen --message-format, stable libtest, nextest)
+    let is_failure = (trimmed.starts_with("test ") && trimmed.ends_with("... FAILED"))
+        || trimmed.starts_with("FAIL [");
+    let is_error = (trimmed.starts_with("error[") || trimmed.starts_with("error:"))
+        && !is_summary_message(trimmed)
+        && !trimmed.contains("to rerun pass");
+    (is_failure || is_error).then(|| trimmed.to_string())
+}
+
+/// Ask cargo (and libtest, when supported) for JSON output unless the user
+/// already chose a format. Text output stays the Degraded fallback.
+fn structured_args(subcommand: &str, args: &[String], libtest_json: bool) -> Vec<String> {
+    if !matches!(subcommand, "build" | "check" | "clippy" | "test") {
+        return args.to_vec();
+    }
+
+    let split = args.iter().position(|a| a == "--").unwrap_or(args.len());
+    let (cargo_args, trailing) = args.split_at(split);
+
+    let mut out = Vec::with_capacity(args.len() + 6);
+    if !cargo_args.iter().any(|a| a.starts_with("--message-format")) {
+        out.push("--message-format=json".to_string());
+    }
+    out.extend(cargo_args.iter().cloned());
+    out.extend(trailing.iter().cloned());
+
+    let user_format = trailing
+        .iter()
+        .any(|a| a == "--format" || a.starts_with("--format="));
+    if libtest_json && !user_format {
+        if trailing.is_empty() {
+            out.push("--".to_string());
+        }
+        out.extend(
+            ["-Z", "unstable-options", "--format", "json"]
+                .iter()
+                .map(|s| s.to_string()),
+        );
     }
+    out
+}
+
+/// libtest JSON is opt-in (`cargo.libtest_json`) and, being unstable, only
+/// nightly/dev toolchains accept it
+fn libtest_json_enabled() -> bool {
+    let opted_in = crate::config::Config::load()
+        .map(|c| c.cargo.libtest_json)
+        .unwrap_or(false);
+    opted_in && nightly_toolchain()
+}
 
-    Ok(())
+fn nightly_toolchain() -> bool {
+    Command::new("rustc")
+        .arg("-V")
+        .output()
+        .map(|o| {
+            let version = String::from_utf8_lossy(&o.stdout);
+            version.contains("-nightly") || version.contains("-dev")
+        })
+        .unwrap_or(false)
 }
 
-fn run_build(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("build", args, verbose, filter_cargo_build)
+fn run_build(args: &[String], verbose: u8) -> Result<ExecResult> {
+    run_cargo_filtered("build", args, verbose, |raw| {
+        filter_cargo_build(raw, verbose)
+    })
 }
 
-fn run_test(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("test", args, verbose, filter_cargo_test)
+fn run_test(args: &[String], verbose: u8) -> Result<ExecResult> {
+    run_cargo_filtered("test", args, verbose, |raw| filter_cargo_test(raw, verbose))
 }
 
-fn run_clippy(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("clippy", args, verbose, filter_cargo_clippy)
+fn run_clippy(args: &[String], verbose: u8) -> Result<ExecResult> {
+    run_cargo_filtered("clippy", args, verbose, |raw| {
+        filter_cargo_clippy(raw, verbose)
+    })
 }
 
-fn run_check(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("check", args, verbose, filter_cargo_build)
+fn run_check(args: &[String], verbose: u8) -> Result<ExecResult> {
+    run_cargo_filtered("check", args, verbose, |raw| {
+        filter_cargo_build(raw, verbose)
+    })
 }
 
-fn run_install(args: &[String], verbose: u8) -> Result<()> {
+fn run_install(args: &[String], verbose: u8) -> Result<ExecResult> {
     run_cargo_filtered("install", args, verbose, filter_cargo_install)
 }
 
-fn run_nextest(args: &[String], verbose: u8) -> Result<()> {
+fn run_nextest(args: &[String], verbose: u8) -> Result<ExecResult> {
     run_cargo_filtered("nextest", args, verbose, filter_cargo_nextest)
 }
 
@@ -114,7 +228,7 @@ fn format_crate_info(name: &str, version: &str, fallback: &str) -> String {
 }
 
 /// Filter cargo install output - strip dep compilation, keep installed/replaced/errors
-fn filter_cargo_install(output: &str) -> String {
+pub(crate) fn filter_cargo_install(output: &str) -> String {
     let mut errors: Vec<String> = Vec::new();
     let mut error_count = 0;
     let mut compiled = 0;
@@ -301,7 +415,7 @@ fn flush_failure_block(header: &mut String, body: &mut Vec<String>, failures: &m
 }
 
 /// Filter cargo nextest output - show failures + compact summary
-fn filter_cargo_nextest(output: &str) -> String {
+pub(crate) fn filter_cargo_nextest(output: &str) -> String {
     static SUMMARY_RE: OnceLock<regex::Regex> = OnceLock::new();
     let summary_re = SUMMARY_RE.get_or_init(|| {
         regex::Regex::new(
@@ -519,98 +633,375 @@ fn filter_cargo_nextest(output: &str) -> String {
     String::new()
 }
 
-/// Filter cargo build/check output - strip "Compiling"/"Checking" lines, keep errors + summary
-fn filter_cargo_build(output: &str) -> String {
-    let mut errors: Vec<String> = Vec::new();
-    let mut warnings = 0;
-    let mut error_count = 0;
-    let mut compiled = 0;
-    let mut in_error = false;
-    let mut current_error = Vec::new();
+/// Cargo `--message-format=json` message (one object per stdout line)
+#[derive(Debug, Deserialize)]
+struct CargoMessage {
+    reason: String,
+    package_id: Option<String>,
+    fresh: Option<bool>,
+    success: Option<bool>,
+    message: Option<RustcDiagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    level: String,
+    code: Option<RustcCode>,
+    #[serde(default)]
+    spans: Vec<RustcSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+/// libtest `--format json` event (nightly only)
+#[derive(Debug, Deserialize)]
+struct LibtestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    stdout: Option<String>,
+    test_count: Option<usize>,
+    passed: Option<usize>,
+    failed: Option<usize>,
+    ignored: Option<usize>,
+    measured: Option<usize>,
+    filtered_out: Option<usize>,
+    exec_time: Option<f64>,
+}
+
+/// Diagnostics and build status collected from cargo JSON messages
+#[derive(Debug, Default)]
+struct CargoMessages {
+    diagnostics: Vec<LintIssue>,
+    compiled: usize,
+    success: Option<bool>,
+}
+
+/// Collect cargo JSON messages. Returns None when there are none (the user
+/// picked another --message-format, or cargo failed before compiling).
+fn parse_cargo_messages(output: &str) -> Option<CargoMessages> {
+    let mut messages = CargoMessages::default();
+    let mut compiled: HashSet<String> = HashSet::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut found = false;
 
     for line in output.lines() {
-        if line.trim_start().starts_with("Compiling") || line.trim_start().starts_with("Checking") {
-            compiled += 1;
-            continue;
-        }
-        if line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-        {
+        let trimmed = line.trim();
+        if !trimmed.starts_with('{') {
             continue;
         }
-        if line.trim_start().starts_with("Finished") {
+        let Ok(msg) = serde_json::from_str::<CargoMessage>(trimmed) else {
             continue;
-        }
+        };
+        found = true;
 
-        // Detect error/warning blocks
-        if line.starts_with("error[") || line.starts_with("error:") {
-            // Skip "error: aborting due to" summary lines
-            if line.contains("aborting due to") || line.contains("could not compile") {
-                continue;
+        match msg.reason.as_str() {
+            "compiler-artifact" if msg.fresh == Some(false) => {
+                if let Some(id) = msg.package_id {
+                    compiled.insert(id);
+                }
             }
-            if in_error && !current_error.is_empty() {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
+            "compiler-message" => {
+                let Some(issue) = msg.message.and_then(diagnostic_to_issue) else {
+                    continue;
+                };
+                // The same diagnostic is reported once per target (lib, bin, test)
+                let key = issue
+                    .rendered
+                    .clone()
+                    .unwrap_or_else(|| issue.message.clone());
+                if seen.insert(key) {
+                    messages.diagnostics.push(issue);
+                }
             }
-            error_count += 1;
-            in_error = true;
-            current_error.push(line.to_string());
-        } else if line.starts_with("warning:")
-            && line.contains("generated")
-            && line.contains("warning")
-        {
-            // "warning: `crate` generated N warnings" summary line
+            "build-finished" => messages.success = msg.success,
+            _ => {}
+        }
+    }
+
+    messages.compiled = compiled.len();
+    found.then_some(messages)
+}
+
+/// rustc/cargo count lines ("aborting due to", "generated 3 warnings", ...)
+fn is_summary_message(message: &str) -> bool {
+    message.contains("aborting due to")
+        || message.contains("could not compile")
+        || (message.contains("generated") && message.contains("warning"))
+        || message.ends_with("emitted")
+}
+
+fn diagnostic_to_issue(diag: RustcDiagnostic) -> Option<LintIssue> {
+    let severity = match diag.level.as_str() {
+        "error" | "error: internal compiler error" => LintSeverity::Error,
+        "warning" => LintSeverity::Warning,
+        _ => return None,
+    };
+    if diag.spans.is_empty() && is_summary_message(&diag.message) {
+        return None;
+    }
+
+    let span = diag
+        .spans
+        .iter()
+        .find(|s| s.is_primary)
+        .or(diag.spans.first());
+    Some(LintIssue {
+        file_path: span.map(|s| s.file_name.clone()).unwrap_or_default(),
+        line: span.map_or(0, |s| s.line_start),
+        column: span.map_or(0, |s| s.column_start),
+        severity,
+        rule_id: diag
+            .code
+            .map(|c| c.code)
+            .unwrap_or_else(|| diag.message.clone()),
+        message: diag.message,
+        rendered: diag.rendered.map(|r| r.trim_end().to_string()),
+    })
+}
+
+/// Diagnostics scraped from human-readable cargo output
+#[derive(Debug, Default)]
+struct TextDiagnostics {
+    diagnostics: Vec<LintIssue>,
+    compiled: usize,
+    finished: bool,
+}
+
+/// Text heuristics: split rustc's human output into error/warning blocks
+fn parse_diagnostics_text(output: &str) -> TextDiagnostics {
+    let mut parsed = TextDiagnostics::default();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_severity = LintSeverity::Error;
+
+    let flush = |current: &mut Vec<String>, severity: &LintSeverity, out: &mut Vec<LintIssue>| {
+        if !current.is_empty() {
+            out.push(text_block_to_issue(current, severity.clone()));
+            current.clear();
+        }
+    };
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("Compiling") || trimmed.starts_with("Checking") {
+            parsed.compiled += 1;
+            continue;
+        }
+        if trimmed.starts_with("Downloading") || trimmed.starts_with("Downloaded") {
             continue;
+        }
+        if trimmed.starts_with("Finished") {
+            parsed.finished = true;
+            continue;
+        }
+
+        let severity = if line.starts_with("error[") || line.starts_with("error:") {
+            Some(LintSeverity::Error)
         } else if line.starts_with("warning:") || line.starts_with("warning[") {
-            if in_error && !current_error.is_empty() {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
+            Some(LintSeverity::Warning)
+        } else {
+            None
+        };
+
+        match severity {
+            Some(_) if is_summary_message(line) => continue,
+            Some(severity) => {
+                flush(&mut current, &current_severity, &mut parsed.diagnostics);
+                current_severity = severity;
+                current.push(line.to_string());
             }
-            warnings += 1;
-            in_error = true;
-            current_error.push(line.to_string());
-        } else if in_error {
-            if line.trim().is_empty() && current_error.len() > 3 {
-                errors.push(current_error.join("\n"));
-                current_error.clear();
-                in_error = false;
-            } else {
-                current_error.push(line.to_string());
+            None if current.is_empty() => {}
+            None => {
+                if line.trim().is_empty() && current.len() > 3 {
+                    flush(&mut current, &current_severity, &mut parsed.diagnostics);
+                } else {
+                    current.push(line.to_string());
+                }
             }
         }
     }
+    flush(&mut current, &current_severity, &mut parsed.diagnostics);
 
-    if !current_error.is_empty() {
-        errors.push(current_error.join("\n"));
+    parsed
+}
+
+/// Build a LintIssue from a text block like "error[E0308]: mismatched types\n --> src/main.rs:10:5"
+fn text_block_to_issue(block: &[String], severity: LintSeverity) -> LintIssue {
+    let header = block[0].as_str();
+    let (head, rest) = header.split_once(':').unwrap_or(("", header));
+    let code = head
+        .find('[')
+        .and_then(|start| head[start + 1..].strip_suffix(']'));
+
+    // "unused variable: `x` [unused_variables]" style trailing lint name
+    let rest = rest.trim();
+    let (message, trailing_rule) = match rest.strip_suffix(']').and_then(|r| r.rsplit_once(" [")) {
+        Some((message, rule)) => (message, Some(rule)),
+        None => (rest, None),
+    };
+
+    let (file_path, line, column) = block
+        .iter()
+        .find_map(|l| l.trim_start().strip_prefix("--> "))
+        .map(parse_location)
+        .unwrap_or_default();
+
+    LintIssue {
+        file_path,
+        line,
+        column,
+        severity,
+        rule_id: code.or(trailing_rule).unwrap_or(message).to_string(),
+        message: message.to_string(),
+        rendered: Some(block.join("\n")),
     }
+}
 
-    if error_count == 0 && warnings == 0 {
-        return format!("✓ cargo build ({} crates compiled)", compiled);
+/// Split "src/main.rs:10:5" into (file, line, column)
+fn parse_location(location: &str) -> (String, usize, usize) {
+    let mut parts = location.trim().rsplitn(3, ':');
+    let column = parts.next().and_then(|c| c.parse().ok());
+    let line = parts.next().and_then(|l| l.parse().ok());
+    match (parts.next(), line, column) {
+        (Some(file), Some(line), Some(column)) => (file.to_string(), line, column),
+        _ => (location.trim().to_string(), 0, 0),
     }
+}
 
-    let mut result = String::new();
-    result.push_str(&format!(
-        "cargo build: {} errors, {} warnings ({} crates)\n",
-        error_count, warnings, compiled
-    ));
-    result.push_str("═══════════════════════════════════════\n");
+/// Output is not cargo build output at all (no diagnostics, no progress lines)
+fn is_unrecognized(output: &str, text: &TextDiagnostics) -> bool {
+    text.diagnostics.is_empty() && text.compiled == 0 && !text.finished && !output.trim().is_empty()
+}
+
+/// Parser for `cargo build/check --message-format=json`
+pub struct CargoBuildParser;
+
+impl OutputParser for CargoBuildParser {
+    type Output = BuildOutput;
+
+    fn parse(input: &str) -> ParseResult<BuildOutput> {
+        // Tier 1: cargo JSON messages. Cargo's own errors (manifest, build
+        // scripts) are still plain text on stderr, so scrape those alongside.
+        if let Some(messages) = parse_cargo_messages(input) {
+            let mut diagnostics = messages.diagnostics;
+            diagnostics.extend(parse_diagnostics_text(&non_json_lines(input)).diagnostics);
+            let mut build = build_output(diagnostics, messages.compiled);
+            if let Some(success) = messages.success {
+                build.success = success;
+            }
+            return ParseResult::Full(build);
+        }
+
+        // Tier 2: human-readable output
+        let text = parse_diagnostics_text(input);
+        if is_unrecognized(input, &text) {
+            // Tier 3: Passthrough
+            return ParseResult::Passthrough(truncate_output(input, 500));
+        }
+        ParseResult::Degraded(
+            build_output(text.diagnostics, text.compiled),
+            vec!["no cargo JSON messages; parsed human-readable output".to_string()],
+        )
+    }
+}
+
+fn build_output(diagnostics: Vec<LintIssue>, compiled_units: usize) -> BuildOutput {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == LintSeverity::Error)
+        .count();
+    BuildOutput {
+        success: errors == 0,
+        duration_ms: None,
+        warnings: diagnostics.len() - errors,
+        errors,
+        bundles: Vec::new(),
+        routes: Vec::new(),
+        compiled_units,
+        diagnostics,
+    }
+}
+
+fn non_json_lines(output: &str) -> String {
+    output
+        .lines()
+        .filter(|l| !l.trim_start().starts_with('{'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    for (i, err) in errors.iter().enumerate().take(15) {
-        result.push_str(err);
+/// Append up to 15 rendered diagnostics, blank-line separated
+fn push_diagnostics(result: &mut String, diagnostics: &[LintIssue]) {
+    for (i, diag) in diagnostics.iter().enumerate().take(15) {
+        match &diag.rendered {
+            Some(rendered) => result.push_str(rendered),
+            None => {
+                let level = match diag.severity {
+                    LintSeverity::Error => "error",
+                    _ => "warning",
+                };
+                result.push_str(&format!("{}: {}", level, diag.message));
+                if !diag.file_path.is_empty() {
+                    result.push_str(&format!(
+                        "\n --> {}:{}:{}",
+                        diag.file_path, diag.line, diag.column
+                    ));
+                }
+            }
+        }
         result.push('\n');
-        if i < errors.len() - 1 {
+        if i < diagnostics.len() - 1 {
             result.push('\n');
         }
     }
 
-    if errors.len() > 15 {
-        result.push_str(&format!("\n... +{} more issues\n", errors.len() - 15));
+    if diagnostics.len() > 15 {
+        result.push_str(&format!("\n... +{} more issues\n", diagnostics.len() - 15));
     }
+}
+
+fn format_build_output(build: &BuildOutput) -> String {
+    if build.success && build.errors == 0 && build.warnings == 0 {
+        return format!("✓ cargo build ({} crates compiled)", build.compiled_units);
+    }
+
+    let mut result = String::new();
+    result.push_str(&format!(
+        "cargo build: {} errors, {} warnings ({} crates)\n",
+        build.errors, build.warnings, build.compiled_units
+    ));
+    result.push_str("═══════════════════════════════════════\n");
+    push_diagnostics(&mut result, &build.diagnostics);
 
     result.trim().to_string()
 }
 
-/// Aggregated test results for compact display
+/// Filter cargo build/check output - strip "Compiling"/"Checking" lines, keep errors + summary
+pub(crate) fn filter_cargo_build(output: &str, verbose: u8) -> String {
+    render_parsed(
+        "cargo build",
+        "BuildOutput",
+        CargoBuildParser::parse(output),
+        verbose,
+        format_build_output,
+    )
+}
+
+/// Per-suite test results, aggregated for compact display
 #[derive(Debug, Default, Clone)]
 struct AggregatedTestResult {
     passed: usize,
@@ -635,13 +1026,6 @@ impl AggregatedTestResult {
         });
 
         let caps = re.captures(line)?;
-        let status = caps.get(1)?.as_str();
-
-        // Only aggregate if status is "ok" (all tests passed)
-        if status != "ok" {
-            return None;
-        }
-
         let passed = caps.get(2)?.as_str().parse().ok()?;
         let failed = caps.get(3)?.as_str().parse().ok()?;
         let ignored = caps.get(4)?.as_str().parse().ok()?;
@@ -666,6 +1050,33 @@ impl AggregatedTestResult {
         })
     }
 
+    /// Build from a libtest JSON suite event
+    fn from_event(event: &LibtestEvent) -> Self {
+        Self {
+            passed: event.passed.unwrap_or(0),
+            failed: event.failed.unwrap_or(0),
+            ignored: event.ignored.unwrap_or(0),
+            measured: event.measured.unwrap_or(0),
+            filtered_out: event.filtered_out.unwrap_or(0),
+            suites: 1,
+            duration_secs: event.exec_time.unwrap_or(0.0),
+            has_duration: event.exec_time.is_some(),
+        }
+    }
+
+    /// The libtest text summary line for this suite
+    fn summary_line(&self) -> String {
+        let status = if self.failed > 0 { "FAILED" } else { "ok" };
+        let mut line = format!(
+            "test result: {}. {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            status, self.passed, self.failed, self.ignored, self.measured, self.filtered_out
+        );
+        if self.has_duration {
+            line.push_str(&format!("; finished in {:.2}s", self.duration_secs));
+        }
+        line
+    }
+
     /// Merge another test result into this one
     fn merge(&mut self, other: &Self) {
         self.passed += other.passed;
@@ -708,89 +1119,265 @@ impl AggregatedTestResult {
     }
 }
 
-/// Filter cargo test output - show failures + summary only
-fn filter_cargo_test(output: &str) -> String {
-    let mut failures: Vec<String> = Vec::new();
-    let mut summary_lines: Vec<String> = Vec::new();
-    let mut in_failure_section = false;
-    let mut current_failure = Vec::new();
+/// Parsed `cargo test` run: the canonical TestResult plus cargo-specific counts
+#[derive(Debug, Clone, Serialize)]
+pub struct CargoTestRun {
+    #[serde(flatten)]
+    pub tests: TestResult,
+    pub suites: usize,
+    pub filtered_out: usize,
+    /// Compile errors when the test build failed
+    pub build_errors: Vec<LintIssue>,
+    #[serde(skip)]
+    summaries: Vec<AggregatedTestResult>,
+    /// `test result:` lines, including any the summary regex could not parse
+    #[serde(skip)]
+    summary_lines: Vec<String>,
+}
+
+impl CargoTestRun {
+    fn new(
+        summaries: Vec<AggregatedTestResult>,
+        summary_lines: Vec<String>,
+        failures: Vec<TestFailure>,
+    ) -> Self {
+        let total = summaries
+            .iter()
+            .fold(AggregatedTestResult::default(), |mut acc, s| {
+                acc.merge(s);
+                acc
+            });
+        let has_duration = !summaries.is_empty() && summaries.iter().all(|s| s.has_duration);
+        let failed = total.failed.max(failures.len());
+
+        Self {
+            tests: TestResult {
+                total: total.passed + failed + total.ignored,
+                passed: total.passed,
+                failed,
+                skipped: total.ignored,
+                duration_ms: has_duration.then_some((total.duration_secs * 1000.0) as u64),
+                failures,
+            },
+            suites: summaries.len(),
+            filtered_out: total.filtered_out,
+            build_errors: Vec::new(),
+            summaries,
+            summary_lines,
+        }
+    }
+
+    fn build_failed(build_errors: Vec<LintIssue>) -> Self {
+        let mut run = Self::new(Vec::new(), Vec::new(), Vec::new());
+        run.build_errors = build_errors;
+        run
+    }
+}
+
+/// Parser for `cargo test`: libtest JSON events when available, text summaries otherwise
+pub struct CargoTestParser;
+
+impl OutputParser for CargoTestParser {
+    type Output = CargoTestRun;
+
+    fn parse(input: &str) -> ParseResult<CargoTestRun> {
+        let build_errors: Vec<LintIssue> = parse_cargo_messages(input)
+            .map(|m| m.diagnostics)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|d| d.severity == LintSeverity::Error)
+            .collect();
+
+        // Tier 1: libtest JSON events
+        if let Some(mut run) = parse_libtest_json(input) {
+            run.build_errors = build_errors;
+            return ParseResult::Full(run);
+        }
+
+        // Tier 2: libtest text summaries and failure blocks
+        match parse_test_text(input) {
+            Some(mut run) => {
+                run.build_errors = build_errors;
+                ParseResult::Degraded(
+                    run,
+                    vec![
+                        "libtest JSON off (cargo.libtest_json, nightly only); parsed text output"
+                            .to_string(),
+                    ],
+                )
+            }
+            // The test build failed before any test ran
+            None if !build_errors.is_empty() => {
+                ParseResult::Full(CargoTestRun::build_failed(build_errors))
+            }
+            // Tier 3: show the last meaningful lines
+            None => {
+                let meaningful: Vec<&str> = input
+                    .lines()
+                    .filter(|l| {
+                        !l.trim().is_empty()
+                            && !l.trim_start().starts_with("Compiling")
+                            && !l.trim_start().starts_with('{')
+                    })
+                    .collect();
+                let start = meaningful.len().saturating_sub(5);
+                ParseResult::Passthrough(meaningful[start..].join("\n"))
+            }
+        }
+    }
+}
+
+fn parse_libtest_json(output: &str) -> Option<CargoTestRun> {
+    let mut summaries = Vec::new();
+    let mut summary_lines = Vec::new();
+    let mut failures = Vec::new();
+    let mut found = false;
 
     for line in output.lines() {
-        // Skip compilation lines
-        if line.trim_start().starts_with("Compiling")
-            || line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-            || line.trim_start().starts_with("Finished")
-        {
+        let trimmed = line.trim();
+        if !trimmed.starts_with('{') {
             continue;
         }
+        let Ok(event) = serde_json::from_str::<LibtestEvent>(trimmed) else {
+            continue;
+        };
+        found = true;
+
+        match (event.kind.as_str(), event.event.as_str()) {
+            ("test", "failed") => {
+                let stdout = event.stdout.unwrap_or_default();
+                failures.push(TestFailure {
+                    test_name: event.name.unwrap_or_default(),
+                    file_path: panic_location(&stdout),
+                    error_message: stdout.trim().to_string(),
+                    stack_trace: None,
+                });
+            }
+            ("suite", "ok" | "failed") => {
+                let summary = AggregatedTestResult::from_event(&event);
+                summary_lines.push(summary.summary_line());
+                summaries.push(summary);
+            }
+            _ => {}
+        }
+    }
 
-        // Skip "running N tests" and individual "test ... ok" lines
-        if line.starts_with("running ") || (line.starts_with("test ") && line.ends_with("... ok")) {
+    found.then(|| CargoTestRun::new(summaries, summary_lines, failures))
+}
+
+fn parse_test_text(output: &str) -> Option<CargoTestRun> {
+    let mut summaries = Vec::new();
+    let mut summary_lines: Vec<String> = Vec::new();
+    let mut failures: Vec<TestFailure> = Vec::new();
+    let mut failed_names: Vec<String> = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+
+    let flush = |current: &mut Option<(String, Vec<String>)>, failures: &mut Vec<TestFailure>| {
+        if let Some((name, body)) = current.take() {
+            let message = body.join("\n").trim().to_string();
+            failures.push(TestFailure {
+                test_name: name,
+                file_path: panic_location(&message),
+                error_message: message,
+                stack_trace: None,
+            });
+        }
+    };
+
+    for line in output.lines() {
+        if line.starts_with("test result:") {
+            flush(&mut current, &mut failures);
+            if let Some(summary) = AggregatedTestResult::parse_line(line) {
+                summaries.push(summary);
+            }
+            summary_lines.push(line.to_string());
             continue;
         }
 
-        // Detect failures section
+        // "---- tests::name stdout ----" opens a failure block
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" stdout ----"))
+        {
+            flush(&mut current, &mut failures);
+            current = Some((name.to_string(), Vec::new()));
+            continue;
+        }
         if line == "failures:" {
-            in_failure_section = true;
+            flush(&mut current, &mut failures);
             continue;
         }
-
-        if in_failure_section {
-            if line.starts_with("test result:") {
-                in_failure_section = false;
-                summary_lines.push(line.to_string());
-            } else if line.starts_with("    ") || line.starts_with("---- ") {
-                current_failure.push(line.to_string());
-            } else if line.trim().is_empty() && !current_failure.is_empty() {
-                failures.push(current_failure.join("\n"));
-                current_failure.clear();
-            } else if !line.trim().is_empty() {
-                current_failure.push(line.to_string());
-            }
+        if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|l| l.strip_suffix(" ... FAILED"))
+        {
+            failed_names.push(name.to_string());
+            continue;
         }
 
-        // Capture test result summary
-        if !in_failure_section && line.starts_with("test result:") {
-            summary_lines.push(line.to_string());
+        if let Some((_, body)) = current.as_mut() {
+            body.push(line.to_string());
+        }
+    }
+    flush(&mut current, &mut failures);
+
+    // Failed tests without a captured output block
+    for name in failed_names {
+        if !failures.iter().any(|f| f.test_name == name) {
+            failures.push(TestFailure {
+                test_name: name,
+                file_path: String::new(),
+                error_message: String::new(),
+                stack_trace: None,
+            });
         }
     }
 
-    if !current_failure.is_empty() {
-        failures.push(current_failure.join("\n"));
+    if summary_lines.is_empty() && failures.is_empty() {
+        return None;
     }
+    Some(CargoTestRun::new(summaries, summary_lines, failures))
+}
+
+/// Source file from "thread 'x' panicked at src/lib.rs:5:9:"
+fn panic_location(output: &str) -> String {
+    static RE: OnceLock<regex::Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| regex::Regex::new(r"panicked at ([^\s:']+):\d+:\d+").unwrap());
+    re.captures(output)
+        .map(|caps| caps[1].to_string())
+        .unwrap_or_default()
+}
 
+fn format_test_run(run: &CargoTestRun) -> String {
     let mut result = String::new();
 
-    if failures.is_empty() && !summary_lines.is_empty() {
-        // All passed - try to aggregate
-        let mut aggregated: Option<AggregatedTestResult> = None;
-        let mut all_parsed = true;
+    if !run.build_errors.is_empty() {
+        result.push_str(&format!(
+            "cargo test: {} errors (build failed)\n",
+            run.build_errors.len()
+        ));
+        result.push_str("═══════════════════════════════════════\n");
+        push_diagnostics(&mut result, &run.build_errors);
+        return result.trim().to_string();
+    }
 
-        for line in &summary_lines {
-            if let Some(parsed) = AggregatedTestResult::parse_line(line) {
-                if let Some(ref mut agg) = aggregated {
-                    agg.merge(&parsed);
+    let failures = &run.tests.failures;
+    if failures.is_empty() && !run.summary_lines.is_empty() {
+        // All passed - aggregate if every summary line parsed
+        if run.summaries.len() == run.summary_lines.len() && run.tests.failed == 0 {
+            let mut aggregated = AggregatedTestResult::default();
+            for (i, summary) in run.summaries.iter().enumerate() {
+                if i == 0 {
+                    aggregated = summary.clone();
                 } else {
-                    aggregated = Some(parsed);
+                    aggregated.merge(summary);
                 }
-            } else {
-                all_parsed = false;
-                break;
             }
+            return aggregated.format_compact();
         }
 
-        // If all lines parsed successfully and we have at least one suite, return compact format
-        if all_parsed {
-            if let Some(agg) = aggregated {
-                if agg.suites > 0 {
-                    return agg.format_compact();
-                }
-            }
-        }
-
-        // Fallback: use original behavior if regex failed
-        for line in &summary_lines {
+        // Fallback: original lines if the regex failed
+        for line in &run.summary_lines {
             result.push_str(&format!("✓ {}\n", line));
         }
         return result.trim().to_string();
@@ -800,7 +1387,12 @@ fn filter_cargo_test(output: &str) -> String {
         result.push_str(&format!("FAILURES ({}):\n", failures.len()));
         result.push_str("═══════════════════════════════════════\n");
         for (i, failure) in failures.iter().enumerate().take(10) {
-            result.push_str(&format!("{}. {}\n", i + 1, truncate(failure, 200)));
+            let block = if failure.error_message.is_empty() {
+                failure.test_name.clone()
+            } else {
+                format!("{}\n{}", failure.test_name, failure.error_message)
+            };
+            result.push_str(&format!("{}. {}\n", i + 1, truncate(&block, 200)));
         }
         if failures.len() > 10 {
             result.push_str(&format!("\n... +{} more failures\n", failures.len() - 10));
@@ -808,104 +1400,96 @@ fn filter_cargo_test(output: &str) -> String {
         result.push('\n');
     }
 
-    for line in &summary_lines {
+    for line in &run.summary_lines {
         result.push_str(&format!("{}\n", line));
     }
 
-    if result.trim().is_empty() {
-        // Fallback: show last meaningful lines
-        let meaningful: Vec<&str> = output
-            .lines()
-            .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with("Compiling"))
-            .collect();
-        for line in meaningful.iter().rev().take(5).rev() {
-            result.push_str(&format!("{}\n", line));
-        }
-    }
-
     result.trim().to_string()
 }
 
-/// Filter cargo clippy output - group warnings by lint rule
-fn filter_cargo_clippy(output: &str) -> String {
-    let mut by_rule: HashMap<String, Vec<String>> = HashMap::new();
-    let mut error_count = 0;
-    let mut warning_count = 0;
-
-    // Parse clippy output lines
-    // Format: "warning: description\n  --> file:line:col\n  |\n  | code\n"
-    let mut current_rule = String::new();
+/// Filter cargo test output - show failures + summary only
+pub(crate) fn filter_cargo_test(output: &str, verbose: u8) -> String {
+    render_parsed(
+        "cargo test",
+        "TestResult",
+        CargoTestParser::parse(output),
+        verbose,
+        format_test_run,
+    )
+}
 
-    for line in output.lines() {
-        // Skip compilation lines
-        if line.trim_start().starts_with("Compiling")
-            || line.trim_start().starts_with("Checking")
-            || line.trim_start().starts_with("Downloading")
-            || line.trim_start().starts_with("Downloaded")
-            || line.trim_start().starts_with("Finished")
-        {
-            continue;
-        }
+/// Parser for `cargo clippy --message-format=json`
+pub struct CargoClippyParser;
 
-        // "warning: unused variable [unused_variables]" or "warning: description [clippy::rule_name]"
-        if (line.starts_with("warning:") || line.starts_with("warning["))
-            || (line.starts_with("error:") || line.starts_with("error["))
-        {
-            // Skip summary lines: "warning: `rtk` (bin) generated 5 warnings"
-            if line.contains("generated") && line.contains("warning") {
-                continue;
-            }
-            // Skip "error: aborting" / "error: could not compile"
-            if line.contains("aborting due to") || line.contains("could not compile") {
-                continue;
-            }
+impl OutputParser for CargoClippyParser {
+    type Output = LintResult;
 
-            let is_error = line.starts_with("error");
-            if is_error {
-                error_count += 1;
-            } else {
-                warning_count += 1;
-            }
+    fn parse(input: &str) -> ParseResult<LintResult> {
+        // Tier 1: cargo JSON messages (lint name comes from the diagnostic code)
+        if let Some(messages) = parse_cargo_messages(input) {
+            let mut issues = messages.diagnostics;
+            issues.extend(parse_diagnostics_text(&non_json_lines(input)).diagnostics);
+            return ParseResult::Full(lint_result(issues));
+        }
 
-            // Extract rule name from brackets
-            current_rule = if let Some(bracket_start) = line.rfind('[') {
-                if let Some(bracket_end) = line.rfind(']') {
-                    line[bracket_start + 1..bracket_end].to_string()
-                } else {
-                    line.to_string()
-                }
-            } else {
-                // No bracket: use the message itself as the rule
-                let prefix = if is_error { "error: " } else { "warning: " };
-                line.strip_prefix(prefix).unwrap_or(line).to_string()
-            };
-        } else if line.trim_start().starts_with("--> ") {
-            let location = line.trim_start().trim_start_matches("--> ").to_string();
-            if !current_rule.is_empty() {
-                by_rule
-                    .entry(current_rule.clone())
-                    .or_default()
-                    .push(location);
-            }
+        // Tier 2: human-readable output
+        let text = parse_diagnostics_text(input);
+        if is_unrecognized(input, &text) {
+            // Tier 3: Passthrough
+            return ParseResult::Passthrough(truncate_output(input, 500));
         }
+        ParseResult::Degraded(
+            lint_result(text.diagnostics),
+            vec!["no cargo JSON messages; parsed human-readable output".to_string()],
+        )
     }
+}
 
-    if error_count == 0 && warning_count == 0 {
+pub(crate) fn lint_result(issues: Vec<LintIssue>) -> LintResult {
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == LintSeverity::Error)
+        .count();
+    let files: HashSet<&str> = issues
+        .iter()
+        .filter(|i| !i.file_path.is_empty())
+        .map(|i| i.file_path.as_str())
+        .collect();
+
+    LintResult {
+        total_files: files.len(),
+        files_with_issues: files.len(),
+        total_issues: issues.len(),
+        errors,
+        warnings: issues.len() - errors,
+        issues,
+    }
+}
+
+/// Group clippy issues by lint rule, most frequent first
+fn format_clippy_output(lint: &LintResult) -> String {
+    if lint.errors == 0 && lint.warnings == 0 {
         return "✓ cargo clippy: No issues found".to_string();
     }
 
+    let mut by_rule: Vec<(&str, Vec<String>)> = Vec::new();
+    for issue in lint.issues.iter().filter(|i| !i.file_path.is_empty()) {
+        let location = format!("{}:{}:{}", issue.file_path, issue.line, issue.column);
+        match by_rule.iter_mut().find(|(rule, _)| *rule == issue.rule_id) {
+            Some((_, locations)) => locations.push(location),
+            None => by_rule.push((&issue.rule_id, vec![location])),
+        }
+    }
+    by_rule.sort_by_key(|(_, locations)| std::cmp::Reverse(locations.len()));
+
     let mut result = String::new();
     result.push_str(&format!(
         "cargo clippy: {} errors, {} warnings\n",
-        error_count, warning_count
+        lint.errors, lint.warnings
     ));
     result.push_str("═══════════════════════════════════════\n");
 
-    // Sort rules by frequency
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
-
-    for (rule, locations) in rule_counts.iter().take(15) {
+    for (rule, locations) in by_rule.iter().take(15) {
         result.push_str(&format!("  {} ({}x)\n", rule, locations.len()));
         for loc in locations.iter().take(3) {
             result.push_str(&format!("    {}\n", loc));
@@ -922,8 +1506,92 @@ fn filter_cargo_clippy(output: &str) -> String {
     result.trim().to_string()
 }
 
+/// Filter cargo clippy output - group warnings by lint rule
+pub(crate) fn filter_cargo_clippy(output: &str, verbose: u8) -> String {
+    render_parsed(
+        "cargo clippy",
+        "LintResult",
+        CargoClippyParser::parse(output),
+        verbose,
+        format_clippy_output,
+    )
+}
+
+/// Render a parse result, reporting it for `--format json` and flagging degraded tiers
+fn render_parsed<T: Serialize>(
+    tool: &str,
+    kind: &str,
+    result: ParseResult<T>,
+    verbose: u8,
+    format: fn(&T) -> String,
+) -> String {
+    crate::json_output::report_parsed(kind, &result);
+    match result {
+        ParseResult::Full(data) => {
+            if verbose > 0 {
+                eprintln!("{} (Tier 1: Full JSON parse)", tool);
+            }
+            format(&data)
+        }
+        ParseResult::Degraded(data, warnings) => {
+            if verbose > 0 {
+                emit_degradation_warning(tool, &warnings.join(", "));
+            }
+            format(&data)
+        }
+        ParseResult::Passthrough(raw) => {
+            emit_passthrough_warning(tool, "All parsing tiers failed");
+            raw
+        }
+    }
+}
+
+/// Turn cargo/libtest JSON lines back into the text a plain run prints, so
+/// tee captures and token tracking see the equivalent human output.
+fn humanize_json_lines(output: &str) -> String {
+    let mut out = String::new();
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('{') {
+            if let Ok(msg) = serde_json::from_str::<CargoMessage>(trimmed) {
+                if let Some(rendered) = msg.message.and_then(|m| m.rendered) {
+                    out.push_str(rendered.trim_end());
+                    out.push('\n');
+                }
+                continue;
+            }
+            if let Ok(event) = serde_json::from_str::<LibtestEvent>(trimmed) {
+                let name = event.name.as_deref().unwrap_or("");
+                match (event.kind.as_str(), event.event.as_str()) {
+                    ("suite", "started") => out.push_str(&format!(
+                        "\nrunning {} tests\n",
+                        event.test_count.unwrap_or(0)
+                    )),
+                    ("suite", "ok" | "failed") => {
+                        out.push_str(&AggregatedTestResult::from_event(&event).summary_line());
+                        out.push('\n');
+                    }
+                    ("test", "ok") => out.push_str(&format!("test {} ... ok\n", name)),
+                    ("test", "ignored") => out.push_str(&format!("test {} ... ignored\n", name)),
+                    ("test", "failed") => {
+                        out.push_str(&format!("test {} ... FAILED\n", name));
+                        if let Some(stdout) = &event.stdout {
+                            out.push_str(&format!("---- {} stdout ----\n{}\n", name, stdout));
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
 /// Runs an unsupported cargo subcommand by passing it through directly
-pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -940,10 +1608,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
         &format!("rtk cargo {} (passthrough)", args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 #[cfg(test)]
@@ -957,7 +1622,7 @@ mod tests {
    Compiling rtk v0.5.0
     Finished dev [unoptimized + debuginfo] target(s) in 15.23s
 "#;
-        let result = filter_cargo_build(output);
+        let result = filter_cargo_build(output, 0);
         assert!(result.contains("✓ cargo build"));
         assert!(result.contains("3 crates compiled"));
     }
@@ -973,7 +1638,7 @@ error[E0308]: mismatched types
 
 error: aborting due to 1 previous error
 "#;
-        let result = filter_cargo_build(output);
+        let result = filter_cargo_build(output, 0);
         assert!(result.contains("1 errors"));
         assert!(result.contains("E0308"));
         assert!(result.contains("mismatched types"));
@@ -992,7 +1657,7 @@ test utils::tests::test_strip_ansi_simple ... ok
 
 test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         assert!(
             result.contains("✓ cargo test: 15 passed (1 suite, 0.01s)"),
             "Expected compact format, got: {}",
@@ -1019,7 +1684,7 @@ failures:
 
 test result: FAILED. 4 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         assert!(result.contains("FAILURES"));
         assert!(result.contains("test_b"));
         assert!(result.contains("test result:"));
@@ -1049,7 +1714,7 @@ test result: ok. 25 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; fin
 running 32 tests
 test result: ok. 32 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.45s
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         assert!(
             result.contains("✓ cargo test: 137 passed (4 suites, 1.45s)"),
             "Expected aggregated format, got: {}",
@@ -1082,7 +1747,7 @@ test result: FAILED. 14 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out;
 running 10 tests
 test result: ok. 10 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.02s
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         // Should NOT aggregate when there are failures
         assert!(result.contains("FAILURES"), "got: {}", result);
         assert!(result.contains("test_bad"), "got: {}", result);
@@ -1113,7 +1778,7 @@ running 0 tests
 
 test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         assert!(
             result.contains("✓ cargo test: 0 passed (3 suites, 0.00s)"),
             "Expected compact format for zero tests, got: {}",
@@ -1133,7 +1798,7 @@ test result: ok. 45 passed; 0 failed; 3 ignored; 0 measured; 2 filtered out; fin
 running 20 tests
 test result: ok. 18 passed; 0 failed; 2 ignored; 0 measured; 0 filtered out; finished in 0.20s
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         assert!(
             result.contains("✓ cargo test: 63 passed, 5 ignored, 2 filtered out (2 suites, 0.70s)"),
             "Expected compact format with ignored and filtered, got: {}",
@@ -1148,7 +1813,7 @@ test result: ok. 18 passed; 0 failed; 2 ignored; 0 measured; 0 filtered out; fin
 running 15 tests
 test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         assert!(
             result.contains("✓ cargo test: 15 passed (1 suite, 0.01s)"),
             "Expected singular 'suite', got: {}",
@@ -1163,7 +1828,7 @@ test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; fin
 running 15 tests
 test result: MALFORMED LINE WITHOUT PROPER FORMAT
 "#;
-        let result = filter_cargo_test(output);
+        let result = filter_cargo_test(output, 0);
         // Should fallback to original behavior (show line with checkmark)
         assert!(
             result.contains("✓ test result: MALFORMED"),
@@ -1177,7 +1842,7 @@ test result: MALFORMED LINE WITHOUT PROPER FORMAT
         let output = r#"    Checking rtk v0.5.0
     Finished dev [unoptimized + debuginfo] target(s) in 1.53s
 "#;
-        let result = filter_cargo_clippy(output);
+        let result = filter_cargo_clippy(output, 0);
         assert!(result.contains("✓ cargo clippy: No issues found"));
     }
 
@@ -1199,7 +1864,7 @@ warning: this function has too many arguments [clippy::too_many_arguments]
 warning: `rtk` (bin) generated 2 warnings
     Finished dev [unoptimized + debuginfo] target(s) in 1.53s
 "#;
-        let result = filter_cargo_clippy(output);
+        let result = filter_cargo_clippy(output, 0);
         assert!(result.contains("0 errors, 2 warnings"));
         assert!(result.contains("unused_variables"));
         assert!(result.contains("clippy::too_many_arguments"));
@@ -1619,4 +2284,231 @@ error: test run failed
             result
         );
     }
+
+    const CARGO_JSON_BUILD: &str = r#"{"reason":"compiler-artifact","package_id":"libc 0.2.153","fresh":false}
+{"reason":"compiler-artifact","package_id":"rtk 0.5.0","fresh":false}
+{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"mismatched types","level":"error","code":{"code":"E0308","explanation":null},"spans":[{"file_name":"src/main.rs","line_start":10,"column_start":5,"is_primary":true}],"rendered":"error[E0308]: mismatched types\n --> src/main.rs:10:5\n  |\n10|     \"hello\"\n  |     ^^^^^^^ expected `i32`, found `&str`\n\n"}}
+{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"unused variable: `x`","level":"warning","code":{"code":"unused_variables","explanation":null},"spans":[{"file_name":"src/git.rs","line_start":3,"column_start":9,"is_primary":true}],"rendered":"warning: unused variable: `x`\n --> src/git.rs:3:9\n"}}
+{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"aborting due to 1 previous error; 1 warning emitted","level":"error","code":null,"spans":[],"rendered":"error: aborting due to 1 previous error; 1 warning emitted\n\n"}}
+{"reason":"build-finished","success":false}
+   Compiling rtk v0.5.0
+error: could not compile `rtk` (bin "rtk") due to 1 previous error
+"#;
+
+    #[test]
+    fn test_cargo_build_parser_json() {
+        let result = CargoBuildParser::parse(CARGO_JSON_BUILD);
+        assert_eq!(result.tier(), 1);
+        let build = result.unwrap();
+        assert!(!build.success);
+        assert_eq!(build.errors, 1);
+        assert_eq!(build.warnings, 1);
+        assert_eq!(build.compiled_units, 2);
+        assert_eq!(build.diagnostics[0].rule_id, "E0308");
+        assert_eq!(build.diagnostics[0].file_path, "src/main.rs");
+        assert_eq!(build.diagnostics[0].line, 10);
+
+        let output = filter_cargo_build(CARGO_JSON_BUILD, 0);
+        assert!(
+            output.contains("1 errors, 1 warnings (2 crates)"),
+            "got: {}",
+            output
+        );
+        assert!(
+            output.contains("expected `i32`, found `&str`"),
+            "got: {}",
+            output
+        );
+        assert!(!output.contains("aborting"), "got: {}", output);
+        assert!(!output.contains("could not compile"), "got: {}", output);
+    }
+
+    #[test]
+    fn test_cargo_build_parser_text_is_degraded() {
+        let output = "   Compiling rtk v0.5.0\nerror[E0425]: cannot find value `foo`\n --> src/lib.rs:20:9\n";
+        let result = CargoBuildParser::parse(output);
+        assert_eq!(result.tier(), 2);
+        let build = result.unwrap();
+        assert_eq!(build.errors, 1);
+        assert_eq!(build.diagnostics[0].rule_id, "E0425");
+        assert_eq!(build.diagnostics[0].message, "cannot find value `foo`");
+        assert_eq!(build.diagnostics[0].column, 9);
+    }
+
+    #[test]
+    fn test_cargo_build_parser_keeps_cargo_text_errors() {
+        let output = r#"{"reason":"build-finished","success":false}
+error: failed to run custom build command for `openssl-sys v0.9.0`
+  process didn't exit successfully
+  --- stderr
+  could not find openssl
+"#;
+        let result = CargoBuildParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let output = format_build_output(&result.unwrap());
+        assert!(output.contains("1 errors"), "got: {}", output);
+        assert!(output.contains("openssl-sys"), "got: {}", output);
+    }
+
+    #[test]
+    fn test_cargo_clippy_parser_json() {
+        let output = r#"{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"this function has too many arguments (8/7)","level":"warning","code":{"code":"clippy::too_many_arguments","explanation":null},"spans":[{"file_name":"src/git.rs","line_start":16,"column_start":1,"is_primary":true}],"rendered":"warning: this function has too many arguments (8/7)\n"}}
+{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"this function has too many arguments (9/7)","level":"warning","code":{"code":"clippy::too_many_arguments","explanation":null},"spans":[{"file_name":"src/gh.rs","line_start":4,"column_start":1,"is_primary":true}],"rendered":"warning: this function has too many arguments (9/7)\n"}}
+{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"`rtk` (bin) generated 2 warnings","level":"warning","code":null,"spans":[],"rendered":"warning: `rtk` (bin) generated 2 warnings\n"}}
+{"reason":"build-finished","success":true}
+"#;
+        let result = CargoClippyParser::parse(output);
+        assert_eq!(result.tier(), 1);
+        let lint = result.unwrap();
+        assert_eq!(lint.warnings, 2);
+        assert_eq!(lint.files_with_issues, 2);
+
+        let filtered = filter_cargo_clippy(output, 0);
+        assert!(
+            filtered.contains("0 errors, 2 warnings"),
+            "got: {}",
+            filtered
+        );
+        assert!(
+            filtered.contains("clippy::too_many_arguments (2x)"),
+            "got: {}",
+            filtered
+        );
+        assert!(filtered.contains("src/gh.rs:4:1"), "got: {}", filtered);
+    }
+
+    const LIBTEST_JSON: &str = r#"{ "type": "suite", "event": "started", "test_count": 3 }
+{ "type": "test", "event": "started", "name": "tests::a" }
+{ "type": "test", "name": "tests::a", "event": "ok" }
+{ "type": "test", "name": "tests::b", "event": "failed", "stdout": "thread 'tests::b' panicked at src/lib.rs:15:9:\nassertion `left == right` failed\n" }
+{ "type": "test", "name": "tests::c", "event": "ignored" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1, "measured": 0, "filtered_out": 0, "exec_time": 0.012 }
+"#;
+
+    #[test]
+    fn test_cargo_test_parser_libtest_json() {
+        let result = CargoTestParser::parse(LIBTEST_JSON);
+        assert_eq!(result.tier(), 1);
+        let run = result.unwrap();
+        assert_eq!(run.tests.passed, 1);
+        assert_eq!(run.tests.failed, 1);
+        assert_eq!(run.tests.skipped, 1);
+        assert_eq!(run.suites, 1);
+        assert_eq!(run.tests.failures[0].test_name, "tests::b");
+        assert_eq!(run.tests.failures[0].file_path, "src/lib.rs");
+
+        let output = filter_cargo_test(LIBTEST_JSON, 0);
+        assert!(output.contains("FAILURES (1)"), "got: {}", output);
+        assert!(
+            output.contains("assertion `left == right` failed"),
+            "got: {}",
+            output
+        );
+        assert!(
+            output.contains("test result: FAILED. 1 passed; 1 failed; 1 ignored"),
+            "got: {}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_cargo_test_parser_text_is_degraded() {
+        let output = "running 2 tests\ntest result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s\n";
+        let result = CargoTestParser::parse(output);
+        assert_eq!(result.tier(), 2);
+        assert_eq!(result.unwrap().tests.passed, 2);
+    }
+
+    #[test]
+    fn test_cargo_test_build_failure_shows_diagnostics() {
+        let output = r#"{"reason":"compiler-message","package_id":"rtk 0.5.0","message":{"message":"cannot find value `foo`","level":"error","code":{"code":"E0425","explanation":null},"spans":[{"file_name":"src/lib.rs","line_start":20,"column_start":9,"is_primary":true}],"rendered":"error[E0425]: cannot find value `foo`\n --> src/lib.rs:20:9\n"}}
+{"reason":"build-finished","success":false}
+error: could not compile `rtk` (lib test) due to 1 previous error
+"#;
+        let result = filter_cargo_test(output, 0);
+        assert!(
+            result.contains("1 errors (build failed)"),
+            "got: {}",
+            result
+        );
+        assert!(result.contains("E0425"), "got: {}", result);
+    }
+
+    #[test]
+    fn test_structured_args() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            structured_args("build", &args(&["--release"]), false),
+            args(&["--message-format=json", "--release"])
+        );
+        assert_eq!(
+            structured_args("clippy", &args(&["--message-format=short"]), false),
+            args(&["--message-format=short"])
+        );
+        assert_eq!(
+            structured_args("test", &args(&["foo"]), true),
+            args(&[
+                "--message-format=json",
+                "foo",
+                "--",
+                "-Z",
+                "unstable-options",
+                "--format",
+                "json"
+            ])
+        );
+        assert_eq!(
+            structured_args("test", &args(&["--", "--format", "terse"]), true),
+            args(&["--message-format=json", "--", "--format", "terse"])
+        );
+        assert_eq!(
+            structured_args("install", &args(&["rtk"]), false),
+            args(&["rtk"])
+        );
+    }
+
+    #[test]
+    fn test_live_cargo_line() {
+        let error = r#"{"reason":"compiler-message","package_id":"demo","message":{"message":"mismatched types","level":"error","code":{"code":"E0308"},"spans":[{"file_name":"src/main.rs","line_start":4,"column_start":9,"is_primary":true}],"rendered":"error[E0308]: mismatched types"}}"#;
+        assert_eq!(
+            live_cargo_line(error).as_deref(),
+            Some("error[E0308]: mismatched types (src/main.rs:4:9)")
+        );
+        let warning = error.replace(r#""level":"error""#, r#""level":"warning""#);
+        assert!(live_cargo_line(&warning).is_none());
+
+        let failed =
+            r#"{ "type": "test", "name": "tests::bad", "event": "failed", "stdout": "panicked" }"#;
+        assert_eq!(
+            live_cargo_line(failed).as_deref(),
+            Some("test tests::bad ... FAILED")
+        );
+        assert!(
+            live_cargo_line(r#"{ "type": "test", "name": "tests::ok", "event": "ok" }"#).is_none()
+        );
+
+        assert_eq!(
+            live_cargo_line("test tests::bad ... FAILED").as_deref(),
+            Some("test tests::bad ... FAILED")
+        );
+        assert!(live_cargo_line(
+            "error: could not compile `demo` (bin \"demo\") due to 1 previous error"
+        )
+        .is_none());
+        assert!(live_cargo_line("error: test failed, to rerun pass `--bin demo`").is_none());
+        assert!(live_cargo_line("   Compiling demo v0.1.0").is_none());
+    }
+
+    #[test]
+    fn test_humanize_json_lines() {
+        let human = humanize_json_lines(CARGO_JSON_BUILD);
+        assert!(human.contains("error[E0308]: mismatched types"));
+        assert!(!human.contains("\"reason\""));
+        assert!(human.contains("Compiling rtk"));
+
+        let human = humanize_json_lines(LIBTEST_JSON);
+        assert!(human.contains("test tests::b ... FAILED"));
+        assert!(human.contains("test result: FAILED. 1 passed; 1 failed"));
+    }
 }
@@ -0,0 +1,455 @@
+//! Failure reports from `gh run view --log-failed` output.
+//!
+//! gh prints one `job<TAB>step<TAB>timestamp line` row per log line. Rows are
+//! split by job and step, timestamps, ANSI codes and runner preamble
+//! (`##[group]` blocks, runner/image banners) are stripped, and each step's
+//! output goes through the rtk filter of the tool it ran (cargo, pytest, tsc,
+//! go test) when that tool can be detected. Otherwise, or when that filter's
+//! summary shows no failure, the error lines and the tail of the step are kept.
+
+use crate::utils::{strip_ansi, truncate};
+use crate::{cargo_cmd, go_cmd, pytest_cmd, tsc_cmd};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Tail lines kept for a step whose tool isn't recognized
+const FALLBACK_TAIL_LINES: usize = 15;
+const MAX_ERROR_LINES: usize = 20;
+/// gh's step column when it can't map log files to steps
+const UNKNOWN_STEP: &str = "UNKNOWN STEP";
+
+/// Banner lines of the "Set up job" / "Complete job" steps
+const PREAMBLE_PREFIXES: &[&str] = &[
+    "Current runner version",
+    "Runner name:",
+    "Runner group name:",
+    "Machine name:",
+    "Operating System",
+    "Runner Image",
+    "Image:",
+    "Version:",
+    "Included Software:",
+    "Image Release:",
+    "GITHUB_TOKEN Permissions",
+    "Secret source:",
+    "Prepare workflow directory",
+    "Prepare all required actions",
+    "Getting action download info",
+    "Download action repository",
+    "Complete job name:",
+    "Cleaning up orphan processes",
+    "Post job cleanup.",
+    "Temporarily overriding HOME=",
+    "Adding repository directory to the temporary git global config",
+];
+
+lazy_static! {
+    static ref TIMESTAMP: Regex =
+        Regex::new(r"^\x{feff}?\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?Z ?").unwrap();
+    static ref ERROR_LINE: Regex =
+        Regex::new(r"(?i)(^##\[error\]|\berror\b|\bfailed\b|^FAIL\b|panicked at|Traceback)")
+            .unwrap();
+    static ref TSC_ERROR: Regex = Regex::new(r"\(\d+,\d+\): error TS\d+").unwrap();
+    /// Tool names as whole words: `tsc` but not `tsconfig`, `pytest` but not `pytest-cov`
+    static ref TOOL_COMMANDS: Vec<(Regex, Tool)> = [
+        ("cargo nextest", Tool::CargoNextest),
+        ("cargo test", Tool::CargoTest),
+        ("cargo clippy", Tool::CargoClippy),
+        ("cargo build", Tool::CargoBuild),
+        ("cargo check", Tool::CargoBuild),
+        ("pytest", Tool::Pytest),
+        ("tsc", Tool::Tsc),
+        ("go test", Tool::GoTest),
+    ]
+    .into_iter()
+    .map(|(cmd, tool)| {
+        let pattern = format!(r"(?:^|[^\w-]){}(?:$|[^\w-])", regex::escape(cmd));
+        (Regex::new(&pattern).unwrap(), tool)
+    })
+    .collect();
+}
+
+/// Tools whose rtk filter can summarize a step's output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tool {
+    CargoTest,
+    CargoNextest,
+    CargoBuild,
+    CargoClippy,
+    Pytest,
+    Tsc,
+    GoTest,
+}
+
+impl Tool {
+    fn name(self) -> &'static str {
+        match self {
+            Tool::CargoTest => "cargo test",
+            Tool::CargoNextest => "cargo nextest",
+            Tool::CargoBuild => "cargo build",
+            Tool::CargoClippy => "cargo clippy",
+            Tool::Pytest => "pytest",
+            Tool::Tsc => "tsc",
+            Tool::GoTest => "go test",
+        }
+    }
+
+    fn filter(self, output: &str) -> String {
+        match self {
+            Tool::CargoTest => cargo_cmd::filter_cargo_test(output, 0),
+            Tool::CargoNextest => cargo_cmd::filter_cargo_nextest(output),
+            Tool::CargoBuild => cargo_cmd::filter_cargo_build(output, 0),
+            Tool::CargoClippy => cargo_cmd::filter_cargo_clippy(output, 0),
+            Tool::Pytest => pytest_cmd::filter_pytest_output(output),
+            Tool::Tsc => tsc_cmd::filter_tsc_output(output),
+            Tool::GoTest if output.lines().any(|l| l.starts_with("{\"Time\"")) => {
+                go_cmd::filter_go_test_json(output)
+            }
+            Tool::GoTest => go_cmd::filter_go_test_text(output),
+        }
+    }
+}
+
+/// Which tool a step ran, from its command (the `Run ...` line or step
+/// name) first, then from tell-tale output.
+fn detect_tool(command: &str, output: &str) -> Option<Tool> {
+    if let Some((_, tool)) = TOOL_COMMANDS.iter().find(|(re, _)| re.is_match(command)) {
+        return Some(*tool);
+    }
+
+    if output.contains("test result: ") && output.contains("running ") {
+        Some(Tool::CargoTest)
+    } else if output.contains("short test summary info") || output.contains("= FAILURES =") {
+        Some(Tool::Pytest)
+    } else if TSC_ERROR.is_match(output) {
+        Some(Tool::Tsc)
+    } else if output.contains("--- FAIL: ") {
+        Some(Tool::GoTest)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Default)]
+struct Step {
+    name: String,
+    /// Command of the step's `##[group]Run ...` header
+    command: String,
+    output: Vec<String>,
+    /// `##[error]` annotations, e.g. "Process completed with exit code 1."
+    errors: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Job {
+    name: String,
+    steps: Vec<Step>,
+}
+
+fn is_preamble(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    PREAMBLE_PREFIXES.iter().any(|p| trimmed.starts_with(p))
+        || trimmed.starts_with("[command]/usr/bin/git")
+}
+
+/// Split gh's tab-separated log rows into jobs and steps, cleaning lines.
+fn parse_log(raw: &str) -> Vec<Job> {
+    let mut jobs: Vec<Job> = Vec::new();
+    let mut in_group = false;
+
+    for row in raw.lines() {
+        let mut columns = row.splitn(3, '\t');
+        let (Some(job_name), Some(step_name), Some(rest)) =
+            (columns.next(), columns.next(), columns.next())
+        else {
+            continue;
+        };
+        let line = strip_ansi(&TIMESTAMP.replace(rest, ""));
+
+        if jobs.last().is_none_or(|j| j.name != job_name) {
+            jobs.push(Job {
+                name: job_name.to_string(),
+                steps: Vec::new(),
+            });
+            in_group = false;
+        }
+        let job = jobs.last_mut().unwrap();
+
+        let title = line.strip_prefix("##[group]");
+        // gh without step names: each `Run` group starts a new step
+        let starts_step = match job.steps.last() {
+            None => true,
+            Some(_) if step_name == UNKNOWN_STEP => title.is_some_and(|t| t.starts_with("Run ")),
+            Some(step) => step.name != step_name,
+        };
+        if starts_step {
+            job.steps.push(Step {
+                name: step_name.to_string(),
+                ..Step::default()
+            });
+            in_group = false;
+        }
+        let step = job.steps.last_mut().unwrap();
+
+        if let Some(title) = title {
+            if let Some(command) = title.strip_prefix("Run ") {
+                step.command = command.trim().to_string();
+                if step.name == UNKNOWN_STEP {
+                    step.name = truncate(title.trim(), 60);
+                }
+            }
+            in_group = true;
+        } else if line.starts_with("##[endgroup]") {
+            in_group = false;
+        } else if let Some(error) = line.strip_prefix("##[error]") {
+            step.errors.push(error.trim().to_string());
+        } else if !in_group && !line.starts_with("##[") && !is_preamble(&line) {
+            step.output.push(line);
+        }
+    }
+    jobs
+}
+
+/// A tool summary is only trusted when it still names an error or failure;
+/// otherwise the tool was misdetected or its filter missed the real output.
+fn reports_failure(summary: &str) -> bool {
+    let lower = summary.to_lowercase();
+    lower.contains("error") || lower.contains("fail") || lower.contains("panicked")
+}
+
+/// Error lines and the tail of an unrecognized step, in log order.
+fn fallback_summary(output: &[String]) -> String {
+    let tail_start = output.len().saturating_sub(FALLBACK_TAIL_LINES);
+    let mut errors = 0;
+    output
+        .iter()
+        .enumerate()
+        .filter(|(i, line)| {
+            if *i >= tail_start {
+                return true;
+            }
+            let keep = errors < MAX_ERROR_LINES && ERROR_LINE.is_match(line);
+            errors += usize::from(keep);
+            keep
+        })
+        .map(|(_, line)| line)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| truncate(line.trim_end(), 160))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent(text: &str, prefix: &str) -> Vec<String> {
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| format!("{}{}", prefix, l))
+        .collect()
+}
+
+/// One compact failure report per job of `gh run view --log-failed` output.
+pub(crate) fn format_failed_logs(run_id: &str, raw: &str) -> String {
+    let jobs = parse_log(raw);
+    let mut out = vec![format!(
+        "🏃 Workflow Run #{}: {} failed job{}",
+        run_id,
+        jobs.len(),
+        if jobs.len() == 1 { "" } else { "s" }
+    )];
+    if jobs.is_empty() {
+        out.push("  (no failed step logs)".to_string());
+    }
+
+    for job in &jobs {
+        out.push(format!("❌ {}", job.name));
+        // Steps left empty once the preamble is gone
+        let steps = job
+            .steps
+            .iter()
+            .filter(|s| !s.errors.is_empty() || s.output.iter().any(|l| !l.trim().is_empty()));
+        for step in steps {
+            let output = step.output.join("\n");
+            let tool =
+                detect_tool(&step.command, &output).or_else(|| detect_tool(&step.name, &output));
+            let filtered = tool
+                .map(|tool| (tool, tool.filter(&output)))
+                .filter(|(_, summary)| reports_failure(summary));
+            let summary = match filtered {
+                Some((tool, summary)) => {
+                    out.push(format!("  ▸ {} [{}]", step.name, tool.name()));
+                    summary
+                }
+                None => {
+                    out.push(format!("  ▸ {}", step.name));
+                    fallback_summary(&step.output)
+                }
+            };
+            out.extend(indent(&summary, "    "));
+            for error in &step.errors {
+                out.push(format!("    error: {}", error));
+            }
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(job: &str, step: &str, line: &str) -> String {
+        format!("{}\t{}\t2024-05-01T10:00:00.1234567Z {}\n", job, step, line)
+    }
+
+    #[test]
+    fn test_cargo_test_step_uses_cargo_filter() {
+        let job = "test (ubuntu-latest)";
+        let step = "Run cargo test";
+        let mut raw = String::new();
+        for line in [
+            "##[group]Run cargo test --all",
+            "\x1b[36;1mcargo test --all\x1b[0m",
+            "shell: /usr/bin/bash -e {0}",
+            "env:",
+            "  CARGO_TERM_COLOR: always",
+            "##[endgroup]",
+            "   Compiling app v0.1.0",
+            "running 2 tests",
+            "test tests::ok ... ok",
+            "test tests::bad ... FAILED",
+            "",
+            "failures:",
+            "",
+            "---- tests::bad stdout ----",
+            "thread 'tests::bad' panicked at src/lib.rs:9:5:",
+            "assertion failed: false",
+            "",
+            "failures:",
+            "    tests::bad",
+            "",
+            "test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out",
+            "##[error]Process completed with exit code 101.",
+        ] {
+            raw.push_str(&row(job, step, line));
+        }
+
+        let report = format_failed_logs("42", &raw);
+        assert!(report.starts_with("🏃 Workflow Run #42: 1 failed job\n❌ test (ubuntu-latest)"));
+        assert!(
+            report.contains("  ▸ Run cargo test [cargo test]"),
+            "{}",
+            report
+        );
+        assert!(report.contains("tests::bad"), "{}", report);
+        assert!(report.contains("    error: Process completed with exit code 101."));
+        assert!(!report.contains("2024-05-01T"));
+        assert!(!report.contains("CARGO_TERM_COLOR"));
+        assert!(!report.contains("test tests::ok ... ok"));
+    }
+
+    #[test]
+    fn test_unknown_steps_split_on_run_groups() {
+        let job = "lint";
+        let mut raw = String::new();
+        for line in [
+            "Current runner version: '2.316.0'",
+            "Runner Image",
+            "##[group]Run npx tsc --noEmit",
+            "npx tsc --noEmit",
+            "##[endgroup]",
+            "src/a.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+            "##[error]Process completed with exit code 2.",
+            "##[group]Run ./scripts/check.sh",
+            "##[endgroup]",
+            "checking 40 files",
+            "ERROR: missing license header in b.py",
+        ] {
+            raw.push_str(&row(job, UNKNOWN_STEP, line));
+        }
+
+        let report = format_failed_logs("7", &raw);
+        assert!(
+            report.contains("  ▸ Run npx tsc --noEmit [tsc]"),
+            "{}",
+            report
+        );
+        assert!(report.contains("TS2322"), "{}", report);
+        assert!(
+            report.contains("  ▸ Run ./scripts/check.sh\n"),
+            "{}",
+            report
+        );
+        assert!(report.contains("    ERROR: missing license header in b.py"));
+        assert!(!report.contains("runner version"), "{}", report);
+        assert!(!report.contains(UNKNOWN_STEP), "{}", report);
+    }
+
+    #[test]
+    fn test_groups_by_job() {
+        let mut raw = row("build", "Run make", "make: *** [all] Error 2");
+        raw.push_str(&row(
+            "test",
+            "Run pytest",
+            "FAILED tests/test_a.py::test_x - assert 1 == 2",
+        ));
+        raw.push_str(&row(
+            "test",
+            "Run pytest",
+            "===== 1 failed, 3 passed in 0.12s =====",
+        ));
+
+        let report = format_failed_logs("9", &raw);
+        assert!(report.contains("2 failed jobs"));
+        assert!(report.contains("❌ build\n  ▸ Run make\n    make: *** [all] Error 2"));
+        assert!(
+            report.contains("❌ test\n  ▸ Run pytest [pytest]"),
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn test_detect_tool_from_output() {
+        assert_eq!(
+            detect_tool("make test", "running 3 tests\ntest result: ok."),
+            Some(Tool::CargoTest)
+        );
+        assert_eq!(
+            detect_tool("./ci.sh", "--- FAIL: TestX (0.00s)"),
+            Some(Tool::GoTest)
+        );
+        assert_eq!(detect_tool("make", "all good"), None);
+    }
+
+    #[test]
+    fn test_detect_tool_matches_whole_words() {
+        assert_eq!(detect_tool("npx tsc --noEmit", ""), Some(Tool::Tsc));
+        assert_eq!(detect_tool("python -m pytest -q", ""), Some(Tool::Pytest));
+        assert_eq!(detect_tool("./scripts/check-tsconfig.sh", ""), None);
+        assert_eq!(detect_tool("pip install pytest-cov", ""), None);
+        assert_eq!(detect_tool("Run tscheck", ""), None);
+    }
+
+    #[test]
+    fn test_tool_filter_without_failure_falls_back() {
+        let job = "build";
+        let step = "Run npx tsc";
+        let mut raw = String::new();
+        for line in [
+            "##[group]Run npx tsc",
+            "##[endgroup]",
+            "node:internal/modules/cjs/loader:1080",
+            "Error: Cannot find module 'typescript/bin/tsc'",
+            "##[error]Process completed with exit code 1.",
+        ] {
+            raw.push_str(&row(job, step, line));
+        }
+
+        let report = format_failed_logs("7", &raw);
+        assert!(report.contains("  ▸ Run npx tsc\n"), "{}", report);
+        assert!(
+            report.contains("Error: Cannot find module 'typescript/bin/tsc'"),
+            "{}",
+            report
+        );
+    }
+}
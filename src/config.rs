@@ -1,8 +1,29 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// Per-repository config file, discovered by walking up from the CWD.
+const PROJECT_CONFIG_FILE: &str = ".rtk.toml";
+
+/// Keys a project `.rtk.toml` may not set: a cloned repo must not redirect
+/// where rtk writes (or rotates/deletes) files.
+const PROJECT_LOCKED_KEYS: &[&str] = &["tracking.database_path", "tee.directory"];
+
+/// Optional keys absent from the defaults that env vars can still set.
+const OPTIONAL_KEYS: &[&str] = &["tracking.database_path", "tee.directory"];
+
+/// Historical env var names, applied before the generic `RTK_<SECTION>_<KEY>` form.
+const ENV_ALIASES: &[(&str, &str)] = &[
+    ("RTK_DB_PATH", "tracking.database_path"),
+    ("RTK_TEE_DIR", "tee.directory"),
+    ("RTK_TEE", "tee.enabled"),
+    ("RTK_TRACKING", "tracking.enabled"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
     pub tracking: TrackingConfig,
@@ -12,14 +33,23 @@ pub struct Config {
     pub filters: FilterConfig,
     #[serde(default)]
     pub tee: crate::tee::TeeConfig,
+    #[serde(default)]
+    pub dedup: crate::dedup::DedupConfig,
+    #[serde(default)]
+    pub gain: crate::gain::GainConfig,
+    #[serde(default)]
+    pub cargo: crate::cargo_cmd::CargoConfig,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackingConfig {
     pub enabled: bool,
     pub history_days: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_path: Option<PathBuf>,
+    /// Token counter used for savings: heuristic, cl100k, o200k
+    #[serde(default)]
+    pub tokenizer: crate::tokenizer::Tokenizer,
 }
 
 impl Default for TrackingConfig {
@@ -28,11 +58,12 @@ impl Default for TrackingConfig {
             enabled: true,
             history_days: 90,
             database_path: None,
+            tokenizer: crate::tokenizer::Tokenizer::default(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplayConfig {
     pub colors: bool,
     pub emoji: bool,
@@ -49,10 +80,15 @@ impl Default for DisplayConfig {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilterConfig {
     pub ignore_dirs: Vec<String>,
     pub ignore_files: Vec<String>,
+    /// User-defined output filters (`[[filters.rules]]`), see `user_filter`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<crate::user_filter::UserFilter>,
+    /// Re-append error lines a filter dropped, see `preserve`
+    pub preserve_errors: bool,
 }
 
 impl Default for FilterConfig {
@@ -67,21 +103,59 @@ impl Default for FilterConfig {
                 "vendor".into(),
             ],
             ignore_files: vec!["*.lock".into(), "*.min.js".into(), "*.min.css".into()],
+            rules: Vec::new(),
+            preserve_errors: true,
+        }
+    }
+}
+
+/// Where an effective config value came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    Default,
+    Global(PathBuf),
+    Project(PathBuf),
+    Env(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::Global(p) => write!(f, "global: {}", p.display()),
+            ConfigSource::Project(p) => write!(f, "project: {}", p.display()),
+            ConfigSource::Env(var) => write!(f, "env: {}", var),
         }
     }
 }
 
+/// Effective config plus the source of every leaf value (keyed `section.key`).
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: Config,
+    pub sources: BTreeMap<String, ConfigSource>,
+    pub global_path: PathBuf,
+    pub project_path: Option<PathBuf>,
+}
+
 impl Config {
+    /// Load the effective config: defaults < global config.toml < nearest `.rtk.toml` < `RTK_*` env.
     pub fn load() -> Result<Self> {
-        let path = get_config_path()?;
+        Ok(Self::load_layered()?.config)
+    }
+
+    /// The layered config, read and merged once per process so its warnings print once.
+    pub fn load_layered() -> Result<LayeredConfig> {
+        static LAYERED: OnceLock<Result<LayeredConfig, String>> = OnceLock::new();
 
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+        // Unit tests change RTK_* env vars between calls, so they always re-read
+        if cfg!(test) {
+            return read_layered();
         }
+        LAYERED
+            .get_or_init(|| read_layered().map_err(|e| format!("{:#}", e)))
+            .clone()
+            .map_err(anyhow::Error::msg)
     }
 
     pub fn save(&self) -> Result<()> {
@@ -103,25 +177,442 @@ impl Config {
     }
 }
 
+fn read_layered() -> Result<LayeredConfig> {
+    let global_path = get_config_path()?;
+    let project_path = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| find_project_config(&cwd));
+    let global = read_toml_table(&global_path)?;
+    layer_config(global_path, global, project_path, |name| {
+        std::env::var(name).ok()
+    })
+}
+
+/// Merge the layers; a project file that can't be read or merged is reported and
+/// skipped, so a broken `.rtk.toml` never discards the global config.
+fn layer_config(
+    global_path: PathBuf,
+    global: Option<toml::Table>,
+    project_path: Option<PathBuf>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<LayeredConfig> {
+    let global_layer = global.as_ref().map(|t| (global_path.as_path(), t));
+    let project = project_path
+        .as_deref()
+        .and_then(|path| match read_toml_table(path) {
+            Ok(table) => table.map(|t| (path, t)),
+            Err(e) => {
+                eprintln!("rtk: ignoring {:#}", e);
+                None
+            }
+        });
+
+    let merged = match &project {
+        Some((path, table)) => merge_layers(global_layer, Some((path, table)), &env).or_else(|e| {
+            eprintln!("rtk: ignoring {}: {:#}", path.display(), e);
+            merge_layers(global_layer, None, &env)
+        }),
+        None => merge_layers(global_layer, None, &env),
+    };
+    let (config, sources) = merged?;
+    Ok(LayeredConfig {
+        config,
+        sources,
+        global_path,
+        project_path,
+    })
+}
+
 fn get_config_path() -> Result<PathBuf> {
     let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
     Ok(config_dir.join("rtk").join("config.toml"))
 }
 
+/// Walk up from `start` to find the nearest `.rtk.toml`.
+fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|p| p.is_file())
+}
+
+fn read_toml_table(path: &Path) -> Result<Option<toml::Table>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let table: toml::Table =
+        toml::from_str(&content).with_context(|| format!("Invalid TOML in {}", path.display()))?;
+    Ok(Some(table))
+}
+
+/// Build the effective config from defaults, optional global/project tables and env lookups.
+fn merge_layers(
+    global: Option<(&Path, &toml::Table)>,
+    project: Option<(&Path, &toml::Table)>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<(Config, BTreeMap<String, ConfigSource>)> {
+    let mut merged = toml::Table::try_from(Config::default())?;
+    let mut sources = BTreeMap::new();
+    record_sources(&merged, "", &ConfigSource::Default, &mut sources);
+
+    if let Some((path, table)) = global {
+        merge_table(
+            &mut merged,
+            table,
+            "",
+            &ConfigSource::Global(path.to_path_buf()),
+            &mut sources,
+        );
+    }
+
+    if let Some((path, table)) = project {
+        let mut table = table.clone();
+        for key in PROJECT_LOCKED_KEYS {
+            let (section, field) = key.split_once('.').unwrap_or(("", key));
+            if let Some(toml::Value::Table(t)) = table.get_mut(section) {
+                if t.remove(field).is_some() {
+                    eprintln!(
+                        "rtk: ignoring {} in {} (only allowed in global config)",
+                        key,
+                        path.display()
+                    );
+                }
+            }
+        }
+        merge_table(
+            &mut merged,
+            &table,
+            "",
+            &ConfigSource::Project(path.to_path_buf()),
+            &mut sources,
+        );
+    }
+
+    apply_env_overrides(&mut merged, &mut sources, env);
+
+    let config: Config = merged
+        .try_into()
+        .context("Invalid value in merged config")?;
+
+    Ok((config, sources))
+}
+
+/// Deep-merge `overlay` into `base`: tables merge recursively, everything else replaces.
+fn merge_table(
+    base: &mut toml::Table,
+    overlay: &toml::Table,
+    prefix: &str,
+    source: &ConfigSource,
+    sources: &mut BTreeMap<String, ConfigSource>,
+) {
+    for (key, value) in overlay {
+        let path = join_key(prefix, key);
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base_t)), toml::Value::Table(over_t)) => {
+                merge_table(base_t, over_t, &path, source, sources);
+            }
+            _ => {
+                sources.retain(|k, _| k != &path && !k.starts_with(&format!("{}.", path)));
+                match value {
+                    toml::Value::Table(t) => record_sources(t, &path, source, sources),
+                    _ => {
+                        sources.insert(path, source.clone());
+                    }
+                }
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn record_sources(
+    table: &toml::Table,
+    prefix: &str,
+    source: &ConfigSource,
+    sources: &mut BTreeMap<String, ConfigSource>,
+) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            toml::Value::Table(t) => record_sources(t, &path, source, sources),
+            _ => {
+                sources.insert(path, source.clone());
+            }
+        }
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// `tee.max_files` -> `RTK_TEE_MAX_FILES`
+fn env_var_name(key: &str) -> String {
+    format!("RTK_{}", key.replace('.', "_").to_ascii_uppercase())
+}
+
+fn apply_env_overrides(
+    merged: &mut toml::Table,
+    sources: &mut BTreeMap<String, ConfigSource>,
+    env: impl Fn(&str) -> Option<String>,
+) {
+    let mut keys: Vec<String> = sources.keys().cloned().collect();
+    for key in OPTIONAL_KEYS {
+        if !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+
+    let generic = keys
+        .iter()
+        .filter(|key| !is_array_of_tables(merged, key))
+        .map(|key| (env_var_name(key), key.as_str()));
+    let overrides: Vec<(String, &str)> = ENV_ALIASES
+        .iter()
+        .map(|(var, key)| (var.to_string(), *key))
+        .chain(generic)
+        .collect();
+
+    for (var, key) in overrides {
+        let Some(raw) = env(&var) else { continue };
+        match parse_env_value(lookup(merged, key), &raw) {
+            Some(value) => {
+                set_key(merged, key, value);
+                sources.insert(key.to_string(), ConfigSource::Env(var));
+            }
+            None => eprintln!(
+                "rtk: ignoring {}={:?} (invalid value for {})",
+                var, raw, key
+            ),
+        }
+    }
+}
+
+fn lookup<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let (section, field) = key.split_once('.')?;
+    table.get(section)?.as_table()?.get(field)
+}
+
+fn is_array_of_tables(table: &toml::Table, key: &str) -> bool {
+    matches!(lookup(table, key), Some(toml::Value::Array(a)) if a.iter().any(|v| v.is_table()))
+}
+
+fn set_key(table: &mut toml::Table, key: &str, value: toml::Value) {
+    let Some((section, field)) = key.split_once('.') else {
+        return;
+    };
+    let entry = table
+        .entry(section)
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    if let toml::Value::Table(t) = entry {
+        t.insert(field.to_string(), value);
+    }
+}
+
+/// Parse an env value using the type of the value it replaces (missing -> string).
+fn parse_env_value(current: Option<&toml::Value>, raw: &str) -> Option<toml::Value> {
+    match current {
+        Some(toml::Value::Boolean(_)) => {
+            crate::tracking::parse_bool_env(raw).map(toml::Value::Boolean)
+        }
+        Some(toml::Value::Integer(_)) => raw.trim().parse().ok().map(toml::Value::Integer),
+        Some(toml::Value::Array(_)) => Some(toml::Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| toml::Value::String(s.to_string()))
+                .collect(),
+        )),
+        _ => Some(toml::Value::String(raw.to_string())),
+    }
+}
+
 pub fn show_config() -> Result<()> {
-    let path = get_config_path()?;
-    println!("Config: {}", path.display());
-    println!();
+    let layered = Config::load_layered()?;
 
-    if path.exists() {
-        let config = Config::load()?;
-        println!("{}", toml::to_string_pretty(&config)?);
+    let global_state = if layered.global_path.exists() {
+        ""
     } else {
-        println!("(default config, file not created)");
+        " (not created)"
+    };
+    println!(
+        "Global config:  {}{}",
+        layered.global_path.display(),
+        global_state
+    );
+    match &layered.project_path {
+        Some(path) => println!("Project config: {}", path.display()),
+        None => println!("Project config: (no {} found)", PROJECT_CONFIG_FILE),
+    }
+    let env_vars: Vec<&str> = layered
+        .sources
+        .values()
+        .filter_map(|s| match s {
+            ConfigSource::Env(var) => Some(var.as_str()),
+            _ => None,
+        })
+        .collect();
+    if !env_vars.is_empty() {
+        println!("Env overrides:  {}", env_vars.join(", "));
+    }
+
+    let effective = toml::Table::try_from(&layered.config)?;
+    for (section, value) in &effective {
+        let Some(fields) = value.as_table() else {
+            continue;
+        };
         println!();
-        let config = Config::default();
-        println!("{}", toml::to_string_pretty(&config)?);
+        println!("[{}]", section);
+        for (field, value) in fields {
+            let key = join_key(section, field);
+            let rendered = match value {
+                toml::Value::Array(a) if a.iter().any(|v| v.is_table()) => {
+                    format!("{} rule(s)", a.len())
+                }
+                other => other.to_string(),
+            };
+            let source = layered
+                .sources
+                .get(&key)
+                .cloned()
+                .unwrap_or(ConfigSource::Default);
+            println!("{} = {}  # {}", field, rendered, source);
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(src: &str) -> toml::Table {
+        toml::from_str(src).unwrap()
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_defaults_only() {
+        let (config, sources) = merge_layers(None, None, no_env).unwrap();
+        assert_eq!(config.display.max_width, 120);
+        assert_eq!(
+            sources.get("display.max_width"),
+            Some(&ConfigSource::Default)
+        );
+    }
+
+    #[test]
+    fn test_project_overrides_global() {
+        let global = table("[display]\nmax_width = 100\ncolors = false");
+        let project = table("[display]\nmax_width = 200\n[tee]\nmode = \"always\"");
+        let (config, sources) = merge_layers(
+            Some((Path::new("/g.toml"), &global)),
+            Some((Path::new("/repo/.rtk.toml"), &project)),
+            no_env,
+        )
+        .unwrap();
+
+        assert_eq!(config.display.max_width, 200);
+        assert!(!config.display.colors);
+        // Partial [tee] table keeps defaults for unset fields
+        assert_eq!(config.tee.mode, crate::tee::TeeMode::Always);
+        assert_eq!(config.tee.max_files, 20);
+        assert_eq!(
+            sources.get("display.colors"),
+            Some(&ConfigSource::Global(PathBuf::from("/g.toml")))
+        );
+        assert_eq!(
+            sources.get("display.max_width"),
+            Some(&ConfigSource::Project(PathBuf::from("/repo/.rtk.toml")))
+        );
+    }
+
+    #[test]
+    fn test_project_cannot_set_locked_keys() {
+        let project =
+            table("[tee]\ndirectory = \"/tmp/evil\"\n[tracking]\ndatabase_path = \"/x.db\"");
+        let (config, _) =
+            merge_layers(None, Some((Path::new("/repo/.rtk.toml"), &project)), no_env).unwrap();
+        assert!(config.tee.directory.is_none());
+        assert!(config.tracking.database_path.is_none());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let project = table("[filters]\nignore_dirs = [\"a\"]");
+        let env = |name: &str| match name {
+            "RTK_DISPLAY_MAX_WIDTH" => Some("80".to_string()),
+            "RTK_FILTERS_IGNORE_DIRS" => Some("x, y".to_string()),
+            "RTK_TEE" => Some("0".to_string()),
+            "RTK_TEE_DIR" => Some("/tmp/tee".to_string()),
+            _ => None,
+        };
+        let (config, sources) =
+            merge_layers(None, Some((Path::new("/repo/.rtk.toml"), &project)), env).unwrap();
+
+        assert_eq!(config.display.max_width, 80);
+        assert_eq!(config.filters.ignore_dirs, vec!["x", "y"]);
+        assert!(!config.tee.enabled);
+        assert_eq!(config.tee.directory, Some(PathBuf::from("/tmp/tee")));
+        assert_eq!(
+            sources.get("filters.ignore_dirs"),
+            Some(&ConfigSource::Env("RTK_FILTERS_IGNORE_DIRS".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_env_value_ignored() {
+        let env = |name: &str| (name == "RTK_DISPLAY_MAX_WIDTH").then(|| "wide".to_string());
+        let (config, _) = merge_layers(None, None, env).unwrap();
+        assert_eq!(config.display.max_width, 120);
+    }
+
+    #[test]
+    fn test_broken_project_config_keeps_global() {
+        let temp = tempfile::tempdir().unwrap();
+        let project_path = temp.path().join(PROJECT_CONFIG_FILE);
+        let global = table("[display]\nmax_width = 100");
+
+        for broken in [
+            "[display\nmax_width = 200",
+            "[display]\nmax_width = \"wide\"",
+        ] {
+            std::fs::write(&project_path, broken).unwrap();
+            let layered = layer_config(
+                PathBuf::from("/g.toml"),
+                Some(global.clone()),
+                Some(project_path.clone()),
+                no_env,
+            )
+            .unwrap();
+            assert_eq!(layered.config.display.max_width, 100);
+            assert_eq!(
+                layered.sources.get("display.max_width"),
+                Some(&ConfigSource::Global(PathBuf::from("/g.toml")))
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_project_config_walks_up() {
+        let temp = tempfile::tempdir().unwrap();
+        let nested = temp.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(temp.path().join(PROJECT_CONFIG_FILE), "").unwrap();
+
+        assert_eq!(
+            find_project_config(&nested),
+            Some(temp.path().join(PROJECT_CONFIG_FILE))
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Merge/rebase conflict summaries for `rtk git status|rebase|merge|cherry-pick`.
+//!
+//! Detects the in-progress operation from the git directory
+//! (`rebase-merge/`, `rebase-apply/`, `MERGE_HEAD`, ...) and prints every
+//! conflict region of every unmerged file as compact ours/theirs blocks with
+//! line numbers, so conflicts can be resolved without reading whole files.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MAX_REGIONS_PER_FILE: usize = 10;
+const MAX_LINES_PER_SIDE: usize = 15;
+
+/// A merge-like operation stopped in the middle.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Operation {
+    Rebase {
+        step: Option<usize>,
+        total: Option<usize>,
+        /// Branch being rebased
+        branch: Option<String>,
+    },
+    Merge {
+        /// Short hash of the commit being merged in
+        head: String,
+    },
+    CherryPick {
+        head: String,
+    },
+    Revert {
+        head: String,
+    },
+}
+
+impl Operation {
+    /// One-line description: "rebase of feature, step 3/7".
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Operation::Rebase {
+                step,
+                total,
+                branch,
+            } => {
+                let mut text = "rebase".to_string();
+                if let Some(branch) = branch {
+                    text.push_str(&format!(" of {}", branch));
+                }
+                if let (Some(step), Some(total)) = (step, total) {
+                    text.push_str(&format!(", step {}/{}", step, total));
+                }
+                text
+            }
+            Operation::Merge { head } => format!("merge of {}", head),
+            Operation::CherryPick { head } => format!("cherry-pick of {}", head),
+            Operation::Revert { head } => format!("revert of {}", head),
+        }
+    }
+
+    /// The git subcommand to continue or abort with.
+    fn command(&self) -> &'static str {
+        match self {
+            Operation::Rebase { .. } => "rebase",
+            Operation::Merge { .. } => "merge",
+            Operation::CherryPick { .. } => "cherry-pick",
+            Operation::Revert { .. } => "revert",
+        }
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn read_number(path: &Path) -> Option<usize> {
+    read_trimmed(path).and_then(|s| s.parse().ok())
+}
+
+fn short_hash(path: &Path) -> String {
+    read_trimmed(path)
+        .map(|h| h.chars().take(7).collect())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+/// Find the operation in progress from the files git keeps in `git_dir`.
+pub(crate) fn detect_operation(git_dir: &Path) -> Option<Operation> {
+    let merge_dir = git_dir.join("rebase-merge");
+    if merge_dir.is_dir() {
+        return Some(Operation::Rebase {
+            step: read_number(&merge_dir.join("msgnum")),
+            total: read_number(&merge_dir.join("end")),
+            branch: read_trimmed(&merge_dir.join("head-name"))
+                .map(|h| h.trim_start_matches("refs/heads/").to_string()),
+        });
+    }
+    let apply_dir = git_dir.join("rebase-apply");
+    if apply_dir.is_dir() {
+        return Some(Operation::Rebase {
+            step: read_number(&apply_dir.join("next")),
+            total: read_number(&apply_dir.join("last")),
+            branch: read_trimmed(&apply_dir.join("head-name"))
+                .map(|h| h.trim_start_matches("refs/heads/").to_string()),
+        });
+    }
+    if git_dir.join("MERGE_HEAD").is_file() {
+        return Some(Operation::Merge {
+            head: short_hash(&git_dir.join("MERGE_HEAD")),
+        });
+    }
+    if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+        return Some(Operation::CherryPick {
+            head: short_hash(&git_dir.join("CHERRY_PICK_HEAD")),
+        });
+    }
+    if git_dir.join("REVERT_HEAD").is_file() {
+        return Some(Operation::Revert {
+            head: short_hash(&git_dir.join("REVERT_HEAD")),
+        });
+    }
+    None
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block. Line numbers are 1-based.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Region {
+    pub start: usize,
+    pub end: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    /// (line number, text)
+    pub ours: Vec<(usize, String)>,
+    /// Common ancestor, present with `merge.conflictStyle = diff3/zdiff3`
+    pub base: Option<Vec<(usize, String)>>,
+    pub theirs: Vec<(usize, String)>,
+}
+
+#[derive(PartialEq)]
+enum Side {
+    Ours,
+    Base,
+    Theirs,
+}
+
+fn marker_label(line: &str) -> String {
+    line.get(7..).unwrap_or("").trim().to_string()
+}
+
+/// Parse the conflict markers of a file's content.
+pub(crate) fn parse_regions(content: &str) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut current: Option<(Region, Side)> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let number = idx + 1;
+        if line.starts_with("<<<<<<<") {
+            current = Some((
+                Region {
+                    start: number,
+                    ours_label: marker_label(line),
+                    ..Region::default()
+                },
+                Side::Ours,
+            ));
+            continue;
+        }
+        let Some((region, side)) = current.as_mut() else {
+            continue;
+        };
+        if line.starts_with("|||||||") && *side == Side::Ours {
+            region.base = Some(Vec::new());
+            *side = Side::Base;
+        } else if line.starts_with("=======") && *side != Side::Theirs {
+            *side = Side::Theirs;
+        } else if line.starts_with(">>>>>>>") && *side == Side::Theirs {
+            region.end = number;
+            region.theirs_label = marker_label(line);
+            regions.push(current.take().unwrap().0);
+        } else {
+            let entry = (number, line.to_string());
+            match side {
+                Side::Ours => region.ours.push(entry),
+                Side::Base => region.base.get_or_insert_with(Vec::new).push(entry),
+                Side::Theirs => region.theirs.push(entry),
+            }
+        }
+    }
+    regions
+}
+
+fn push_side(out: &mut Vec<String>, marker: &str, label: &str, lines: &[(usize, String)]) {
+    let label = if label.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", label)
+    };
+    if lines.is_empty() {
+        out.push(format!("  {}{} (empty)", marker, label));
+        return;
+    }
+    out.push(format!("  {}{}", marker, label));
+    for (number, text) in lines.iter().take(MAX_LINES_PER_SIDE) {
+        out.push(format!("  {:>5}| {}", number, text));
+    }
+    if lines.len() > MAX_LINES_PER_SIDE {
+        out.push(format!(
+            "       ... +{} lines",
+            lines.len() - MAX_LINES_PER_SIDE
+        ));
+    }
+}
+
+fn squashed(lines: &[(usize, String)]) -> String {
+    lines
+        .iter()
+        .flat_map(|(_, text)| text.chars().filter(|c| !c.is_whitespace()))
+        .collect()
+}
+
+/// Compact ours/theirs view of one file's conflicts.
+pub(crate) fn format_file(path: &str, regions: &[Region]) -> String {
+    let mut out = vec![format!(
+        "⚠️  {} ({} conflict{})",
+        path,
+        regions.len(),
+        if regions.len() == 1 { "" } else { "s" }
+    )];
+    for region in regions.iter().take(MAX_REGIONS_PER_FILE) {
+        let mut header = format!("  @ L{}-{}", region.start, region.end);
+        if squashed(&region.ours) == squashed(&region.theirs) {
+            header.push_str(" (whitespace only)");
+        }
+        out.push(header);
+        push_side(
+            &mut out,
+            "<",
+            &format!("ours {}", region.ours_label),
+            &region.ours,
+        );
+        if let Some(base) = &region.base {
+            push_side(&mut out, "|", "base", base);
+        }
+        push_side(
+            &mut out,
+            ">",
+            &format!("theirs {}", region.theirs_label),
+            &region.theirs,
+        );
+    }
+    if regions.len() > MAX_REGIONS_PER_FILE {
+        out.push(format!(
+            "  ... +{} more conflicts",
+            regions.len() - MAX_REGIONS_PER_FILE
+        ));
+    }
+    out.iter()
+        .map(|line| line.trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn git_output(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Status line, conflicts and next steps of the operation in progress, or
+/// `None` when nothing is in progress and nothing is conflicted.
+pub(crate) fn summarize_in_progress() -> Result<Option<String>> {
+    let dirs = git_output(&["rev-parse", "--absolute-git-dir", "--show-toplevel"])?;
+    let mut dirs = dirs.lines();
+    let git_dir = PathBuf::from(dirs.next().unwrap_or_default());
+    let root = PathBuf::from(dirs.next().unwrap_or_default());
+    let operation = detect_operation(&git_dir);
+
+    let unmerged = git_output(&["diff", "--name-only", "--diff-filter=U"])?;
+    let files: Vec<&str> = unmerged.lines().filter(|l| !l.is_empty()).collect();
+    if operation.is_none() && files.is_empty() {
+        return Ok(None);
+    }
+
+    let mut out = Vec::new();
+    let mut status = match &operation {
+        Some(op) => format!("🔀 {}", op.describe()),
+        None => "🔀 unmerged paths".to_string(),
+    };
+    if files.is_empty() {
+        status.push_str(", no conflicts left");
+    } else {
+        status.push_str(&format!(
+            ", {} file{} conflicted",
+            files.len(),
+            if files.len() == 1 { "" } else { "s" }
+        ));
+    }
+    out.push(status);
+
+    for file in &files {
+        let path = root.join(file);
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let regions = parse_regions(&content);
+                if regions.is_empty() {
+                    // Markers already removed, or a both-added binary
+                    out.push(format!(
+                        "⚠️  {} (no markers left, git add to resolve)",
+                        file
+                    ));
+                } else {
+                    out.push(format_file(file, &regions));
+                }
+            }
+            // Modify/delete conflicts leave one side missing
+            Err(_) => out.push(format!("⚠️  {} (deleted on one side)", file)),
+        }
+    }
+
+    if let Some(op) = &operation {
+        let cmd = op.command();
+        out.push(if files.is_empty() {
+            format!("Next: git {} --continue", cmd)
+        } else {
+            format!(
+                "Next: fix, git add <files>, git {} --continue (or --abort)",
+                cmd
+            )
+        });
+    }
+    Ok(Some(out.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_rebase_merge() {
+        let dir = TempDir::new().unwrap();
+        let rebase = dir.path().join("rebase-merge");
+        fs::create_dir(&rebase).unwrap();
+        fs::write(rebase.join("msgnum"), "3\n").unwrap();
+        fs::write(rebase.join("end"), "7\n").unwrap();
+        fs::write(rebase.join("head-name"), "refs/heads/feature\n").unwrap();
+
+        let op = detect_operation(dir.path()).unwrap();
+        assert_eq!(op.describe(), "rebase of feature, step 3/7");
+    }
+
+    #[test]
+    fn test_detect_merge_and_cherry_pick() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(detect_operation(dir.path()), None);
+
+        fs::write(dir.path().join("CHERRY_PICK_HEAD"), "0123456789abcdef\n").unwrap();
+        assert_eq!(
+            detect_operation(dir.path()).unwrap().describe(),
+            "cherry-pick of 0123456"
+        );
+
+        fs::write(dir.path().join("MERGE_HEAD"), "fedcba9876543210\n").unwrap();
+        assert_eq!(
+            detect_operation(dir.path()),
+            Some(Operation::Merge {
+                head: "fedcba9".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_regions() {
+        let content = "fn main() {
+<<<<<<< HEAD
+    let x = 1;
+=======
+    let x = 2;
+    let y = 3;
+>>>>>>> abc1234 (Change x)
+}
+<<<<<<< HEAD
+a
+||||||| base
+b
+=======
+>>>>>>> abc1234 (Change x)
+";
+        let regions = parse_regions(content);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].start, 2);
+        assert_eq!(regions[0].end, 7);
+        assert_eq!(regions[0].ours, vec![(3, "    let x = 1;".to_string())]);
+        assert_eq!(regions[0].theirs.len(), 2);
+        assert_eq!(regions[0].theirs_label, "abc1234 (Change x)");
+        assert_eq!(regions[0].base, None);
+        assert_eq!(regions[1].base, Some(vec![(12, "b".to_string())]));
+        assert!(regions[1].theirs.is_empty());
+    }
+
+    #[test]
+    fn test_format_file() {
+        let content = "<<<<<<< HEAD
+let x = 1;
+=======
+let  x = 1;
+>>>>>>> feature
+";
+        let regions = parse_regions(content);
+        assert_eq!(
+            format_file("src/a.rs", &regions),
+            "⚠️  src/a.rs (1 conflict)
+  @ L1-5 (whitespace only)
+  < ours HEAD
+      2| let x = 1;
+  > theirs feature
+      4| let  x = 1;"
+        );
+    }
+
+    #[test]
+    fn test_format_file_truncates_long_sides() {
+        let mut content = String::from("<<<<<<< HEAD\n");
+        for i in 0..20 {
+            content.push_str(&format!("ours {}\n", i));
+        }
+        content.push_str("=======\n>>>>>>> other\n");
+        let out = format_file("big.txt", &parse_regions(&content));
+        assert!(out.contains("     16| ours 14"));
+        assert!(!out.contains("ours 15"));
+        assert!(out.contains("... +5 lines"));
+        assert!(out.ends_with("  > theirs other (empty)"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::ffi::OsString;
@@ -13,7 +14,7 @@ pub enum ContainerCmd {
     KubectlLogs,
 }
 
-pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<()> {
+pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<ExecResult> {
     match cmd {
         ContainerCmd::DockerPs => docker_ps(verbose),
         ContainerCmd::DockerImages => docker_images(verbose),
@@ -24,7 +25,7 @@ pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
-fn docker_ps(_verbose: u8) -> Result<()> {
+fn docker_ps(_verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let raw = Command::new("docker")
@@ -41,6 +42,7 @@ fn docker_ps(_verbose: u8) -> Result<()> {
         ])
         .output()
         .context("Failed to run docker ps")?;
+    print_failure_stderr(&output);
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let mut rtk = String::new();
@@ -49,7 +51,7 @@ fn docker_ps(_verbose: u8) -> Result<()> {
         rtk.push_str("🐳 0 containers");
         println!("{}", rtk);
         timer.track("docker ps", "rtk docker ps", &raw, &rtk);
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let count = stdout.lines().count();
@@ -83,10 +85,10 @@ fn docker_ps(_verbose: u8) -> Result<()> {
 
     print!("{}", rtk);
     timer.track("docker ps", "rtk docker ps", &raw, &rtk);
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn docker_images(_verbose: u8) -> Result<()> {
+fn docker_images(_verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let raw = Command::new("docker")
@@ -99,6 +101,7 @@ fn docker_images(_verbose: u8) -> Result<()> {
         .args(["images", "--format", "{{.Repository}}:{{.Tag}}\t{{.Size}}"])
         .output()
         .context("Failed to run docker images")?;
+    print_failure_stderr(&output);
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let lines: Vec<&str> = stdout.lines().collect();
@@ -108,7 +111,7 @@ fn docker_images(_verbose: u8) -> Result<()> {
         rtk.push_str("🐳 0 images");
         println!("{}", rtk);
         timer.track("docker images", "rtk docker images", &raw, &rtk);
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let mut total_size_mb: f64 = 0.0;
@@ -153,16 +156,16 @@ fn docker_images(_verbose: u8) -> Result<()> {
 
     print!("{}", rtk);
     timer.track("docker images", "rtk docker images", &raw, &rtk);
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
+fn docker_logs(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let container = args.first().map(|s| s.as_str()).unwrap_or("");
     if container.is_empty() {
         println!("Usage: rtk docker logs <container>");
-        return Ok(());
+        return Ok(ExecResult::SUCCESS);
     }
 
     let output = Command::new("docker")
@@ -183,10 +186,10 @@ fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
         &raw,
         &rtk,
     );
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
+fn kubectl_pods(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("kubectl");
@@ -196,6 +199,7 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
     }
 
     let output = cmd.output().context("Failed to run kubectl get pods")?;
+    print_failure_stderr(&output);
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let mut rtk = String::new();
 
@@ -205,7 +209,7 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
             rtk.push_str("☸️  No pods found");
             println!("{}", rtk);
             timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
-            return Ok(());
+            return Ok(ExecResult::from_status(&output.status));
         }
     };
 
@@ -214,7 +218,7 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
         rtk.push_str("☸️  No pods found");
         println!("{}", rtk);
         timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let pods = items.unwrap();
@@ -284,10 +288,10 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
 
     print!("{}", rtk);
     timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
+fn kubectl_services(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("kubectl");
@@ -297,6 +301,7 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
     }
 
     let output = cmd.output().context("Failed to run kubectl get services")?;
+    print_failure_stderr(&output);
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let mut rtk = String::new();
 
@@ -306,7 +311,7 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
             rtk.push_str("☸️  No services found");
             println!("{}", rtk);
             timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
-            return Ok(());
+            return Ok(ExecResult::from_status(&output.status));
         }
     };
 
@@ -315,7 +320,7 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
         rtk.push_str("☸️  No services found");
         println!("{}", rtk);
         timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let services = items.unwrap();
@@ -358,16 +363,16 @@ fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
 
     print!("{}", rtk);
     timer.track("kubectl get svc", "rtk kubectl svc", &raw, &rtk);
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
+fn kubectl_logs(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let pod = args.first().map(|s| s.as_str()).unwrap_or("");
     if pod.is_empty() {
         println!("Usage: rtk kubectl logs <pod>");
-        return Ok(());
+        return Ok(ExecResult::SUCCESS);
     }
 
     let mut cmd = Command::new("kubectl");
@@ -377,6 +382,7 @@ fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
     }
 
     let output = cmd.output().context("Failed to run kubectl logs")?;
+    print_failure_stderr(&output);
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
     let analyzed = crate::log_cmd::run_stdin_str(&raw);
     let rtk = format!("☸️  Logs for {}:\n{}", pod, analyzed);
@@ -387,7 +393,7 @@ fn kubectl_logs(args: &[String], _verbose: u8) -> Result<()> {
         &raw,
         &rtk,
     );
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Format `docker compose ps --format` output into compact form.
@@ -505,6 +511,13 @@ pub fn format_compose_build(raw: &str) -> String {
     result.trim_end().to_string()
 }
 
+/// The summaries below only read stdout; keep the child's errors visible.
+fn print_failure_stderr(output: &std::process::Output) {
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
+
 fn compact_ports(ports: &str) -> String {
     if ports.is_empty() {
         return "-".to_string();
@@ -532,7 +545,7 @@ fn compact_ports(ports: &str) -> String {
 }
 
 /// Runs an unsupported docker subcommand by passing it through directly
-pub fn run_docker_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_docker_passthrough(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -549,14 +562,11 @@ pub fn run_docker_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
         &format!("rtk docker {} (passthrough)", args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 /// Run `docker compose ps` with compact output
-pub fn run_compose_ps(verbose: u8) -> Result<()> {
+pub fn run_compose_ps(verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Raw output for token tracking
@@ -568,7 +578,7 @@ pub fn run_compose_ps(verbose: u8) -> Result<()> {
     if !raw_output.status.success() {
         let stderr = String::from_utf8_lossy(&raw_output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(raw_output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&raw_output.status));
     }
     let raw = String::from_utf8_lossy(&raw_output.stdout).to_string();
 
@@ -586,7 +596,7 @@ pub fn run_compose_ps(verbose: u8) -> Result<()> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
     let structured = String::from_utf8_lossy(&output.stdout).to_string();
 
@@ -597,11 +607,11 @@ pub fn run_compose_ps(verbose: u8) -> Result<()> {
     let rtk = format_compose_ps(&structured);
     println!("{}", rtk);
     timer.track("docker compose ps", "rtk docker compose ps", &raw, &rtk);
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Run `docker compose logs` with deduplication
-pub fn run_compose_logs(service: Option<&str>, verbose: u8) -> Result<()> {
+pub fn run_compose_logs(service: Option<&str>, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("docker");
@@ -615,7 +625,7 @@ pub fn run_compose_logs(service: Option<&str>, verbose: u8) -> Result<()> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -635,11 +645,11 @@ pub fn run_compose_logs(service: Option<&str>, verbose: u8) -> Result<()> {
         &raw,
         &rtk,
     );
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Run `docker compose build` with summary output
-pub fn run_compose_build(service: Option<&str>, verbose: u8) -> Result<()> {
+pub fn run_compose_build(service: Option<&str>, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("docker");
@@ -653,7 +663,7 @@ pub fn run_compose_build(service: Option<&str>, verbose: u8) -> Result<()> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -667,17 +677,18 @@ pub fn run_compose_build(service: Option<&str>, verbose: u8) -> Result<()> {
     let rtk = format_compose_build(&raw);
     println!("{}", rtk);
     let svc_label = service.unwrap_or("all");
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("docker compose build {}", svc_label),
         "rtk docker compose build",
         &raw,
         &rtk,
     );
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Runs an unsupported docker compose subcommand by passing it through directly
-pub fn run_compose_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_compose_passthrough(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -695,14 +706,11 @@ pub fn run_compose_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
         &format!("rtk docker compose {} (passthrough)", args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 /// Runs an unsupported kubectl subcommand by passing it through directly
-pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -719,10 +727,7 @@ pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
         &format!("rtk kubectl {} (passthrough)", args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 #[cfg(test)]
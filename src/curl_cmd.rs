@@ -1,10 +1,11 @@
+use crate::exec::ExecResult;
 use crate::json_cmd;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
 use std::process::Command;
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
     let mut cmd = Command::new("curl");
     cmd.arg("-s"); // Silent mode (no progress bar)
@@ -28,7 +29,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
             stderr.trim().to_string()
         };
         eprintln!("FAILED: curl {}", msg);
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let raw = stdout.to_string();
@@ -37,17 +38,18 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let filtered = filter_curl_output(&stdout);
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("curl {}", args.join(" ")),
         &format!("rtk curl {}", args.join(" ")),
         &raw,
         &filtered,
     );
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn filter_curl_output(output: &str) -> String {
+pub(crate) fn filter_curl_output(output: &str) -> String {
     let trimmed = output.trim();
 
     // Try JSON detection: starts with { or [
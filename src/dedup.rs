@@ -0,0 +1,484 @@
+//! Session-level dedup: don't resend output the agent has already seen.
+//!
+//! Opt-in (`[dedup] enabled = true` or `RTK_DEDUP_ENABLED=1`) and keyed by the
+//! agent session id plus the working directory and normalized command. The
+//! command runs as a child rtk (like `--format json`); its filtered stdout is
+//! compared with the last output stored for the same key. Identical output
+//! becomes a one-line `[unchanged since 3m ago, id abc1234]`, a small change is
+//! printed as a line diff with `@@` hunk headers, anything else is printed in
+//! full. The child reports its tracking row through `RTK_DEDUP_REPORT` so the
+//! extra savings are counted. The cache lives next to the tracking database.
+
+use crate::exec::ExecResult;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Env var pointing the child at the file where its tracking row id is reported.
+const REPORT_ENV: &str = "RTK_DEDUP_REPORT";
+
+const DEDUP_DB_FILE: &str = "dedup.db";
+
+/// Diffs are only worth it while they stay small.
+const MAX_DIFF_LINES: usize = 40;
+
+/// Skip the LCS table for huge outputs (lines_old * lines_new).
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    pub enabled: bool,
+    /// Older outputs are resent in full (the agent may no longer have them)
+    pub max_age_minutes: u64,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_minutes: 120,
+        }
+    }
+}
+
+/// Last output stored for a (session, command) key
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    hash: String,
+    content: String,
+    exit_code: i32,
+    timestamp: i64,
+}
+
+/// What the parent prints instead of (or as) the child's output
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Full,
+    Unchanged(String),
+    Diff(String),
+}
+
+/// Whether this invocation should go through the dedup cache.
+pub fn should_wrap(eligible: bool) -> bool {
+    if !eligible || std::env::var_os(REPORT_ENV).is_some() {
+        return false;
+    }
+    if crate::tracking::current_session().is_none() {
+        return false;
+    }
+    crate::config::Config::load()
+        .map(|c| c.dedup.enabled)
+        .unwrap_or(false)
+}
+
+/// Called by the tracker in the child: hand the new row id to the parent.
+pub fn report_row(id: i64) {
+    if let Ok(path) = std::env::var(REPORT_ENV) {
+        let _ = std::fs::write(path, id.to_string());
+    }
+}
+
+/// Re-run this invocation, then print it in full, as unchanged, or as a diff.
+pub fn run_wrapped() -> Result<ExecResult> {
+    let args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    let Some(session) = crate::tracking::current_session() else {
+        anyhow::bail!("dedup requires RTK_SESSION_ID");
+    };
+    let config = crate::config::Config::load()?.dedup;
+
+    let report_file = tempfile::NamedTempFile::new().context("Failed to create report file")?;
+    let exe = std::env::current_exe().context("Failed to locate rtk executable")?;
+    let output = Command::new(exe)
+        .args(&args)
+        .env(REPORT_ENV, report_file.path())
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Failed to run rtk")?;
+
+    let exit_code = crate::exec::exit_code(&output.status);
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let key = command_key(&args);
+
+    let printed = dedup_output(&session, &key, &stdout, exit_code, &config)
+        // The cache is an optimization: never lose output over it
+        .unwrap_or_else(|_| stdout.clone());
+
+    print!("{}", printed);
+    if printed != stdout {
+        record_savings(report_file.path(), &printed);
+    }
+
+    Ok(ExecResult::code(exit_code))
+}
+
+fn dedup_output(
+    session: &str,
+    key: &str,
+    stdout: &str,
+    exit_code: i32,
+    config: &DedupConfig,
+) -> Result<String> {
+    let cache = Cache::open(&db_path()?)?;
+    let now = chrono::Utc::now().timestamp();
+    let max_age = (config.max_age_minutes * 60) as i64;
+    let previous = cache
+        .lookup(session, key)?
+        .filter(|e| now - e.timestamp <= max_age);
+
+    let body = strip_hints(stdout);
+    let outcome = decide(previous.as_ref(), &body, exit_code, now);
+    cache.store(session, key, &body, exit_code, now)?;
+    Ok(match outcome {
+        Outcome::Full => stdout.to_string(),
+        Outcome::Unchanged(text) | Outcome::Diff(text) => text,
+    })
+}
+
+/// Update the child's tracking row with what was actually printed.
+fn record_savings(report: &Path, printed: &str) {
+    let Some(id) = std::fs::read_to_string(report)
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+    else {
+        return;
+    };
+    let tokens = crate::tokenizer::Tokenizer::from_config().count(printed);
+    if let Ok(tracker) = crate::tracking::Tracker::new() {
+        let _ = tracker.apply_dedup(id, tokens);
+    }
+}
+
+/// Cache key: working directory plus the command line with global flags dropped
+fn command_key(args: &[OsString]) -> String {
+    let cwd = std::env::current_dir()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    let command = args
+        .iter()
+        .map(|a| a.to_string_lossy().into_owned())
+        .skip_while(|a| a.starts_with('-'))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{}\0{}",
+        cwd,
+        command.split_whitespace().collect::<Vec<_>>().join(" ")
+    )
+}
+
+/// Tee hints name a fresh capture file on every run; keep them out of the comparison
+fn strip_hints(stdout: &str) -> String {
+    let mut out = String::with_capacity(stdout.len());
+    for line in stdout.lines() {
+        if crate::tee::parse_hint(line).is_none() {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn decide(previous: Option<&CacheEntry>, body: &str, exit_code: i32, now: i64) -> Outcome {
+    let Some(prev) = previous else {
+        return Outcome::Full;
+    };
+    if prev.exit_code != exit_code || body.trim().is_empty() {
+        return Outcome::Full;
+    }
+
+    let age = format_age(now - prev.timestamp);
+    if prev.content == body {
+        return Outcome::Unchanged(format!(
+            "[unchanged since {}, id {}]\n",
+            age,
+            short_id(&prev.hash)
+        ));
+    }
+
+    let old: Vec<&str> = prev.content.lines().collect();
+    let new: Vec<&str> = body.lines().collect();
+    match line_diff(&old, &new) {
+        Some(diff) if diff.len() <= MAX_DIFF_LINES && diff.len() * 2 < new.len() => {
+            let added = diff.iter().filter(|l| l.starts_with('+')).count();
+            let removed = diff.iter().filter(|l| l.starts_with('-')).count();
+            let mut out = format!(
+                "[changed since {}, id {} -> {}: +{} -{}]\n",
+                age,
+                short_id(&prev.hash),
+                short_id(&fingerprint(body)),
+                added,
+                removed
+            );
+            for line in diff {
+                out.push_str(&line);
+                out.push('\n');
+            }
+            Outcome::Diff(out)
+        }
+        _ => Outcome::Full,
+    }
+}
+
+/// Minimal line diff from an LCS table: `@@ -a,b +c,d @@` hunks of `- old` / `+ new`
+fn line_diff(old: &[&str], new: &[&str]) -> Option<Vec<String>> {
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        // Collect one run of changes up to the next common line
+        let (start_old, start_new) = (i, j);
+        let mut lines = Vec::new();
+        while i < n || j < m {
+            if i < n && j < m && old[i] == new[j] {
+                break;
+            }
+            if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                lines.push(format!("- {}", old[i]));
+                i += 1;
+            } else {
+                lines.push(format!("+ {}", new[j]));
+                j += 1;
+            }
+        }
+        diff.push(format!(
+            "@@ -{} +{} @@",
+            hunk_range(start_old, i - start_old),
+            hunk_range(start_new, j - start_new)
+        ));
+        diff.extend(lines);
+    }
+    Some(diff)
+}
+
+/// Unified-diff range: 1-based start, and the line before for empty ranges
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+/// FNV-1a: stable across rtk builds, unlike `DefaultHasher`
+fn fingerprint(content: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in content.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn short_id(hash: &str) -> &str {
+    &hash[..7.min(hash.len())]
+}
+
+fn format_age(secs: i64) -> String {
+    match secs.max(0) {
+        s @ 0..=59 => format!("{}s ago", s),
+        s @ 60..=3599 => format!("{}m ago", s / 60),
+        s => format!("{}h ago", s / 3600),
+    }
+}
+
+/// Next to the tracking database, so `RTK_DB_PATH` / `tracking.database_path` move both
+fn db_path() -> Result<PathBuf> {
+    Ok(crate::tracking::get_db_path()?.with_file_name(DEDUP_DB_FILE))
+}
+
+/// Content-addressed store: outputs by hash, plus the latest hash per key
+struct Cache {
+    conn: Connection,
+}
+
+impl Cache {
+    fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS outputs (
+                hash TEXT PRIMARY KEY,
+                content TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS latest (
+                session_id TEXT NOT NULL,
+                command_key TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                exit_code INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL,
+                PRIMARY KEY (session_id, command_key)
+            );",
+        )?;
+        // Cached outputs can hold anything a command printed
+        crate::tracking::enforce_local_db_permissions(path)?;
+        Ok(Self { conn })
+    }
+
+    fn lookup(&self, session: &str, key: &str) -> Result<Option<CacheEntry>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT l.hash, o.content, l.exit_code, l.timestamp
+                 FROM latest l JOIN outputs o ON o.hash = l.hash
+                 WHERE l.session_id = ?1 AND l.command_key = ?2",
+                params![session, key],
+                |row| {
+                    Ok(CacheEntry {
+                        hash: row.get(0)?,
+                        content: row.get(1)?,
+                        exit_code: row.get(2)?,
+                        timestamp: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn store(
+        &self,
+        session: &str,
+        key: &str,
+        content: &str,
+        exit_code: i32,
+        now: i64,
+    ) -> Result<()> {
+        let hash = fingerprint(content);
+        self.conn.execute(
+            "INSERT OR IGNORE INTO outputs (hash, content) VALUES (?1, ?2)",
+            params![hash, content],
+        )?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO latest (session_id, command_key, hash, exit_code, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session, key, hash, exit_code, now],
+        )?;
+
+        // Sessions are short-lived: drop entries after a day, then orphaned outputs
+        self.conn.execute(
+            "DELETE FROM latest WHERE timestamp < ?1",
+            params![now - 86_400],
+        )?;
+        self.conn.execute(
+            "DELETE FROM outputs WHERE hash NOT IN (SELECT hash FROM latest)",
+            [],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(content: &str, exit_code: i32, timestamp: i64) -> CacheEntry {
+        CacheEntry {
+            hash: fingerprint(content),
+            content: content.to_string(),
+            exit_code,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_decide_unchanged() {
+        let body = "M src/main.rs\n?? notes.txt\n";
+        let prev = entry(body, 0, 1_000);
+        let outcome = decide(Some(&prev), body, 0, 1_180);
+        assert_eq!(
+            outcome,
+            Outcome::Unchanged(format!(
+                "[unchanged since 3m ago, id {}]\n",
+                &fingerprint(body)[..7]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decide_small_change_is_diff() {
+        let old: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let new = old.replace("line 7\n", "line seven\n");
+        let prev = entry(&old, 0, 1_000);
+
+        let Outcome::Diff(text) = decide(Some(&prev), &new, 0, 1_010) else {
+            panic!("expected a diff");
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("[changed since 10s ago, id "));
+        assert!(lines[0].ends_with(": +1 -1]"));
+        assert_eq!(&lines[1..], &["@@ -7 +7 @@", "- line 7", "+ line seven"]);
+    }
+
+    #[test]
+    fn test_decide_full_output() {
+        let prev = entry("a\nb\n", 0, 0);
+        // First run, different exit code, or mostly different output
+        assert_eq!(decide(None, "a\nb\n", 0, 5), Outcome::Full);
+        assert_eq!(decide(Some(&prev), "a\nb\n", 1, 5), Outcome::Full);
+        assert_eq!(decide(Some(&prev), "x\ny\n", 0, 5), Outcome::Full);
+    }
+
+    #[test]
+    fn test_line_diff() {
+        let diff = line_diff(&["a", "b", "c"], &["a", "c", "d"]).unwrap();
+        assert_eq!(diff, vec!["@@ -2 +1,0 @@", "- b", "@@ -3,0 +3 @@", "+ d"]);
+
+        let diff = line_diff(&["a", "b", "c", "x"], &["a", "B", "C", "x"]).unwrap();
+        assert_eq!(diff, vec!["@@ -2,2 +2,2 @@", "- b", "- c", "+ B", "+ C"]);
+        assert!(line_diff(&[], &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_strip_hints() {
+        let out = "ok\n[full output: ~/.local/share/rtk/tee/1_x.log | rtk tee show abc123]\n";
+        assert_eq!(strip_hints(out), "ok\n");
+    }
+
+    #[test]
+    fn test_cache_roundtrip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = Cache::open(&dir.path().join("dedup.db"))?;
+
+        assert!(cache.lookup("s1", "k")?.is_none());
+        cache.store("s1", "k", "hello\n", 0, 100)?;
+        cache.store("s1", "k", "hello again\n", 2, 200)?;
+
+        let hit = cache.lookup("s1", "k")?.unwrap();
+        assert_eq!(hit.content, "hello again\n");
+        assert_eq!((hit.exit_code, hit.timestamp), (2, 200));
+        assert!(cache.lookup("s2", "k")?.is_none());
+
+        // The superseded output is garbage-collected
+        let outputs: i64 = cache
+            .conn
+            .query_row("SELECT COUNT(*) FROM outputs", [], |r| r.get(0))?;
+        assert_eq!(outputs, 1);
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
 pub mod provider;
 pub mod registry;
 mod report;
+mod suggest;
 
 use anyhow::Result;
 use std::collections::HashMap;
 
+use crate::tokenizer::Tokenizer;
 use crate::tracking::sanitize_command_for_tracking;
-use provider::{ClaudeProvider, SessionProvider};
+use provider::SessionQuery;
 use registry::{category_avg_tokens, classify_command, split_command_chain, Classification};
 use report::{DiscoverReport, SupportedEntry, UnsupportedEntry};
 
@@ -25,37 +27,30 @@ struct SupportedBucket {
 struct UnsupportedBucket {
     count: usize,
     example: String,
+    total_output_tokens: usize,
+    // Outputs of unchained occurrences, for --suggest
+    samples: Vec<String>,
 }
 
+/// `suggest` drafts filters for unsupported commands; `apply` also writes them
+/// to the project's `.rtk/filters.toml`.
 pub fn run(
-    project: Option<&str>,
-    all: bool,
-    since_days: u64,
+    query: &SessionQuery,
     limit: usize,
     format: &str,
+    suggest: bool,
+    apply: bool,
     verbose: u8,
 ) -> Result<()> {
-    let provider = ClaudeProvider;
-
-    // Determine project filter
-    let project_filter = if all {
-        None
-    } else if let Some(p) = project {
-        Some(p.to_string())
-    } else {
-        // Default: current working directory
-        let cwd = std::env::current_dir()?;
-        let cwd_str = cwd.to_string_lossy().to_string();
-        let encoded = ClaudeProvider::encode_project_path(&cwd_str);
-        Some(encoded)
-    };
-
-    let sessions = provider.discover_sessions(project_filter.as_deref(), Some(since_days))?;
+    let found = query.discover()?;
+    let session_count: usize = found.iter().map(|f| f.sessions.len()).sum();
 
     if verbose > 0 {
-        eprintln!("Scanning {} session files...", sessions.len());
-        for s in &sessions {
-            eprintln!("  {}", s.display());
+        eprintln!("Scanning {} session files...", session_count);
+        for f in &found {
+            for s in &f.sessions {
+                eprintln!("  [{}] {}", f.provider.name(), s.display());
+            }
         }
     }
 
@@ -64,8 +59,12 @@ pub fn run(
     let mut parse_errors: usize = 0;
     let mut supported_map: HashMap<&'static str, SupportedBucket> = HashMap::new();
     let mut unsupported_map: HashMap<String, UnsupportedBucket> = HashMap::new();
+    let tokenizer = Tokenizer::from_config();
 
-    for session_path in &sessions {
+    let sessions = found
+        .iter()
+        .flat_map(|f| f.sessions.iter().map(move |s| (f.provider.as_ref(), s)));
+    for (provider, session_path) in sessions {
         let extracted = match provider.extract_commands(session_path) {
             Ok(cmds) => cmds,
             Err(e) => {
@@ -79,6 +78,8 @@ pub fn run(
 
         for ext_cmd in &extracted {
             let parts = split_command_chain(&ext_cmd.command);
+            // The output can only be attributed to a command run on its own
+            let chained = parts.len() > 1;
             for part in parts {
                 total_commands += 1;
 
@@ -104,8 +105,8 @@ pub fn run(
 
                         // Estimate tokens for this command
                         let output_tokens = if let Some(len) = ext_cmd.output_len {
-                            // Real: from tool_result content length
-                            len / 4
+                            // Real: from tool_result content
+                            output_tokens(tokenizer, len, ext_cmd.output_content.as_deref())
                         } else {
                             // Fallback: category average
                             let subcmd = extract_subcmd(part);
@@ -130,9 +131,20 @@ pub fn run(
                             UnsupportedBucket {
                                 count: 0,
                                 example: sanitize_command_for_tracking(part),
+                                total_output_tokens: 0,
+                                samples: Vec::new(),
                             }
                         });
                         bucket.count += 1;
+                        bucket.total_output_tokens += ext_cmd.output_len.map_or(0, |len| {
+                            output_tokens(tokenizer, len, ext_cmd.output_content.as_deref())
+                        });
+
+                        if let (false, Some(output)) = (chained, &ext_cmd.output_content) {
+                            if bucket.samples.len() < suggest::MAX_SAMPLES && !output.is_empty() {
+                                bucket.samples.push(output.clone());
+                            }
+                        }
                     }
                     Classification::Ignored => {
                         // Check if it starts with "rtk "
@@ -171,7 +183,23 @@ pub fn run(
         .collect();
 
     // Sort by estimated savings descending
-    supported.sort_by(|a, b| b.estimated_savings_tokens.cmp(&a.estimated_savings_tokens));
+    supported.sort_by_key(|b| std::cmp::Reverse(b.estimated_savings_tokens));
+
+    let suggestions = if suggest || apply {
+        suggest::suggest(
+            unsupported_map
+                .iter()
+                .map(|(base, bucket)| suggest::Candidate {
+                    base_command: base,
+                    count: bucket.count,
+                    total_output_tokens: bucket.total_output_tokens,
+                    samples: &bucket.samples,
+                })
+                .collect(),
+        )
+    } else {
+        Vec::new()
+    };
 
     let mut unsupported: Vec<UnsupportedEntry> = unsupported_map
         .into_iter()
@@ -183,27 +211,65 @@ pub fn run(
         .collect();
 
     // Sort by count descending
-    unsupported.sort_by(|a, b| b.count.cmp(&a.count));
+    unsupported.sort_by_key(|b| std::cmp::Reverse(b.count));
 
     let report = DiscoverReport {
-        sessions_scanned: sessions.len(),
+        sessions_scanned: session_count,
         total_commands,
         already_rtk,
-        since_days,
+        since_days: query.since_days,
         supported,
         unsupported,
         parse_errors,
+        suggestions,
     };
 
     match format {
         "json" => println!("{}", report::format_json(&report)),
-        _ => print!("{}", report::format_text(&report, limit, verbose > 0)),
+        _ => {
+            print!("{}", report::format_text(&report, limit, verbose > 0));
+            if suggest || apply {
+                print!("{}", suggest::format_text(&report.suggestions));
+            }
+        }
+    }
+
+    if apply && !report.suggestions.is_empty() {
+        let rules: Vec<_> = report
+            .suggestions
+            .iter()
+            .map(|s| s.filter.clone())
+            .collect();
+        let (path, added) =
+            crate::user_filter::append_project_filters(&std::env::current_dir()?, &rules)?;
+        // stderr keeps --format json parseable
+        eprintln!(
+            "Added {} filter(s) to {} ({} already present)",
+            added,
+            path.display(),
+            rules.len() - added
+        );
     }
 
     Ok(())
 }
 
 /// Extract the subcommand from a command string (second word).
+/// Tokens in a tool result of `len` bytes. Sessions only keep a preview, so
+/// its count is scaled up to the full length.
+fn output_tokens(tokenizer: Tokenizer, len: usize, preview: Option<&str>) -> usize {
+    let preview = preview.unwrap_or("");
+    if preview.is_empty() {
+        return 0;
+    }
+    let counted = tokenizer.count(preview);
+    if preview.len() >= len {
+        counted
+    } else {
+        (counted as f64 * len as f64 / preview.len() as f64).round() as usize
+    }
+}
+
 fn extract_subcmd(cmd: &str) -> &str {
     let parts: Vec<&str> = cmd.trim().splitn(3, char::is_whitespace).collect();
     if parts.len() >= 2 {
@@ -224,3 +290,27 @@ fn truncate_command(cmd: &str) -> String {
         _ => format!("{} {}", parts[0], parts[1]),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tokens_scales_preview() {
+        let preview = "word ".repeat(200);
+        let full = Tokenizer::Cl100k.count(&preview);
+        assert_eq!(
+            output_tokens(Tokenizer::Cl100k, preview.len(), Some(&preview)),
+            full
+        );
+        assert_eq!(
+            output_tokens(Tokenizer::Cl100k, preview.len() * 3, Some(&preview)),
+            full * 3
+        );
+        assert_eq!(
+            output_tokens(Tokenizer::Heuristic, 400, Some(&"x".repeat(100))),
+            100
+        );
+        assert_eq!(output_tokens(Tokenizer::Cl100k, 0, None), 0);
+    }
+}
@@ -1,4 +1,7 @@
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader};
@@ -6,6 +9,19 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
 
+/// Output kept per command for error detection.
+const PREVIEW_CHARS: usize = 1000;
+
+lazy_static! {
+    /// Failure markers for transcripts that don't record exit codes (Aider, Cursor).
+    static ref FAILURE_RE: Regex = Regex::new(
+        r"(?im)^(error|fatal|usage)\b|command not found|no such file or directory|unrecognized (option|argument)|unexpected argument|unknown (option|flag|command)|exit (code|status):? [1-9]|^traceback \(most recent call last\)|\bFAILED\b"
+    )
+    .unwrap();
+    static ref EXIT_CODE_RE: Regex = Regex::new(r"(?im)^exit code:\s*(-?\d+)").unwrap();
+    static ref AIDER_PROMPT_RE: Regex = Regex::new(r"\(Y\)es/\(N\)o").unwrap();
+}
+
 /// A command extracted from a session file.
 #[derive(Debug)]
 pub struct ExtractedCommand {
@@ -22,8 +38,15 @@ pub struct ExtractedCommand {
     pub sequence_index: usize,
 }
 
-/// Trait for session providers (Claude Code, future: Cursor, Windsurf).
+/// Trait for session providers (one per agent transcript format).
 pub trait SessionProvider {
+    /// Agent name used in messages ("Claude Code", "Codex CLI", ...)
+    fn name(&self) -> &'static str;
+    /// Project filter applied when neither `--project` nor `--all` is given.
+    /// `None` means the provider's sessions are already scoped to the project.
+    fn default_project_filter(&self, cwd: &str) -> Option<String> {
+        Some(cwd.to_string())
+    }
     fn discover_sessions(
         &self,
         project_filter: Option<&str>,
@@ -32,6 +55,129 @@ pub trait SessionProvider {
     fn extract_commands(&self, path: &Path) -> Result<Vec<ExtractedCommand>>;
 }
 
+/// `--provider` for `rtk discover` / `rtk learn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProviderKind {
+    Claude,
+    Codex,
+    Aider,
+    Gemini,
+    Cursor,
+    /// Every provider whose history exists on this machine
+    All,
+}
+
+impl ProviderKind {
+    /// Name used in "No ... sessions found" messages
+    pub fn label(self) -> &'static str {
+        match self {
+            ProviderKind::All => "agent",
+            kind => kind.providers()[0].name(),
+        }
+    }
+
+    pub fn providers(self) -> Vec<Box<dyn SessionProvider>> {
+        match self {
+            ProviderKind::Claude => vec![Box::new(ClaudeProvider)],
+            ProviderKind::Codex => vec![Box::new(CodexProvider)],
+            ProviderKind::Aider => vec![Box::new(AiderProvider)],
+            ProviderKind::Gemini => vec![Box::new(GeminiProvider)],
+            ProviderKind::Cursor => vec![Box::new(CursorProvider)],
+            ProviderKind::All => vec![
+                Box::new(ClaudeProvider),
+                Box::new(CodexProvider),
+                Box::new(AiderProvider),
+                Box::new(GeminiProvider),
+                Box::new(CursorProvider),
+            ],
+        }
+    }
+}
+
+/// Which sessions `rtk discover` / `rtk learn` scan.
+pub struct SessionQuery {
+    pub provider: ProviderKind,
+    pub project: Option<String>,
+    /// Scan all projects instead of the current one
+    pub all_projects: bool,
+    pub since_days: u64,
+}
+
+/// Sessions found by one provider.
+pub struct ProviderSessions {
+    pub provider: Box<dyn SessionProvider>,
+    pub sessions: Vec<PathBuf>,
+}
+
+impl SessionQuery {
+    /// Discover sessions for the selected provider(s). With `--provider all`,
+    /// agents that were never used on this machine are skipped, not errors.
+    pub fn discover(&self) -> Result<Vec<ProviderSessions>> {
+        let cwd = std::env::current_dir()?.to_string_lossy().to_string();
+        let mut found = Vec::new();
+        for provider in self.provider.providers() {
+            let filter = if self.all_projects {
+                None
+            } else if let Some(p) = &self.project {
+                Some(p.clone())
+            } else {
+                provider.default_project_filter(&cwd)
+            };
+            match provider.discover_sessions(filter.as_deref(), Some(self.since_days)) {
+                Ok(sessions) => found.push(ProviderSessions { provider, sessions }),
+                Err(_) if self.provider == ProviderKind::All => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(found)
+    }
+}
+
+fn mtime_cutoff(since_days: Option<u64>) -> Option<SystemTime> {
+    since_days.map(|days| {
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(days * 86400))
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    })
+}
+
+/// Files whose mtime can't be read are kept.
+fn modified_since(path: &Path, cutoff: Option<SystemTime>) -> bool {
+    match (cutoff, fs::metadata(path).and_then(|m| m.modified())) {
+        (Some(cutoff), Ok(mtime)) => mtime >= cutoff,
+        _ => true,
+    }
+}
+
+fn session_id_of(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn extracted(
+    command: String,
+    output: Option<&str>,
+    is_error: bool,
+    session_id: &str,
+    sequence_index: usize,
+) -> ExtractedCommand {
+    ExtractedCommand {
+        command,
+        output_len: output.map(str::len),
+        session_id: session_id.to_string(),
+        output_content: output.map(|o| o.chars().take(PREVIEW_CHARS).collect()),
+        is_error,
+        sequence_index,
+    }
+}
+
+/// Heuristic error flag for transcripts without exit codes.
+fn looks_like_failure(output: &str) -> bool {
+    FAILURE_RE.is_match(output)
+}
+
 pub struct ClaudeProvider;
 
 impl ClaudeProvider {
@@ -56,17 +202,21 @@ impl ClaudeProvider {
 }
 
 impl SessionProvider for ClaudeProvider {
+    fn name(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    fn default_project_filter(&self, cwd: &str) -> Option<String> {
+        Some(Self::encode_project_path(cwd))
+    }
+
     fn discover_sessions(
         &self,
         project_filter: Option<&str>,
         since_days: Option<u64>,
     ) -> Result<Vec<PathBuf>> {
         let projects_dir = Self::projects_dir()?;
-        let cutoff = since_days.map(|days| {
-            SystemTime::now()
-                .checked_sub(Duration::from_secs(days * 86400))
-                .unwrap_or(SystemTime::UNIX_EPOCH)
-        });
+        let cutoff = mtime_cutoff(since_days);
 
         let mut sessions = Vec::new();
 
@@ -100,14 +250,8 @@ impl SessionProvider for ClaudeProvider {
                 }
 
                 // Apply mtime filter
-                if let Some(cutoff_time) = cutoff {
-                    if let Ok(meta) = fs::metadata(file_path) {
-                        if let Ok(mtime) = meta.modified() {
-                            if mtime < cutoff_time {
-                                continue;
-                            }
-                        }
-                    }
+                if !modified_since(file_path, cutoff) {
+                    continue;
                 }
 
                 sessions.push(file_path.to_path_buf());
@@ -122,11 +266,7 @@ impl SessionProvider for ClaudeProvider {
             fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
         let reader = BufReader::new(file);
 
-        let session_id = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let session_id = session_id_of(path);
 
         // First pass: collect all tool_use Bash commands with their IDs and sequence
         // Second pass (same loop): collect tool_result output lengths, content, and error status
@@ -235,6 +375,651 @@ impl SessionProvider for ClaudeProvider {
     }
 }
 
+/// Push a command from a transcript that doesn't record exit codes.
+fn push_inferred(
+    commands: &mut Vec<ExtractedCommand>,
+    command: String,
+    output: Option<&str>,
+    session_id: &str,
+) {
+    let is_error = output.is_some_and(looks_like_failure);
+    let index = commands.len();
+    commands.push(extracted(command, output, is_error, session_id, index));
+}
+
+/// Codex CLI: `$CODEX_HOME/sessions/YYYY/MM/DD/rollout-*.jsonl` (default `~/.codex`).
+pub struct CodexProvider;
+
+/// Codex tool names that run a shell command
+const CODEX_SHELL_TOOLS: &[&str] = &["shell", "container.exec", "exec_command", "shell_command"];
+
+impl CodexProvider {
+    fn sessions_dir() -> Result<PathBuf> {
+        let home = match std::env::var("CODEX_HOME") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => dirs::home_dir()
+                .context("could not determine home directory")?
+                .join(".codex"),
+        };
+        let dir = home.join("sessions");
+        if !dir.exists() {
+            anyhow::bail!("Codex CLI sessions directory not found: {}", dir.display());
+        }
+        Ok(dir)
+    }
+
+    /// Working directory recorded in the rollout's `session_meta` / `turn_context`.
+    fn session_cwd(path: &Path) -> Option<String> {
+        let file = fs::File::open(path).ok()?;
+        BufReader::new(file)
+            .lines()
+            .take(20)
+            .map_while(Result::ok)
+            .find_map(|line| {
+                let entry: Value = serde_json::from_str(&line).ok()?;
+                entry
+                    .pointer("/payload/cwd")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+    }
+
+    /// The script of `["bash", "-lc", script]`, else the argv joined.
+    fn command_from_args(args: &Value) -> Option<String> {
+        if let Some(cmd) = args
+            .get("cmd")
+            .or_else(|| args.get("command"))
+            .and_then(Value::as_str)
+        {
+            return Some(cmd.to_string());
+        }
+        let argv: Vec<&str> = args
+            .get("command")?
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        match argv.as_slice() {
+            [] => None,
+            [shell, "-c" | "-lc", script] if shell.ends_with("sh") => Some(script.to_string()),
+            _ => Some(argv.join(" ")),
+        }
+    }
+
+    /// Output text and error flag of a call. Codex has written both
+    /// `{"output": .., "metadata": {"exit_code": ..}}` and plain
+    /// `Exit code: N\nWall time: ..\nOutput:\n..` text.
+    fn parse_output(raw: &Value) -> (String, bool) {
+        let structured = match raw {
+            Value::String(s) => serde_json::from_str::<Value>(s)
+                .ok()
+                .filter(Value::is_object),
+            Value::Object(_) => Some(raw.clone()),
+            _ => None,
+        };
+        if let Some(obj) = structured {
+            let text = obj.get("output").and_then(Value::as_str).unwrap_or("");
+            let exit_code = obj
+                .pointer("/metadata/exit_code")
+                .and_then(Value::as_i64)
+                .unwrap_or(0);
+            return (text.to_string(), exit_code != 0);
+        }
+
+        let text = raw.as_str().unwrap_or("");
+        let exit_code = EXIT_CODE_RE
+            .captures(text)
+            .and_then(|c| c[1].parse::<i64>().ok())
+            .unwrap_or(0);
+        let body = text
+            .split_once("\nOutput:\n")
+            .map(|(_, body)| body)
+            .unwrap_or(text);
+        (body.to_string(), exit_code != 0)
+    }
+}
+
+impl SessionProvider for CodexProvider {
+    fn name(&self) -> &'static str {
+        "Codex CLI"
+    }
+
+    fn discover_sessions(
+        &self,
+        project_filter: Option<&str>,
+        since_days: Option<u64>,
+    ) -> Result<Vec<PathBuf>> {
+        let sessions_dir = Self::sessions_dir()?;
+        let cutoff = mtime_cutoff(since_days);
+
+        Ok(WalkDir::new(&sessions_dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+            .filter(|p| modified_since(p, cutoff))
+            // Rollouts live in date folders: match the recorded cwd instead
+            .filter(|p| match project_filter {
+                Some(filter) => Self::session_cwd(p).is_some_and(|cwd| cwd.contains(filter)),
+                None => true,
+            })
+            .collect())
+    }
+
+    fn extract_commands(&self, path: &Path) -> Result<Vec<ExtractedCommand>> {
+        let file =
+            fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        let session_id = session_id_of(path);
+
+        let mut calls: Vec<(String, String)> = Vec::new(); // (call_id, command)
+        let mut outputs: HashMap<String, (String, bool)> = HashMap::new(); // (output, is_error)
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            // Pre-filter: only call / call output items matter
+            if !line.contains("call") {
+                continue;
+            }
+            let entry: Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            // Newer rollouts wrap items as {"type":"response_item","payload":{..}}
+            let item = if entry.get("type").and_then(Value::as_str) == Some("response_item") {
+                &entry["payload"]
+            } else {
+                &entry
+            };
+            let call_id = item
+                .get("call_id")
+                .or_else(|| item.get("id"))
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string();
+
+            match item.get("type").and_then(Value::as_str).unwrap_or("") {
+                "function_call" => {
+                    let name = item.get("name").and_then(Value::as_str).unwrap_or("");
+                    if !CODEX_SHELL_TOOLS.contains(&name) {
+                        continue;
+                    }
+                    let args = match item.get("arguments") {
+                        Some(Value::String(s)) => serde_json::from_str(s).unwrap_or(Value::Null),
+                        Some(v) => v.clone(),
+                        None => Value::Null,
+                    };
+                    if let Some(cmd) = Self::command_from_args(&args) {
+                        calls.push((call_id, cmd));
+                    }
+                }
+                "local_shell_call" => {
+                    if let Some(cmd) = item.get("action").and_then(Self::command_from_args) {
+                        calls.push((call_id, cmd));
+                    }
+                }
+                "function_call_output" | "local_shell_call_output" => {
+                    if let Some(output) = item.get("output") {
+                        outputs.insert(call_id, Self::parse_output(output));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(calls
+            .into_iter()
+            .enumerate()
+            .map(|(index, (call_id, command))| {
+                let result = outputs.get(&call_id);
+                extracted(
+                    command,
+                    result.map(|(output, _)| output.as_str()),
+                    result.is_some_and(|(_, is_error)| *is_error),
+                    &session_id,
+                    index,
+                )
+            })
+            .collect())
+    }
+}
+
+/// Aider: `.aider.chat.history.md` at the project root (nearest ancestor of
+/// the current directory), or `$AIDER_CHAT_HISTORY_FILE`. Histories live in
+/// each project, so there is no global index for `--all` to scan.
+pub struct AiderProvider;
+
+const AIDER_HISTORY: &str = ".aider.chat.history.md";
+
+impl AiderProvider {
+    fn history_file() -> Option<PathBuf> {
+        if let Ok(path) = std::env::var("AIDER_CHAT_HISTORY_FILE") {
+            if !path.is_empty() {
+                return Some(PathBuf::from(path)).filter(|p| p.exists());
+            }
+        }
+        let cwd = std::env::current_dir().ok()?;
+        cwd.ancestors()
+            .map(|dir| dir.join(AIDER_HISTORY))
+            .find(|p| p.exists())
+    }
+
+    /// Commands are `#### /run cmd`, `#### /test cmd`, `#### !cmd` (user) or
+    /// `> Running cmd` (accepted suggestion); their output follows as `> ` quotes
+    /// until a confirmation prompt or the next non-quoted line.
+    fn parse_history(content: &str, session_id: &str) -> Vec<ExtractedCommand> {
+        let mut commands = Vec::new();
+        let mut current: Option<(String, Vec<&str>)> = None;
+
+        let mut flush = |current: &mut Option<(String, Vec<&str>)>| {
+            if let Some((cmd, lines)) = current.take() {
+                let output = lines.join("\n");
+                let output = output.trim();
+                let output = (!output.is_empty()).then_some(output);
+                push_inferred(&mut commands, cmd, output, session_id);
+            }
+        };
+
+        for raw in content.lines() {
+            let line = raw.trim_end();
+            if let Some(input) = line.strip_prefix("#### ") {
+                flush(&mut current);
+                let cmd = input
+                    .strip_prefix("/run ")
+                    .or_else(|| input.strip_prefix("/test "))
+                    .or_else(|| input.strip_prefix('!'));
+                if let Some(cmd) = cmd.map(str::trim).filter(|c| !c.is_empty()) {
+                    current = Some((cmd.to_string(), Vec::new()));
+                }
+            } else if let Some(cmd) = line.strip_prefix("> Running ") {
+                flush(&mut current);
+                current = Some((cmd.trim().to_string(), Vec::new()));
+            } else if let Some(quoted) = line.strip_prefix('>') {
+                let text = quoted.strip_prefix(' ').unwrap_or(quoted);
+                if AIDER_PROMPT_RE.is_match(text) {
+                    flush(&mut current);
+                } else if let Some((_, output)) = current.as_mut() {
+                    output.push(text);
+                }
+            } else if !line.is_empty() {
+                flush(&mut current);
+            }
+        }
+        flush(&mut current);
+
+        commands
+    }
+}
+
+impl SessionProvider for AiderProvider {
+    fn name(&self) -> &'static str {
+        "Aider"
+    }
+
+    fn default_project_filter(&self, _cwd: &str) -> Option<String> {
+        None
+    }
+
+    fn discover_sessions(
+        &self,
+        project_filter: Option<&str>,
+        since_days: Option<u64>,
+    ) -> Result<Vec<PathBuf>> {
+        let cutoff = mtime_cutoff(since_days);
+        Ok(Self::history_file()
+            .into_iter()
+            .filter(|p| project_filter.is_none_or(|f| p.to_string_lossy().contains(f)))
+            .filter(|p| modified_since(p, cutoff))
+            .collect())
+    }
+
+    fn extract_commands(&self, path: &Path) -> Result<Vec<ExtractedCommand>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let session_id = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("aider")
+            .to_string();
+        Ok(Self::parse_history(&content, &session_id))
+    }
+}
+
+/// Gemini CLI: chat recordings and `/chat save` checkpoints (JSON) under
+/// `~/.gemini/tmp/<project hash>/`. The hash isn't derivable here, so the
+/// default scope is every project; `--project` matches the hash directory
+/// or the project root Gemini recorded next to it.
+pub struct GeminiProvider;
+
+const GEMINI_SHELL_TOOL: &str = "run_shell_command";
+
+/// Metadata lines `run_shell_command` adds around the output
+const GEMINI_META_PREFIXES: &[&str] = &[
+    "Command: ",
+    "Directory: ",
+    "Signal: ",
+    "Background PIDs: ",
+    "Process Group PGID: ",
+];
+
+impl GeminiProvider {
+    fn tmp_dir() -> Result<PathBuf> {
+        let home = dirs::home_dir().context("could not determine home directory")?;
+        let dir = home.join(".gemini").join("tmp");
+        if !dir.exists() {
+            anyhow::bail!("Gemini CLI directory not found: {}", dir.display());
+        }
+        Ok(dir)
+    }
+
+    fn matches_project(dir: &Path, filter: &str) -> bool {
+        dir.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.contains(filter))
+            || fs::read_to_string(dir.join(".project_root")).is_ok_and(|root| root.contains(filter))
+    }
+
+    /// Drop the metadata lines and read the exit code / error fields.
+    fn parse_output(text: &str) -> (String, bool) {
+        let mut failed = false;
+        let mut body = Vec::new();
+        for line in text.lines() {
+            if let Some(code) = line.strip_prefix("Exit Code: ") {
+                failed |= !matches!(code.trim(), "0" | "(none)");
+                continue;
+            }
+            if let Some(error) = line.strip_prefix("Error: ") {
+                if error.trim() == "(none)" {
+                    continue;
+                }
+                failed = true;
+            }
+            if GEMINI_META_PREFIXES.iter().any(|p| line.starts_with(p))
+                || line.ends_with(": (empty)")
+            {
+                continue;
+            }
+            let line = line
+                .strip_prefix("Output: ")
+                .or_else(|| line.strip_prefix("Stdout: "))
+                .or_else(|| line.strip_prefix("Stderr: "))
+                .unwrap_or(line);
+            body.push(line);
+        }
+        (body.join("\n"), failed)
+    }
+
+    /// `functionResponse.response` is `{"output": ..}` or `{"error": ..}`.
+    fn response_text(response: &Value) -> Option<&str> {
+        response
+            .get("output")
+            .or_else(|| response.get("error"))
+            .and_then(Value::as_str)
+    }
+
+    fn parse_session(root: &Value) -> Vec<(String, Option<(String, bool)>)> {
+        let mut calls: Vec<(String, Option<(String, bool)>)> = Vec::new();
+
+        if let Some(messages) = root.get("messages").and_then(Value::as_array) {
+            // Chat recording: each tool call carries its own result
+            for call in messages
+                .iter()
+                .filter_map(|m| m.get("toolCalls")?.as_array())
+                .flatten()
+            {
+                if call.get("name").and_then(Value::as_str) != Some(GEMINI_SHELL_TOOL) {
+                    continue;
+                }
+                let Some(cmd) = call.pointer("/args/command").and_then(Value::as_str) else {
+                    continue;
+                };
+                let status_error = call.get("status").and_then(Value::as_str) == Some("error");
+                let output = call
+                    .get("result")
+                    .and_then(Value::as_array)
+                    .and_then(|parts| {
+                        parts.iter().find_map(|p| {
+                            Self::response_text(p.pointer("/functionResponse/response")?)
+                        })
+                    })
+                    .or_else(|| call.get("resultDisplay").and_then(Value::as_str));
+                let result = output.map(|o| {
+                    let (text, failed) = Self::parse_output(o);
+                    (text, failed || status_error)
+                });
+                calls.push((cmd.to_string(), result));
+            }
+        } else if let Some(contents) = root.as_array() {
+            // Checkpoint: Gemini API `Content[]`; responses pair by id, else in order
+            let mut pending: Vec<(Option<&str>, usize)> = Vec::new();
+            for part in contents
+                .iter()
+                .filter_map(|c| c.get("parts")?.as_array())
+                .flatten()
+            {
+                if let Some(call) = part.get("functionCall") {
+                    if call.get("name").and_then(Value::as_str) != Some(GEMINI_SHELL_TOOL) {
+                        continue;
+                    }
+                    if let Some(cmd) = call.pointer("/args/command").and_then(Value::as_str) {
+                        pending.push((call.get("id").and_then(Value::as_str), calls.len()));
+                        calls.push((cmd.to_string(), None));
+                    }
+                } else if let Some(response) = part.get("functionResponse") {
+                    if response.get("name").and_then(Value::as_str) != Some(GEMINI_SHELL_TOOL) {
+                        continue;
+                    }
+                    let position = match response.get("id").and_then(Value::as_str) {
+                        Some(id) => pending.iter().position(|(pid, _)| *pid == Some(id)),
+                        None => (!pending.is_empty()).then_some(0),
+                    };
+                    if let Some(position) = position {
+                        let (_, index) = pending.remove(position);
+                        calls[index].1 = response
+                            .get("response")
+                            .and_then(Self::response_text)
+                            .map(Self::parse_output);
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+}
+
+impl SessionProvider for GeminiProvider {
+    fn name(&self) -> &'static str {
+        "Gemini CLI"
+    }
+
+    fn default_project_filter(&self, _cwd: &str) -> Option<String> {
+        None
+    }
+
+    fn discover_sessions(
+        &self,
+        project_filter: Option<&str>,
+        since_days: Option<u64>,
+    ) -> Result<Vec<PathBuf>> {
+        let tmp_dir = Self::tmp_dir()?;
+        let cutoff = mtime_cutoff(since_days);
+        let mut sessions = Vec::new();
+
+        let entries = fs::read_dir(&tmp_dir)
+            .with_context(|| format!("failed to read {}", tmp_dir.display()))?;
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            if !dir.is_dir() || project_filter.is_some_and(|f| !Self::matches_project(&dir, f)) {
+                continue;
+            }
+            sessions.extend(
+                WalkDir::new(&dir)
+                    .follow_links(false)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.into_path())
+                    .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+                    // logs.json holds user prompts only
+                    .filter(|p| p.file_name().and_then(|n| n.to_str()) != Some("logs.json"))
+                    .filter(|p| modified_since(p, cutoff)),
+            );
+        }
+
+        Ok(sessions)
+    }
+
+    fn extract_commands(&self, path: &Path) -> Result<Vec<ExtractedCommand>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let root: Value = serde_json::from_str(&content)
+            .with_context(|| format!("invalid JSON in {}", path.display()))?;
+        let session_id = session_id_of(path);
+
+        Ok(Self::parse_session(&root)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (command, result))| {
+                extracted(
+                    command,
+                    result.as_ref().map(|(output, _)| output.as_str()),
+                    result.as_ref().is_some_and(|(_, is_error)| *is_error),
+                    &session_id,
+                    index,
+                )
+            })
+            .collect())
+    }
+}
+
+/// Cursor: chats saved with "Export Chat" (Markdown), looked up in the current
+/// directory and `$RTK_CURSOR_EXPORT_DIR` (default: the Downloads folder).
+/// Exports aren't tied to a project, so all of them are scanned by default.
+pub struct CursorProvider;
+
+const CURSOR_SHELL_LANGS: &[&str] = &["bash", "sh", "shell", "shellscript", "zsh", "console"];
+const CURSOR_OUTPUT_LANGS: &[&str] = &["", "text", "plaintext", "txt", "output", "log"];
+
+impl CursorProvider {
+    fn export_dirs() -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = std::env::current_dir().into_iter().collect();
+        match std::env::var("RTK_CURSOR_EXPORT_DIR") {
+            Ok(dir) if !dir.is_empty() => found.push(PathBuf::from(dir)),
+            _ => found.extend(dirs::download_dir()),
+        }
+        found
+    }
+
+    /// Exports start with `_Exported on <date> from Cursor (<version>)_`.
+    fn is_export(path: &Path) -> bool {
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            return false;
+        }
+        fs::File::open(path).is_ok_and(|file| {
+            BufReader::new(file)
+                .lines()
+                .take(5)
+                .map_while(Result::ok)
+                .any(|line| line.contains("from Cursor"))
+        })
+    }
+
+    /// In `**Cursor**` turns, a shell-fenced block is a command and a plain
+    /// block right after it is that command's output.
+    fn parse_export(content: &str, session_id: &str) -> Vec<ExtractedCommand> {
+        let mut commands = Vec::new();
+        let mut agent_turn = false;
+        let mut last: Option<(String, Option<String>)> = None;
+        // Prose since the last shell block: a later plain block isn't its output
+        let mut gap = false;
+
+        let mut flush = |last: &mut Option<(String, Option<String>)>| {
+            if let Some((cmd, output)) = last.take() {
+                push_inferred(&mut commands, cmd, output.as_deref(), session_id);
+            }
+        };
+
+        let mut lines = content.lines();
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            match trimmed {
+                "**User**" | "**Cursor**" => {
+                    flush(&mut last);
+                    agent_turn = trimmed == "**Cursor**";
+                    continue;
+                }
+                _ => {}
+            }
+
+            let Some(lang) = trimmed.strip_prefix("```") else {
+                if !trimmed.is_empty() {
+                    gap = true;
+                }
+                continue;
+            };
+            let lang = lang.trim().to_lowercase();
+            let body: Vec<&str> = lines
+                .by_ref()
+                .take_while(|l| !l.trim_start().starts_with("```"))
+                .collect();
+            if !agent_turn {
+                continue;
+            }
+
+            if CURSOR_SHELL_LANGS.contains(&lang.as_str()) {
+                flush(&mut last);
+                let cmd = body.join("\n").trim().to_string();
+                if !cmd.is_empty() {
+                    last = Some((cmd, None));
+                }
+                gap = false;
+            } else if CURSOR_OUTPUT_LANGS.contains(&lang.as_str()) && !gap {
+                if let Some((_, output @ None)) = last.as_mut() {
+                    *output = Some(body.join("\n"));
+                }
+            }
+        }
+        flush(&mut last);
+
+        commands
+    }
+}
+
+impl SessionProvider for CursorProvider {
+    fn name(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn default_project_filter(&self, _cwd: &str) -> Option<String> {
+        None
+    }
+
+    fn discover_sessions(
+        &self,
+        project_filter: Option<&str>,
+        since_days: Option<u64>,
+    ) -> Result<Vec<PathBuf>> {
+        let cutoff = mtime_cutoff(since_days);
+        let mut sessions: Vec<PathBuf> = Self::export_dirs()
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.path()))
+            .filter(|p| project_filter.is_none_or(|f| p.to_string_lossy().contains(f)))
+            .filter(|p| modified_since(p, cutoff) && Self::is_export(p))
+            .collect();
+        sessions.sort();
+        sessions.dedup();
+        Ok(sessions)
+    }
+
+    fn extract_commands(&self, path: &Path) -> Result<Vec<ExtractedCommand>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Ok(Self::parse_export(&content, &session_id_of(path)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,4 +1171,138 @@ mod tests {
         assert_eq!(cmds[1].command, "second");
         assert_eq!(cmds[2].command, "third");
     }
+
+    #[test]
+    fn test_codex_extract_rollout() {
+        let jsonl = make_jsonl(&[
+            r#"{"type":"session_meta","payload":{"id":"s1","cwd":"/home/me/rtk"}}"#,
+            r#"{"type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"bash\",\"-lc\",\"git commit --ammend\"]}","call_id":"call_1"}}"#,
+            r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"{\"output\":\"error: unexpected argument '--ammend'\",\"metadata\":{\"exit_code\":2}}"}}"#,
+            r#"{"type":"response_item","payload":{"type":"function_call","name":"exec_command","arguments":"{\"cmd\":\"git status\"}","call_id":"call_2"}}"#,
+            r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_2","output":"Exit code: 0\nWall time: 0.1 seconds\nOutput:\nOn branch main"}}"#,
+        ]);
+
+        assert_eq!(
+            CodexProvider::session_cwd(jsonl.path()).as_deref(),
+            Some("/home/me/rtk")
+        );
+        let cmds = CodexProvider.extract_commands(jsonl.path()).unwrap();
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[0].command, "git commit --ammend");
+        assert!(cmds[0].is_error);
+        assert_eq!(
+            cmds[0].output_content.as_deref(),
+            Some("error: unexpected argument '--ammend'")
+        );
+        assert_eq!(cmds[1].command, "git status");
+        assert!(!cmds[1].is_error);
+        assert_eq!(cmds[1].output_content.as_deref(), Some("On branch main"));
+    }
+
+    #[test]
+    fn test_codex_command_from_argv() {
+        let args = serde_json::json!({"command": ["ls", "-la", "src"]});
+        assert_eq!(
+            CodexProvider::command_from_args(&args).as_deref(),
+            Some("ls -la src")
+        );
+        assert_eq!(CodexProvider::command_from_args(&Value::Null), None);
+    }
+
+    #[test]
+    fn test_aider_parse_history() {
+        let history = "# aider chat started at 2025-01-01 10:00:00\n\n\
+#### /run cargo tset\n\
+> error: no such command: `tset`  \n\
+> Add command output to the chat? (Y)es/(N)o [Yes]: n\n\n\
+#### fix the build\n\n\
+Run the tests:\n\n\
+> Run shell command? (Y)es/(N)o [Yes]: y\n\
+> Running cargo test  \n\
+> test result: ok. 3 passed\n\n\
+#### thanks\n";
+
+        let cmds = AiderProvider::parse_history(history, "proj");
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[0].command, "cargo tset");
+        assert!(cmds[0].is_error);
+        assert_eq!(
+            cmds[0].output_content.as_deref(),
+            Some("error: no such command: `tset`")
+        );
+        assert_eq!(cmds[1].command, "cargo test");
+        assert!(!cmds[1].is_error);
+        assert_eq!(cmds[1].sequence_index, 1);
+    }
+
+    #[test]
+    fn test_gemini_chat_recording() {
+        let root = serde_json::json!({
+            "sessionId": "abc",
+            "messages": [
+                {"type": "user", "content": "check status"},
+                {"type": "gemini", "toolCalls": [
+                    {"id": "t1", "name": "run_shell_command", "args": {"command": "git sttus"},
+                     "status": "success",
+                     "result": [{"functionResponse": {"id": "t1", "name": "run_shell_command",
+                        "response": {"output": "Command: git sttus\nDirectory: (root)\nOutput: git: 'sttus' is not a git command.\nError: (none)\nExit Code: 1\nSignal: (none)"}}}]},
+                    {"id": "t2", "name": "read_file", "args": {"path": "a.rs"}}
+                ]}
+            ]
+        });
+
+        let calls = GeminiProvider::parse_session(&root);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "git sttus");
+        let (output, failed) = calls[0].1.as_ref().unwrap();
+        assert_eq!(output, "git: 'sttus' is not a git command.");
+        assert!(failed);
+    }
+
+    #[test]
+    fn test_gemini_checkpoint_contents() {
+        let root = serde_json::json!([
+            {"role": "user", "parts": [{"text": "list files"}]},
+            {"role": "model", "parts": [{"functionCall": {"name": "run_shell_command", "args": {"command": "ls"}}}]},
+            {"role": "user", "parts": [{"functionResponse": {"name": "run_shell_command",
+                "response": {"output": "Command: ls\nStdout: Cargo.toml\nStderr: (empty)\nError: (none)\nExit Code: 0"}}}]}
+        ]);
+
+        let calls = GeminiProvider::parse_session(&root);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "ls");
+        assert_eq!(calls[0].1, Some(("Cargo.toml".to_string(), false)));
+    }
+
+    #[test]
+    fn test_cursor_parse_export() {
+        let export =
+            "# Fix tests\n_Exported on 10/1/2025 at 10:00:00 GMT from Cursor (1.7.0)_\n\n---\n\n\
+**User**\n\n```bash\nnot a command\n```\n\n---\n\n\
+**Cursor**\n\nLet me run them.\n\n```shellscript\nnpm tset\n```\n\n\
+```\nnpm error Unknown command: \"tset\"\n```\n\n\
+```bash\nnpm test\n```\n\nAll good now.\n\n```\nunrelated block\n```\n";
+
+        let cmds = CursorProvider::parse_export(export, "fix-tests");
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[0].command, "npm tset");
+        assert!(cmds[0].is_error);
+        assert_eq!(cmds[1].command, "npm test");
+        assert_eq!(cmds[1].output_content, None);
+    }
+
+    #[test]
+    fn test_provider_kind_all() {
+        let names: Vec<_> = ProviderKind::All
+            .providers()
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(
+            names,
+            ["Claude Code", "Codex CLI", "Aider", "Gemini CLI", "Cursor"]
+        );
+        assert_eq!(ProviderKind::Codex.label(), "Codex CLI");
+        assert_eq!(ProviderKind::All.label(), "agent");
+    }
 }
@@ -52,17 +52,17 @@ pub fn category_avg_tokens(category: &str, subcmd: &str) -> usize {
 
 // Patterns ordered to match RTK_RULES indices exactly.
 const PATTERNS: &[&str] = &[
-    r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree)",
-    r"^gh\s+(pr|issue|run|repo|api)",
-    r"^cargo\s+(build|test|clippy|check|fmt)",
+    r"^git\s+(status|log|diff|show|add|commit|push|pull|branch|fetch|stash|worktree|rebase|merge|cherry-pick|blame)",
+    r"^gh\s+(pr|issue|run|repo|api|release)",
+    r"^cargo\s+(build|test|clippy|check|fmt|install|nextest)",
     r"^pnpm\s+(list|ls|outdated|install)",
-    r"^npm\s+(run|exec)",
+    r"^npm\s+(run|exec|test)",
     r"^npx\s+",
     r"^(cat|head|tail)\s+",
     r"^(rg|grep)\s+",
     r"^ls(\s|$)",
     r"^find\s+",
-    r"^(npx\s+|pnpm\s+)?tsc(\s|$)",
+    r"^(npx\s+|pnpm\s+)?(?:vue-)?tsc(\s|$)",
     r"^(npx\s+|pnpm\s+)?(eslint|biome|lint)(\s|$)",
     r"^(npx\s+|pnpm\s+)?prettier",
     r"^(npx\s+|pnpm\s+)?next\s+build",
@@ -80,7 +80,10 @@ const PATTERNS: &[&str] = &[
     r"^python(3)?(\s|$)",
     r"^(python(3)?\s+-m\s+)?pytest(\s|$)",
     r"^(python(3)?\s+-m\s+)?ruff(\s|$)",
-    r"^pip\s+(list|outdated|install|show)(\s|$)",
+    r"^(?:uv\s+)?pip\s+(list|outdated|install|show)(\s|$)",
+    r"^tree(\s|$)",
+    r"^go\s+(test|build|vet)(\s|$)",
+    r"^golangci-lint(\s|$)",
 ];
 
 const RULES: &[RtkRule] = &[
@@ -292,6 +295,27 @@ const RULES: &[RtkRule] = &[
         subcmd_savings: &[],
         subcmd_status: &[],
     },
+    RtkRule {
+        rtk_cmd: "rtk tree",
+        category: "Files",
+        savings_pct: 60.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk go",
+        category: "Build",
+        savings_pct: 80.0,
+        subcmd_savings: &[("test", 90.0)],
+        subcmd_status: &[],
+    },
+    RtkRule {
+        rtk_cmd: "rtk golangci-lint",
+        category: "Build",
+        savings_pct: 85.0,
+        subcmd_savings: &[],
+        subcmd_status: &[],
+    },
 ];
 
 /// Commands to ignore (shell builtins, trivial, already rtk).
@@ -342,17 +366,21 @@ const IGNORED_PREFIXES: &[&str] = &[
     "then ",
     "else\n",
     "else ",
-    "fi",
+    "fi ",
+    "fi;",
     "do\n",
     "do ",
-    "done",
+    "done ",
+    "done;",
     "for ",
     "while ",
     "if ",
     "case ",
 ];
 
-const IGNORED_EXACT: &[&str] = &["cd", "echo", "true", "false", "wait", "pwd", "bash", "sh"];
+const IGNORED_EXACT: &[&str] = &[
+    "cd", "echo", "true", "false", "wait", "pwd", "bash", "sh", "fi", "done",
+];
 
 lazy_static! {
     static ref REGEX_SET: RegexSet = RegexSet::new(PATTERNS).expect("invalid regex patterns");
@@ -374,7 +402,9 @@ fn is_ignored_command(cmd: &str) -> bool {
     if IGNORED_EXACT.contains(&cmd) {
         return true;
     }
-    IGNORED_PREFIXES.iter().any(|prefix| cmd.starts_with(prefix))
+    IGNORED_PREFIXES
+        .iter()
+        .any(|prefix| cmd.starts_with(prefix))
 }
 
 fn normalize_command_for_classification(cmd: &str) -> Option<String> {
@@ -452,6 +482,25 @@ fn normalize_command_for_classification(cmd: &str) -> Option<String> {
     Some(normalized.to_string())
 }
 
+/// Split env assignments, `sudo`/`env`, no-op wrappers and runner prefixes
+/// (`uv run`, `npm exec`, ...) off the front of a command.
+/// Returns `(prefix, core)`; the prefix is kept verbatim so callers can rebuild the command.
+pub fn split_command_prefix(cmd: &str) -> (&str, &str) {
+    let mut offset = 0;
+    loop {
+        let rest = &cmd[offset..];
+        let matched = ENV_PREFIX
+            .find(rest)
+            .or_else(|| WRAPPER_PREFIX.find(rest))
+            .or_else(|| RUNNER_PREFIX.find(rest));
+        match matched {
+            Some(m) if m.end() > 0 => offset += m.end(),
+            _ => break,
+        }
+    }
+    cmd.split_at(offset)
+}
+
 /// Classify a single (already-split) command.
 pub fn classify_command(cmd: &str) -> Classification {
     let normalized = match normalize_command_for_classification(cmd) {
@@ -472,7 +521,9 @@ pub fn classify_command(cmd: &str) -> Classification {
     for _ in 0..6 {
         let stripped_env = ENV_PREFIX.replace(cmd_clean_owned.trim(), "").to_string();
         let stripped_wrapper = WRAPPER_PREFIX.replace(stripped_env.trim(), "").to_string();
-        let stripped_runner = RUNNER_PREFIX.replace(stripped_wrapper.trim(), "").to_string();
+        let stripped_runner = RUNNER_PREFIX
+            .replace(stripped_wrapper.trim(), "")
+            .to_string();
         let stripped_wrapper_again = WRAPPER_PREFIX
             .replace(stripped_runner.trim(), "")
             .to_string();
@@ -591,6 +642,41 @@ fn extract_base_command(cmd: &str) -> &str {
     }
 }
 
+/// Split one command into words on whitespace outside quotes.
+/// Words are slices of `cmd` with quotes and escapes kept, so callers can
+/// splice a replacement back in without disturbing the rest.
+pub fn split_command_words(cmd: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+
+    for (i, c) in cmd.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if !in_single => escaped = true,
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            c if c.is_whitespace() && !in_single && !in_double => {
+                if let Some(s) = start.take() {
+                    words.push(&cmd[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(s) = start {
+        words.push(&cmd[s..]);
+    }
+    words
+}
+
 /// Split a command chain on `&&`, `||`, `;` outside quotes.
 /// For pipes `|`, only keep the first command.
 /// Lines with `<<` (heredoc) or `$((` are returned whole.
@@ -660,12 +746,11 @@ pub fn split_command_chain(cmd: &str) -> Vec<&str> {
                     break;
                 }
             }
-            b'&'
-                if !in_single
-                    && !in_double
-                    && !in_backtick
-                    && i + 1 < len
-                    && bytes[i + 1] == b'&' =>
+            b'&' if !in_single
+                && !in_double
+                && !in_backtick
+                && i + 1 < len
+                && bytes[i + 1] == b'&' =>
             {
                 let segment = trimmed[start..i].trim();
                 if !segment.is_empty() {
@@ -1051,9 +1136,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_find_not_mistaken_for_fi() {
+        assert!(matches!(
+            classify_command("find . -name '*.rs'"),
+            Classification::Supported {
+                rtk_equivalent: "rtk find",
+                ..
+            }
+        ));
+        assert_eq!(classify_command("fi"), Classification::Ignored);
+        assert_eq!(classify_command("done"), Classification::Ignored);
+    }
+
     #[test]
     fn test_classify_noise_comment_ignored() {
-        assert_eq!(classify_command("# Check credentials"), Classification::Ignored);
+        assert_eq!(
+            classify_command("# Check credentials"),
+            Classification::Ignored
+        );
     }
 
     #[test]
@@ -1095,8 +1196,21 @@ mod tests {
     fn test_registry_covers_all_git_subcommands() {
         // Verify that every GitCommand subcommand has a matching pattern
         for subcmd in [
-            "status", "log", "diff", "show", "add", "commit", "push", "pull", "branch", "fetch",
-            "stash", "worktree",
+            "status",
+            "log",
+            "diff",
+            "show",
+            "add",
+            "commit",
+            "push",
+            "pull",
+            "branch",
+            "fetch",
+            "stash",
+            "worktree",
+            "rebase",
+            "merge",
+            "cherry-pick",
         ] {
             let cmd = format!("git {subcmd}");
             match classify_command(&cmd) {
@@ -1106,6 +1220,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_split_command_words_keeps_quotes() {
+        assert_eq!(
+            split_command_words(r#"git commit -m "two  words" --author='A B' a\ b"#),
+            vec![
+                "git",
+                "commit",
+                "-m",
+                r#""two  words""#,
+                "--author='A B'",
+                r"a\ b"
+            ]
+        );
+        assert!(split_command_words("   ").is_empty());
+    }
+
     #[test]
     fn test_split_chain_and() {
         assert_eq!(split_command_chain("a && b"), vec!["a", "b"]);
@@ -1142,7 +1272,10 @@ mod tests {
 
     #[test]
     fn test_split_newline() {
-        assert_eq!(split_command_chain("git status\nls -la"), vec!["git status", "ls -la"]);
+        assert_eq!(
+            split_command_chain("git status\nls -la"),
+            vec!["git status", "ls -la"]
+        );
     }
 
     #[test]
@@ -1155,6 +1288,9 @@ mod tests {
 
     #[test]
     fn test_split_escaped_semicolon_not_split() {
-        assert_eq!(split_command_chain(r#"echo foo\;bar"#), vec![r#"echo foo\;bar"#]);
+        assert_eq!(
+            split_command_chain(r#"echo foo\;bar"#),
+            vec![r#"echo foo\;bar"#]
+        );
     }
 }
@@ -48,6 +48,9 @@ pub struct DiscoverReport {
     pub supported: Vec<SupportedEntry>,
     pub unsupported: Vec<UnsupportedEntry>,
     pub parse_errors: usize,
+    /// Draft filters (`--suggest`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<super::suggest::Suggestion>,
 }
 
 impl DiscoverReport {
@@ -77,11 +80,9 @@ pub fn format_text(report: &DiscoverReport, limit: usize, verbose: bool) -> Stri
     out.push_str(&format!(
         "Already using RTK: {} commands ({}%)\n",
         report.already_rtk,
-        if report.total_commands > 0 {
-            report.already_rtk * 100 / report.total_commands
-        } else {
-            0
-        }
+        (report.already_rtk * 100)
+            .checked_div(report.total_commands)
+            .unwrap_or(0)
     ));
 
     if report.supported.is_empty() && report.unsupported.is_empty() {
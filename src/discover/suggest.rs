@@ -0,0 +1,341 @@
+//! Draft declarative filters for commands rtk doesn't handle.
+//!
+//! `rtk discover --suggest` samples the outputs stored in session transcripts
+//! for the top unsupported commands, infers a [`UserFilter`] per command
+//! (blank/noise-prefix stripping, repeated-line collapse) that never strips
+//! an error line, and replays it on the samples to estimate the savings.
+
+use crate::tracking::estimate_tokens;
+use crate::user_filter::UserFilter;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Output samples kept per unsupported command
+pub const MAX_SAMPLES: usize = 5;
+/// Fewer samples than this says nothing about the output's shape
+const MIN_SAMPLES: usize = 2;
+/// Commands considered, by descending count
+const MAX_SUGGESTIONS: usize = 5;
+/// Drafts saving less than this on their own samples are dropped
+const MIN_SAVINGS_PCT: f64 = 10.0;
+/// A step is only worth adding when it touches this share of the lines
+const MIN_LINE_SHARE: f64 = 0.10;
+/// Noise prefixes kept per draft
+const MAX_STRIP_PREFIXES: usize = 3;
+
+lazy_static! {
+    /// Lines a draft must keep: errors, failures and warnings.
+    static ref ERROR_LINE: Regex = Regex::new(
+        r"(?i)\b(error|errors|fail|failed|failure|fatal|panic|panicked|exception|traceback|denied|warning|warn)\b|not found"
+    )
+    .unwrap();
+}
+
+/// An unsupported command with the samples collected for it.
+pub struct Candidate<'a> {
+    pub base_command: &'a str,
+    pub count: usize,
+    /// Output tokens across every occurrence, sampled or not
+    pub total_output_tokens: usize,
+    /// Stored outputs (transcripts keep the first ~1000 chars)
+    pub samples: &'a [String],
+}
+
+/// A draft filter and its replayed savings.
+#[derive(Debug, Serialize)]
+pub struct Suggestion {
+    pub base_command: String,
+    pub count: usize,
+    pub samples: usize,
+    pub savings_pct: f64,
+    pub estimated_savings_tokens: usize,
+    pub errors_kept: usize,
+    pub errors_total: usize,
+    pub filter: UserFilter,
+}
+
+/// Draft filters for the most frequent candidates that have enough samples.
+pub fn suggest(mut candidates: Vec<Candidate>) -> Vec<Suggestion> {
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.count));
+    candidates
+        .into_iter()
+        .filter(|c| c.samples.len() >= MIN_SAMPLES)
+        .take(MAX_SUGGESTIONS)
+        .filter_map(|c| {
+            let filter = infer_filter(c.base_command, c.samples)?;
+            let replay = replay(&filter, c.base_command, c.samples);
+            if replay.savings_pct < MIN_SAVINGS_PCT {
+                return None;
+            }
+            Some(Suggestion {
+                base_command: c.base_command.to_string(),
+                count: c.count,
+                samples: c.samples.len(),
+                savings_pct: replay.savings_pct,
+                estimated_savings_tokens: (c.total_output_tokens as f64 * replay.savings_pct
+                    / 100.0) as usize,
+                errors_kept: replay.errors_kept,
+                errors_total: replay.errors_total,
+                filter,
+            })
+        })
+        .collect()
+}
+
+/// Infer a draft rule from the samples; `None` when nothing looks like noise.
+fn infer_filter(base_command: &str, samples: &[String]) -> Option<UserFilter> {
+    let lines: Vec<Vec<&str>> = samples.iter().map(|s| s.lines().collect()).collect();
+    let total = lines.iter().map(Vec::len).sum::<usize>();
+    if total == 0 {
+        return None;
+    }
+    let share = |n: usize| n as f64 / total as f64 >= MIN_LINE_SHARE;
+
+    let mut strip = Vec::new();
+
+    let blank = lines
+        .iter()
+        .flatten()
+        .filter(|l| l.trim().is_empty())
+        .count();
+    if share(blank) {
+        strip.push(r"^\s*$".to_string());
+    }
+
+    strip.extend(
+        noise_prefixes(&lines)
+            .into_iter()
+            .filter(|(_, n)| share(*n))
+            .map(|(prefix, _)| format!(r"^\s*{}(\s|$)", regex::escape(prefix))),
+    );
+
+    // Consecutive repeats collapse in place; scattered repeats are deduped
+    let mut runs = 0;
+    let mut repeats = 0;
+    for sample in &lines {
+        let mut seen = HashSet::new();
+        for (i, line) in sample.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if i > 0 && sample[i - 1] == *line {
+                runs += 1;
+            }
+            if !seen.insert(*line) {
+                repeats += 1;
+            }
+        }
+    }
+    let collapse_runs = share(runs);
+    let dedupe = !collapse_runs && share(repeats);
+
+    if strip.is_empty() && !collapse_runs && !dedupe {
+        return None;
+    }
+
+    Some(UserFilter {
+        name: base_command.replace(char::is_whitespace, "-"),
+        match_command: format!(
+            r"^{}(\s|$)",
+            base_command
+                .split_whitespace()
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(r"\s+")
+        ),
+        strip,
+        keep: Vec::new(),
+        dedupe,
+        collapse_runs,
+        head: None,
+        tail: None,
+        summary: None,
+    })
+}
+
+/// Leading words shared by many lines in most samples, never on an error line.
+fn noise_prefixes<'a>(samples: &[Vec<&'a str>]) -> Vec<(&'a str, usize)> {
+    // prefix -> (lines, samples seen in, seen on an error line)
+    let mut stats: HashMap<&str, (usize, HashSet<usize>, bool)> = HashMap::new();
+    for (index, lines) in samples.iter().enumerate() {
+        for line in lines {
+            let Some(prefix) = line_prefix(line) else {
+                continue;
+            };
+            let entry = stats.entry(prefix).or_default();
+            entry.0 += 1;
+            entry.1.insert(index);
+            entry.2 |= ERROR_LINE.is_match(line);
+        }
+    }
+
+    let min_samples = samples.len().div_ceil(2);
+    let mut prefixes: Vec<(&str, usize)> = stats
+        .into_iter()
+        .filter(|(_, (lines, seen, error))| !error && *lines >= 3 && seen.len() >= min_samples)
+        .map(|(prefix, (lines, _, _))| (prefix, lines))
+        .collect();
+    prefixes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    prefixes.truncate(MAX_STRIP_PREFIXES);
+    prefixes
+}
+
+/// First word of a line, unless it varies by nature (numbers, paths, one char).
+fn line_prefix(line: &str) -> Option<&str> {
+    let word = line.split_whitespace().next()?;
+    let varies = word.chars().count() < 2
+        || word.chars().any(|c| c.is_ascii_digit())
+        || word.contains('/')
+        || word.contains('\\');
+    (!varies).then_some(word)
+}
+
+struct Replay {
+    savings_pct: f64,
+    errors_kept: usize,
+    errors_total: usize,
+}
+
+fn replay(filter: &UserFilter, command: &str, samples: &[String]) -> Replay {
+    let mut before = 0;
+    let mut after = 0;
+    let mut errors_kept = 0;
+    let mut errors_total = 0;
+
+    for sample in samples {
+        let filtered = filter
+            .apply(command, sample, 0)
+            .unwrap_or_else(|_| sample.clone());
+        before += estimate_tokens(sample);
+        after += estimate_tokens(&filtered);
+
+        let errors: HashSet<&str> = sample.lines().filter(|l| ERROR_LINE.is_match(l)).collect();
+        errors_total += errors.len();
+        errors_kept += errors.iter().filter(|e| filtered.contains(*e)).count();
+    }
+
+    let savings_pct = if before == 0 {
+        0.0
+    } else {
+        before.saturating_sub(after) as f64 / before as f64 * 100.0
+    };
+    Replay {
+        savings_pct,
+        errors_kept,
+        errors_total,
+    }
+}
+
+/// Suggestions as text, each with its draft rule in `.rtk/filters.toml` form.
+pub fn format_text(suggestions: &[Suggestion]) -> String {
+    let mut out = String::new();
+    out.push_str("\nSUGGESTED FILTERS -- replayed on session output samples\n");
+    out.push_str(&"-".repeat(52));
+    out.push('\n');
+
+    if suggestions.is_empty() {
+        out.push_str("No draft filters: not enough samples, or the output has no obvious noise.\n");
+        return out;
+    }
+
+    for s in suggestions {
+        out.push_str(&format!(
+            "\n{} ({}x, {} samples): -{:.0}% (~{} tokens), errors kept {}/{}\n",
+            s.base_command,
+            s.count,
+            s.samples,
+            s.savings_pct,
+            s.estimated_savings_tokens,
+            s.errors_kept,
+            s.errors_total
+        ));
+        out.push_str(&crate::user_filter::to_project_toml(std::slice::from_ref(
+            &s.filter,
+        )));
+    }
+
+    out.push_str(&"-".repeat(52));
+    out.push('\n');
+    out.push_str(
+        "-> rtk discover --apply adds these to .rtk/filters.toml (applied by rtk proxy)\n",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(output: &str) -> String {
+        output.to_string()
+    }
+
+    fn deploy_samples() -> Vec<String> {
+        vec![
+            sample("DEBUG loading config\nDEBUG resolving hosts\nDEBUG connecting\n\nDeployed web to prod\n"),
+            sample("DEBUG loading config\nDEBUG resolving hosts\n\nerror: host db-2 unreachable\nDEBUG retrying\n"),
+            sample("DEBUG loading config\nDEBUG connecting\nDeployed api to prod\n"),
+        ]
+    }
+
+    #[test]
+    fn test_infer_strips_noise_prefix() {
+        let filter = infer_filter("mycli deploy", &deploy_samples()).unwrap();
+        assert_eq!(filter.name, "mycli-deploy");
+        assert_eq!(filter.match_command, r"^mycli\s+deploy(\s|$)");
+        assert!(filter.strip.contains(&r"^\s*DEBUG(\s|$)".to_string()));
+        assert!(filter.strip.contains(&r"^\s*$".to_string()));
+        // "Deployed" lines are content, not noise: only 2 lines
+        assert!(!filter.strip.iter().any(|s| s.contains("Deployed")));
+    }
+
+    #[test]
+    fn test_infer_never_strips_error_prefix() {
+        let samples = vec![
+            sample("error: a\nerror: b\nerror: c\nok"),
+            sample("error: d\nerror: e\nok"),
+        ];
+        assert!(infer_filter("lint", &samples).is_none());
+    }
+
+    #[test]
+    fn test_infer_collapse_runs() {
+        let samples = vec![
+            sample("waiting\nwaiting\nwaiting\nwaiting\nready"),
+            sample("waiting\nwaiting\nready"),
+        ];
+        let filter = infer_filter("svc up", &samples).unwrap();
+        assert!(filter.collapse_runs);
+        assert!(!filter.dedupe);
+    }
+
+    #[test]
+    fn test_suggest_replays_savings() {
+        let samples = deploy_samples();
+        let suggestions = suggest(vec![Candidate {
+            base_command: "mycli deploy",
+            count: 12,
+            total_output_tokens: 1000,
+            samples: &samples,
+        }]);
+        assert_eq!(suggestions.len(), 1);
+        let s = &suggestions[0];
+        assert!(s.savings_pct > 40.0, "savings {}", s.savings_pct);
+        assert_eq!(s.estimated_savings_tokens, (10.0 * s.savings_pct) as usize);
+        assert_eq!((s.errors_kept, s.errors_total), (1, 1));
+    }
+
+    #[test]
+    fn test_suggest_needs_samples() {
+        let samples = vec![sample("DEBUG a\nDEBUG b\nDEBUG c\nx")];
+        let candidates = vec![Candidate {
+            base_command: "mycli deploy",
+            count: 3,
+            total_output_tokens: 100,
+            samples: &samples,
+        }];
+        assert!(suggest(candidates).is_empty());
+    }
+}
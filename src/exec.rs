@@ -0,0 +1,86 @@
+//! Exit-code contract shared by every command wrapper.
+//!
+//! Wrappers return an [`ExecResult`] instead of calling `process::exit`
+//! themselves, so output is flushed and tracking recorded before `main` exits
+//! with the child's exact code. A child killed by a signal maps to
+//! `128 + signal`, the way a shell reports it.
+
+use std::process::ExitStatus;
+
+/// Exit code used when the child's status carries neither a code nor a signal.
+const UNKNOWN_EXIT_CODE: i32 = 1;
+
+/// What a wrapped command exited with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecResult {
+    pub exit_code: i32,
+}
+
+impl ExecResult {
+    pub const SUCCESS: ExecResult = ExecResult { exit_code: 0 };
+
+    pub fn code(exit_code: i32) -> Self {
+        ExecResult { exit_code }
+    }
+
+    pub fn from_status(status: &ExitStatus) -> Self {
+        ExecResult {
+            exit_code: exit_code(status),
+        }
+    }
+
+    pub fn success(self) -> bool {
+        self.exit_code == 0
+    }
+
+    /// End the process with this code (returns for success, like `main`).
+    pub fn exit(self) {
+        if !self.success() {
+            std::process::exit(self.exit_code);
+        }
+    }
+}
+
+impl From<ExitStatus> for ExecResult {
+    fn from(status: ExitStatus) -> Self {
+        ExecResult::from_status(&status)
+    }
+}
+
+/// The child's exit code, or `128 + signal` when it was killed.
+pub fn exit_code(status: &ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    UNKNOWN_EXIT_CODE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_pass_through() {
+        let status = Command::new("sh").args(["-c", "exit 3"]).status().unwrap();
+        assert_eq!(ExecResult::from(status), ExecResult::code(3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signal_exit_code() {
+        let status = Command::new("sh")
+            .args(["-c", "kill -TERM $$"])
+            .status()
+            .unwrap();
+        assert_eq!(exit_code(&status), 143);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::prettier_cmd;
 use crate::ruff_cmd;
 use crate::tracking;
@@ -51,7 +52,7 @@ fn detect_formatter_in_dir(args: &[String], dir: &Path) -> String {
     "ruff".to_string()
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Detect formatter
@@ -80,6 +81,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     // Add formatter-specific flags
     let user_args = args[start_idx..].to_vec();
 
+    #[allow(clippy::collapsible_match)]
     match formatter.as_str() {
         "black" => {
             // Inject --check if not present for check mode
@@ -129,7 +131,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("{} {}", formatter, user_args.join(" ")),
         &format!("rtk format {} {}", formatter, user_args.join(" ")),
         &raw,
@@ -137,11 +140,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Filter black output - show files that need formatting
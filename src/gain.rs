@@ -1,9 +1,11 @@
 use crate::display_helpers::{format_duration, print_period_table};
-use crate::tracking::{DayStats, MonthStats, Tracker, WeekStats};
+use crate::exec::ExecResult;
+use crate::tokenizer::{self, Calibration, Tokenizer};
+use crate::tracking::{DayStats, GainFilter, GainGroup, MonthStats, Tracker, WeekStats};
 use crate::utils::format_tokens;
 use anyhow::{Context, Result};
 use colored::Colorize; // added: terminal colors
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::io::IsTerminal; // added: TTY detection for graceful degradation
 
 pub struct GainOptions<'a> {
@@ -16,10 +18,53 @@ pub struct GainOptions<'a> {
     pub monthly: bool,
     pub all: bool,
     pub format: &'a str,
+    pub recalibrate: bool,
+    pub project: Option<&'a str>,
+    pub session: Option<&'a str>,
+    pub by: Option<&'a str>,
+    pub check: bool,
+    pub report: Option<&'a str>,
     pub verbose: u8,
 }
 
-pub fn run(opts: GainOptions<'_>) -> Result<()> {
+/// `[gain]` section of config.toml. Budgets count the tokens rtk printed
+/// (output tokens, what reached the agent); 0 means no budget.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GainConfig {
+    pub daily_budget: usize,
+    /// Weeks as in `rtk gain --weekly`
+    pub weekly_budget: usize,
+}
+
+/// Usage against one configured budget, for the current day or week.
+#[derive(Debug, Serialize)]
+pub struct BudgetStatus {
+    /// "daily" or "weekly"
+    pub period: &'static str,
+    /// Date, or week range, being measured
+    pub window: String,
+    pub used: usize,
+    pub budget: usize,
+}
+
+impl BudgetStatus {
+    pub fn exceeded(&self) -> bool {
+        self.used > self.budget
+    }
+
+    pub fn used_pct(&self) -> f64 {
+        if self.budget == 0 {
+            0.0
+        } else {
+            self.used as f64 / self.budget as f64 * 100.0
+        }
+    }
+}
+
+/// Max tee samples measured by `--recalibrate` (newest first)
+const RECALIBRATION_SAMPLES: usize = 50;
+
+pub fn run(opts: GainOptions<'_>) -> Result<ExecResult> {
     let GainOptions {
         graph,
         history,
@@ -30,15 +75,58 @@ pub fn run(opts: GainOptions<'_>) -> Result<()> {
         monthly,
         all,
         format,
+        recalibrate,
+        project,
+        session,
+        by,
+        check,
+        report,
         verbose: _verbose,
     } = opts;
 
-    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    if recalibrate {
+        print_recalibration(format)?;
+        return Ok(ExecResult::SUCCESS);
+    }
+
+    let filter = GainFilter {
+        project: project.map(resolve_project),
+        session: session.map(str::to_string),
+    };
+    let scope = scope_label(&filter);
+    let tracker = Tracker::new()
+        .context("Failed to initialize tracking database")?
+        .with_filter(filter);
+
+    if check {
+        return check_budgets(&tracker, format);
+    }
+
+    if let Some(report) = report {
+        print_report(&tracker, scope, report)?;
+        return Ok(ExecResult::SUCCESS);
+    }
+
+    if let Some(by) = by {
+        let group = if by == "session" {
+            GainGroup::Session
+        } else {
+            GainGroup::Project
+        };
+        print_by_group(&tracker, group, format)?;
+        return Ok(ExecResult::SUCCESS);
+    }
 
     // Handle export formats
     match format {
-        "json" => return export_json(&tracker, daily, weekly, monthly, all),
-        "csv" => return export_csv(&tracker, daily, weekly, monthly, all),
+        "json" => {
+            export_json(&tracker, daily, weekly, monthly, all)?;
+            return Ok(ExecResult::SUCCESS);
+        }
+        "csv" => {
+            export_csv(&tracker, daily, weekly, monthly, all)?;
+            return Ok(ExecResult::SUCCESS);
+        }
         _ => {} // Continue with text format
     }
 
@@ -49,13 +137,16 @@ pub fn run(opts: GainOptions<'_>) -> Result<()> {
     if summary.total_commands == 0 {
         println!("No tracking data yet.");
         println!("Run some rtk commands to start tracking savings.");
-        return Ok(());
+        return Ok(ExecResult::SUCCESS);
     }
 
     // Default view (summary)
     if !daily && !weekly && !monthly && !all {
         // added: styled header with bold title
-        println!("{}", styled("RTK Token Savings (Global Scope)", true));
+        println!(
+            "{}",
+            styled(&format!("RTK Token Savings ({})", scope), true)
+        );
         println!("{}", "═".repeat(60));
         println!();
 
@@ -233,7 +324,7 @@ pub fn run(opts: GainOptions<'_>) -> Result<()> {
             println!("      Actual limits use rolling 5-hour windows, not monthly caps.");
         }
 
-        return Ok(());
+        return Ok(ExecResult::SUCCESS);
     }
 
     // Time breakdown views
@@ -249,7 +340,7 @@ pub fn run(opts: GainOptions<'_>) -> Result<()> {
         print_monthly(&tracker)?;
     }
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 // ── Display helpers (TTY-aware) ── // added: entire section
@@ -302,6 +393,16 @@ fn truncate_for_column(text: &str, width: usize) -> String {
     out
 }
 
+/// Keep the end of long keys (project paths end with the repo name).
+fn truncate_left(text: &str, width: usize) -> String {
+    let char_count = text.chars().count();
+    if char_count <= width || width <= 3 {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(char_count - (width - 3)).collect();
+    format!("...{}", tail)
+}
+
 /// Style command names with cyan+bold (TTY-aware). // added
 fn style_command_cell(cmd: &str) -> String {
     if !std::io::stdout().is_terminal() {
@@ -394,6 +495,118 @@ fn print_monthly(tracker: &Tracker) -> Result<()> {
     Ok(())
 }
 
+/// Today's and this week's output tokens against the configured budgets.
+fn budget_statuses(
+    config: &GainConfig,
+    days: &[DayStats],
+    weeks: &[WeekStats],
+    today: &str,
+) -> Vec<BudgetStatus> {
+    let mut statuses = Vec::new();
+    if config.daily_budget > 0 {
+        statuses.push(BudgetStatus {
+            period: "daily",
+            window: today.to_string(),
+            used: days
+                .iter()
+                .find(|d| d.date == today)
+                .map_or(0, |d| d.output_tokens),
+            budget: config.daily_budget,
+        });
+    }
+    if config.weekly_budget > 0 {
+        let week = weeks
+            .iter()
+            .find(|w| w.week_start.as_str() <= today && today <= w.week_end.as_str());
+        statuses.push(BudgetStatus {
+            period: "weekly",
+            window: week.map_or_else(
+                || "this week".to_string(),
+                |w| format!("{} → {}", w.week_start, w.week_end),
+            ),
+            used: week.map_or(0, |w| w.output_tokens),
+            budget: config.weekly_budget,
+        });
+    }
+    statuses
+}
+
+fn load_budget_statuses(tracker: &Tracker) -> Result<Vec<BudgetStatus>> {
+    let config = crate::config::Config::load()?.gain;
+    // Tracking timestamps are UTC, and so are SQLite's DATE() buckets
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    Ok(budget_statuses(
+        &config,
+        &tracker.get_all_days()?,
+        &tracker.get_by_week()?,
+        &today,
+    ))
+}
+
+/// `rtk gain --check`: exit 1 when a daily or weekly budget is exceeded.
+fn check_budgets(tracker: &Tracker, format: &str) -> Result<ExecResult> {
+    let statuses = load_budget_statuses(tracker)?;
+    let exceeded = statuses.iter().any(BudgetStatus::exceeded);
+
+    if format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "exceeded": exceeded,
+                "budgets": statuses,
+            }))?
+        );
+    } else if statuses.is_empty() {
+        println!("No token budgets configured.");
+        println!("Set daily_budget / weekly_budget under [gain] in config.toml.");
+    } else {
+        for status in &statuses {
+            let state = if status.exceeded() { "EXCEEDED" } else { "ok" };
+            print_kpi(
+                &format!("{} budget", capitalize(status.period)),
+                format!(
+                    "{} / {} ({:.0}%) {} [{}]",
+                    format_tokens(status.used),
+                    format_tokens(status.budget),
+                    status.used_pct(),
+                    state,
+                    status.window
+                ),
+            );
+        }
+    }
+
+    Ok(if exceeded {
+        ExecResult::code(1)
+    } else {
+        ExecResult::SUCCESS
+    })
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+}
+
+/// `rtk gain --report html|md`, printed to stdout.
+fn print_report(tracker: &Tracker, scope: String, format: &str) -> Result<()> {
+    let report = crate::gain_report::Report {
+        scope,
+        generated: chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string(),
+        summary: tracker
+            .get_summary()
+            .context("Failed to load token savings summary from database")?,
+        worst: tracker.get_worst_commands(10)?,
+        weeks: tracker.get_by_week()?,
+        months: tracker.get_by_month()?,
+        budgets: load_budget_statuses(tracker)?,
+    };
+    print!("{}", crate::gain_report::render(&report, format)?);
+    Ok(())
+}
+
 #[derive(Serialize)]
 struct ExportData {
     summary: ExportSummary,
@@ -416,6 +629,197 @@ struct ExportSummary {
     avg_time_ms: u64,
 }
 
+/// `--project` value to the recorded project root ("." = current project).
+fn resolve_project(path: &str) -> String {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| std::path::PathBuf::from(path));
+    crate::tracking::project_root(&path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn scope_label(filter: &GainFilter) -> String {
+    match (&filter.project, &filter.session) {
+        (None, None) => "Global Scope".to_string(),
+        (Some(p), None) => format!("Project: {}", p),
+        (None, Some(s)) => format!("Session: {}", s),
+        (Some(p), Some(s)) => format!("Project: {}, Session: {}", p, s),
+    }
+}
+
+fn print_by_group(tracker: &Tracker, group: GainGroup, format: &str) -> Result<()> {
+    let groups = tracker
+        .get_by_group(group)
+        .context("Failed to load grouped savings from database")?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&groups)?);
+        return Ok(());
+    }
+
+    if groups.is_empty() {
+        println!("No tracking data yet.");
+        return Ok(());
+    }
+
+    let label = match group {
+        GainGroup::Project => "Project",
+        GainGroup::Session => "Session",
+    };
+    let key_width = 40usize;
+    println!(
+        "{}",
+        styled(&format!("RTK Token Savings by {}", label), true)
+    );
+    println!("{}", "═".repeat(key_width + 40));
+    println!(
+        "{:<key_width$} {:>7} {:>10} {:>10} {:>7} {:>8}",
+        label,
+        "Cmds",
+        "Input",
+        "Saved",
+        "Save%",
+        "Time",
+        key_width = key_width
+    );
+    println!("{}", "─".repeat(key_width + 40));
+    for g in &groups {
+        println!(
+            "{:<key_width$} {:>7} {:>10} {:>10} {:>6.1}% {:>8}",
+            truncate_left(&g.key, key_width),
+            g.commands,
+            format_tokens(g.input_tokens),
+            format_tokens(g.saved_tokens),
+            g.savings_pct,
+            format_duration(g.avg_time_ms),
+            key_width = key_width
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Raw outputs saved by tee, newest first.
+fn load_calibration_samples() -> Vec<(String, String)> {
+    let Some(dir) = crate::tee::tee_dir() else {
+        return Vec::new();
+    };
+
+    crate::tee::list_captures(&dir)
+        .into_iter()
+        .take(RECALIBRATION_SAMPLES)
+        .filter_map(|c| {
+            let content = std::fs::read_to_string(&c.path).ok()?;
+            Some((format!("{} {}", c.id, c.command), content))
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct RecalibrationReport {
+    configured: Tokenizer,
+    samples: usize,
+    tokenizers: Vec<RecalibrationSummary>,
+}
+
+#[derive(Serialize)]
+struct RecalibrationSummary {
+    tokenizer: Tokenizer,
+    heuristic_tokens: usize,
+    actual_tokens: usize,
+    error_pct: f64,
+    bytes_per_token: f64,
+    worst: Vec<Calibration>,
+}
+
+fn summarize_calibration(
+    samples: &[(String, String)],
+    tokenizer: Tokenizer,
+) -> RecalibrationSummary {
+    let mut measured: Vec<Calibration> = samples
+        .iter()
+        .map(|(label, text)| Calibration::measure(label, text, tokenizer))
+        .collect();
+    let bytes: usize = measured.iter().map(|c| c.bytes).sum();
+    let heuristic_tokens = measured.iter().map(|c| c.heuristic).sum();
+    let actual_tokens: usize = measured.iter().map(|c| c.actual).sum();
+
+    measured.sort_by(|a, b| b.error_pct().abs().total_cmp(&a.error_pct().abs()));
+    measured.truncate(5);
+
+    RecalibrationSummary {
+        tokenizer,
+        heuristic_tokens,
+        actual_tokens,
+        error_pct: tokenizer::error_pct(heuristic_tokens, actual_tokens),
+        bytes_per_token: if actual_tokens == 0 {
+            0.0
+        } else {
+            bytes as f64 / actual_tokens as f64
+        },
+        worst: measured,
+    }
+}
+
+fn print_recalibration(format: &str) -> Result<()> {
+    let samples = load_calibration_samples();
+    let report = RecalibrationReport {
+        configured: Tokenizer::from_config(),
+        samples: samples.len(),
+        tokenizers: Tokenizer::BPE
+            .iter()
+            .map(|t| summarize_calibration(&samples, *t))
+            .collect(),
+    };
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if samples.is_empty() {
+        println!("No stored samples to recalibrate against.");
+        println!("Tee saves raw output on failures; set tee.mode = \"always\" to collect more.");
+        return Ok(());
+    }
+
+    println!("{}", styled("RTK Token Recalibration", true));
+    println!("{}", "═".repeat(60));
+    println!();
+    print_kpi("Samples", format!("{} tee files", report.samples));
+    print_kpi("Configured", report.configured.name().to_string());
+    println!();
+
+    for summary in &report.tokenizers {
+        println!("{}", styled(summary.tokenizer.name(), true));
+        print_kpi("Heuristic", format_tokens(summary.heuristic_tokens));
+        print_kpi("Actual", format_tokens(summary.actual_tokens));
+        print_kpi("Heuristic error", format!("{:+.1}%", summary.error_pct));
+        print_kpi(
+            "Bytes/token",
+            format!("{:.2} (heuristic assumes 4.00)", summary.bytes_per_token),
+        );
+        for cal in &summary.worst {
+            println!(
+                "  {:<40} {:>7} vs {:>7}  {:+.1}%",
+                truncate_for_column(&cal.label, 40),
+                cal.heuristic,
+                cal.actual,
+                cal.error_pct()
+            );
+        }
+        println!();
+    }
+
+    if report.configured == Tokenizer::Heuristic {
+        println!(
+            "Set tracking.tokenizer = \"cl100k\" or \"o200k\" in config to record real counts."
+        );
+    }
+
+    Ok(())
+}
+
 fn export_json(
     tracker: &Tracker,
     daily: bool,
@@ -531,3 +935,68 @@ fn export_csv(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, output_tokens: usize) -> DayStats {
+        DayStats {
+            date: date.to_string(),
+            commands: 1,
+            input_tokens: output_tokens * 4,
+            output_tokens,
+            saved_tokens: output_tokens * 3,
+            savings_pct: 75.0,
+            total_time_ms: 10,
+            avg_time_ms: 10,
+        }
+    }
+
+    fn week(start: &str, end: &str, output_tokens: usize) -> WeekStats {
+        WeekStats {
+            week_start: start.to_string(),
+            week_end: end.to_string(),
+            commands: 1,
+            input_tokens: output_tokens * 4,
+            output_tokens,
+            saved_tokens: output_tokens * 3,
+            savings_pct: 75.0,
+            total_time_ms: 10,
+            avg_time_ms: 10,
+        }
+    }
+
+    #[test]
+    fn test_budget_statuses() {
+        let days = [day("2026-10-17", 9_000), day("2026-10-18", 1_500)];
+        let weeks = [week("2026-10-12", "2026-10-18", 10_500)];
+        let config = GainConfig {
+            daily_budget: 1_000,
+            weekly_budget: 50_000,
+        };
+
+        let statuses = budget_statuses(&config, &days, &weeks, "2026-10-18");
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].used, 1_500);
+        assert!(statuses[0].exceeded());
+        assert_eq!(statuses[1].used, 10_500);
+        assert_eq!(statuses[1].window, "2026-10-12 → 2026-10-18");
+        assert!(!statuses[1].exceeded());
+        assert_eq!(statuses[1].used_pct(), 21.0);
+    }
+
+    #[test]
+    fn test_budget_statuses_unset_or_idle() {
+        let days = [day("2026-10-17", 9_000)];
+        assert!(budget_statuses(&GainConfig::default(), &days, &[], "2026-10-18").is_empty());
+
+        let config = GainConfig {
+            daily_budget: 1_000,
+            weekly_budget: 1_000,
+        };
+        let statuses = budget_statuses(&config, &days, &[], "2026-10-18");
+        assert!(statuses.iter().all(|s| s.used == 0 && !s.exceeded()));
+        assert_eq!(statuses[1].window, "this week");
+    }
+}
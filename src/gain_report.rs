@@ -0,0 +1,413 @@
+//! `rtk gain --report html|md`: a savings report to share with the team.
+//!
+//! Built from the same tracker queries as `rtk gain`: a summary, per-command
+//! leaderboards (most tokens saved, lowest savings), budget status and
+//! weekly/monthly trend charts. Markdown charts are text bars; the HTML
+//! report is a single self-contained file with inline SVG charts.
+
+use crate::display_helpers::{format_duration, PeriodStats};
+use crate::gain::BudgetStatus;
+use crate::tracking::{CommandStats, GainSummary, MonthStats, WeekStats};
+use crate::utils::format_tokens;
+use anyhow::Result;
+
+/// Periods shown in each trend chart (most recent)
+const TREND_PERIODS: usize = 12;
+const MD_BAR_WIDTH: usize = 20;
+const SVG_WIDTH: usize = 640;
+const SVG_HEIGHT: usize = 160;
+
+pub struct Report {
+    pub scope: String,
+    pub generated: String,
+    pub summary: GainSummary,
+    pub worst: Vec<CommandStats>,
+    pub weeks: Vec<WeekStats>,
+    pub months: Vec<MonthStats>,
+    pub budgets: Vec<BudgetStatus>,
+}
+
+pub fn render(report: &Report, format: &str) -> Result<String> {
+    match format {
+        "md" => Ok(render_markdown(report)),
+        "html" => Ok(render_html(report)),
+        other => anyhow::bail!("Unknown report format '{}' (use html or md)", other),
+    }
+}
+
+fn recent<T>(data: &[T]) -> &[T] {
+    &data[data.len().saturating_sub(TREND_PERIODS)..]
+}
+
+fn budget_state(b: &BudgetStatus) -> &'static str {
+    if b.exceeded() {
+        "exceeded"
+    } else {
+        "ok"
+    }
+}
+
+// ── Markdown ──
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn text_bar(value: usize, max: usize) -> String {
+    let filled = if max == 0 {
+        0
+    } else {
+        ((value as f64 / max as f64) * MD_BAR_WIDTH as f64).round() as usize
+    };
+    let filled = filled.min(MD_BAR_WIDTH);
+    format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(MD_BAR_WIDTH - filled)
+    )
+}
+
+fn md_command_table(out: &mut String, commands: &[CommandStats]) {
+    out.push_str("| # | Command | Runs | Saved | Avg% | Avg time |\n");
+    out.push_str("|--:|---------|-----:|------:|-----:|---------:|\n");
+    for (i, (cmd, count, saved, pct, time)) in commands.iter().enumerate() {
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} | {:.1}% | {} |\n",
+            i + 1,
+            md_cell(cmd),
+            count,
+            format_tokens(*saved),
+            pct,
+            format_duration(*time)
+        ));
+    }
+    out.push('\n');
+}
+
+fn md_trend<T: PeriodStats>(out: &mut String, data: &[T]) {
+    out.push_str(&format!("## {} Trend\n\n", T::label()));
+    let data = recent(data);
+    if data.is_empty() {
+        out.push_str("No data.\n\n");
+        return;
+    }
+    let max = data.iter().map(|d| d.saved_tokens()).max().unwrap_or(0);
+    out.push_str("| Period | Commands | Saved | Savings | |\n");
+    out.push_str("|--------|---------:|------:|--------:|-|\n");
+    for d in data {
+        out.push_str(&format!(
+            "| {} | {} | {} | {:.1}% | `{}` |\n",
+            d.period(),
+            d.commands(),
+            format_tokens(d.saved_tokens()),
+            d.savings_pct(),
+            text_bar(d.saved_tokens(), max)
+        ));
+    }
+    out.push('\n');
+}
+
+fn render_markdown(report: &Report) -> String {
+    let s = &report.summary;
+    let mut out = String::new();
+    out.push_str("# RTK Token Savings Report\n\n");
+    out.push_str(&format!(
+        "{} · generated {}\n\n",
+        report.scope, report.generated
+    ));
+
+    out.push_str("| Metric | Value |\n|--------|------:|\n");
+    out.push_str(&format!("| Commands | {} |\n", s.total_commands));
+    out.push_str(&format!(
+        "| Input tokens | {} |\n",
+        format_tokens(s.total_input)
+    ));
+    out.push_str(&format!(
+        "| Output tokens | {} |\n",
+        format_tokens(s.total_output)
+    ));
+    out.push_str(&format!(
+        "| Tokens saved | {} ({:.1}%) |\n",
+        format_tokens(s.total_saved),
+        s.avg_savings_pct
+    ));
+    out.push_str(&format!(
+        "| Exec time | {} (avg {}) |\n\n",
+        format_duration(s.total_time_ms),
+        format_duration(s.avg_time_ms)
+    ));
+
+    if !report.budgets.is_empty() {
+        out.push_str("## Budgets\n\n");
+        out.push_str("| Budget | Window | Used | Limit | Status |\n");
+        out.push_str("|--------|--------|-----:|------:|--------|\n");
+        for b in &report.budgets {
+            out.push_str(&format!(
+                "| {} | {} | {} ({:.0}%) | {} | {} |\n",
+                b.period,
+                b.window,
+                format_tokens(b.used),
+                b.used_pct(),
+                format_tokens(b.budget),
+                budget_state(b)
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Top Commands (tokens saved)\n\n");
+    md_command_table(&mut out, &s.by_command);
+    out.push_str("## Worst-Filtered Commands (lowest savings)\n\n");
+    md_command_table(&mut out, &report.worst);
+
+    md_trend(&mut out, &report.weeks);
+    md_trend(&mut out, &report.months);
+    out
+}
+
+// ── HTML ──
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_command_table(out: &mut String, title: &str, commands: &[CommandStats]) {
+    out.push_str(&format!("<h2>{}</h2>\n<table>\n", title));
+    out.push_str("<tr><th>#</th><th>Command</th><th>Runs</th><th>Saved</th><th>Avg%</th><th>Avg time</th></tr>\n");
+    for (i, (cmd, count, saved, pct, time)) in commands.iter().enumerate() {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{}</td></tr>\n",
+            i + 1,
+            escape_html(cmd),
+            count,
+            format_tokens(*saved),
+            pct,
+            format_duration(*time)
+        ));
+    }
+    out.push_str("</table>\n");
+}
+
+/// Bar chart of tokens saved per period, savings % in each bar's tooltip.
+fn svg_chart<T: PeriodStats>(data: &[T]) -> String {
+    let max = data
+        .iter()
+        .map(|d| d.saved_tokens())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let slot = SVG_WIDTH / data.len().max(1);
+    let bar_width = slot.saturating_sub(8).max(4);
+    let plot_height = SVG_HEIGHT - 20;
+
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\" role=\"img\">\n",
+        SVG_WIDTH, SVG_HEIGHT, SVG_WIDTH, SVG_HEIGHT
+    );
+    for (i, d) in data.iter().enumerate() {
+        let height = d.saved_tokens() * plot_height / max;
+        let x = i * slot + 4;
+        let y = plot_height - height;
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"><title>{}: {} saved ({:.1}%)</title></rect>\n",
+            x,
+            y,
+            bar_width,
+            height,
+            escape_html(&d.period()),
+            format_tokens(d.saved_tokens()),
+            d.savings_pct()
+        ));
+        // Weeks are labelled by their first day to fit under the bar
+        let period = d.period();
+        let label = period.split(' ').next().unwrap_or_default();
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{}</text>\n",
+            x,
+            SVG_HEIGHT - 4,
+            escape_html(label)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn html_trend<T: PeriodStats>(out: &mut String, data: &[T]) {
+    out.push_str(&format!("<h2>{} Trend</h2>\n", T::label()));
+    let data = recent(data);
+    if data.is_empty() {
+        out.push_str("<p>No data.</p>\n");
+        return;
+    }
+    out.push_str(&svg_chart(data));
+    out.push_str(
+        "<table>\n<tr><th>Period</th><th>Commands</th><th>Saved</th><th>Savings</th></tr>\n",
+    );
+    for d in data {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td></tr>\n",
+            escape_html(&d.period()),
+            d.commands(),
+            format_tokens(d.saved_tokens()),
+            d.savings_pct()
+        ));
+    }
+    out.push_str("</table>\n");
+}
+
+const HTML_STYLE: &str =
+    "body{font-family:system-ui,sans-serif;max-width:760px;margin:2em auto;color:#222}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{padding:4px 10px;border-bottom:1px solid #ddd;text-align:right}\
+th:nth-child(2),td:nth-child(2){text-align:left}\
+rect{fill:#2a9d8f}text{font-size:10px;fill:#666}\
+.exceeded{color:#c0392b;font-weight:bold}";
+
+fn render_html(report: &Report) -> String {
+    let s = &report.summary;
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>RTK Token Savings Report</title>\n");
+    out.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", HTML_STYLE));
+    out.push_str("<h1>RTK Token Savings Report</h1>\n");
+    out.push_str(&format!(
+        "<p>{} · generated {}</p>\n",
+        escape_html(&report.scope),
+        escape_html(&report.generated)
+    ));
+
+    out.push_str("<table>\n");
+    for (label, value) in [
+        ("Commands", s.total_commands.to_string()),
+        ("Input tokens", format_tokens(s.total_input)),
+        ("Output tokens", format_tokens(s.total_output)),
+        (
+            "Tokens saved",
+            format!(
+                "{} ({:.1}%)",
+                format_tokens(s.total_saved),
+                s.avg_savings_pct
+            ),
+        ),
+        (
+            "Exec time",
+            format!(
+                "{} (avg {})",
+                format_duration(s.total_time_ms),
+                format_duration(s.avg_time_ms)
+            ),
+        ),
+    ] {
+        out.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value));
+    }
+    out.push_str("</table>\n");
+
+    if !report.budgets.is_empty() {
+        out.push_str("<h2>Budgets</h2>\n<table>\n");
+        out.push_str(
+            "<tr><th>Budget</th><th>Window</th><th>Used</th><th>Limit</th><th>Status</th></tr>\n",
+        );
+        for b in &report.budgets {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{} ({:.0}%)</td><td>{}</td><td class=\"{}\">{}</td></tr>\n",
+                b.period,
+                escape_html(&b.window),
+                format_tokens(b.used),
+                b.used_pct(),
+                format_tokens(b.budget),
+                budget_state(b),
+                budget_state(b)
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    html_command_table(&mut out, "Top Commands (tokens saved)", &s.by_command);
+    html_command_table(
+        &mut out,
+        "Worst-Filtered Commands (lowest savings)",
+        &report.worst,
+    );
+    html_trend(&mut out, &report.weeks);
+    html_trend(&mut out, &report.months);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn month(month: &str, saved: usize) -> MonthStats {
+        MonthStats {
+            month: month.to_string(),
+            commands: 10,
+            input_tokens: saved * 2,
+            output_tokens: saved,
+            saved_tokens: saved,
+            savings_pct: 50.0,
+            total_time_ms: 100,
+            avg_time_ms: 10,
+        }
+    }
+
+    fn sample() -> Report {
+        Report {
+            scope: "Global Scope".to_string(),
+            generated: "2026-10-18 09:00 UTC".to_string(),
+            summary: GainSummary {
+                total_commands: 20,
+                total_input: 10_000,
+                total_output: 2_000,
+                total_saved: 8_000,
+                avg_savings_pct: 80.0,
+                total_time_ms: 2_000,
+                avg_time_ms: 100,
+                by_command: vec![("rtk cargo test".to_string(), 12, 7_000, 92.5, 800)],
+                by_day: Vec::new(),
+            },
+            worst: vec![("rtk grep a|b <x>".to_string(), 3, 10, 2.0, 40)],
+            weeks: Vec::new(),
+            months: vec![month("2026-09", 1_000), month("2026-10", 4_000)],
+            budgets: vec![BudgetStatus {
+                period: "daily",
+                window: "2026-10-18".to_string(),
+                used: 2_000,
+                budget: 1_000,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_sections() {
+        let md = render(&sample(), "md").unwrap();
+        assert!(md.starts_with("# RTK Token Savings Report"));
+        assert!(md.contains("| Tokens saved | 8.0K (80.0%) |"));
+        assert!(md.contains("| 1 | `rtk cargo test` | 12 | 7.0K | 92.5% | 800ms |"));
+        // Pipes in commands don't break the table
+        assert!(md.contains("`rtk grep a\\|b <x>`"));
+        assert!(md.contains("| daily | 2026-10-18 | 2.0K (200%) | 1.0K | exceeded |"));
+        assert!(md.contains("## Weekly Trend\n\nNo data."));
+        // Largest month gets the full bar
+        assert!(md.contains(&format!("`{}`", "█".repeat(MD_BAR_WIDTH))));
+        assert!(md.contains(&format!("`{}{}`", "█".repeat(5), "░".repeat(15))));
+    }
+
+    #[test]
+    fn test_html_report_escapes_and_charts() {
+        let html = render(&sample(), "html").unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<code>rtk grep a|b &lt;x&gt;</code>"));
+        assert!(!html.contains("<x>"));
+        assert_eq!(html.matches("<rect ").count(), 2);
+        assert!(html.contains("class=\"exceeded\""));
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        assert!(render(&sample(), "pdf").is_err());
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides token-optimized alternatives to verbose `gh` commands.
 //! Focuses on extracting essential information from JSON outputs.
 
+use crate::ci_log;
+use crate::exec::ExecResult;
 use crate::git;
 use crate::json_cmd;
 use crate::tracking;
@@ -12,7 +14,12 @@ use serde_json::Value;
 use std::process::Command;
 
 /// Run a gh command with token-optimized output
-pub fn run(subcommand: &str, args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
+pub fn run(
+    subcommand: &str,
+    args: &[String],
+    verbose: u8,
+    ultra_compact: bool,
+) -> Result<ExecResult> {
     match subcommand {
         "pr" => run_pr(args, verbose, ultra_compact),
         "issue" => run_issue(args, verbose, ultra_compact),
@@ -26,7 +33,7 @@ pub fn run(subcommand: &str, args: &[String], verbose: u8, ultra_compact: bool)
     }
 }
 
-fn run_pr(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
+fn run_pr(args: &[String], verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     if args.is_empty() {
         return run_passthrough("gh", "pr", args);
     }
@@ -45,7 +52,7 @@ fn run_pr(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
     }
 }
 
-fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
+fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -68,7 +75,7 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh pr list", "rtk gh pr list", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -126,10 +133,10 @@ fn list_prs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
     }
 
     timer.track("gh pr list", "rtk gh pr list", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
+fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if args.is_empty() {
@@ -159,7 +166,7 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
             &stderr,
         );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -299,10 +306,10 @@ fn view_pr(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         &raw,
         &filtered,
     );
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
+fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if args.is_empty() {
@@ -317,7 +324,8 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
     let output = cmd.output().context("Failed to run gh pr checks")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
 
-    if !output.status.success() {
+    // gh exits 1 for failing checks and 8 for pending ones: still a summary
+    if !output.status.success() && raw.trim().is_empty() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track(
             &format!("gh pr checks {}", pr_number),
@@ -326,7 +334,7 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
             &stderr,
         );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -385,10 +393,10 @@ fn pr_checks(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()>
         &raw,
         &filtered,
     );
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
+fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -406,7 +414,7 @@ fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh pr status", "rtk gh pr status", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -429,10 +437,10 @@ fn pr_status(_verbose: u8, _ultra_compact: bool) -> Result<()> {
     }
 
     timer.track("gh pr status", "rtk gh pr status", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_issue(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
+fn run_issue(args: &[String], verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     if args.is_empty() {
         return run_passthrough("gh", "issue", args);
     }
@@ -444,7 +452,7 @@ fn run_issue(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
     }
 }
 
-fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
+fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -461,7 +469,7 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh issue list", "rtk gh issue list", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -506,10 +514,10 @@ fn list_issues(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()>
     }
 
     timer.track("gh issue list", "rtk gh issue list", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
+fn view_issue(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if args.is_empty() {
@@ -539,7 +547,7 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
             &stderr,
         );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -592,10 +600,10 @@ fn view_issue(args: &[String], _verbose: u8) -> Result<()> {
         &raw,
         &filtered,
     );
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_workflow(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()> {
+fn run_workflow(args: &[String], verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     if args.is_empty() {
         return run_passthrough("gh", "run", args);
     }
@@ -607,7 +615,7 @@ fn run_workflow(args: &[String], verbose: u8, ultra_compact: bool) -> Result<()>
     }
 }
 
-fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
+fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -630,7 +638,7 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh run list", "rtk gh run list", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -687,19 +695,17 @@ fn list_runs(args: &[String], _verbose: u8, ultra_compact: bool) -> Result<()> {
     }
 
     timer.track("gh run list", "rtk gh run list", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// Check if run view args should bypass filtering and pass through directly.
 /// Flags like --log-failed, --log, and --json produce output that the filter
 /// would incorrectly strip.
 fn should_passthrough_run_view(extra_args: &[String]) -> bool {
-    extra_args
-        .iter()
-        .any(|a| a == "--log-failed" || a == "--log" || a == "--json")
+    extra_args.iter().any(|a| a == "--log" || a == "--json")
 }
 
-fn view_run(args: &[String], _verbose: u8) -> Result<()> {
+fn view_run(args: &[String], _verbose: u8) -> Result<ExecResult> {
     if args.is_empty() {
         return Err(anyhow::anyhow!("Run ID required"));
     }
@@ -707,7 +713,11 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
     let run_id = &args[0];
     let extra_args = &args[1..];
 
-    // Pass through when user requests logs or JSON — the filter would strip them
+    if extra_args.iter().any(|a| a == "--log-failed") {
+        return view_run_failed_logs(run_id, extra_args);
+    }
+
+    // Pass through when user requests full logs or JSON — the filter would strip them
     if should_passthrough_run_view(extra_args) {
         return run_passthrough_with_extra("gh", &["run", "view", run_id], extra_args);
     }
@@ -729,7 +739,7 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
             &stderr,
         );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Parse output and show only failures
@@ -770,10 +780,40 @@ fn view_run(args: &[String], _verbose: u8) -> Result<()> {
         &raw,
         &filtered,
     );
-    Ok(())
+    Ok(ExecResult::SUCCESS)
+}
+
+/// `gh run view --log-failed`: one failure report per job, each step's
+/// output through the matching rtk filter.
+fn view_run_failed_logs(run_id: &str, extra_args: &[String]) -> Result<ExecResult> {
+    let timer = tracking::TimedExecution::start();
+
+    let output = Command::new("gh")
+        .args(["run", "view", run_id])
+        .args(extra_args)
+        .output()
+        .context("Failed to run gh run view --log-failed")?;
+    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        eprintln!("{}", stderr.trim());
+        return Ok(ExecResult::from_status(&output.status));
+    }
+
+    let filtered = ci_log::format_failed_logs(run_id, &raw);
+    println!("{}", filtered);
+
+    timer.track(
+        &format!("gh run view {} {}", run_id, extra_args.join(" ")),
+        &format!("rtk gh run view {} {}", run_id, extra_args.join(" ")),
+        &raw,
+        &filtered,
+    );
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
+fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<ExecResult> {
     // Parse subcommand (default to "view")
     let (subcommand, rest_args) = if args.is_empty() {
         ("view", args)
@@ -806,7 +846,7 @@ fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh repo view", "rtk gh repo view", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let json: Value =
@@ -851,10 +891,10 @@ fn run_repo(args: &[String], _verbose: u8, _ultra_compact: bool) -> Result<()> {
     print!("{}", line);
 
     timer.track("gh repo view", "rtk gh repo view", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
+fn pr_create(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -870,7 +910,7 @@ fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
     if !output.status.success() {
         timer.track("gh pr create", "rtk gh pr create", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // gh pr create outputs the URL on success
@@ -889,10 +929,10 @@ fn pr_create(args: &[String], _verbose: u8) -> Result<()> {
     println!("{}", filtered);
 
     timer.track("gh pr create", "rtk gh pr create", &stdout, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
+fn pr_merge(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -908,7 +948,7 @@ fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
     if !output.status.success() {
         timer.track("gh pr merge", "rtk gh pr merge", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Extract PR number from args (first non-flag arg)
@@ -935,10 +975,10 @@ fn pr_merge(args: &[String], _verbose: u8) -> Result<()> {
     };
 
     timer.track("gh pr merge", "rtk gh pr merge", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn pr_diff(args: &[String], _verbose: u8) -> Result<()> {
+fn pr_diff(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -954,7 +994,7 @@ fn pr_diff(args: &[String], _verbose: u8) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh pr diff", "rtk gh pr diff", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let filtered = if raw.trim().is_empty() {
@@ -968,11 +1008,11 @@ fn pr_diff(args: &[String], _verbose: u8) -> Result<()> {
     };
 
     timer.track("gh pr diff", "rtk gh pr diff", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// Generic PR action handler for comment/edit
-fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
+fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -995,7 +1035,7 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
             &stderr,
         );
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Extract PR number from args
@@ -1021,10 +1061,10 @@ fn pr_action(action: &str, args: &[String], _verbose: u8) -> Result<()> {
         &raw,
         &filtered,
     );
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_api(args: &[String], _verbose: u8) -> Result<()> {
+fn run_api(args: &[String], _verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("gh");
@@ -1040,7 +1080,7 @@ fn run_api(args: &[String], _verbose: u8) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         timer.track("gh api", "rtk gh api", &stderr, &stderr);
         eprintln!("{}", stderr.trim());
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Try to parse as JSON and filter
@@ -1065,11 +1105,15 @@ fn run_api(args: &[String], _verbose: u8) -> Result<()> {
     };
 
     timer.track("gh api", "rtk gh api", &raw, &filtered);
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// Pass through a command with base args + extra args, tracking as passthrough.
-fn run_passthrough_with_extra(cmd: &str, base_args: &[&str], extra_args: &[String]) -> Result<()> {
+fn run_passthrough_with_extra(
+    cmd: &str,
+    base_args: &[&str],
+    extra_args: &[String],
+) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut command = Command::new(cmd);
@@ -1093,14 +1137,10 @@ fn run_passthrough_with_extra(cmd: &str, base_args: &[&str], extra_args: &[Strin
     );
     timer.track_passthrough(&full_cmd, &format!("rtk {} (passthrough)", full_cmd));
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
-fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<()> {
+fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut command = Command::new(cmd);
@@ -1119,11 +1159,7 @@ fn run_passthrough(cmd: &str, subcommand: &str, args: &[String]) -> Result<()> {
         &format!("rtk {} {} {} (passthrough)", cmd, subcommand, args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 #[cfg(test)]
@@ -1182,8 +1218,9 @@ mod tests {
     }
 
     #[test]
-    fn test_run_view_passthrough_log_failed() {
-        assert!(should_passthrough_run_view(&["--log-failed".into()]));
+    fn test_run_view_no_passthrough_log_failed() {
+        // --log-failed gets its own failure report
+        assert!(!should_passthrough_run_view(&["--log-failed".into()]));
     }
 
     #[test]
@@ -1,8 +1,21 @@
+use crate::exec::ExecResult;
+use crate::history;
+use crate::parser::{GitCommit, GitResult, ParseResult};
 use crate::tracking;
 use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::ffi::OsString;
 use std::process::Command;
 
+lazy_static! {
+    /// pre-commit framework status line of a hook that didn't fail
+    static ref HOOK_OK: Regex =
+        Regex::new(r"^.+?(\.{3,}|\s)(\(no files to check\))?(Passed|Skipped)$").unwrap();
+    /// A line of rtk's default `git log` format: `%h %s (%ar) <%an>`
+    static ref LOG_LINE: Regex = Regex::new(r"^([0-9a-f]{7,}) (.*) \((.+?)\) <(.*)>$").unwrap();
+}
+
 #[derive(Debug, Clone)]
 pub enum GitCommand {
     Diff,
@@ -10,33 +23,51 @@ pub enum GitCommand {
     Status,
     Show,
     Add,
-    Commit { message: String },
+    Commit,
     Push,
     Pull,
     Branch,
     Fetch,
     Stash { subcommand: Option<String> },
     Worktree,
+    Rebase,
+    Merge,
+    CherryPick,
+    Blame,
 }
 
-pub fn run(cmd: GitCommand, args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
+pub fn run(
+    cmd: GitCommand,
+    args: &[String],
+    max_lines: Option<usize>,
+    verbose: u8,
+) -> Result<ExecResult> {
     match cmd {
         GitCommand::Diff => run_diff(args, max_lines, verbose),
         GitCommand::Log => run_log(args, max_lines, verbose),
         GitCommand::Status => run_status(args, verbose),
         GitCommand::Show => run_show(args, max_lines, verbose),
         GitCommand::Add => run_add(args, verbose),
-        GitCommand::Commit { message } => run_commit(&message, verbose),
+        GitCommand::Commit => run_commit(args, verbose),
         GitCommand::Push => run_push(args, verbose),
         GitCommand::Pull => run_pull(args, verbose),
         GitCommand::Branch => run_branch(args, verbose),
         GitCommand::Fetch => run_fetch(args, verbose),
         GitCommand::Stash { subcommand } => run_stash(subcommand.as_deref(), args, verbose),
         GitCommand::Worktree => run_worktree(args, verbose),
+        GitCommand::Rebase => run_sequencer("rebase", args, verbose),
+        GitCommand::Merge => run_sequencer("merge", args, verbose),
+        GitCommand::CherryPick => run_sequencer("cherry-pick", args, verbose),
+        GitCommand::Blame => run_blame(args, verbose),
     }
 }
 
-fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
+fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<ExecResult> {
+    if args.iter().any(|arg| arg == "--semantic") {
+        let (rest, only) = split_semantic_args(args);
+        return run_semantic_diff(&rest, only.as_deref(), verbose);
+    }
+
     let timer = tracking::TimedExecution::start();
 
     // Check if user wants stat output
@@ -60,7 +91,7 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             eprintln!("{}", stderr);
-            std::process::exit(output.status.code().unwrap_or(1));
+            return Ok(ExecResult::from_status(&output.status));
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -73,7 +104,7 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
             &stdout,
         );
 
-        return Ok(());
+        return Ok(ExecResult::SUCCESS);
     }
 
     // Default RTK behavior: stat first, then compacted diff
@@ -85,6 +116,10 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     }
 
     let output = cmd.output().context("Failed to run git diff")?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
+    }
     let stat_stdout = String::from_utf8_lossy(&output.stdout);
 
     if verbose > 0 {
@@ -104,6 +139,9 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     let diff_output = diff_cmd.output().context("Failed to run git diff")?;
     let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
 
+    let (files, insertions, deletions) = parse_change_stats(&stat_stdout);
+    report_git_result("diff", (files, insertions, deletions), Vec::new());
+
     let mut final_output = stat_stdout.to_string();
     if !diff_stdout.is_empty() {
         println!("\n--- Changes ---");
@@ -120,10 +158,71 @@ fn run_diff(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         &final_output,
     );
 
-    Ok(())
+    // --exit-code / --quiet report differences through the status
+    Ok(ExecResult::from_status(&diff_output.status))
+}
+
+/// Remove `--semantic` and `--only <spec>` (rtk flags) from the git arguments.
+fn split_semantic_args(args: &[String]) -> (Vec<String>, Option<String>) {
+    let mut rest = Vec::new();
+    let mut only = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--semantic" {
+            continue;
+        } else if arg == "--only" {
+            only = iter.next().cloned();
+        } else if let Some(spec) = arg.strip_prefix("--only=") {
+            only = Some(spec.to_string());
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    (rest, only)
+}
+
+/// `git diff --semantic`: changes grouped per file and enclosing symbol, or
+/// the full hunks of one group with `--only path::symbol`.
+fn run_semantic_diff(args: &[String], only: Option<&str>, verbose: u8) -> Result<ExecResult> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut cmd = Command::new("git");
+    // Renames must be detected for the rename/move one-liners
+    cmd.arg("diff").arg("-M");
+    for arg in args {
+        cmd.arg(arg);
+    }
+
+    let output = cmd.output().context("Failed to run git diff")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() && stdout.is_empty() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
+    }
+
+    if verbose > 0 {
+        eprintln!("Git diff grouped by symbol:");
+    }
+
+    let filtered = match only {
+        Some(spec) => crate::semantic_diff::expand(&stdout, spec)?,
+        None => crate::semantic_diff::summarize(&stdout),
+    };
+    if !filtered.is_empty() {
+        println!("{}", filtered);
+    }
+
+    timer.track(
+        &format!("git diff {}", args.join(" ")),
+        &format!("rtk git diff --semantic {}", args.join(" ")),
+        &stdout,
+        &filtered,
+    );
+
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()> {
+fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // If user wants --stat or --format only, pass through
@@ -145,7 +244,7 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             eprintln!("{}", stderr);
-            std::process::exit(output.status.code().unwrap_or(1));
+            return Ok(ExecResult::from_status(&output.status));
         }
         let stdout = String::from_utf8_lossy(&output.stdout);
         println!("{}", stdout.trim());
@@ -157,7 +256,7 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
             &stdout,
         );
 
-        return Ok(());
+        return Ok(ExecResult::SUCCESS);
     }
 
     // Get raw output for tracking
@@ -181,7 +280,7 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     if !summary_output.status.success() {
         let stderr = String::from_utf8_lossy(&summary_output.stderr);
         eprintln!("{}", stderr);
-        std::process::exit(summary_output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&summary_output.status));
     }
     let summary = String::from_utf8_lossy(&summary_output.stdout);
     println!("{}", summary.trim());
@@ -226,7 +325,7 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
         &final_output,
     );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 pub(crate) fn compact_diff(diff: &str, max_lines: usize) -> String {
@@ -295,17 +394,26 @@ pub(crate) fn compact_diff(diff: &str, max_lines: usize) -> String {
     result.join("\n")
 }
 
-fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()> {
-    let timer = tracking::TimedExecution::start();
-
-    let mut cmd = Command::new("git");
-    cmd.arg("log");
-
+fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<ExecResult> {
     // Check if user provided format flags
     let has_format_flag = args.iter().any(|arg| {
         arg.starts_with("--oneline") || arg.starts_with("--pretty") || arg.starts_with("--format")
     });
 
+    // The history views parse their own format: a user format gets the regular log
+    if !has_format_flag
+        && args
+            .iter()
+            .any(|arg| arg.starts_with("-L") || arg == "--follow")
+    {
+        return run_log_history(args, verbose);
+    }
+
+    let timer = tracking::TimedExecution::start();
+
+    let mut cmd = Command::new("git");
+    cmd.arg("log");
+
     // Check if user provided limit flag
     let has_limit_flag = args
         .iter()
@@ -348,7 +456,7 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprintln!("{}", stderr);
         // Propagate git's exit code
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -357,6 +465,10 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         eprintln!("Git log output:");
     }
 
+    if !has_format_flag {
+        report_git_result("log", (0, 0, 0), parse_log_commits(&stdout));
+    }
+
     // Post-process: truncate long messages, cap lines
     let filtered = filter_log_output(&stdout, limit);
     println!("{}", filtered);
@@ -368,11 +480,83 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         &filtered,
     );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
+}
+
+/// History of a line range (`-L`) or of a file across renames (`--follow`),
+/// one legend line per commit.
+fn run_log_history(args: &[String], verbose: u8) -> Result<ExecResult> {
+    let timer = tracking::TimedExecution::start();
+    let line_range = args.iter().any(|arg| arg.starts_with("-L"));
+
+    let mut cmd = Command::new("git");
+    cmd.args(["log", history::HISTORY_FORMAT, "--date=short"]);
+    if !line_range {
+        cmd.arg("--numstat");
+    }
+    for arg in args {
+        cmd.arg(arg);
+    }
+
+    let output = cmd.output().context("Failed to run git log")?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if verbose > 0 {
+        eprintln!("Git log history:");
+    }
+
+    let filtered = if line_range {
+        history::format_line_history(&stdout)
+    } else {
+        history::format_follow_history(&stdout)
+    };
+    println!("{}", filtered);
+
+    timer.track(
+        &format!("git log {}", args.join(" ")),
+        &format!("rtk git log {}", args.join(" ")),
+        &stdout,
+        &filtered,
+    );
+
+    Ok(ExecResult::SUCCESS)
+}
+
+/// Commits from rtk's default `git log` format.
+fn parse_log_commits(output: &str) -> Vec<GitCommit> {
+    output
+        .lines()
+        .filter_map(|line| LOG_LINE.captures(line))
+        .map(|caps| GitCommit {
+            hash: caps[1].to_string(),
+            author: caps[4].to_string(),
+            message: caps[2].to_string(),
+            timestamp: Some(caps[3].to_string()),
+        })
+        .collect()
+}
+
+/// Record a `GitResult` for `--format json`.
+fn report_git_result(operation: &str, stats: (usize, usize, usize), commits: Vec<GitCommit>) {
+    let (files_changed, insertions, deletions) = stats;
+    crate::json_output::report_parsed(
+        "GitResult",
+        &ParseResult::Full(GitResult {
+            operation: operation.to_string(),
+            files_changed,
+            insertions,
+            deletions,
+            commits,
+        }),
+    );
 }
 
 /// Filter git log output: truncate long messages, cap lines
-fn filter_log_output(output: &str, limit: usize) -> String {
+pub(crate) fn filter_log_output(output: &str, limit: usize) -> String {
     let lines: Vec<&str> = output.lines().collect();
     let capped: Vec<String> = lines
         .iter()
@@ -487,7 +671,7 @@ fn format_status_output(porcelain: &str) -> String {
 }
 
 /// Minimal filtering for git status with user-provided args
-fn filter_status_with_args(output: &str) -> String {
+pub(crate) fn filter_status_with_args(output: &str) -> String {
     let mut result = Vec::new();
 
     for line in output.lines() {
@@ -523,7 +707,7 @@ fn filter_status_with_args(output: &str) -> String {
     }
 }
 
-fn run_status(args: &[String], verbose: u8) -> Result<()> {
+fn run_status(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // If user provided flags, apply minimal filtering
@@ -552,7 +736,7 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
             &filtered,
         );
 
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Default RTK compact mode (no args provided)
@@ -574,7 +758,13 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
     let formatted = if !stderr.is_empty() && stderr.contains("not a git repository") {
         "Not a git repository".to_string()
     } else {
-        format_status_output(&stdout)
+        let mut formatted = format_status_output(&stdout);
+        // Mid-rebase/merge: where we are and what conflicts
+        if let Some(summary) = crate::conflicts::summarize_in_progress().ok().flatten() {
+            formatted.push('\n');
+            formatted.push_str(&summary);
+        }
+        formatted
     };
 
     println!("{}", formatted);
@@ -582,10 +772,10 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
     // Track for statistics
     timer.track("git status", "rtk git status", &raw_output, &formatted);
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn run_add(args: &[String], verbose: u8) -> Result<()> {
+fn run_add(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("git");
@@ -634,7 +824,8 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
+        crate::preserve::track(
+            &timer,
             &format!("git add {}", args.join(" ")),
             &format!("rtk git add {}", args.join(" ")),
             &raw_output,
@@ -651,29 +842,48 @@ fn run_add(args: &[String], verbose: u8) -> Result<()> {
             eprintln!("{}", stdout);
         }
         // Propagate git's exit code
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_commit(message: &str, verbose: u8) -> Result<()> {
+fn run_commit(args: &[String], verbose: u8) -> Result<ExecResult> {
+    // git opens an editor for the message: it needs the terminal
+    if commit_needs_editor(args) {
+        let mut git_args = vec![OsString::from("commit")];
+        git_args.extend(args.iter().map(OsString::from));
+        return run_passthrough(&git_args, verbose);
+    }
+
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
-        eprintln!("git commit -m \"{}\"", message);
+        eprintln!("git commit {}", args.join(" "));
     }
 
     let output = Command::new("git")
-        .args(["commit", "-m", message])
+        .arg("commit")
+        .args(args)
         .output()
         .context("Failed to run git commit")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw_output = format!("{}\n{}", stdout, stderr);
+    let original_cmd = format!("git commit {}", args.join(" "));
 
-    if output.status.success() {
+    if output.status.success() && commit_is_dry_run(args) {
+        // --dry-run and friends print the would-be status, which is the point
+        println!("{}", stdout.trim());
+        crate::preserve::track(
+            &timer,
+            &original_cmd,
+            "rtk git commit",
+            &raw_output,
+            &stdout,
+        );
+    } else if output.status.success() {
         // Extract commit hash from output like "[main abc1234] message"
         let compact = if let Some(line) = stdout.lines().next() {
             if let (Some(open_bracket), Some(close_bracket)) = (line.find('['), line.find(']')) {
@@ -693,34 +903,114 @@ fn run_commit(message: &str, verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
-            &format!("git commit -m \"{}\"", message),
+        crate::preserve::track(
+            &timer,
+            &original_cmd,
             "rtk git commit",
             &raw_output,
             &compact,
         );
     } else if stderr.contains("nothing to commit") || stdout.contains("nothing to commit") {
         println!("ok (nothing to commit)");
-        timer.track(
-            &format!("git commit -m \"{}\"", message),
+        crate::preserve::track(
+            &timer,
+            &original_cmd,
             "rtk git commit",
             &raw_output,
             "ok (nothing to commit)",
         );
     } else {
+        // Hook output arrives on stderr (git redirects hook stdout there)
+        let filtered = filter_hook_output(&stderr);
         eprintln!("FAILED: git commit");
-        if !stderr.trim().is_empty() {
-            eprintln!("{}", stderr);
+        if !filtered.is_empty() {
+            eprintln!("{}", filtered);
         }
         if !stdout.trim().is_empty() {
             eprintln!("{}", stdout);
         }
+        crate::preserve::track(
+            &timer,
+            &original_cmd,
+            "rtk git commit",
+            &raw_output,
+            &format!("FAILED: git commit\n{}\n{}", filtered, stdout),
+        );
     }
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
+}
+
+/// Whether `git commit <args>` would open an editor for the message.
+fn commit_needs_editor(args: &[String]) -> bool {
+    let mut has_message = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--" => break,
+            "-e" | "--edit" => return true,
+            "--no-edit" => has_message = true,
+            "-m" | "--message" | "-F" | "--file" | "-C" | "--reuse-message" | "--fixup" => {
+                has_message = true;
+                iter.next();
+            }
+            a if a.starts_with("--message=")
+                || a.starts_with("--file=")
+                || a.starts_with("--reuse-message=")
+                || a.starts_with("--fixup=") =>
+            {
+                has_message = true
+            }
+            // Short flag clusters: -am "msg", -mmsg, -Ffile
+            a if a.starts_with('-') && !a.starts_with("--") => {
+                let flags = &a[1..];
+                if let Some(pos) = flags.find(['m', 'F', 'C']) {
+                    has_message = true;
+                    if pos == flags.len() - 1 {
+                        iter.next();
+                    }
+                } else if flags.contains('e') {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    !has_message && !commit_is_dry_run(args)
+}
+
+/// Flags that make `git commit` print status instead of committing.
+fn commit_is_dry_run(args: &[String]) -> bool {
+    args.iter().any(|arg| {
+        matches!(
+            arg.as_str(),
+            "--dry-run" | "--short" | "--porcelain" | "--long"
+        )
+    })
 }
 
-fn run_push(args: &[String], verbose: u8) -> Result<()> {
+/// Keep only the failing parts of pre-commit hook output: drops the
+/// "Passed"/"Skipped" lines of the pre-commit framework and lint-staged's
+/// progress lines.
+fn filter_hook_output(stderr: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in stderr.lines() {
+        let trimmed = line.trim();
+        let is_progress = HOOK_OK.is_match(trimmed)
+            || trimmed.starts_with('✔')
+            || trimmed.starts_with("[STARTED]")
+            || trimmed.starts_with("[COMPLETED]")
+            || trimmed.starts_with("[SUCCESS]")
+            || trimmed.starts_with("→ No staged files match");
+        let repeated_blank = trimmed.is_empty() && lines.last().is_none_or(|l| l.trim().is_empty());
+        if !is_progress && !repeated_blank {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn run_push(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -762,7 +1052,8 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
+        crate::preserve::track(
+            &timer,
             &format!("git push {}", args.join(" ")),
             &format!("rtk git push {}", args.join(" ")),
             &raw,
@@ -778,10 +1069,10 @@ fn run_push(args: &[String], verbose: u8) -> Result<()> {
         }
     }
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn run_pull(args: &[String], verbose: u8) -> Result<()> {
+fn run_pull(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -801,42 +1092,12 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
     let raw_output = format!("{}\n{}", stdout, stderr);
 
     if output.status.success() {
+        report_git_result("pull", parse_change_stats(&stdout), Vec::new());
         let compact =
             if stdout.contains("Already up to date") || stdout.contains("Already up-to-date") {
                 "ok (up-to-date)".to_string()
             } else {
-                // Count files changed
-                let mut files = 0;
-                let mut insertions = 0;
-                let mut deletions = 0;
-
-                for line in stdout.lines() {
-                    if line.contains("file") && line.contains("changed") {
-                        // Parse "3 files changed, 10 insertions(+), 2 deletions(-)"
-                        for part in line.split(',') {
-                            let part = part.trim();
-                            if part.contains("file") {
-                                files = part
-                                    .split_whitespace()
-                                    .next()
-                                    .and_then(|n| n.parse().ok())
-                                    .unwrap_or(0);
-                            } else if part.contains("insertion") {
-                                insertions = part
-                                    .split_whitespace()
-                                    .next()
-                                    .and_then(|n| n.parse().ok())
-                                    .unwrap_or(0);
-                            } else if part.contains("deletion") {
-                                deletions = part
-                                    .split_whitespace()
-                                    .next()
-                                    .and_then(|n| n.parse().ok())
-                                    .unwrap_or(0);
-                            }
-                        }
-                    }
-                }
+                let (files, insertions, deletions) = parse_change_stats(&stdout);
 
                 if files > 0 {
                     format!("ok ✓ {} files +{} -{}", files, insertions, deletions)
@@ -847,7 +1108,8 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
 
         println!("{}", compact);
 
-        timer.track(
+        crate::preserve::track(
+            &timer,
             &format!("git pull {}", args.join(" ")),
             &format!("rtk git pull {}", args.join(" ")),
             &raw_output,
@@ -863,10 +1125,206 @@ fn run_pull(args: &[String], verbose: u8) -> Result<()> {
         }
     }
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
+}
+
+/// Parse "3 files changed, 10 insertions(+), 2 deletions(-)" from pull/merge output.
+fn parse_change_stats(stdout: &str) -> (usize, usize, usize) {
+    let mut files = 0;
+    let mut insertions = 0;
+    let mut deletions = 0;
+
+    for line in stdout.lines() {
+        if line.contains("file") && line.contains("changed") {
+            for part in line.split(',') {
+                let part = part.trim();
+                let count = part
+                    .split_whitespace()
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(0);
+                if part.contains("file") {
+                    files = count;
+                } else if part.contains("insertion") {
+                    insertions = count;
+                } else if part.contains("deletion") {
+                    deletions = count;
+                }
+            }
+        }
+    }
+    (files, insertions, deletions)
+}
+
+/// `git rebase|merge|cherry-pick`: compact confirmation, or the operation's
+/// step and conflict regions when it stops.
+fn run_sequencer(subcommand: &str, args: &[String], verbose: u8) -> Result<ExecResult> {
+    if sequencer_needs_editor(args) {
+        let mut git_args = vec![OsString::from(subcommand)];
+        git_args.extend(args.iter().map(OsString::from));
+        return run_passthrough(&git_args, verbose);
+    }
+
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("git {} {}", subcommand, args.join(" "));
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg(subcommand).args(args);
+    // Output is captured, so no editor can run: keep the prepared messages
+    if std::env::var_os("GIT_EDITOR").is_none() {
+        cmd.env("GIT_EDITOR", "true");
+    }
+
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run git {}", subcommand))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let raw_output = format!("{}\n{}", stdout, stderr);
+
+    // Stopped on conflicts (or an `edit`/`break` step): show where and what
+    let summary = crate::conflicts::summarize_in_progress().ok().flatten();
+
+    let filtered = if let Some(summary) = summary {
+        // Git's own error (e.g. an unfinished merge refusing to start) comes first
+        let errors = sequencer_errors(&stderr);
+        if !errors.is_empty() {
+            println!("{}", errors);
+        }
+        println!("{}", summary);
+        if errors.is_empty() {
+            summary
+        } else {
+            format!("{}\n{}", errors, summary)
+        }
+    } else if output.status.success() {
+        let compact = format_sequencer_ok(&stdout, &stderr, args);
+        println!("{}", compact);
+        compact
+    } else {
+        eprintln!("FAILED: git {}", subcommand);
+        if !stderr.trim().is_empty() {
+            eprintln!("{}", stderr);
+        }
+        if !stdout.trim().is_empty() {
+            eprintln!("{}", stdout);
+        }
+        raw_output.clone()
+    };
+
+    crate::preserve::track(
+        &timer,
+        &format!("git {} {}", subcommand, args.join(" ")),
+        &format!("rtk git {} {}", subcommand, args.join(" ")),
+        &raw_output,
+        &filtered,
+    );
+
+    Ok(ExecResult::from_status(&output.status))
+}
+
+/// The todo list (`-i`, `--edit-todo`) and `-e/--edit` messages need the terminal.
+fn sequencer_needs_editor(args: &[String]) -> bool {
+    args.iter().any(|a| {
+        matches!(
+            a.as_str(),
+            "-i" | "--interactive" | "--edit-todo" | "-e" | "--edit"
+        )
+    })
+}
+
+/// Stderr without git's `hint:` advice.
+fn sequencer_errors(stderr: &str) -> String {
+    stderr
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty() && !l.starts_with("hint:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_sequencer_ok(stdout: &str, stderr: &str, args: &[String]) -> String {
+    let text = format!("{}\n{}", stdout, stderr);
+    if args.iter().any(|a| a == "--abort" || a == "--quit") {
+        return "ok (aborted)".to_string();
+    }
+    if text.contains("Already up to date")
+        || text.contains("Already up-to-date")
+        || text.contains(" is up to date.")
+    {
+        return "ok (up-to-date)".to_string();
+    }
+    // The line follows "\r"-separated progress output on the same line
+    if let Some(pos) = text.find("Successfully rebased") {
+        let line = text[pos..].lines().next().unwrap_or("");
+        let branch = line
+            .rsplit("refs/heads/")
+            .next()
+            .filter(|_| line.contains("refs/heads/"))
+            .map(|b| b.trim_end_matches('.'));
+        return match branch {
+            Some(branch) => format!("ok ✓ rebased {}", branch),
+            None => "ok ✓ rebased".to_string(),
+        };
+    }
+    let (files, insertions, deletions) = parse_change_stats(stdout);
+    let kind = if stdout.contains("Fast-forward") {
+        " fast-forward"
+    } else {
+        ""
+    };
+    if files > 0 {
+        format!(
+            "ok ✓{} {} files +{} -{}",
+            kind, files, insertions, deletions
+        )
+    } else {
+        format!("ok ✓{}", kind)
+    }
+}
+
+/// `git blame` with lines of one commit collapsed into ranges and a
+/// legend of the commits. Accepts `file:10-40` for `-L 10,40 file`.
+fn run_blame(args: &[String], verbose: u8) -> Result<ExecResult> {
+    let timer = tracking::TimedExecution::start();
+    let blame_args = history::blame_args(args);
+
+    if verbose > 0 {
+        eprintln!("git blame --porcelain {}", blame_args.join(" "));
+    }
+
+    let output = Command::new("git")
+        .args(["blame", "--porcelain"])
+        .args(&blame_args)
+        .output()
+        .context("Failed to run git blame")?;
+
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let filtered = history::format_blame(&stdout);
+    println!("{}", filtered);
+
+    // What plain `git blame` would have printed, for the savings estimate
+    let raw = history::plain_blame(&stdout);
+    timer.track(
+        &format!("git blame {}", args.join(" ")),
+        &format!("rtk git blame {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_branch(args: &[String], verbose: u8) -> Result<()> {
+fn run_branch(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -914,7 +1372,7 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
                 eprintln!("{}", stdout);
             }
         }
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // List mode: show compact branch list
@@ -924,6 +1382,10 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
     }
 
     let output = cmd.output().context("Failed to run git branch")?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
+    }
     let stdout = String::from_utf8_lossy(&output.stdout);
     let raw = stdout.to_string();
 
@@ -937,10 +1399,10 @@ fn run_branch(args: &[String], verbose: u8) -> Result<()> {
         &filtered,
     );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn filter_branch_output(output: &str) -> String {
+pub(crate) fn filter_branch_output(output: &str) -> String {
     let mut current = String::new();
     let mut local: Vec<String> = Vec::new();
     let mut remote: Vec<String> = Vec::new();
@@ -994,7 +1456,7 @@ fn filter_branch_output(output: &str) -> String {
     result.join("\n")
 }
 
-fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
+fn run_fetch(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -1017,7 +1479,7 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
         if !stderr.trim().is_empty() {
             eprintln!("{}", stderr);
         }
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Count new refs from stderr (git fetch outputs to stderr)
@@ -1033,19 +1495,19 @@ fn run_fetch(args: &[String], verbose: u8) -> Result<()> {
     };
 
     println!("{}", msg);
-    timer.track("git fetch", "rtk git fetch", &raw, &msg);
+    crate::preserve::track(&timer, "git fetch", "rtk git fetch", &raw, &msg);
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<()> {
+fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("git stash {:?}", subcommand);
     }
 
-    match subcommand {
+    let output = match subcommand {
         Some("list") => {
             let output = Command::new("git")
                 .args(["stash", "list"])
@@ -1058,12 +1520,13 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 let msg = "No stashes";
                 println!("{}", msg);
                 timer.track("git stash list", "rtk git stash list", &raw, msg);
-                return Ok(());
+                return Ok(ExecResult::from_status(&output.status));
             }
 
             let filtered = filter_stash_list(&stdout);
             println!("{}", filtered);
             timer.track("git stash list", "rtk git stash list", &raw, &filtered);
+            output
         }
         Some("show") => {
             let mut cmd = Command::new("git");
@@ -1086,6 +1549,7 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
             };
 
             timer.track("git stash show", "rtk git stash show", &raw, &filtered);
+            output
         }
         Some("pop") | Some("apply") | Some("drop") | Some("push") => {
             let sub = subcommand.unwrap();
@@ -1111,12 +1575,14 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 combined.clone()
             };
 
-            timer.track(
+            crate::preserve::track(
+                &timer,
                 &format!("git stash {}", sub),
                 &format!("rtk git stash {}", sub),
                 &combined,
                 &msg,
             );
+            output
         }
         _ => {
             // Default: git stash (push)
@@ -1148,11 +1614,12 @@ fn run_stash(subcommand: Option<&str>, args: &[String], verbose: u8) -> Result<(
                 combined.clone()
             };
 
-            timer.track("git stash", "rtk git stash", &combined, &msg);
+            crate::preserve::track(&timer, "git stash", "rtk git stash", &combined, &msg);
+            output
         }
-    }
+    };
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 fn filter_stash_list(output: &str) -> String {
@@ -1176,7 +1643,7 @@ fn filter_stash_list(output: &str) -> String {
     result.join("\n")
 }
 
-fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
+fn run_worktree(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -1220,7 +1687,7 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
                 eprintln!("{}", stderr);
             }
         }
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     // Default: list mode
@@ -1236,7 +1703,7 @@ fn run_worktree(args: &[String], verbose: u8) -> Result<()> {
     println!("{}", filtered);
     timer.track("git worktree list", "rtk git worktree", &raw, &filtered);
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 fn filter_worktree_list(output: &str) -> String {
@@ -1267,7 +1734,7 @@ fn filter_worktree_list(output: &str) -> String {
 }
 
 /// Runs an unsupported git subcommand by passing it through directly
-pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -1284,10 +1751,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
         &format!("rtk git {} (passthrough)", args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 #[cfg(test)]
@@ -1427,6 +1891,123 @@ M  file7.rs
         // Compile-time verification that the function exists with correct signature
     }
 
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_commit_needs_editor() {
+        for args in [
+            &["-m", "msg"][..],
+            &["-am", "msg"],
+            &["-mmsg"],
+            &["--message=msg", "-m", "second paragraph"],
+            &["-F", "msg.txt"],
+            &["--amend", "--no-edit"],
+            &["--fixup", "abc1234"],
+            &["--fixup=amend:abc1234", "--no-verify"],
+            &["-C", "HEAD"],
+            &["--dry-run"],
+        ] {
+            assert!(!commit_needs_editor(&strings(args)), "{:?}", args);
+        }
+        for args in [
+            &[][..],
+            &["--amend"],
+            &["-a"],
+            &["-m", "msg", "-e"],
+            &["--", "-m"],
+        ] {
+            assert!(commit_needs_editor(&strings(args)), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_format_sequencer_ok() {
+        let no_args: Vec<String> = Vec::new();
+        assert_eq!(
+            format_sequencer_ok(
+                "",
+                "Rebasing (3/3)\r\rSuccessfully rebased and updated refs/heads/feature.\n",
+                &no_args
+            ),
+            "ok ✓ rebased feature"
+        );
+        assert_eq!(
+            format_sequencer_ok(
+                "Updating abc..def\nFast-forward\n a.rs | 2 +-\n 1 file changed, 1 insertion(+), 1 deletion(-)\n",
+                "",
+                &no_args
+            ),
+            "ok ✓ fast-forward 1 files +1 -1"
+        );
+        assert_eq!(
+            format_sequencer_ok("Already up to date.\n", "", &no_args),
+            "ok (up-to-date)"
+        );
+        assert_eq!(
+            format_sequencer_ok("", "", &strings(&["--abort"])),
+            "ok (aborted)"
+        );
+    }
+
+    #[test]
+    fn test_sequencer_needs_editor() {
+        assert!(sequencer_needs_editor(&strings(&["-i", "main"])));
+        assert!(sequencer_needs_editor(&strings(&["-e", "abc123"])));
+        assert!(sequencer_needs_editor(&strings(&["--edit", "feature"])));
+        assert!(!sequencer_needs_editor(&strings(&["--no-edit", "feature"])));
+        assert!(!sequencer_needs_editor(&strings(&["--continue"])));
+    }
+
+    #[test]
+    fn test_sequencer_errors_drop_hints() {
+        let stderr = "error: You have not concluded your merge (MERGE_HEAD exists).\nhint: Please, commit your changes before merging.\nfatal: Exiting because of unfinished merge.\n";
+        assert_eq!(
+            sequencer_errors(stderr),
+            "error: You have not concluded your merge (MERGE_HEAD exists).\nfatal: Exiting because of unfinished merge."
+        );
+        assert_eq!(sequencer_errors("hint: only advice\n"), "");
+    }
+
+    #[test]
+    fn test_filter_hook_output() {
+        let stderr = "\
+check yaml...............................................................Passed
+trim trailing whitespace.................................................Passed
+mypy.................................................(no files to check)Skipped
+ruff.....................................................................Failed
+- hook id: ruff
+- exit code: 1
+
+src/app.py:3:1: F401 `os` imported but unused
+Found 1 error.
+
+";
+        assert_eq!(
+            filter_hook_output(stderr),
+            "ruff.....................................................................Failed\n\
+             - hook id: ruff\n\
+             - exit code: 1\n\
+             \n\
+             src/app.py:3:1: F401 `os` imported but unused\n\
+             Found 1 error."
+        );
+
+        let lint_staged = "\
+[STARTED] Preparing lint-staged...
+[COMPLETED] Preparing lint-staged...
+✔ Running tasks for staged files...
+✖ eslint --fix:
+  src/a.ts  1:7  error  'x' is assigned a value but never used
+husky - pre-commit script failed (code 1)";
+        let filtered = filter_hook_output(lint_staged);
+        assert!(!filtered.contains("Preparing"));
+        assert!(!filtered.contains("✔"));
+        assert!(filtered.starts_with("✖ eslint --fix:"));
+        assert!(filtered.ends_with("husky - pre-commit script failed (code 1)"));
+    }
+
     #[test]
     fn test_filter_log_output() {
         let output = "abc1234 This is a commit message (2 days ago) <author>\ndef5678 Another commit (1 week ago) <other>\n";
@@ -1436,6 +2017,18 @@ M  file7.rs
         assert_eq!(result.lines().count(), 2);
     }
 
+    #[test]
+    fn test_parse_log_commits() {
+        let output =
+            "abc1234 fix(parser): handle (nested) parens (2 days ago) <Jane Doe>\nnot a log line\n";
+        let commits = parse_log_commits(output);
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].hash, "abc1234");
+        assert_eq!(commits[0].message, "fix(parser): handle (nested) parens");
+        assert_eq!(commits[0].timestamp.as_deref(), Some("2 days ago"));
+        assert_eq!(commits[0].author, "Jane Doe");
+    }
+
     #[test]
     fn test_filter_log_output_truncate_long() {
         let long_line = "abc1234 ".to_string() + &"x".repeat(100) + " (2 days ago) <author>";
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -31,7 +32,7 @@ struct PackageResult {
     failed_tests: Vec<(String, Vec<String>)>, // (test_name, output_lines)
 }
 
-pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
+pub fn run_test(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("go");
@@ -50,19 +51,15 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: go test -json {}", args.join(" "));
     }
 
-    let output = cmd
-        .output()
+    let output = crate::stream::run_streaming(&mut cmd, live_go_failure)
         .context("Failed to run go test. Is Go installed?")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = &output.stdout;
+    let stderr = &output.stderr;
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let filtered = filter_go_test_json(&stdout);
+    let exit_code = output.exit_code;
+    let filtered = output.dedupe(&filter_go_test_json(stdout));
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_test", exit_code) {
         println!("{}\n{}", filtered, hint);
@@ -75,22 +72,37 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("{}", stderr.trim());
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("go test {}", args.join(" ")),
         &format!("rtk go test {}", args.join(" ")),
         &raw,
-        &filtered,
+        &output.printed(&filtered),
+        exit_code,
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
+    Ok(ExecResult::code(exit_code))
+}
 
-    Ok(())
+/// Print each failed test as soon as go reports it
+fn live_go_failure(line: &str) -> Option<String> {
+    let event: GoTestEvent = serde_json::from_str(line.trim()).ok()?;
+    if event.action != "fail" {
+        return None;
+    }
+    let package = event.package.unwrap_or_default();
+    let elapsed = event
+        .elapsed
+        .map(|e| format!(" ({:.2}s)", e))
+        .unwrap_or_default();
+    Some(match event.test {
+        Some(test) => format!("FAIL {} {}{}", package, test, elapsed),
+        None => format!("FAIL {}{}", package, elapsed),
+    })
 }
 
-pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
+pub fn run_build(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("go");
@@ -112,10 +124,7 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = crate::exec::exit_code(&output.status);
     let filtered = filter_go_build(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_build", exit_code) {
@@ -128,22 +137,20 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
         println!("{}", filtered);
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("go build {}", args.join(" ")),
         &format!("rtk go build {}", args.join(" ")),
         &raw,
         &filtered,
+        exit_code,
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
-
-    Ok(())
+    Ok(ExecResult::code(exit_code))
 }
 
-pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
+pub fn run_vet(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("go");
@@ -165,10 +172,7 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = crate::exec::exit_code(&output.status);
     let filtered = filter_go_vet(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_vet", exit_code) {
@@ -181,22 +185,20 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
         println!("{}", filtered);
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("go vet {}", args.join(" ")),
         &format!("rtk go vet {}", args.join(" ")),
         &raw,
         &filtered,
+        exit_code,
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
-
-    Ok(())
+    Ok(ExecResult::code(exit_code))
 }
 
-pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_other(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     if args.is_empty() {
         anyhow::bail!("go: no subcommand specified");
     }
@@ -226,7 +228,8 @@ pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
     print!("{}", stdout);
     eprint!("{}", stderr);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("go {}", subcommand),
         &format!("rtk go {}", subcommand),
         &raw,
@@ -234,15 +237,11 @@ pub fn run_other(args: &[OsString], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Parse go test -json output (NDJSON format)
-fn filter_go_test_json(output: &str) -> String {
+pub(crate) fn filter_go_test_json(output: &str) -> String {
     let mut packages: HashMap<String, PackageResult> = HashMap::new();
     let mut current_test_output: HashMap<(String, String), Vec<String>> = HashMap::new(); // (package, test) -> outputs
 
@@ -261,10 +260,8 @@ fn filter_go_test_json(output: &str) -> String {
         let pkg_result = packages.entry(package.clone()).or_default();
 
         match event.action.as_str() {
-            "pass" => {
-                if event.test.is_some() {
-                    pkg_result.pass += 1;
-                }
+            "pass" if event.test.is_some() => {
+                pkg_result.pass += 1;
             }
             "fail" => {
                 if let Some(test) = &event.test {
@@ -276,10 +273,8 @@ fn filter_go_test_json(output: &str) -> String {
                     pkg_result.failed_tests.push((test.clone(), outputs));
                 }
             }
-            "skip" => {
-                if event.test.is_some() {
-                    pkg_result.skip += 1;
-                }
+            "skip" if event.test.is_some() => {
+                pkg_result.skip += 1;
             }
             "output" => {
                 // Collect output for current test
@@ -366,7 +361,7 @@ fn filter_go_test_json(output: &str) -> String {
 }
 
 /// Filter go build output - show only errors
-fn filter_go_build(output: &str) -> String {
+pub(crate) fn filter_go_build(output: &str) -> String {
     let mut errors: Vec<String> = Vec::new();
 
     for line in output.lines() {
@@ -408,8 +403,52 @@ fn filter_go_build(output: &str) -> String {
     result.trim().to_string()
 }
 
+/// Filter plain-text go test output (CI logs, no -json) - failures only
+pub(crate) fn filter_go_test_text(output: &str) -> String {
+    let mut failures: Vec<String> = Vec::new();
+    let mut failed_packages = 0;
+    let mut passed_packages = 0;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("ok ") || trimmed.starts_with("ok\t") {
+            passed_packages += 1;
+        } else if trimmed.starts_with("FAIL\t") || trimmed.starts_with("FAIL ") {
+            failed_packages += 1;
+            failures.push(trimmed.to_string());
+        } else if trimmed.starts_with("--- FAIL")
+            || trimmed.starts_with("panic:")
+            || trimmed.contains(".go:")
+        {
+            failures.push(trimmed.to_string());
+        }
+    }
+
+    if failures.is_empty() {
+        return format!("✓ Go test: {} packages passed", passed_packages);
+    }
+
+    let mut result = String::new();
+    result.push_str(&format!(
+        "Go test: {} packages failed, {} passed\n",
+        failed_packages, passed_packages
+    ));
+    result.push_str("═══════════════════════════════════════\n");
+
+    for failure in failures.iter().take(30) {
+        result.push_str(&format!("{}\n", truncate(failure, 120)));
+    }
+
+    if failures.len() > 30 {
+        result.push_str(&format!("\n... +{} more lines\n", failures.len() - 30));
+    }
+
+    result.trim().to_string()
+}
+
 /// Filter go vet output - show issues
-fn filter_go_vet(output: &str) -> String {
+pub(crate) fn filter_go_vet(output: &str) -> String {
     let mut issues: Vec<String> = Vec::new();
 
     for line in output.lines() {
@@ -454,6 +493,19 @@ fn compact_package_name(package: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_live_go_failure() {
+        let fail =
+            r#"{"Action":"fail","Package":"example.com/foo","Test":"TestBar","Elapsed":0.5}"#;
+        assert_eq!(
+            live_go_failure(fail).as_deref(),
+            Some("FAIL example.com/foo TestBar (0.50s)")
+        );
+        let pass = r#"{"Action":"pass","Package":"example.com/foo","Test":"TestBar"}"#;
+        assert!(live_go_failure(pass).is_none());
+        assert!(live_go_failure("# example.com/foo").is_none());
+    }
+
     #[test]
     fn test_filter_go_test_all_pass() {
         let output = r#"{"Time":"2024-01-01T10:00:00Z","Action":"run","Package":"example.com/foo","Test":"TestBar"}
@@ -501,6 +553,25 @@ main.go:15:2: cannot use x (type int) as type string"#;
         assert!(result.contains("cannot use x"));
     }
 
+    #[test]
+    fn test_filter_go_test_text() {
+        let output = "=== RUN   TestAdd
+--- FAIL: TestAdd (0.00s)
+    math_test.go:12: expected 4, got 5
+=== RUN   TestSub
+--- PASS: TestSub (0.00s)
+FAIL
+FAIL\texample.com/math\t0.004s
+ok  \texample.com/util\t0.002s";
+
+        let result = filter_go_test_text(output);
+        assert!(result.starts_with("Go test: 1 packages failed, 1 passed"));
+        assert!(result.contains("--- FAIL: TestAdd"));
+        assert!(result.contains("math_test.go:12: expected 4, got 5"));
+        assert!(!result.contains("TestSub"));
+        assert!(filter_go_test_text("ok  \texample.com/util\t0.002s").contains("✓"));
+    }
+
     #[test]
     fn test_filter_go_vet_no_issues() {
         let output = "";
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -34,7 +35,7 @@ struct GolangciOutput {
     issues: Vec<Issue>,
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("golangci-lint");
@@ -70,25 +71,27 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    // Include stderr if present (config errors, etc.)
-    if !stderr.trim().is_empty() && verbose > 0 {
+    // golangci-lint exits 1 when issues are found; anything above is a real
+    // failure (config errors, etc.) whose stderr must stay visible
+    let result = ExecResult::from_status(&output.status);
+    if !stderr.trim().is_empty() && (verbose > 0 || result.exit_code > 1) {
         eprintln!("{}", stderr.trim());
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("golangci-lint {}", args.join(" ")),
         &format!("rtk golangci-lint {}", args.join(" ")),
         &raw,
         &filtered,
+        result.exit_code,
     );
 
-    // golangci-lint returns exit code 1 when issues found (expected behavior)
-    // Don't exit with error code in that case
-    Ok(())
+    Ok(result)
 }
 
 /// Filter golangci-lint JSON output - group by linter and file
-fn filter_golangci_json(output: &str) -> String {
+pub(crate) fn filter_golangci_json(output: &str) -> String {
     let result: Result<GolangciOutput, _> = serde_json::from_str(output);
 
     let golangci_output = match result {
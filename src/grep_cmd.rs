@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
@@ -15,7 +16,7 @@ pub struct GrepOptions<'a> {
     pub verbose: u8,
 }
 
-pub fn run(opts: GrepOptions<'_>) -> Result<()> {
+pub fn run(opts: GrepOptions<'_>) -> Result<ExecResult> {
     let GrepOptions {
         pattern,
         path,
@@ -50,6 +51,8 @@ pub fn run(opts: GrepOptions<'_>) -> Result<()> {
         .context("grep/rg failed")?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
+    // Bad pattern, unreadable path: rg/grep exit 2 and explain on stderr
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
 
     let raw_output = stdout.to_string();
 
@@ -62,9 +65,37 @@ pub fn run(opts: GrepOptions<'_>) -> Result<()> {
             &raw_output,
             &msg,
         );
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
+    let rtk_output = format_matches(
+        &stdout,
+        pattern,
+        path,
+        max_line_len,
+        max_results,
+        context_only,
+    );
+    print!("{}", rtk_output);
+    timer.track(
+        &format!("grep -rn '{}' {}", pattern, path),
+        "rtk grep",
+        &raw_output,
+        &rtk_output,
+    );
+
+    Ok(ExecResult::from_status(&output.status))
+}
+
+/// Group `file:line:content` matches by file, capped at `max_results` lines.
+pub(crate) fn format_matches(
+    stdout: &str,
+    pattern: &str,
+    path: &str,
+    max_line_len: usize,
+    max_results: usize,
+    context_only: bool,
+) -> String {
     let mut by_file: HashMap<String, Vec<(usize, String)>> = HashMap::new();
     let mut total = 0;
 
@@ -118,16 +149,7 @@ pub fn run(opts: GrepOptions<'_>) -> Result<()> {
     if total > shown {
         rtk_output.push_str(&format!("... +{}\n", total - shown));
     }
-
-    print!("{}", rtk_output);
-    timer.track(
-        &format!("grep -rn '{}' {}", pattern, path),
-        "rtk grep",
-        &raw_output,
-        &rtk_output,
-    );
-
-    Ok(())
+    rtk_output
 }
 
 fn clean_line(line: &str, max_len: usize, context_only: bool, pattern: &str) -> String {
@@ -208,6 +230,23 @@ mod tests {
         assert!(cleaned.len() <= 50);
     }
 
+    #[test]
+    fn test_format_matches_groups_by_file() {
+        let out = format_matches(
+            "src/b.rs:3:  let x = 1;\nsrc/a.rs:10:fn x()\nsrc/b.rs:7:x += 1\n",
+            "x",
+            ".",
+            80,
+            50,
+            false,
+        );
+        assert!(out.starts_with("🔍 3 in 2F:\n"));
+        let a = out.find("📄 src/a.rs (1):").unwrap();
+        let b = out.find("📄 src/b.rs (2):").unwrap();
+        assert!(a < b);
+        assert!(out.contains("     3: let x = 1;\n"));
+    }
+
     #[test]
     fn test_compact_path() {
         let path = "/Users/patrick/dev/project/src/components/Button.tsx";
@@ -0,0 +1,496 @@
+//! Compact `git blame` and `git log -L` / `--follow` views.
+//!
+//! Both print each commit once, in a legend line shaped like `rtk git log`
+//! output (`abc1234 subject (date) <author>`), and refer to it by short hash
+//! everywhere else instead of repeating hash, author and date per line.
+
+use crate::utils::truncate;
+use std::collections::HashMap;
+
+/// Subjects are cut so author and date stay visible
+const MAX_SUBJECT_WIDTH: usize = 60;
+/// Changed lines shown per commit in `log -L` history
+const MAX_HISTORY_LINES: usize = 20;
+/// Record and field separators of the `log` format used for history
+const RECORD_SEP: char = '\x1e';
+const FIELD_SEP: char = '\x1f';
+/// `git log --format` producing records [`parse_history`] understands
+pub(crate) const HISTORY_FORMAT: &str = "--format=%x1e%h%x1f%s%x1f%an%x1f%ad";
+
+/// Commits in first-seen order, printed once each.
+#[derive(Debug, Default)]
+struct CommitLegend {
+    entries: Vec<LegendEntry>,
+    index: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone)]
+struct LegendEntry {
+    short: String,
+    subject: String,
+    author: String,
+    date: String,
+}
+
+impl CommitLegend {
+    fn insert(&mut self, entry: LegendEntry) {
+        if !self.index.contains_key(&entry.short) {
+            self.index.insert(entry.short.clone(), self.entries.len());
+            self.entries.push(entry);
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.entries.iter().map(legend_line).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+fn legend_line(entry: &LegendEntry) -> String {
+    if entry.short == NOT_COMMITTED {
+        return format!("{} not committed yet", entry.short);
+    }
+    format!(
+        "{} {} ({}) <{}>",
+        entry.short,
+        truncate(&entry.subject, MAX_SUBJECT_WIDTH),
+        entry.date,
+        entry.author
+    )
+}
+
+const NOT_COMMITTED: &str = "0000000";
+
+/// One line of `git blame --porcelain` output.
+#[derive(Debug)]
+struct BlameLine {
+    short: String,
+    number: usize,
+    content: String,
+}
+
+/// "1714550400" + "+0200" → "2024-05-01" in the author's timezone.
+fn format_epoch(epoch: &str, tz: &str) -> String {
+    let Ok(secs) = epoch.parse::<i64>() else {
+        return String::new();
+    };
+    let offset = tz
+        .get(1..)
+        .filter(|digits| digits.len() == 4)
+        .and_then(|digits| {
+            let hours: i64 = digits[..2].parse().ok()?;
+            let minutes: i64 = digits[2..].parse().ok()?;
+            let sign = if tz.starts_with('-') { -1 } else { 1 };
+            Some(sign * (hours * 3600 + minutes * 60))
+        })
+        .unwrap_or(0);
+    chrono::DateTime::from_timestamp(secs + offset, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Parse `git blame --porcelain` into lines and the legend of their commits.
+fn parse_blame(porcelain: &str) -> (Vec<BlameLine>, CommitLegend) {
+    let mut lines = Vec::new();
+    let mut legend = CommitLegend::default();
+    // Header fields come once, with the first line blamed on each commit
+    let mut headers: HashMap<String, LegendEntry> = HashMap::new();
+    let mut current: Option<(String, usize)> = None;
+    let mut epoch = String::new();
+
+    for line in porcelain.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((hash, number)) = current.take() {
+                let short: String = hash.chars().take(7).collect();
+                if let Some(entry) = headers.get(&hash) {
+                    legend.insert(entry.clone());
+                }
+                lines.push(BlameLine {
+                    short,
+                    number,
+                    content: content.to_string(),
+                });
+            }
+            continue;
+        }
+
+        let mut parts = line.split(' ');
+        let first = parts.next().unwrap_or("");
+        if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            let number = parts.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+            headers
+                .entry(first.to_string())
+                .or_insert_with(|| LegendEntry {
+                    short: first.chars().take(7).collect(),
+                    subject: String::new(),
+                    author: String::new(),
+                    date: String::new(),
+                });
+            current = Some((first.to_string(), number));
+            continue;
+        }
+
+        let Some((hash, _)) = &current else {
+            continue;
+        };
+        let Some(entry) = headers.get_mut(hash) else {
+            continue;
+        };
+        let value = line.split_once(' ').map_or("", |(_, v)| v);
+        match first {
+            "author" => entry.author = value.to_string(),
+            "author-time" => epoch = value.to_string(),
+            "author-tz" => entry.date = format_epoch(&epoch, value),
+            "summary" => entry.subject = value.to_string(),
+            _ => {}
+        }
+    }
+    (lines, legend)
+}
+
+/// Blame with consecutive lines of one commit collapsed under a single
+/// hash, followed by the commit legend.
+pub(crate) fn format_blame(porcelain: &str) -> String {
+    let (lines, legend) = parse_blame(porcelain);
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let width = lines.last().map_or(1, |l| l.number.to_string().len());
+    let mut out = Vec::new();
+    let mut previous: Option<&str> = None;
+    let mut ranges = 0;
+    for line in &lines {
+        let label = if previous == Some(line.short.as_str()) {
+            " ".repeat(line.short.len())
+        } else {
+            ranges += 1;
+            line.short.clone()
+        };
+        previous = Some(&line.short);
+        out.push(
+            format!(
+                "{} {:>width$}| {}",
+                label,
+                line.number,
+                line.content,
+                width = width
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+
+    let first = lines.first().map_or(0, |l| l.number);
+    let last = lines.last().map_or(0, |l| l.number);
+    out.insert(
+        0,
+        format!(
+            "L{}-{}: {} from {}",
+            first,
+            last,
+            plural(ranges, "range"),
+            plural(legend.len(), "commit")
+        ),
+    );
+    out.push(String::new());
+    out.extend(legend.lines());
+    out.join("\n")
+}
+
+/// Size stand-in for plain `git blame` output, rebuilt from the porcelain
+/// so the savings estimate doesn't need a second blame run. Columns keep
+/// git's widths (8-char hash, padded author, `YYYY-MM-DD HH:MM:SS +ZZZZ`).
+pub(crate) fn plain_blame(porcelain: &str) -> String {
+    let (lines, legend) = parse_blame(porcelain);
+    let author_width = legend
+        .entries
+        .iter()
+        .map(|e| e.author.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = lines.last().map_or(1, |l| l.number.to_string().len());
+
+    let mut out = String::new();
+    for line in &lines {
+        let (author, date) = legend
+            .index
+            .get(&line.short)
+            .map(|&i| &legend.entries[i])
+            .map_or(("", ""), |e| (e.author.as_str(), e.date.as_str()));
+        out.push_str(&format!(
+            "{:<8} ({:<author_width$} {:<25} {:>width$}) {}\n",
+            line.short,
+            author,
+            date,
+            line.number,
+            line.content,
+            author_width = author_width,
+            width = width
+        ));
+    }
+    out
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+/// Turn `file:10-40` / `file:10,+5` into `-L 10,40 file`; other arguments
+/// are kept. A path that really contains `:<digits>` can still be blamed
+/// with an explicit `-L`.
+pub(crate) fn blame_args(args: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    for arg in args {
+        if let Some((path, range)) = split_range(arg) {
+            out.push("-L".to_string());
+            out.push(range);
+            out.push(path.to_string());
+        } else {
+            out.push(arg.clone());
+        }
+    }
+    out
+}
+
+fn split_range(arg: &str) -> Option<(&str, String)> {
+    if arg.starts_with('-') {
+        return None;
+    }
+    let (path, range) = arg.rsplit_once(':')?;
+    if path.is_empty() || std::path::Path::new(arg).exists() {
+        return None;
+    }
+    let (start, end) = match range.split_once(['-', ',']) {
+        Some((start, end)) => (start, Some(end)),
+        None => (range, None),
+    };
+    let start: usize = start.parse().ok()?;
+    let range = match end {
+        None => format!("{},{}", start, start),
+        Some(end) if end.starts_with('+') => {
+            let count: usize = end[1..].parse().ok()?;
+            format!("{},+{}", start, count)
+        }
+        Some(end) => format!("{},{}", start, end.parse::<usize>().ok()?),
+    };
+    Some((path, range))
+}
+
+/// One commit of `git log <HISTORY_FORMAT>` output, with what followed the
+/// header (patch for `-L`, numstat for `--follow`).
+struct HistoryRecord<'a> {
+    entry: LegendEntry,
+    body: &'a str,
+}
+
+fn parse_history(output: &str) -> Vec<HistoryRecord<'_>> {
+    output
+        .split(RECORD_SEP)
+        .filter(|r| !r.trim().is_empty())
+        .filter_map(|record| {
+            let (header, body) = record.split_once('\n').unwrap_or((record, ""));
+            let mut fields = header.split(FIELD_SEP);
+            Some(HistoryRecord {
+                entry: LegendEntry {
+                    short: fields.next()?.to_string(),
+                    subject: fields.next()?.to_string(),
+                    author: fields.next()?.to_string(),
+                    date: fields.next()?.to_string(),
+                },
+                body,
+            })
+        })
+        .collect()
+}
+
+/// `git log -L`: each commit's legend line and the lines it changed.
+pub(crate) fn format_line_history(output: &str) -> String {
+    let mut out = Vec::new();
+    for record in parse_history(output) {
+        out.push(legend_line(&record.entry));
+        let changed: Vec<&str> = record
+            .body
+            .lines()
+            .filter(|l| {
+                l.starts_with("@@")
+                    || (l.starts_with('+') && !l.starts_with("+++"))
+                    || (l.starts_with('-') && !l.starts_with("---"))
+            })
+            .collect();
+        for line in changed.iter().take(MAX_HISTORY_LINES) {
+            out.push(format!("  {}", line));
+        }
+        if changed.len() > MAX_HISTORY_LINES {
+            out.push(format!(
+                "  ... +{} lines",
+                changed.len() - MAX_HISTORY_LINES
+            ));
+        }
+    }
+    out.join("\n")
+}
+
+/// `git log --follow --numstat`: one line per commit with its line counts
+/// and the renames `--follow` went through.
+pub(crate) fn format_follow_history(output: &str) -> String {
+    let mut out = Vec::new();
+    for record in parse_history(output) {
+        let mut line = legend_line(&record.entry);
+        for stat in record.body.lines() {
+            let mut fields = stat.splitn(3, '\t');
+            let (Some(added), Some(removed), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            line.push_str(&format!("  +{} -{}", added, removed));
+            if path.contains(" => ") {
+                line.push_str(&format!(" (renamed {})", path));
+            }
+        }
+        out.push(line);
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLAME: &str = "\
+1111111111111111111111111111111111111111 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1714550400
+author-tz +0200
+committer Alice
+summary Add main
+filename src/main.rs
+\tfn main() {
+1111111111111111111111111111111111111111 2 2
+\t    let x = 1;
+2222222222222222222222222222222222222222 3 3 1
+author Bob
+author-time 1717200000
+author-tz -0500
+summary Add y
+filename src/main.rs
+\t    let y = 2;
+1111111111111111111111111111111111111111 3 4 1
+\t}
+";
+
+    #[test]
+    fn test_format_blame_collapses_ranges() {
+        assert_eq!(
+            format_blame(BLAME),
+            "\
+L1-4: 3 ranges from 2 commits
+1111111 1| fn main() {
+        2|     let x = 1;
+2222222 3|     let y = 2;
+1111111 4| }
+
+1111111 Add main (2024-05-01) <Alice>
+2222222 Add y (2024-05-31) <Bob>"
+        );
+    }
+
+    #[test]
+    fn test_plain_blame_matches_git_layout() {
+        let plain = plain_blame(BLAME);
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "1111111  (Alice 2024-05-01                1) fn main() {"
+        );
+        assert_eq!(
+            lines[2],
+            "2222222  (Bob   2024-05-31                3)     let y = 2;"
+        );
+        // Same length as git's "11111111 (Alice 2024-05-01 10:00:00 +0200 1) fn main() {"
+        assert_eq!(
+            lines[0].len(),
+            "11111111 (Alice 2024-05-01 10:00:00 +0200 1) fn main() {".len()
+        );
+    }
+
+    #[test]
+    fn test_blame_args_ranges() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            blame_args(&args(&["src/nope.rs:10-40"])),
+            args(&["-L", "10,40", "src/nope.rs"])
+        );
+        assert_eq!(
+            blame_args(&args(&["-w", "src/nope.rs:10,+5"])),
+            args(&["-w", "-L", "10,+5", "src/nope.rs"])
+        );
+        assert_eq!(
+            blame_args(&args(&["src/nope.rs:7"])),
+            args(&["-L", "7,7", "src/nope.rs"])
+        );
+        assert_eq!(
+            blame_args(&args(&["HEAD~1", "--", "src/nope.rs"])),
+            args(&["HEAD~1", "--", "src/nope.rs"])
+        );
+        assert_eq!(blame_args(&args(&["a:b"])), args(&["a:b"]));
+    }
+
+    #[test]
+    fn test_format_line_history() {
+        let output = "\x1eabc1234\x1fFix overflow\x1fAlice\x1f2024-05-01
+diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -10,3 +10,3 @@ fn add(a: u8, b: u8) -> u8 {
+ fn add(a: u8, b: u8) -> u8 {
+-    a + b
++    a.saturating_add(b)
+ }
+\x1edef5678\x1fAdd add\x1fBob\x1f2024-04-01
+diff --git a/src/a.rs b/src/a.rs
+--- /dev/null
++++ b/src/a.rs
+@@ -0,0 +10,3 @@
++fn add(a: u8, b: u8) -> u8 {
++    a + b
++}
+";
+        assert_eq!(
+            format_line_history(output),
+            "\
+abc1234 Fix overflow (2024-05-01) <Alice>
+  @@ -10,3 +10,3 @@ fn add(a: u8, b: u8) -> u8 {
+  -    a + b
+  +    a.saturating_add(b)
+def5678 Add add (2024-04-01) <Bob>
+  @@ -0,0 +10,3 @@
+  +fn add(a: u8, b: u8) -> u8 {
+  +    a + b
+  +}"
+        );
+    }
+
+    #[test]
+    fn test_format_follow_history() {
+        let output = "\x1eabc1234\x1fTweak\x1fAlice\x1f2024-05-01
+
+3\t1\tsrc/new.rs
+\x1edef5678\x1fMove file\x1fBob\x1f2024-04-01
+
+0\t0\tsrc/{old.rs => new.rs}
+";
+        assert_eq!(
+            format_follow_history(output),
+            "\
+abc1234 Tweak (2024-05-01) <Alice>  +3 -1
+def5678 Move file (2024-04-01) <Bob>  +0 -0 (renamed src/{old.rs => new.rs})"
+        );
+    }
+}
@@ -2,8 +2,8 @@ use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-/// Default log file location (aligned with hook's $HOME/.local/share/rtk/).
-fn default_log_path() -> PathBuf {
+/// Default log file location, shared with the `rtk rewrite` hook writer.
+pub fn default_log_path() -> PathBuf {
     if let Ok(dir) = std::env::var("RTK_AUDIT_DIR") {
         PathBuf::from(dir).join("hook-audit.log")
     } else {
@@ -141,7 +141,7 @@ pub fn run(since_days: u64, verbose: u8) -> Result<()> {
 
     if !skip_actions.is_empty() {
         let mut sorted_skips = skip_actions;
-        sorted_skips.sort_by(|a, b| b.1.cmp(&a.1));
+        sorted_skips.sort_by_key(|b| std::cmp::Reverse(b.1));
         for (action, count) in &sorted_skips {
             let reason = action.strip_prefix("skip:").unwrap_or(action);
             println!(
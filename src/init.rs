@@ -4,7 +4,7 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
-// Embedded hook script (guards before set -euo pipefail)
+// Embedded hook shim (delegates to `rtk hook pretooluse`)
 const REWRITE_HOOK: &str = include_str!("../hooks/rtk-rewrite.sh");
 
 // Embedded slim RTK awareness instructions
@@ -310,7 +310,7 @@ fn prompt_user_consent(settings_path: &Path) -> Result<bool> {
     Ok(response == "y" || response == "yes")
 }
 
-fn shell_escape_for_hook(value: &str) -> String {
+pub(crate) fn shell_escape_for_hook(value: &str) -> String {
     if value.is_empty() {
         return "''".to_string();
     }
@@ -706,8 +706,8 @@ fn hook_already_present(root: &serde_json::Value, hook_command: &str) -> bool {
             .any(|token| token.ends_with("rtk-rewrite.sh"))
     };
 
-    let expected_hook_path = extract_hook_path_from_command(hook_command)
-        .map(|path| normalize_hook_path(&path));
+    let expected_hook_path =
+        extract_hook_path_from_command(hook_command).map(|path| normalize_hook_path(&path));
 
     let pre_tool_use_array = match root
         .get("hooks")
@@ -1109,18 +1109,23 @@ pub fn show_config() -> Result<()> {
             let is_executable = perms.mode() & 0o111 != 0;
 
             let hook_content = fs::read_to_string(&hook_path)?;
-            let has_guards =
-                hook_content.contains("command -v rtk") && hook_content.contains("command -v jq");
+            let is_native = hook_content.contains("hook pretooluse");
 
-            if is_executable && has_guards {
-                println!("✅ Hook: {} (executable, with guards)", hook_path.display());
+            if is_executable && is_native {
+                println!(
+                    "✅ Hook: {} (executable, native rewrite)",
+                    hook_path.display()
+                );
             } else if !is_executable {
                 println!(
                     "⚠️  Hook: {} (NOT executable - run: chmod +x)",
                     hook_path.display()
                 );
             } else {
-                println!("⚠️  Hook: {} (no guards - outdated)", hook_path.display());
+                println!(
+                    "⚠️  Hook: {} (legacy bash/jq hook - run: rtk init -g)",
+                    hook_path.display()
+                );
             }
         }
 
@@ -1242,16 +1247,12 @@ mod tests {
     }
 
     #[test]
-    fn test_hook_has_guards() {
-        assert!(REWRITE_HOOK.contains("command -v rtk"));
-        assert!(REWRITE_HOOK.contains("command -v jq"));
-        // Guards must be BEFORE set -euo pipefail
-        let guard_pos = REWRITE_HOOK.find("command -v rtk").unwrap();
-        let set_pos = REWRITE_HOOK.find("set -euo pipefail").unwrap();
-        assert!(
-            guard_pos < set_pos,
-            "Guards must come before set -euo pipefail"
-        );
+    fn test_hook_delegates_to_native_rewrite() {
+        assert!(REWRITE_HOOK.contains("hook pretooluse"));
+        // No jq dependency: the shim must fail open when rtk is missing
+        assert!(!REWRITE_HOOK.contains("jq"));
+        assert!(REWRITE_HOOK.contains("command -v"));
+        assert!(REWRITE_HOOK.contains("|| exit 0"));
     }
 
     #[test]
@@ -0,0 +1,267 @@
+//! `rtk --format json <command>`: structured output for CI bots and wrappers.
+//!
+//! The command runs as a child rtk in text mode and its filtered stdout becomes
+//! a generic envelope (`command`, `exit_code`, `summary`, `lines`, `truncated`,
+//! `tee_path`). Commands with a parser (cargo, vitest, playwright, pnpm, lint,
+//! git diff/log/pull) additionally hand their parsed struct back through the
+//! file named by `RTK_JSON_REPORT`, which is emitted as `data`. The
+//! `ParseResult` tier and warnings are always included.
+
+use crate::exec::ExecResult;
+use crate::parser::ParseResult;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::process::{Command, Stdio};
+
+/// Env var pointing the child at the file where parsed data is reported.
+const REPORT_ENV: &str = "RTK_JSON_REPORT";
+
+/// Max filtered lines included in the generic envelope.
+const MAX_LINES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+lazy_static! {
+    static ref ELIDED: Regex =
+        Regex::new(r"(^\s*\.\.\. ?\+\d+ more|\[RTK:PASSTHROUGH\]|\d+ lines omitted)").unwrap();
+}
+
+/// Parsed data handed from the child to the parent process.
+#[derive(Debug, Serialize, Deserialize)]
+struct ParsedReport {
+    #[serde(rename = "type")]
+    kind: String,
+    tier: u8,
+    warnings: Vec<String>,
+    data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct Envelope {
+    command: String,
+    exit_code: i32,
+    /// 1 = full structured parse, 2 = degraded, 3 = passthrough
+    tier: u8,
+    warnings: Vec<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<serde_json::Value>,
+    summary: String,
+    lines: Vec<String>,
+    truncated: bool,
+    tee_path: Option<String>,
+}
+
+/// Record a parser result for `--format json`. No-op in normal text mode.
+pub fn report_parsed<T: Serialize>(kind: &str, result: &ParseResult<T>) {
+    let Ok(path) = std::env::var(REPORT_ENV) else {
+        return;
+    };
+
+    let report = match result {
+        ParseResult::Full(data) => ParsedReport {
+            kind: kind.to_string(),
+            tier: 1,
+            warnings: Vec::new(),
+            data: serde_json::to_value(data).ok(),
+        },
+        ParseResult::Degraded(data, warnings) => ParsedReport {
+            kind: kind.to_string(),
+            tier: 2,
+            warnings: warnings.clone(),
+            data: serde_json::to_value(data).ok(),
+        },
+        ParseResult::Passthrough(_) => ParsedReport {
+            kind: kind.to_string(),
+            tier: 3,
+            warnings: vec![format!("{} parser failed; lines are raw output", kind)],
+            data: None,
+        },
+    };
+
+    if let Ok(json) = serde_json::to_string(&report) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Re-run this invocation in text mode and print it as one JSON object.
+pub fn run_wrapped() -> Result<ExecResult> {
+    let args = strip_format_flag(std::env::args_os().skip(1).collect());
+    let command = command_label(&args);
+
+    let report_file = tempfile::NamedTempFile::new().context("Failed to create report file")?;
+    let exe = std::env::current_exe().context("Failed to locate rtk executable")?;
+    let output = Command::new(exe)
+        .args(&args)
+        .env(REPORT_ENV, report_file.path())
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Failed to run rtk in text mode")?;
+
+    let exit_code = crate::exec::exit_code(&output.status);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report = std::fs::read_to_string(report_file.path())
+        .ok()
+        .and_then(|json| serde_json::from_str::<ParsedReport>(&json).ok());
+
+    let envelope = build_envelope(&command, exit_code, &stdout, report);
+    println!("{}", serde_json::to_string_pretty(&envelope)?);
+
+    Ok(ExecResult::code(exit_code))
+}
+
+/// Drop the top-level `--format json` / `--format=json` so the child runs in text mode.
+fn strip_format_flag(args: Vec<OsString>) -> Vec<OsString> {
+    let mut out = Vec::with_capacity(args.len());
+    let mut iter = args.into_iter();
+    let mut stripped = false;
+    while let Some(arg) = iter.next() {
+        if !stripped {
+            let s = arg.to_string_lossy();
+            if s == "--format" {
+                iter.next();
+                stripped = true;
+                continue;
+            }
+            if s.starts_with("--format=") {
+                stripped = true;
+                continue;
+            }
+        }
+        out.push(arg);
+    }
+    out
+}
+
+/// Command line without leading rtk global flags, e.g. "cargo test".
+fn command_label(args: &[OsString]) -> String {
+    args.iter()
+        .map(|a| a.to_string_lossy().into_owned())
+        .skip_while(|a| a.starts_with('-'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn build_envelope(
+    command: &str,
+    exit_code: i32,
+    stdout: &str,
+    report: Option<ParsedReport>,
+) -> Envelope {
+    let mut tee_path = None;
+    let mut lines: Vec<String> = Vec::new();
+    for line in stdout.lines() {
+        if let Some((path, _)) = crate::tee::parse_hint(line) {
+            tee_path = Some(expand_home(&path));
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    let mut truncated = lines.iter().any(|l| ELIDED.is_match(l));
+    if lines.len() > MAX_LINES {
+        lines.truncate(MAX_LINES);
+        truncated = true;
+    }
+
+    let summary = lines
+        .iter()
+        .find(|l| !l.trim().is_empty())
+        .cloned()
+        .unwrap_or_default();
+
+    let (tier, warnings, kind, data) = match report {
+        Some(r) => (r.tier, r.warnings, Some(r.kind), r.data),
+        None => {
+            let passthrough = lines.iter().any(|l| l.contains("[RTK:PASSTHROUGH]"));
+            (
+                if passthrough { 3 } else { 2 },
+                vec!["no structured parser for this command; lines are filtered text".to_string()],
+                None,
+                None,
+            )
+        }
+    };
+
+    Envelope {
+        command: command.to_string(),
+        exit_code,
+        tier,
+        warnings,
+        kind,
+        data,
+        summary,
+        lines,
+        truncated,
+        tee_path,
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_strip_format_flag() {
+        assert_eq!(
+            strip_format_flag(os(&["--format", "json", "git", "log", "--format", "%h"])),
+            os(&["git", "log", "--format", "%h"])
+        );
+        assert_eq!(
+            strip_format_flag(os(&["-v", "--format=json", "cargo", "test"])),
+            os(&["-v", "cargo", "test"])
+        );
+    }
+
+    #[test]
+    fn test_command_label_skips_global_flags() {
+        assert_eq!(command_label(&os(&["-v", "cargo", "test"])), "cargo test");
+    }
+
+    #[test]
+    fn test_generic_envelope() {
+        let stdout = "3 errors\nsrc/a.rs:1 bad\n... +4 more\n[full output: /tmp/tee/abc123.log | rtk tee show abc123]\n";
+        let env = build_envelope("cargo build", 101, stdout, None);
+        assert_eq!(env.summary, "3 errors");
+        assert_eq!(env.lines.len(), 3);
+        assert!(env.truncated);
+        assert_eq!(env.tee_path.as_deref(), Some("/tmp/tee/abc123.log"));
+        assert_eq!(env.tier, 2);
+        assert!(env.data.is_none());
+    }
+
+    #[test]
+    fn test_parsed_envelope_keeps_tier_and_data() {
+        let report = ParsedReport {
+            kind: "TestResult".to_string(),
+            tier: 2,
+            warnings: vec!["JSON parse failed".to_string()],
+            data: Some(serde_json::json!({"passed": 3})),
+        };
+        let env = build_envelope("vitest run", 0, "PASS (3) FAIL (0)\n", Some(report));
+        assert_eq!(env.tier, 2);
+        assert_eq!(env.kind.as_deref(), Some("TestResult"));
+        assert_eq!(env.data.unwrap()["passed"], 3);
+        assert!(!env.truncated);
+    }
+}
@@ -0,0 +1,480 @@
+//! Machine-readable corrections learned from agent sessions.
+//!
+//! `rtk learn --apply` merges detected rules into `corrections.toml` next to
+//! the global config; the rewrite hook, `rtk proxy` and `rtk mcp` consult it so
+//! known-bad invocations are fixed (a mistyped command or subcommand such as
+//! `git stauts`) or rejected with the right form before they run.
+//! `rtk learn --revert` undoes the last `--apply`.
+
+use crate::discover::registry::{split_command_chain, split_command_prefix, split_command_words};
+use crate::learn::detector::{extract_base_command, CorrectionRule};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CORRECTIONS_FILE: &str = "corrections.toml";
+
+/// Used when neither `--min-confidence` nor the file sets a threshold
+pub const DEFAULT_MIN_CONFIDENCE: f64 = 0.6;
+
+/// Rewrites only touch the command and subcommand words, never arguments
+const REWRITE_POSITIONS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorrectionAction {
+    /// Replace the mistyped command/subcommand word and run the fixed command
+    Rewrite,
+    /// Block the command and tell the agent the right form
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Correction {
+    /// Base command the rule applies to (`git commit`)
+    pub base: String,
+    /// Wrong command/subcommand word (rewrite) or full wrong command (reject)
+    pub wrong: String,
+    pub right: String,
+    pub action: CorrectionAction,
+    pub confidence: f64,
+    pub occurrences: usize,
+    #[serde(default)]
+    pub error_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrectionsFile {
+    /// Rules below this confidence are kept but not enforced
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+    #[serde(default, rename = "correction")]
+    pub corrections: Vec<Correction>,
+}
+
+fn default_min_confidence() -> f64 {
+    DEFAULT_MIN_CONFIDENCE
+}
+
+/// Result of checking a command line against the corrections file
+#[derive(Debug, PartialEq)]
+pub enum Checked {
+    Unchanged,
+    /// Fixed command plus one `wrong -> right` note per applied rule
+    Rewritten(String, Vec<String>),
+    /// Explanation naming the right form
+    Rejected(String),
+}
+
+pub fn corrections_path() -> PathBuf {
+    if let Ok(path) = std::env::var("RTK_CORRECTIONS_PATH") {
+        if !path.is_empty() {
+            return PathBuf::from(path);
+        }
+    }
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rtk")
+        .join(CORRECTIONS_FILE)
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("toml.bak")
+}
+
+impl CorrectionsFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                min_confidence: default_min_confidence(),
+                corrections: Vec::new(),
+            });
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut content = String::from(
+            "# CLI corrections learned by `rtk learn --apply` (consulted by the hook and rtk proxy)\n\
+             # action = \"rewrite\" fixes the token; \"reject\" blocks the command with the right form\n\n",
+        );
+        content.push_str(&toml::to_string_pretty(self)?);
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Merge detected rules: new ones are added, known ones get fresh counts.
+    /// The stored threshold only changes when `--min-confidence` was given.
+    pub fn merge(&mut self, rules: &[CorrectionRule], min_confidence: Option<f64>) -> usize {
+        if let Some(min_confidence) = min_confidence {
+            self.min_confidence = min_confidence;
+        }
+        let mut added = 0;
+        for rule in rules {
+            let Some(new) = Correction::from_rule(rule) else {
+                continue;
+            };
+            match self
+                .corrections
+                .iter_mut()
+                .find(|c| c.base == new.base && c.wrong == new.wrong)
+            {
+                Some(existing) => {
+                    existing.right = new.right;
+                    existing.confidence = existing.confidence.max(new.confidence);
+                    existing.occurrences = existing.occurrences.max(new.occurrences);
+                }
+                None => {
+                    self.corrections.push(new);
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+
+    /// Check every segment of a command chain
+    pub fn check(&self, cmd: &str) -> Checked {
+        let active: Vec<&Correction> = self
+            .corrections
+            .iter()
+            .filter(|c| c.confidence >= self.min_confidence)
+            .collect();
+        if active.is_empty() {
+            return Checked::Unchanged;
+        }
+
+        // Words are slices of `cmd`: fixes are spliced in, everything else stays verbatim
+        let mut rebuilt = String::with_capacity(cmd.len());
+        let mut cursor = 0;
+        let mut notes = Vec::new();
+        for segment in split_command_chain(cmd) {
+            let (_, core) = split_command_prefix(segment);
+            let words = split_command_words(core);
+            let normalized = words.join(" ");
+            let base = extract_base_command(core);
+
+            for rule in active.iter().filter(|c| c.base == base) {
+                if rule.action == CorrectionAction::Reject && normalized == rule.wrong {
+                    return Checked::Rejected(format!(
+                        "`{}` is a known-bad invocation (seen {}x); use `{}` instead",
+                        rule.wrong, rule.occurrences, rule.right
+                    ));
+                }
+            }
+
+            for word in words.iter().take(REWRITE_POSITIONS) {
+                let Some(rule) = active.iter().find(|c| {
+                    c.action == CorrectionAction::Rewrite && c.base == base && c.wrong == *word
+                }) else {
+                    continue;
+                };
+                let start = word.as_ptr() as usize - cmd.as_ptr() as usize;
+                rebuilt.push_str(&cmd[cursor..start]);
+                rebuilt.push_str(&rule.right);
+                cursor = start + word.len();
+                notes.push(format!("{} -> {}", rule.wrong, rule.right));
+            }
+        }
+
+        if notes.is_empty() {
+            return Checked::Unchanged;
+        }
+        rebuilt.push_str(&cmd[cursor..]);
+        Checked::Rewritten(rebuilt, notes)
+    }
+}
+
+impl CorrectionsFile {
+    /// Argv flavour of [`check`](Self::check) for `rtk proxy`: the command and
+    /// subcommand are swapped in place so argument boundaries survive. Returns
+    /// the applied notes, or the rejection reason.
+    pub fn correct_argv(&self, argv: &mut [String]) -> std::result::Result<Vec<String>, String> {
+        let joined = argv.join(" ");
+        let normalized = normalize(&joined);
+        let base = extract_base_command(&joined);
+        let mut notes = Vec::new();
+        for rule in self
+            .corrections
+            .iter()
+            .filter(|c| c.confidence >= self.min_confidence && c.base == base)
+        {
+            match rule.action {
+                CorrectionAction::Reject if normalized == rule.wrong => {
+                    return Err(format!(
+                        "`{}` is a known-bad invocation (seen {}x); use `{}` instead",
+                        rule.wrong, rule.occurrences, rule.right
+                    ));
+                }
+                CorrectionAction::Rewrite => {
+                    if let Some(arg) = argv
+                        .iter_mut()
+                        .take(REWRITE_POSITIONS)
+                        .find(|a| **a == rule.wrong)
+                    {
+                        *arg = rule.right.clone();
+                        notes.push(format!("{} -> {}", rule.wrong, rule.right));
+                    }
+                }
+                CorrectionAction::Reject => {}
+            }
+        }
+        Ok(notes)
+    }
+}
+
+impl Correction {
+    /// A single swapped command or subcommand word becomes a rewrite; anything
+    /// else is rejected with the whole right command as the suggestion.
+    fn from_rule(rule: &CorrectionRule) -> Option<Self> {
+        let wrong = normalize(&rule.wrong_pattern);
+        let right = normalize(&rule.right_pattern);
+        if wrong.is_empty() || wrong == right {
+            return None;
+        }
+
+        let wrong_tokens = split_command_words(&wrong);
+        let right_tokens = split_command_words(&right);
+        let swapped: Vec<(usize, &str, &str)> = wrong_tokens
+            .iter()
+            .zip(&right_tokens)
+            .enumerate()
+            .filter(|(_, (w, r))| w != r)
+            .map(|(i, (w, r))| (i, *w, *r))
+            .collect();
+        let base = extract_base_command(&wrong);
+
+        let (wrong, right, action) = match swapped.as_slice() {
+            [(i, w, r)] if wrong_tokens.len() == right_tokens.len() && *i < REWRITE_POSITIONS => {
+                (w.to_string(), r.to_string(), CorrectionAction::Rewrite)
+            }
+            _ => (wrong, right, CorrectionAction::Reject),
+        };
+        Some(Correction {
+            base,
+            wrong,
+            right,
+            action,
+            confidence: rule.confidence,
+            occurrences: rule.occurrences,
+            error_type: rule.error_type.as_str().to_string(),
+        })
+    }
+}
+
+fn normalize(cmd: &str) -> String {
+    split_command_words(cmd).join(" ")
+}
+
+/// Check `cmd` against the user's corrections file (missing or invalid file: no-op)
+pub fn check_command(cmd: &str) -> Checked {
+    CorrectionsFile::load(&corrections_path())
+        .map(|file| file.check(cmd))
+        .unwrap_or(Checked::Unchanged)
+}
+
+/// [`CorrectionsFile::correct_argv`] against the user's corrections file
+pub fn correct_argv(argv: &mut [String]) -> std::result::Result<Vec<String>, String> {
+    match CorrectionsFile::load(&corrections_path()) {
+        Ok(file) => file.correct_argv(argv),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// `rtk learn --apply`: merge rules into the corrections file, keeping a
+/// backup. Returns a one-line summary for the caller to print.
+pub fn apply(rules: &[CorrectionRule], min_confidence: Option<f64>) -> Result<String> {
+    let path = corrections_path();
+    let backup = backup_path(&path);
+    if path.exists() {
+        fs::copy(&path, &backup)
+            .with_context(|| format!("Failed to back up {}", path.display()))?;
+    } else if backup.exists() {
+        fs::remove_file(&backup)?;
+    }
+
+    let mut file = CorrectionsFile::load(&path)?;
+    let added = file.merge(rules, min_confidence);
+    file.save(&path)?;
+
+    let rewrites = file
+        .corrections
+        .iter()
+        .filter(|c| c.action == CorrectionAction::Rewrite)
+        .count();
+    Ok(format!(
+        "Applied: {} new, {} total ({} rewrite, {} reject) -> {}",
+        added,
+        file.corrections.len(),
+        rewrites,
+        file.corrections.len() - rewrites,
+        path.display()
+    ))
+}
+
+/// `rtk learn --revert`: restore the file as it was before the last `--apply`
+pub fn revert() -> Result<()> {
+    let path = corrections_path();
+    let backup = backup_path(&path);
+    if backup.exists() {
+        fs::rename(&backup, &path)
+            .with_context(|| format!("Failed to restore {}", path.display()))?;
+        println!("Restored previous corrections: {}", path.display());
+    } else if path.exists() {
+        fs::remove_file(&path)?;
+        println!("Removed {}", path.display());
+    } else {
+        println!("No corrections file to revert");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learn::detector::ErrorType;
+
+    fn rule(wrong: &str, right: &str, confidence: f64) -> CorrectionRule {
+        CorrectionRule {
+            wrong_pattern: wrong.to_string(),
+            right_pattern: right.to_string(),
+            error_type: ErrorType::UnknownFlag,
+            occurrences: 2,
+            base_command: extract_base_command(wrong),
+            example_error: String::new(),
+            confidence,
+        }
+    }
+
+    fn file(rules: &[CorrectionRule]) -> CorrectionsFile {
+        let mut file = CorrectionsFile {
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+            corrections: Vec::new(),
+        };
+        file.merge(rules, None);
+        file
+    }
+
+    #[test]
+    fn test_from_rule_subcommand_swap_is_rewrite() {
+        let file = file(&[rule("git stauts --short", "git status --short", 0.9)]);
+        let c = &file.corrections[0];
+        assert_eq!(c.action, CorrectionAction::Rewrite);
+        assert_eq!(
+            (c.base.as_str(), c.wrong.as_str(), c.right.as_str()),
+            ("git stauts", "stauts", "status")
+        );
+    }
+
+    #[test]
+    fn test_from_rule_other_change_is_reject() {
+        let file = file(&[
+            rule("gh pr edit -t foo", "gh pr edit 12 --title foo", 0.8),
+            // An argument swap is not rewritten: the same word may be a value elsewhere
+            rule("git commit --ammend -m x", "git commit --amend -m x", 0.9),
+        ]);
+        assert!(file
+            .corrections
+            .iter()
+            .all(|c| c.action == CorrectionAction::Reject));
+        assert_eq!(file.corrections[0].wrong, "gh pr edit -t foo");
+        assert_eq!(file.corrections[1].right, "git commit --amend -m x");
+    }
+
+    #[test]
+    fn test_check_rewrites_subcommand_in_chain() {
+        let file = file(&[rule("git stauts --short", "git status --short", 0.9)]);
+        let checked = file.check("cd repo && GIT_EDITOR=true git stauts --short");
+        assert_eq!(
+            checked,
+            Checked::Rewritten(
+                "cd repo && GIT_EDITOR=true git status --short".to_string(),
+                vec!["stauts -> status".to_string()]
+            )
+        );
+        // Other base commands are untouched
+        assert_eq!(file.check("echo stauts"), Checked::Unchanged);
+    }
+
+    #[test]
+    fn test_check_keeps_quoting_and_arguments() {
+        let file = file(&[rule("git stauts", "git status", 0.9)]);
+        let Checked::Rewritten(fixed, _) = file.check(r#"git  stauts -- "a  b" 'stauts' stauts"#)
+        else {
+            panic!("expected rewrite");
+        };
+        assert_eq!(fixed, r#"git  status -- "a  b" 'stauts' stauts"#);
+    }
+
+    #[test]
+    fn test_check_rejects_known_bad_command() {
+        let file = file(&[rule("gh pr edit -t foo", "gh pr edit 12 --title foo", 0.8)]);
+        let Checked::Rejected(reason) = file.check("gh pr edit -t  foo") else {
+            panic!("expected rejection");
+        };
+        assert!(reason.contains("gh pr edit 12 --title foo"));
+    }
+
+    #[test]
+    fn test_check_respects_min_confidence() {
+        let mut file = file(&[rule("git stauts", "git status", 0.7)]);
+        file.min_confidence = 0.8;
+        assert_eq!(file.check("git stauts"), Checked::Unchanged);
+    }
+
+    #[test]
+    fn test_merge_updates_existing_and_roundtrips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrections.toml");
+
+        let mut file = file(&[rule("git stauts -s", "git status -s", 0.7)]);
+        file.min_confidence = 0.8;
+        assert_eq!(
+            file.merge(&[rule("git stauts", "git status", 0.9)], None),
+            0
+        );
+        assert_eq!(file.corrections[0].confidence, 0.9);
+        // The stored threshold survives a merge without --min-confidence
+        assert_eq!(file.min_confidence, 0.8);
+        file.save(&path).unwrap();
+
+        let mut loaded = CorrectionsFile::load(&path).unwrap();
+        assert_eq!(loaded.corrections, file.corrections);
+        assert_eq!(loaded.min_confidence, 0.8);
+        loaded.merge(&[], Some(0.5));
+        assert_eq!(loaded.min_confidence, 0.5);
+    }
+
+    #[test]
+    fn test_correct_argv() {
+        let file = file(&[
+            rule("git stauts -s", "git status -s", 0.9),
+            rule("gh pr edit -t foo", "gh pr edit 12 --title foo", 0.8),
+        ]);
+        let mut argv: Vec<String> = ["git", "stauts", "--", "stauts", "two words"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            file.correct_argv(&mut argv),
+            Ok(vec!["stauts -> status".to_string()])
+        );
+        assert_eq!(argv[1], "status");
+        assert_eq!(argv[3], "stauts");
+        assert_eq!(argv[4], "two words");
+
+        let mut argv: Vec<String> = ["gh", "pr", "edit", "-t", "foo"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(file
+            .correct_argv(&mut argv)
+            .unwrap_err()
+            .contains("--title"));
+    }
+}
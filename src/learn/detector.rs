@@ -44,6 +44,8 @@ pub struct CorrectionRule {
     pub occurrences: usize,
     pub base_command: String,
     pub example_error: String,
+    /// Best confidence among the grouped pairs
+    pub confidence: f64,
 }
 
 lazy_static! {
@@ -235,7 +237,6 @@ pub fn find_corrections(commands: &[CommandExecution]) -> Vec<CorrectionPair> {
             .take(std::cmp::min(i + 1 + CORRECTION_WINDOW, commands.len()))
             .skip(i + 1)
         {
-
             let similarity = command_similarity(&cmd.command, &candidate.command);
 
             // Must meet minimum similarity
@@ -344,11 +345,12 @@ pub fn deduplicate_corrections(pairs: Vec<CorrectionPair>) -> Vec<CorrectionRule
             occurrences,
             base_command,
             example_error: best.error_output.clone(),
+            confidence: best.confidence,
         });
     }
 
     // Sort by occurrences descending (most common mistakes first)
-    rules.sort_by(|a, b| b.occurrences.cmp(&a.occurrences));
+    rules.sort_by_key(|b| std::cmp::Reverse(b.occurrences));
 
     rules
 }
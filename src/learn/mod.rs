@@ -1,60 +1,60 @@
+pub mod corrections;
 pub mod detector;
 pub mod report;
 
-use crate::discover::provider::{ClaudeProvider, SessionProvider};
+use crate::discover::provider::SessionQuery;
 use anyhow::Result;
 use detector::{deduplicate_corrections, find_corrections, CommandExecution};
 use report::{format_console_report, write_rules_file};
 
+/// What to do with the detected rules besides reporting them
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Actions {
+    /// Write .claude/rules/cli-corrections.md
+    pub write_rules: bool,
+    /// Merge into corrections.toml (consulted by the hook, rtk proxy and rtk mcp)
+    pub apply: bool,
+}
+
 pub fn run(
-    project: Option<String>,
-    all: bool,
-    since: u64,
+    query: &SessionQuery,
     format: String,
-    write_rules: bool,
-    min_confidence: f64,
+    actions: Actions,
+    min_confidence: Option<f64>,
     min_occurrences: usize,
 ) -> Result<()> {
-    let provider = ClaudeProvider;
-
-    // Determine project filter (same logic as discover)
-    let project_filter = if all {
-        None
-    } else if let Some(p) = project {
-        Some(p)
-    } else {
-        // Default: current working directory
-        let cwd = std::env::current_dir()?;
-        let cwd_str = cwd.to_string_lossy().to_string();
-        let encoded = ClaudeProvider::encode_project_path(&cwd_str);
-        Some(encoded)
-    };
-
-    // Discover sessions
-    let sessions = provider.discover_sessions(project_filter.as_deref(), Some(since))?;
-
-    if sessions.is_empty() {
-        println!("No Claude Code sessions found in the last {} days.", since);
+    let since = query.since_days;
+    let found = query.discover()?;
+    let session_count: usize = found.iter().map(|f| f.sessions.len()).sum();
+
+    if session_count == 0 {
+        println!(
+            "No {} sessions found in the last {} days.",
+            query.provider.label(),
+            since
+        );
         return Ok(());
     }
 
     // Extract commands from all sessions
     let mut all_commands: Vec<CommandExecution> = Vec::new();
 
-    for session_path in &sessions {
-        let extracted = match provider.extract_commands(session_path) {
-            Ok(cmds) => cmds,
-            Err(_) => continue, // Skip malformed sessions
-        };
-
-        for ext_cmd in extracted {
-            // Only process commands with output content
-            if let Some(output) = ext_cmd.output_content {
-                all_commands.push(CommandExecution {
-                    command: ext_cmd.command,
-                    is_error: ext_cmd.is_error,
-                    output,
-                });
+    for f in &found {
+        for session_path in &f.sessions {
+            let extracted = match f.provider.extract_commands(session_path) {
+                Ok(cmds) => cmds,
+                Err(_) => continue, // Skip malformed sessions
+            };
+
+            for ext_cmd in extracted {
+                // Only process commands with output content
+                if let Some(output) = ext_cmd.output_content {
+                    all_commands.push(CommandExecution {
+                        command: ext_cmd.command,
+                        is_error: ext_cmd.is_error,
+                        output,
+                    });
+                }
             }
         }
     }
@@ -66,17 +66,14 @@ pub fn run(
     let corrections = find_corrections(&all_commands);
 
     if corrections.is_empty() {
-        println!(
-            "No CLI corrections detected in {} sessions.",
-            sessions.len()
-        );
+        println!("No CLI corrections detected in {} sessions.", session_count);
         return Ok(());
     }
 
     // Filter by confidence
     let filtered: Vec<_> = corrections
         .into_iter()
-        .filter(|c| c.confidence >= min_confidence)
+        .filter(|c| c.confidence >= min_confidence.unwrap_or(corrections::DEFAULT_MIN_CONFIDENCE))
         .collect();
 
     // Deduplicate
@@ -90,7 +87,7 @@ pub fn run(
         "json" => {
             // JSON output
             let json = serde_json::json!({
-                "sessions_scanned": sessions.len(),
+                "sessions_scanned": session_count,
                 "total_corrections": filtered.len(),
                 "rules": rules.iter().map(|r| serde_json::json!({
                     "wrong": r.wrong_pattern,
@@ -101,17 +98,26 @@ pub fn run(
                 })).collect::<Vec<_>>(),
             });
             println!("{}", serde_json::to_string_pretty(&json)?);
+
+            if actions.apply {
+                // Keep stdout valid JSON
+                eprintln!("{}", corrections::apply(&rules, min_confidence)?);
+            }
         }
         _ => {
             // Text output
-            let report = format_console_report(&rules, filtered.len(), sessions.len(), since);
+            let report = format_console_report(&rules, filtered.len(), session_count, since);
             print!("{}", report);
 
-            if write_rules && !rules.is_empty() {
+            if actions.write_rules && !rules.is_empty() {
                 let rules_path = ".claude/rules/cli-corrections.md";
                 write_rules_file(&rules, rules_path)?;
                 println!("\nWritten to: {}", rules_path);
             }
+
+            if actions.apply {
+                println!("\n{}", corrections::apply(&rules, min_confidence)?);
+            }
         }
     }
 
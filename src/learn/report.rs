@@ -138,6 +138,7 @@ mod tests {
                 occurrences: 3,
                 base_command: "git commit".to_string(),
                 example_error: "error: unexpected argument '--ammend'".to_string(),
+                confidence: 0.9,
             },
             CorrectionRule {
                 wrong_pattern: "gh pr edit -t".to_string(),
@@ -146,6 +147,7 @@ mod tests {
                 occurrences: 1,
                 base_command: "gh pr".to_string(),
                 example_error: "unknown flag: -t".to_string(),
+                confidence: 0.9,
             },
         ];
 
@@ -167,6 +169,7 @@ mod tests {
             occurrences: 3,
             base_command: "git commit".to_string(),
             example_error: "error: unexpected argument '--ammend'".to_string(),
+            confidence: 0.9,
         }];
 
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1,3 +1,5 @@
+use crate::exec::ExecResult;
+use crate::parser::{LintIssue, LintResult, LintSeverity, ParseResult};
 use crate::ruff_cmd;
 use crate::tracking;
 use crate::utils::{package_manager_exec, truncate};
@@ -13,8 +15,6 @@ struct EslintMessage {
     rule_id: Option<String>,
     severity: u8,
     message: String,
-    #[allow(dead_code)]
-    #[allow(dead_code)]
     line: usize,
     column: usize,
 }
@@ -40,11 +40,9 @@ struct PylintDiagnostic {
     #[allow(dead_code)]
     obj: String,
     line: usize,
-    #[allow(dead_code)]
     column: usize,
     path: String,
     symbol: String, // rule code like "unused-variable"
-    #[allow(dead_code)]
     message: String,
     #[serde(rename = "message-id")]
     message_id: String, // e.g., "W0612"
@@ -55,7 +53,7 @@ fn is_python_linter(linter: &str) -> bool {
     matches!(linter, "ruff" | "pylint" | "mypy" | "flake8")
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Detect linter name (first arg if not a path/flag, else default to eslint)
@@ -75,6 +73,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     };
 
     // Add format flags based on linter
+    #[allow(clippy::collapsible_match)]
     match linter {
         "eslint" => {
             cmd.arg("-f").arg("json");
@@ -154,7 +153,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
                 stderr.lines().take(5).collect::<Vec<_>>().join("\n")
             );
         }
-        return Ok(());
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -177,28 +176,115 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         _ => filter_generic_lint(&raw),
     };
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    if let Some(result) = parse_lint_result(linter, &stdout, &raw) {
+        crate::json_output::report_parsed("LintResult", &result);
+    }
+
+    let exit_code = crate::exec::exit_code(&output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "lint", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("{} {}", linter, args.join(" ")),
         &format!("rtk lint {} {}", linter, args.join(" ")),
         &raw,
         &filtered,
+        exit_code,
     );
 
-    Ok(())
+    Ok(ExecResult::code(exit_code))
+}
+
+/// Structured issues for `--format json` (None for linters without a parser)
+fn parse_lint_result(linter: &str, stdout: &str, raw: &str) -> Option<ParseResult<LintResult>> {
+    let issues = match linter {
+        "eslint" => parse_eslint_issues(stdout),
+        "ruff" if stdout.trim().is_empty() => Some(Vec::new()),
+        "ruff" => ruff_cmd::parse_ruff_check_issues(stdout),
+        "pylint" => parse_pylint_issues(stdout),
+        "mypy" => Some(parse_mypy_issues(raw)),
+        _ => return None,
+    };
+    Some(match issues {
+        Some(issues) => ParseResult::Full(crate::cargo_cmd::lint_result(issues)),
+        None => ParseResult::Passthrough(truncate(raw, 500)),
+    })
+}
+
+fn parse_eslint_issues(output: &str) -> Option<Vec<LintIssue>> {
+    let results: Vec<EslintResult> = serde_json::from_str(output).ok()?;
+    Some(
+        results
+            .into_iter()
+            .flat_map(|r| {
+                let file_path = r.file_path;
+                r.messages.into_iter().map(move |m| LintIssue {
+                    file_path: file_path.clone(),
+                    line: m.line,
+                    column: m.column,
+                    severity: if m.severity >= 2 {
+                        LintSeverity::Error
+                    } else {
+                        LintSeverity::Warning
+                    },
+                    rule_id: m.rule_id.unwrap_or_default(),
+                    message: m.message,
+                    rendered: None,
+                })
+            })
+            .collect(),
+    )
+}
+
+fn parse_pylint_issues(output: &str) -> Option<Vec<LintIssue>> {
+    let diagnostics: Vec<PylintDiagnostic> = serde_json::from_str(output).ok()?;
+    Some(
+        diagnostics
+            .into_iter()
+            .map(|d| LintIssue {
+                file_path: d.path,
+                line: d.line,
+                column: d.column,
+                severity: match d.msg_type.as_str() {
+                    "error" | "fatal" => LintSeverity::Error,
+                    "warning" => LintSeverity::Warning,
+                    _ => LintSeverity::Info,
+                },
+                rule_id: d.message_id,
+                message: d.message,
+                rendered: None,
+            })
+            .collect(),
+    )
+}
+
+fn parse_mypy_issues(output: &str) -> Vec<LintIssue> {
+    let re = Regex::new(r"^(.+\.py):(\d+): (error|warning|note): (.+?) \[(.+?)\]").unwrap();
+    output
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .map(|caps| LintIssue {
+            file_path: caps[1].to_string(),
+            line: caps[2].parse().unwrap_or(0),
+            column: 0,
+            severity: match &caps[3] {
+                "error" => LintSeverity::Error,
+                "warning" => LintSeverity::Warning,
+                _ => LintSeverity::Info,
+            },
+            rule_id: caps[5].to_string(),
+            message: caps[4].to_string(),
+            rendered: None,
+        })
+        .collect()
 }
 
 /// Filter ESLint JSON output - group by rule and file
-fn filter_eslint_json(output: &str) -> String {
+pub(crate) fn filter_eslint_json(output: &str) -> String {
     let results: Result<Vec<EslintResult>, _> = serde_json::from_str(output);
 
     let results = match results {
@@ -238,7 +324,7 @@ fn filter_eslint_json(output: &str) -> String {
         .filter(|r| !r.messages.is_empty())
         .map(|r| (r, r.messages.len()))
         .collect();
-    by_file.sort_by(|a, b| b.1.cmp(&a.1));
+    by_file.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     // Build output
     let mut result = String::new();
@@ -290,7 +376,7 @@ fn filter_eslint_json(output: &str) -> String {
 }
 
 /// Filter pylint JSON2 output - group by symbol and file
-fn filter_pylint_json(output: &str) -> String {
+pub(crate) fn filter_pylint_json(output: &str) -> String {
     let diagnostics: Result<Vec<PylintDiagnostic>, _> = serde_json::from_str(output);
 
     let diagnostics = match diagnostics {
@@ -407,7 +493,7 @@ fn filter_pylint_json(output: &str) -> String {
 }
 
 /// Filter mypy text output - parse and group by error code and file
-fn filter_mypy_output(output: &str) -> String {
+pub(crate) fn filter_mypy_output(output: &str) -> String {
     // Regex pattern: path/to/file.py:line: error: message [error-code]
     let re = Regex::new(r"^(.+\.py):(\d+): (error|warning|note): (.+?) \[(.+?)\]").unwrap();
 
@@ -525,7 +611,7 @@ fn filter_mypy_output(output: &str) -> String {
 }
 
 /// Filter generic linter output (fallback for non-ESLint linters)
-fn filter_generic_lint(output: &str) -> String {
+pub(crate) fn filter_generic_lint(output: &str) -> String {
     let mut warnings = 0;
     let mut errors = 0;
     let mut issues: Vec<String> = Vec::new();
@@ -724,6 +810,34 @@ Found 4 errors in 2 files (checked 5 source files)"#;
         assert!(result.contains("utils.py"));
     }
 
+    #[test]
+    fn test_parse_lint_result() {
+        let json = r#"[{"filePath": "src/a.ts", "messages": [
+            {"ruleId": "prefer-const", "severity": 1, "message": "Use const", "line": 3, "column": 5},
+            {"ruleId": "no-undef", "severity": 2, "message": "x is not defined", "line": 9, "column": 1}
+        ], "errorCount": 1, "warningCount": 1}]"#;
+        let Some(ParseResult::Full(lint)) = parse_lint_result("eslint", json, json) else {
+            panic!("expected a full parse");
+        };
+        assert_eq!(lint.total_issues, 2);
+        assert_eq!((lint.errors, lint.warnings), (1, 1));
+        assert_eq!(lint.issues[1].rule_id, "no-undef");
+        assert_eq!(lint.issues[1].line, 9);
+
+        let mypy = "src/main.py:10: error: Bad return [return-value]\nFound 1 error";
+        let Some(ParseResult::Full(lint)) = parse_lint_result("mypy", "", mypy) else {
+            panic!("expected a full parse");
+        };
+        assert_eq!(lint.errors, 1);
+        assert_eq!(lint.issues[0].file_path, "src/main.py");
+
+        assert!(matches!(
+            parse_lint_result("eslint", "not json", "not json"),
+            Some(ParseResult::Passthrough(_))
+        ));
+        assert!(parse_lint_result("flake8", "", "x.py:1:1: E1").is_none());
+    }
+
     #[test]
     fn test_is_python_linter() {
         assert!(is_python_linter("ruff"));
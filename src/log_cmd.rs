@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::Result;
 use regex::Regex;
@@ -7,7 +8,7 @@ use std::io::{self, BufRead};
 use std::path::Path;
 
 /// Filter and deduplicate log output
-pub fn run_file(file: &Path, verbose: u8) -> Result<()> {
+pub fn run_file(file: &Path, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -23,11 +24,11 @@ pub fn run_file(file: &Path, verbose: u8) -> Result<()> {
         &content,
         &result,
     );
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// Filter logs from stdin
-pub fn run_stdin(_verbose: u8) -> Result<()> {
+pub fn run_stdin(_verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut content = String::new();
@@ -42,7 +43,7 @@ pub fn run_stdin(_verbose: u8) -> Result<()> {
 
     timer.track("log (stdin)", "rtk log (stdin)", &content, &result);
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// For use by other modules
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -30,7 +31,7 @@ const NOISE_DIRS: &[&str] = &[
     ".eggs",
 ];
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Separate flags from paths
@@ -85,7 +86,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprint!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
@@ -117,7 +118,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         &filtered,
     );
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Format bytes into human-readable size
@@ -1,48 +1,65 @@
+mod bench_cmd;
 mod cargo_cmd;
 mod cc_economics;
 mod ccusage;
+mod ci_log;
 mod config;
+mod conflicts;
 mod container;
 mod curl_cmd;
+mod dedup;
 mod deps;
 mod diff_cmd;
 mod discover;
 mod display_helpers;
 mod env_cmd;
+mod exec;
 mod filter;
 mod find_cmd;
 mod format_cmd;
 mod gain;
+mod gain_report;
 mod gh_cmd;
 mod git;
 mod go_cmd;
 mod golangci_cmd;
 mod grep_cmd;
+mod history;
 mod hook_audit_cmd;
 mod init;
 mod json_cmd;
+mod json_output;
 mod learn;
 mod lint_cmd;
 mod local_llm;
 mod log_cmd;
 mod ls;
+mod mcp_cmd;
 mod next_cmd;
 mod npm_cmd;
+mod outline;
 mod parser;
 mod pip_cmd;
 mod playwright_cmd;
 mod pnpm_cmd;
+mod preserve;
 mod prettier_cmd;
 mod prisma_cmd;
 mod pytest_cmd;
 mod read;
+mod rewrite_cmd;
 mod ruff_cmd;
 mod runner;
+mod semantic_diff;
+mod stream;
 mod summary;
 mod tee;
+mod tee_cmd;
+mod tokenizer;
 mod tracking;
 mod tree;
 mod tsc_cmd;
+mod user_filter;
 mod utils;
 mod vitest_cmd;
 mod wc_cmd;
@@ -50,6 +67,8 @@ mod wget_cmd;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use discover::provider::{ProviderKind, SessionQuery};
+use exec::ExecResult;
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
@@ -75,6 +94,10 @@ struct Cli {
     /// Set SKIP_ENV_VALIDATION=1 for child processes (Next.js, tsc, lint, prisma)
     #[arg(long = "skip-env", global = true)]
     skip_env: bool,
+
+    /// Output format for the wrapped command: text, json (place before the command)
+    #[arg(long, value_enum, default_value = "text")]
+    format: json_output::OutputFormat,
 }
 
 #[derive(Subcommand)]
@@ -95,8 +118,9 @@ enum Commands {
 
     /// Read file with intelligent filtering
     Read {
-        /// File to read
-        file: PathBuf,
+        /// Files, globs or ranges (file:10-20, file:50±5); `-` for stdin
+        #[arg(required = true)]
+        files: Vec<String>,
         /// Filter: none, minimal, aggressive
         #[arg(short, long, default_value = "minimal")]
         level: filter::FilterLevel,
@@ -106,6 +130,15 @@ enum Commands {
         /// Show line numbers
         #[arg(short = 'n', long)]
         line_numbers: bool,
+        /// Signatures only (modules, types, fns) with line ranges
+        #[arg(long, conflicts_with = "symbol")]
+        outline: bool,
+        /// Print only this item's source (e.g. Foo::bar)
+        #[arg(long)]
+        symbol: Option<String>,
+        /// Total token budget shared across all files
+        #[arg(long)]
+        budget: Option<usize>,
     },
 
     /// Generate 2-line technical summary (heuristic-based)
@@ -303,6 +336,27 @@ enum Commands {
         args: Vec<String>,
     },
 
+    /// Replay filters over recorded raw outputs (tee files, fixtures) offline
+    Bench {
+        /// Corpus directory (e.g. ~/.local/share/rtk/tee or tests/fixtures)
+        dir: PathBuf,
+        /// Only replay this filter (e.g. cargo_test)
+        #[arg(long)]
+        filter: Option<String>,
+        /// Baseline JSON to diff against; exits 1 on regressions
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Write the current results to --baseline instead of diffing
+        #[arg(long, requires = "baseline")]
+        update_baseline: bool,
+        /// Savings drop (percentage points) tolerated before a regression
+        #[arg(long, default_value = "1.0")]
+        tolerance: f64,
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
     /// Show token savings summary and history
     Gain {
         /// Show ASCII graph of daily savings
@@ -332,6 +386,24 @@ enum Commands {
         /// Output format: text, json, csv
         #[arg(short, long, default_value = "text")]
         format: String,
+        /// Compare the 4-chars/token heuristic with real BPE counts on saved tee output
+        #[arg(long)]
+        recalibrate: bool,
+        /// Only count commands run in this project (git root; default: current)
+        #[arg(long, num_args = 0..=1, default_missing_value = ".")]
+        project: Option<String>,
+        /// Only count commands from this agent session (RTK_SESSION_ID)
+        #[arg(long)]
+        session: Option<String>,
+        /// Break savings down by: project, session
+        #[arg(long, value_parser = ["project", "session"])]
+        by: Option<String>,
+        /// Exit 1 if today's or this week's tokens exceed the [gain] budgets
+        #[arg(long, conflicts_with = "report")]
+        check: bool,
+        /// Export a shareable report (leaderboards, trends): html, md
+        #[arg(long, value_parser = ["html", "md"])]
+        report: Option<String>,
     },
 
     /// Claude Code economics: spending (ccusage) vs savings (rtk) analysis
@@ -458,9 +530,18 @@ enum Commands {
         /// Output format: text, json
         #[arg(short, long, default_value = "text")]
         format: String,
+        /// Agent whose session history to scan
+        #[arg(long, value_enum, default_value = "claude")]
+        provider: ProviderKind,
+        /// Draft filters for unsupported commands from their recorded output
+        #[arg(long)]
+        suggest: bool,
+        /// Like --suggest, and add the drafts to .rtk/filters.toml
+        #[arg(long)]
+        apply: bool,
     },
 
-    /// Learn CLI corrections from Claude Code error history
+    /// Learn CLI corrections from agent session error history
     Learn {
         /// Filter by project path (substring match)
         #[arg(short, long)]
@@ -477,14 +558,26 @@ enum Commands {
         /// Generate .claude/rules/cli-corrections.md file
         #[arg(short, long)]
         write_rules: bool,
-        /// Minimum confidence threshold (0.0-1.0)
-        #[arg(long, default_value = "0.6")]
-        min_confidence: f64,
+        /// Minimum confidence threshold (0.0-1.0, default 0.6; with --apply, stored in corrections.toml)
+        #[arg(long)]
+        min_confidence: Option<f64>,
         /// Minimum occurrences to include in report
         #[arg(long, default_value = "1")]
         min_occurrences: usize,
+        /// Agent whose session history to scan
+        #[arg(long, value_enum, default_value = "claude")]
+        provider: ProviderKind,
+        /// Merge rules into corrections.toml so the hook, rtk proxy and rtk mcp enforce them
+        #[arg(long, conflicts_with = "revert")]
+        apply: bool,
+        /// Undo the last --apply
+        #[arg(long)]
+        revert: bool,
     },
 
+    /// Serve rtk filters as Model Context Protocol tools over stdio
+    Mcp,
+
     /// Execute command without filtering but track usage
     Proxy {
         /// Command and arguments to execute
@@ -534,6 +627,70 @@ enum Commands {
         #[arg(short, long, default_value = "7")]
         since: u64,
     },
+
+    /// Rewrite a command to its rtk equivalent (omit command to read PreToolUse JSON on stdin)
+    Rewrite {
+        /// Command to rewrite (prints the rewritten command, exits 1 if unchanged)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Agent hook entry points (used by the installed rtk-rewrite.sh)
+    Hook {
+        #[command(subcommand)]
+        command: HookCommands,
+    },
+
+    /// Retrieve raw output saved by tee (list, show slices, grep)
+    Tee {
+        #[command(subcommand)]
+        command: TeeCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum TeeCommands {
+    /// List recent captures (id, age, exit code, size, command)
+    List {
+        /// Max captures to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+    },
+    /// Print a capture by id (or unique id prefix)
+    Show {
+        /// Capture id from `rtk tee list` or the [full output: ...] hint
+        id: String,
+        /// Only lines a:b (1-based, inclusive; a: and :b allowed)
+        #[arg(long)]
+        lines: Option<String>,
+        /// Only lines matching this regex (printed with line numbers)
+        #[arg(long)]
+        grep: Option<String>,
+    },
+    /// Print the most recent capture
+    Last {
+        /// Only lines a:b (1-based, inclusive; a: and :b allowed)
+        #[arg(long)]
+        lines: Option<String>,
+        /// Only lines matching this regex (printed with line numbers)
+        #[arg(long)]
+        grep: Option<String>,
+    },
+    /// Search captures for a regex (prints id:line: text)
+    Grep {
+        /// Regex pattern
+        pattern: String,
+        /// Only search this capture
+        #[arg(long)]
+        id: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookCommands {
+    /// Claude Code PreToolUse:Bash hook (reads hook JSON on stdin, RTK_HOOK_MODE=flex|strict)
+    #[command(name = "pretooluse")]
+    PreToolUse,
 }
 
 #[derive(Subcommand)]
@@ -570,9 +727,9 @@ enum GitCommands {
     },
     /// Commit → "ok ✓ \<hash\>"
     Commit {
-        /// Commit message
-        #[arg(short, long)]
-        message: String,
+        /// Git commit arguments (supports -m, -a, --amend, --no-verify, -F, --fixup, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
     /// Push → "ok ✓ \<branch\>"
     Push {
@@ -612,6 +769,30 @@ enum GitCommands {
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Rebase → "ok ✓ rebased \<branch\>", or step and conflict regions when it stops
+    Rebase {
+        /// Git rebase arguments (supports --continue, --abort, --skip, --onto, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Merge → "ok ✓ N files +a -r", or conflict regions
+    Merge {
+        /// Git merge arguments (supports --continue, --abort, --no-ff, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Cherry-pick → "ok ✓", or conflict regions
+    CherryPick {
+        /// Git cherry-pick arguments (supports --continue, --abort, --skip, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Blame with same-commit lines collapsed and a commit legend
+    Blame {
+        /// Git blame arguments; `file:10-40` limits to a line range
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     /// Passthrough: runs any unsupported git subcommand directly
     #[command(external_subcommand)]
     Other(Vec<OsString>),
@@ -849,29 +1030,73 @@ enum GoCommands {
     Other(Vec<OsString>),
 }
 
+/// Read-only commands whose output agents re-request verbatim within a session
+fn dedup_eligible(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Ls { .. }
+            | Commands::Tree { .. }
+            | Commands::Read { .. }
+            | Commands::Find { .. }
+            | Commands::Grep { .. }
+            | Commands::Json { .. }
+            | Commands::Deps { .. }
+            | Commands::Env { .. }
+            | Commands::Wc { .. }
+            | Commands::Git {
+                command: GitCommands::Status { .. }
+                    | GitCommands::Diff { .. }
+                    | GitCommands::Log { .. }
+                    | GitCommands::Show { .. }
+                    | GitCommands::Branch { .. }
+                    | GitCommands::Blame { .. }
+            }
+    )
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Ls { args } => {
-            ls::run(&args, cli.verbose)?;
-        }
+    if cli.format == json_output::OutputFormat::Json {
+        return json_output::run_wrapped().map(ExecResult::exit);
+    }
+    if dedup::should_wrap(dedup_eligible(&cli.command)) {
+        return dedup::run_wrapped().map(ExecResult::exit);
+    }
 
-        Commands::Tree { args } => {
-            tree::run(&args, cli.verbose)?;
-        }
+    let result = match cli.command {
+        Commands::Ls { args } => ls::run(&args, cli.verbose)?,
+
+        Commands::Tree { args } => tree::run(&args, cli.verbose)?,
 
         Commands::Read {
-            file,
+            files,
             level,
             max_lines,
             line_numbers,
+            outline,
+            symbol,
+            budget,
         } => {
-            if file == Path::new("-") {
-                read::run_stdin(level, max_lines, line_numbers, cli.verbose)?;
+            let targets = read::expand_targets(&files)?;
+            let options = read::ReadOptions {
+                level,
+                max_lines,
+                line_numbers,
+                budget,
+            };
+            if outline || symbol.is_some() {
+                let file = read::outline_target(&targets)?;
+                match &symbol {
+                    Some(symbol) => read::run_symbol(file, symbol, line_numbers, cli.verbose)?,
+                    None => read::run_outline(file, cli.verbose)?,
+                }
+            } else if targets.len() == 1 && targets[0].path == Path::new("-") {
+                read::run_stdin(targets[0].range, &options, cli.verbose)?;
             } else {
-                read::run(&file, level, max_lines, line_numbers, cli.verbose)?;
+                read::run(&targets, &options, cli.verbose)?;
             }
+            ExecResult::SUCCESS
         }
 
         Commands::Smart {
@@ -880,92 +1105,89 @@ fn main() -> Result<()> {
             force_download,
         } => {
             local_llm::run(&file, &model, force_download, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Git { command } => match command {
             GitCommands::Diff { args } => {
-                git::run(git::GitCommand::Diff, &args, None, cli.verbose)?;
-            }
-            GitCommands::Log { args } => {
-                git::run(git::GitCommand::Log, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Diff, &args, None, cli.verbose)?
             }
+            GitCommands::Log { args } => git::run(git::GitCommand::Log, &args, None, cli.verbose)?,
             GitCommands::Status { args } => {
-                git::run(git::GitCommand::Status, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Status, &args, None, cli.verbose)?
             }
             GitCommands::Show { args } => {
-                git::run(git::GitCommand::Show, &args, None, cli.verbose)?;
-            }
-            GitCommands::Add { args } => {
-                git::run(git::GitCommand::Add, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Show, &args, None, cli.verbose)?
             }
-            GitCommands::Commit { message } => {
-                git::run(git::GitCommand::Commit { message }, &[], None, cli.verbose)?;
+            GitCommands::Add { args } => git::run(git::GitCommand::Add, &args, None, cli.verbose)?,
+            GitCommands::Commit { args } => {
+                git::run(git::GitCommand::Commit, &args, None, cli.verbose)?
             }
             GitCommands::Push { args } => {
-                git::run(git::GitCommand::Push, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Push, &args, None, cli.verbose)?
             }
             GitCommands::Pull { args } => {
-                git::run(git::GitCommand::Pull, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Pull, &args, None, cli.verbose)?
             }
             GitCommands::Branch { args } => {
-                git::run(git::GitCommand::Branch, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Branch, &args, None, cli.verbose)?
             }
             GitCommands::Fetch { args } => {
-                git::run(git::GitCommand::Fetch, &args, None, cli.verbose)?;
-            }
-            GitCommands::Stash { subcommand, args } => {
-                git::run(
-                    git::GitCommand::Stash { subcommand },
-                    &args,
-                    None,
-                    cli.verbose,
-                )?;
+                git::run(git::GitCommand::Fetch, &args, None, cli.verbose)?
             }
+            GitCommands::Stash { subcommand, args } => git::run(
+                git::GitCommand::Stash { subcommand },
+                &args,
+                None,
+                cli.verbose,
+            )?,
             GitCommands::Worktree { args } => {
-                git::run(git::GitCommand::Worktree, &args, None, cli.verbose)?;
+                git::run(git::GitCommand::Worktree, &args, None, cli.verbose)?
+            }
+            GitCommands::Rebase { args } => {
+                git::run(git::GitCommand::Rebase, &args, None, cli.verbose)?
+            }
+            GitCommands::Merge { args } => {
+                git::run(git::GitCommand::Merge, &args, None, cli.verbose)?
             }
-            GitCommands::Other(args) => {
-                git::run_passthrough(&args, cli.verbose)?;
+            GitCommands::CherryPick { args } => {
+                git::run(git::GitCommand::CherryPick, &args, None, cli.verbose)?
             }
+            GitCommands::Blame { args } => {
+                git::run(git::GitCommand::Blame, &args, None, cli.verbose)?
+            }
+            GitCommands::Other(args) => git::run_passthrough(&args, cli.verbose)?,
         },
 
         Commands::Gh { subcommand, args } => {
-            gh_cmd::run(&subcommand, &args, cli.verbose, cli.ultra_compact)?;
+            gh_cmd::run(&subcommand, &args, cli.verbose, cli.ultra_compact)?
         }
 
         Commands::Pnpm { command } => match command {
             PnpmCommands::List { depth, args } => {
-                pnpm_cmd::run(pnpm_cmd::PnpmCommand::List { depth }, &args, cli.verbose)?;
+                pnpm_cmd::run(pnpm_cmd::PnpmCommand::List { depth }, &args, cli.verbose)?
             }
             PnpmCommands::Outdated { args } => {
-                pnpm_cmd::run(pnpm_cmd::PnpmCommand::Outdated, &args, cli.verbose)?;
-            }
-            PnpmCommands::Install { packages, args } => {
-                pnpm_cmd::run(
-                    pnpm_cmd::PnpmCommand::Install { packages },
-                    &args,
-                    cli.verbose,
-                )?;
-            }
-            PnpmCommands::Build { args } => {
-                next_cmd::run(&args, cli.verbose)?;
-            }
-            PnpmCommands::Typecheck { args } => {
-                tsc_cmd::run(&args, cli.verbose)?;
-            }
-            PnpmCommands::Other(args) => {
-                pnpm_cmd::run_passthrough(&args, cli.verbose)?;
+                pnpm_cmd::run(pnpm_cmd::PnpmCommand::Outdated, &args, cli.verbose)?
             }
+            PnpmCommands::Install { packages, args } => pnpm_cmd::run(
+                pnpm_cmd::PnpmCommand::Install { packages },
+                &args,
+                cli.verbose,
+            )?,
+            PnpmCommands::Build { args } => next_cmd::run(&args, cli.verbose)?,
+            PnpmCommands::Typecheck { args } => tsc_cmd::run(&args, cli.verbose)?,
+            PnpmCommands::Other(args) => pnpm_cmd::run_passthrough(&args, cli.verbose)?,
         },
 
         Commands::Err { command } => {
             let cmd = command.join(" ");
-            runner::run_err(&cmd, cli.verbose)?;
+            runner::run_err(&cmd, cli.verbose)?
         }
 
         Commands::Test { command } => {
             let cmd = command.join(" ");
-            runner::run_test(&cmd, cli.verbose)?;
+            runner::run_test(&cmd, cli.verbose)?
         }
 
         Commands::Json { file, depth } => {
@@ -974,14 +1196,17 @@ fn main() -> Result<()> {
             } else {
                 json_cmd::run(&file, depth, cli.verbose)?;
             }
+            ExecResult::SUCCESS
         }
 
         Commands::Deps { path } => {
             deps::run(&path, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Env { filter, show_all } => {
             env_cmd::run(filter.as_deref(), show_all, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Find {
@@ -991,6 +1216,7 @@ fn main() -> Result<()> {
             file_type,
         } => {
             find_cmd::run(&pattern, &path, max, &file_type, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Diff { file1, file2 } => {
@@ -999,43 +1225,40 @@ fn main() -> Result<()> {
             } else {
                 diff_cmd::run_stdin(cli.verbose)?;
             }
+            ExecResult::SUCCESS
         }
 
         Commands::Log { file } => {
             if let Some(f) = file {
-                log_cmd::run_file(&f, cli.verbose)?;
+                log_cmd::run_file(&f, cli.verbose)?
             } else {
-                log_cmd::run_stdin(cli.verbose)?;
+                log_cmd::run_stdin(cli.verbose)?
             }
         }
 
         Commands::Docker { command } => match command {
             DockerCommands::Ps => {
-                container::run(container::ContainerCmd::DockerPs, &[], cli.verbose)?;
+                container::run(container::ContainerCmd::DockerPs, &[], cli.verbose)?
             }
             DockerCommands::Images => {
-                container::run(container::ContainerCmd::DockerImages, &[], cli.verbose)?;
+                container::run(container::ContainerCmd::DockerImages, &[], cli.verbose)?
             }
             DockerCommands::Logs { container: c } => {
-                container::run(container::ContainerCmd::DockerLogs, &[c], cli.verbose)?;
+                container::run(container::ContainerCmd::DockerLogs, &[c], cli.verbose)?
             }
             DockerCommands::Compose { command: compose } => match compose {
-                ComposeCommands::Ps => {
-                    container::run_compose_ps(cli.verbose)?;
-                }
+                ComposeCommands::Ps => container::run_compose_ps(cli.verbose)?,
                 ComposeCommands::Logs { service } => {
-                    container::run_compose_logs(service.as_deref(), cli.verbose)?;
+                    container::run_compose_logs(service.as_deref(), cli.verbose)?
                 }
                 ComposeCommands::Build { service } => {
-                    container::run_compose_build(service.as_deref(), cli.verbose)?;
+                    container::run_compose_build(service.as_deref(), cli.verbose)?
                 }
                 ComposeCommands::Other(args) => {
-                    container::run_compose_passthrough(&args, cli.verbose)?;
+                    container::run_compose_passthrough(&args, cli.verbose)?
                 }
             },
-            DockerCommands::Other(args) => {
-                container::run_docker_passthrough(&args, cli.verbose)?;
-            }
+            DockerCommands::Other(args) => container::run_docker_passthrough(&args, cli.verbose)?,
         },
 
         Commands::Kubectl { command } => match command {
@@ -1047,7 +1270,7 @@ fn main() -> Result<()> {
                     args.push("-n".to_string());
                     args.push(n);
                 }
-                container::run(container::ContainerCmd::KubectlPods, &args, cli.verbose)?;
+                container::run(container::ContainerCmd::KubectlPods, &args, cli.verbose)?
             }
             KubectlCommands::Services { namespace, all } => {
                 let mut args: Vec<String> = Vec::new();
@@ -1057,7 +1280,7 @@ fn main() -> Result<()> {
                     args.push("-n".to_string());
                     args.push(n);
                 }
-                container::run(container::ContainerCmd::KubectlServices, &args, cli.verbose)?;
+                container::run(container::ContainerCmd::KubectlServices, &args, cli.verbose)?
             }
             KubectlCommands::Logs { pod, container: c } => {
                 let mut args = vec![pod];
@@ -1065,16 +1288,14 @@ fn main() -> Result<()> {
                     args.push("-c".to_string());
                     args.push(cont);
                 }
-                container::run(container::ContainerCmd::KubectlLogs, &args, cli.verbose)?;
-            }
-            KubectlCommands::Other(args) => {
-                container::run_kubectl_passthrough(&args, cli.verbose)?;
+                container::run(container::ContainerCmd::KubectlLogs, &args, cli.verbose)?
             }
+            KubectlCommands::Other(args) => container::run_kubectl_passthrough(&args, cli.verbose)?,
         },
 
         Commands::Summary { command } => {
             let cmd = command.join(" ");
-            summary::run(&cmd, cli.verbose)?;
+            summary::run(&cmd, cli.verbose)?
         }
 
         Commands::Grep {
@@ -1085,18 +1306,16 @@ fn main() -> Result<()> {
             context_only,
             file_type,
             extra_args,
-        } => {
-            grep_cmd::run(grep_cmd::GrepOptions {
-                pattern: &pattern,
-                path: &path,
-                max_line_len: max_len,
-                max_results: max,
-                context_only,
-                file_type: file_type.as_deref(),
-                extra_args: &extra_args,
-                verbose: cli.verbose,
-            })?;
-        }
+        } => grep_cmd::run(grep_cmd::GrepOptions {
+            pattern: &pattern,
+            path: &path,
+            max_line_len: max_len,
+            max_results: max,
+            context_only,
+            file_type: file_type.as_deref(),
+            extra_args: &extra_args,
+            verbose: cli.verbose,
+        })?,
 
         Commands::Init {
             global,
@@ -1121,19 +1340,35 @@ fn main() -> Result<()> {
                 };
                 init::run(global, claude_md, hook_only, patch_mode, cli.verbose)?;
             }
+            ExecResult::SUCCESS
         }
 
         Commands::Wget { url, stdout, args } => {
             if stdout {
-                wget_cmd::run_stdout(&url, &args, cli.verbose)?;
+                wget_cmd::run_stdout(&url, &args, cli.verbose)?
             } else {
-                wget_cmd::run(&url, &args, cli.verbose)?;
+                wget_cmd::run(&url, &args, cli.verbose)?
             }
         }
 
-        Commands::Wc { args } => {
-            wc_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Wc { args } => wc_cmd::run(&args, cli.verbose)?,
+
+        Commands::Bench {
+            dir,
+            filter,
+            baseline,
+            update_baseline,
+            tolerance,
+            format,
+        } => bench_cmd::run(
+            &dir,
+            filter.as_deref(),
+            baseline.as_deref(),
+            update_baseline,
+            tolerance,
+            &format,
+            cli.verbose,
+        )?,
 
         Commands::Gain {
             graph,
@@ -1145,20 +1380,30 @@ fn main() -> Result<()> {
             monthly,
             all,
             format,
-        } => {
-            gain::run(gain::GainOptions {
-                graph,
-                history,
-                quota,
-                tier: &tier,
-                daily,
-                weekly,
-                monthly,
-                all,
-                format: &format,
-                verbose: cli.verbose,
-            })?;
-        }
+            recalibrate,
+            project,
+            session,
+            by,
+            check,
+            report,
+        } => gain::run(gain::GainOptions {
+            graph,
+            history,
+            quota,
+            tier: &tier,
+            daily,
+            weekly,
+            monthly,
+            all,
+            format: &format,
+            recalibrate,
+            project: project.as_deref(),
+            session: session.as_deref(),
+            by: by.as_deref(),
+            check,
+            report: report.as_deref(),
+            verbose: cli.verbose,
+        })?,
 
         Commands::CcEconomics {
             daily,
@@ -1168,6 +1413,7 @@ fn main() -> Result<()> {
             format,
         } => {
             cc_economics::run(daily, weekly, monthly, all, &format, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Config { create } => {
@@ -1177,107 +1423,84 @@ fn main() -> Result<()> {
             } else {
                 config::show_config()?;
             }
+            ExecResult::SUCCESS
         }
 
         Commands::Vitest { command } => match command {
             VitestCommands::Run { args } => {
-                vitest_cmd::run(vitest_cmd::VitestCommand::Run, &args, cli.verbose)?;
+                vitest_cmd::run(vitest_cmd::VitestCommand::Run, &args, cli.verbose)?
             }
         },
 
         Commands::Prisma { command } => match command {
             PrismaCommands::Generate { args } => {
-                prisma_cmd::run(prisma_cmd::PrismaCommand::Generate, &args, cli.verbose)?;
+                prisma_cmd::run(prisma_cmd::PrismaCommand::Generate, &args, cli.verbose)?
             }
             PrismaCommands::Migrate { command } => match command {
-                PrismaMigrateCommands::Dev { name, args } => {
-                    prisma_cmd::run(
-                        prisma_cmd::PrismaCommand::Migrate {
-                            subcommand: prisma_cmd::MigrateSubcommand::Dev { name },
-                        },
-                        &args,
-                        cli.verbose,
-                    )?;
-                }
-                PrismaMigrateCommands::Status { args } => {
-                    prisma_cmd::run(
-                        prisma_cmd::PrismaCommand::Migrate {
-                            subcommand: prisma_cmd::MigrateSubcommand::Status,
-                        },
-                        &args,
-                        cli.verbose,
-                    )?;
-                }
-                PrismaMigrateCommands::Deploy { args } => {
-                    prisma_cmd::run(
-                        prisma_cmd::PrismaCommand::Migrate {
-                            subcommand: prisma_cmd::MigrateSubcommand::Deploy,
-                        },
-                        &args,
-                        cli.verbose,
-                    )?;
-                }
+                PrismaMigrateCommands::Dev { name, args } => prisma_cmd::run(
+                    prisma_cmd::PrismaCommand::Migrate {
+                        subcommand: prisma_cmd::MigrateSubcommand::Dev { name },
+                    },
+                    &args,
+                    cli.verbose,
+                )?,
+                PrismaMigrateCommands::Status { args } => prisma_cmd::run(
+                    prisma_cmd::PrismaCommand::Migrate {
+                        subcommand: prisma_cmd::MigrateSubcommand::Status,
+                    },
+                    &args,
+                    cli.verbose,
+                )?,
+                PrismaMigrateCommands::Deploy { args } => prisma_cmd::run(
+                    prisma_cmd::PrismaCommand::Migrate {
+                        subcommand: prisma_cmd::MigrateSubcommand::Deploy,
+                    },
+                    &args,
+                    cli.verbose,
+                )?,
             },
             PrismaCommands::DbPush { args } => {
-                prisma_cmd::run(prisma_cmd::PrismaCommand::DbPush, &args, cli.verbose)?;
+                prisma_cmd::run(prisma_cmd::PrismaCommand::DbPush, &args, cli.verbose)?
             }
         },
 
-        Commands::Tsc { args } => {
-            tsc_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Tsc { args } => tsc_cmd::run(&args, cli.verbose)?,
 
-        Commands::Next { args } => {
-            next_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Next { args } => next_cmd::run(&args, cli.verbose)?,
 
-        Commands::Lint { args } => {
-            lint_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Lint { args } => lint_cmd::run(&args, cli.verbose)?,
 
-        Commands::Prettier { args } => {
-            prettier_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Prettier { args } => prettier_cmd::run(&args, cli.verbose)?,
 
-        Commands::Format { args } => {
-            format_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Format { args } => format_cmd::run(&args, cli.verbose)?,
 
-        Commands::Playwright { args } => {
-            playwright_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Playwright { args } => playwright_cmd::run(&args, cli.verbose)?,
 
         Commands::Cargo { command } => match command {
             CargoCommands::Build { args } => {
-                cargo_cmd::run(cargo_cmd::CargoCommand::Build, &args, cli.verbose)?;
+                cargo_cmd::run(cargo_cmd::CargoCommand::Build, &args, cli.verbose)?
             }
             CargoCommands::Test { args } => {
-                cargo_cmd::run(cargo_cmd::CargoCommand::Test, &args, cli.verbose)?;
+                cargo_cmd::run(cargo_cmd::CargoCommand::Test, &args, cli.verbose)?
             }
             CargoCommands::Clippy { args } => {
-                cargo_cmd::run(cargo_cmd::CargoCommand::Clippy, &args, cli.verbose)?;
+                cargo_cmd::run(cargo_cmd::CargoCommand::Clippy, &args, cli.verbose)?
             }
             CargoCommands::Check { args } => {
-                cargo_cmd::run(cargo_cmd::CargoCommand::Check, &args, cli.verbose)?;
+                cargo_cmd::run(cargo_cmd::CargoCommand::Check, &args, cli.verbose)?
             }
             CargoCommands::Install { args } => {
-                cargo_cmd::run(cargo_cmd::CargoCommand::Install, &args, cli.verbose)?;
+                cargo_cmd::run(cargo_cmd::CargoCommand::Install, &args, cli.verbose)?
             }
             CargoCommands::Nextest { args } => {
-                cargo_cmd::run(cargo_cmd::CargoCommand::Nextest, &args, cli.verbose)?;
-            }
-            CargoCommands::Other(args) => {
-                cargo_cmd::run_passthrough(&args, cli.verbose)?;
+                cargo_cmd::run(cargo_cmd::CargoCommand::Nextest, &args, cli.verbose)?
             }
+            CargoCommands::Other(args) => cargo_cmd::run_passthrough(&args, cli.verbose)?,
         },
 
-        Commands::Npm { args } => {
-            npm_cmd::run(&args, cli.verbose, cli.skip_env)?;
-        }
+        Commands::Npm { args } => npm_cmd::run(&args, cli.verbose, cli.skip_env)?,
 
-        Commands::Curl { args } => {
-            curl_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Curl { args } => curl_cmd::run(&args, cli.verbose)?,
 
         Commands::Discover {
             project,
@@ -1285,8 +1508,18 @@ fn main() -> Result<()> {
             all,
             since,
             format,
+            provider,
+            suggest,
+            apply,
         } => {
-            discover::run(project.as_deref(), all, since, limit, &format, cli.verbose)?;
+            let query = SessionQuery {
+                provider,
+                project,
+                all_projects: all,
+                since_days: since,
+            };
+            discover::run(&query, limit, &format, suggest, apply, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Learn {
@@ -1297,16 +1530,33 @@ fn main() -> Result<()> {
             write_rules,
             min_confidence,
             min_occurrences,
+            apply,
+            revert,
+            provider,
         } => {
-            learn::run(
-                project,
-                all,
-                since,
-                format,
-                write_rules,
-                min_confidence,
-                min_occurrences,
-            )?;
+            if revert {
+                learn::corrections::revert()?;
+            } else {
+                let query = SessionQuery {
+                    provider,
+                    project,
+                    all_projects: all,
+                    since_days: since,
+                };
+                learn::run(
+                    &query,
+                    format,
+                    learn::Actions { write_rules, apply },
+                    min_confidence,
+                    min_occurrences,
+                )?;
+            }
+            ExecResult::SUCCESS
+        }
+
+        Commands::Mcp => {
+            mcp_cmd::run(cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
         Commands::Npx { args } => {
@@ -1316,31 +1566,23 @@ fn main() -> Result<()> {
 
             // Intelligent routing: delegate to specialized filters
             match args[0].as_str() {
-                "tsc" | "typescript" => {
-                    tsc_cmd::run(&args[1..], cli.verbose)?;
-                }
-                "eslint" => {
-                    lint_cmd::run(&args[1..], cli.verbose)?;
-                }
+                "tsc" | "typescript" => tsc_cmd::run(&args[1..], cli.verbose)?,
+                "eslint" => lint_cmd::run(&args[1..], cli.verbose)?,
                 "prisma" => {
                     // Route to prisma_cmd based on subcommand
                     if args.len() > 1 {
                         let prisma_args: Vec<String> = args[2..].to_vec();
                         match args[1].as_str() {
-                            "generate" => {
-                                prisma_cmd::run(
-                                    prisma_cmd::PrismaCommand::Generate,
-                                    &prisma_args,
-                                    cli.verbose,
-                                )?;
-                            }
-                            "db" if args.len() > 2 && args[2] == "push" => {
-                                prisma_cmd::run(
-                                    prisma_cmd::PrismaCommand::DbPush,
-                                    &args[3..],
-                                    cli.verbose,
-                                )?;
-                            }
+                            "generate" => prisma_cmd::run(
+                                prisma_cmd::PrismaCommand::Generate,
+                                &prisma_args,
+                                cli.verbose,
+                            )?,
+                            "db" if args.len() > 2 && args[2] == "push" => prisma_cmd::run(
+                                prisma_cmd::PrismaCommand::DbPush,
+                                &args[3..],
+                                cli.verbose,
+                            )?,
                             _ => {
                                 // Passthrough other prisma subcommands
                                 let timer = tracking::TimedExecution::start();
@@ -1354,9 +1596,7 @@ fn main() -> Result<()> {
                                     &format!("npx {}", args_str),
                                     &format!("rtk npx {} (passthrough)", args_str),
                                 );
-                                if !status.success() {
-                                    std::process::exit(status.code().unwrap_or(1));
-                                }
+                                ExecResult::from_status(&status)
                             }
                         }
                     } else {
@@ -1366,62 +1606,67 @@ fn main() -> Result<()> {
                             .status()
                             .context("Failed to run npx prisma")?;
                         timer.track_passthrough("npx prisma", "rtk npx prisma (passthrough)");
-                        if !status.success() {
-                            std::process::exit(status.code().unwrap_or(1));
-                        }
+                        ExecResult::from_status(&status)
                     }
                 }
-                "next" => {
-                    next_cmd::run(&args[1..], cli.verbose)?;
-                }
-                "prettier" => {
-                    prettier_cmd::run(&args[1..], cli.verbose)?;
-                }
-                "playwright" => {
-                    playwright_cmd::run(&args[1..], cli.verbose)?;
-                }
+                "next" => next_cmd::run(&args[1..], cli.verbose)?,
+                "prettier" => prettier_cmd::run(&args[1..], cli.verbose)?,
+                "playwright" => playwright_cmd::run(&args[1..], cli.verbose)?,
                 _ => {
                     // Generic passthrough with npm boilerplate filter
-                    npm_cmd::run(&args, cli.verbose, cli.skip_env)?;
+                    npm_cmd::run(&args, cli.verbose, cli.skip_env)?
                 }
             }
         }
 
-        Commands::Ruff { args } => {
-            ruff_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Ruff { args } => ruff_cmd::run(&args, cli.verbose)?,
 
-        Commands::Pytest { args } => {
-            pytest_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Pytest { args } => pytest_cmd::run(&args, cli.verbose)?,
 
-        Commands::Pip { args } => {
-            pip_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::Pip { args } => pip_cmd::run(&args, cli.verbose)?,
 
         Commands::Go { command } => match command {
-            GoCommands::Test { args } => {
-                go_cmd::run_test(&args, cli.verbose)?;
-            }
-            GoCommands::Build { args } => {
-                go_cmd::run_build(&args, cli.verbose)?;
-            }
-            GoCommands::Vet { args } => {
-                go_cmd::run_vet(&args, cli.verbose)?;
-            }
-            GoCommands::Other(args) => {
-                go_cmd::run_other(&args, cli.verbose)?;
-            }
+            GoCommands::Test { args } => go_cmd::run_test(&args, cli.verbose)?,
+            GoCommands::Build { args } => go_cmd::run_build(&args, cli.verbose)?,
+            GoCommands::Vet { args } => go_cmd::run_vet(&args, cli.verbose)?,
+            GoCommands::Other(args) => go_cmd::run_other(&args, cli.verbose)?,
         },
 
-        Commands::GolangciLint { args } => {
-            golangci_cmd::run(&args, cli.verbose)?;
-        }
+        Commands::GolangciLint { args } => golangci_cmd::run(&args, cli.verbose)?,
 
         Commands::HookAudit { since } => {
             hook_audit_cmd::run(since, cli.verbose)?;
+            ExecResult::SUCCESS
         }
 
+        Commands::Rewrite { command } => rewrite_cmd::run(&command, cli.verbose)?,
+
+        Commands::Hook { command } => match command {
+            HookCommands::PreToolUse => {
+                rewrite_cmd::run_pretooluse(cli.verbose)?;
+                ExecResult::SUCCESS
+            }
+        },
+
+        Commands::Tee { command } => match command {
+            TeeCommands::List { limit } => {
+                tee_cmd::run_list(limit, cli.verbose)?;
+                ExecResult::SUCCESS
+            }
+            TeeCommands::Show { id, lines, grep } => {
+                tee_cmd::run_show(&id, lines.as_deref(), grep.as_deref(), cli.verbose)?;
+                ExecResult::SUCCESS
+            }
+            TeeCommands::Last { lines, grep } => {
+                tee_cmd::run_last(lines.as_deref(), grep.as_deref(), cli.verbose)?;
+                ExecResult::SUCCESS
+            }
+            TeeCommands::Grep { pattern, id } => {
+                tee_cmd::run_grep(&pattern, id.as_deref(), cli.verbose)?;
+                ExecResult::SUCCESS
+            }
+        },
+
         Commands::Proxy { args } => {
             use std::process::Command;
 
@@ -1433,17 +1678,27 @@ fn main() -> Result<()> {
 
             let timer = tracking::TimedExecution::start();
 
-            let cmd_name = args[0].to_string_lossy();
-            let cmd_args: Vec<String> = args[1..]
+            let mut argv: Vec<String> = args
                 .iter()
                 .map(|s| s.to_string_lossy().into_owned())
                 .collect();
+            // Learned corrections (`rtk learn --apply`): fix typos, refuse known-bad forms
+            match learn::corrections::correct_argv(&mut argv) {
+                Ok(notes) => {
+                    for note in notes {
+                        eprintln!("rtk: corrected {}", note);
+                    }
+                }
+                Err(reason) => anyhow::bail!(reason),
+            }
+            let cmd_name = argv[0].as_str();
+            let cmd_args: Vec<String> = argv[1..].to_vec();
 
             if cli.verbose > 0 {
                 eprintln!("Proxy mode: {} {}", cmd_name, cmd_args.join(" "));
             }
 
-            let output = Command::new(cmd_name.as_ref())
+            let output = Command::new(cmd_name)
                 .args(&cmd_args)
                 .output()
                 .context(format!("Failed to execute command: {}", cmd_name))?;
@@ -1451,25 +1706,60 @@ fn main() -> Result<()> {
             let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
             let full_output = format!("{}{}", stdout, stderr);
+            let original_cmd = format!("{} {}", cmd_name, cmd_args.join(" "));
+            let exit_code = exec::exit_code(&output.status);
 
-            // Print output
-            print!("{}", stdout);
-            eprint!("{}", stderr);
+            // User-defined [[filters]] rule for this command, if any
+            let user_filtered = user_filter::find_filter(&original_cmd).and_then(|rule| {
+                if cli.verbose > 0 {
+                    eprintln!("Applying filter: {}", rule.name);
+                }
+                match rule.apply(&original_cmd, &full_output, exit_code) {
+                    Ok(filtered) => Some(filtered),
+                    Err(e) => {
+                        eprintln!(
+                            "rtk: filter '{}' failed, showing raw output: {:#}",
+                            rule.name, e
+                        );
+                        None
+                    }
+                }
+            });
 
-            // Track usage (input = output since no filtering)
-            timer.track(
-                &format!("{} {}", cmd_name, cmd_args.join(" ")),
-                &format!("rtk proxy {} {}", cmd_name, cmd_args.join(" ")),
-                &full_output,
-                &full_output,
-            );
+            if let Some(filtered) = user_filtered {
+                if let Some(hint) = tee::tee_and_hint(&full_output, cmd_name, exit_code) {
+                    println!("{}\n{}", filtered, hint);
+                } else {
+                    println!("{}", filtered);
+                }
+                preserve::track_with_exit(
+                    &timer,
+                    &original_cmd,
+                    &format!("rtk proxy {}", original_cmd),
+                    &full_output,
+                    &filtered,
+                    exit_code,
+                );
+            } else {
+                // Print output
+                print!("{}", stdout);
+                eprint!("{}", stderr);
+
+                // Track usage (input = output since no filtering)
+                timer.track_with_exit(
+                    &original_cmd,
+                    &format!("rtk proxy {}", original_cmd),
+                    &full_output,
+                    &full_output,
+                    exit_code,
+                );
+            }
 
             // Exit with same code as child process
-            if !output.status.success() {
-                std::process::exit(output.status.code().unwrap_or(1));
-            }
+            ExecResult::code(exit_code)
         }
-    }
+    };
 
+    result.exit();
     Ok(())
 }
@@ -0,0 +1,539 @@
+//! `rtk mcp`: Model Context Protocol server over stdio.
+//!
+//! For agents that can't use the PreToolUse hook. Each tool maps onto an rtk
+//! subcommand (`read`, `grep`, `git`, `test`, `tee show`) and runs it as a child
+//! rtk process: the command modules print to stdout and may exit, and stdout is
+//! the protocol channel here. The child tracks savings through `TimedExecution`
+//! exactly like a CLI call; the server returns its filtered text plus the tee id.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::learn::corrections::{self, Checked};
+use crate::rewrite_cmd::{self, HookMode, RewriteResult};
+
+/// Protocol revisions this server understands, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serve MCP requests on stdin/stdout until stdin closes.
+pub fn run(verbose: u8) -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+
+    if verbose > 0 {
+        eprintln!("rtk mcp: serving on stdio");
+    }
+
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if verbose > 1 {
+            eprintln!("<- {}", line);
+        }
+
+        if let Some(response) = handle_message(&line, verbose) {
+            let out = serde_json::to_string(&response)?;
+            if verbose > 1 {
+                eprintln!("-> {}", out);
+            }
+            writeln!(stdout, "{}", out)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle one JSON-RPC message. Notifications get no response.
+fn handle_message(line: &str, verbose: u8) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+
+    let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+        return Some(error_response(
+            message.get("id").cloned().unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "missing method",
+        ));
+    };
+    // Notifications (initialized, cancelled, ...) carry no id
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(&params, verbose),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params
+        .get("protocolVersion")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| **v == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "rtk", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Prefer these tools over raw shell commands: output is filtered to save tokens. \
+                         When a result mentions a tee id, call tee_show with it to read the full raw output."
+    })
+}
+
+fn tool_definitions() -> Value {
+    let cwd =
+        json!({ "type": "string", "description": "Working directory (defaults to the server's)" });
+    json!([
+        {
+            "name": "run_command",
+            "description": "Run a shell command. Commands rtk supports (git, cargo, npm, pytest, ...) are rewritten to their filtered rtk form; others run unfiltered.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "command": { "type": "string" }, "cwd": cwd },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "read_file",
+            "description": "Read a file with comments/blank lines filtered by level.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "level": { "type": "string", "enum": ["none", "minimal", "aggressive"] },
+                    "max_lines": { "type": "integer", "minimum": 1 },
+                    "line_numbers": { "type": "boolean" },
+                    "cwd": cwd
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "grep",
+            "description": "Search files with ripgrep; results grouped by file.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" },
+                    "path": { "type": "string" },
+                    "file_type": { "type": "string", "description": "e.g. rust, ts, py" },
+                    "max": { "type": "integer", "minimum": 1 },
+                    "cwd": cwd
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "git_status",
+            "description": "Compact git status.",
+            "inputSchema": { "type": "object", "properties": { "cwd": cwd } }
+        },
+        {
+            "name": "git_diff",
+            "description": "Compact git diff. Pass extra git diff arguments in args.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "args": { "type": "array", "items": { "type": "string" } },
+                    "cwd": cwd
+                }
+            }
+        },
+        {
+            "name": "test",
+            "description": "Run a test command and show only failures.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string", "description": "e.g. cargo test, npm test" },
+                    "cwd": cwd
+                },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "tee_show",
+            "description": "Show raw output saved by an earlier call (by tee id), optionally sliced.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string" },
+                    "lines": { "type": "string", "description": "1-based range a:b" },
+                    "grep": { "type": "string", "description": "regex filter" }
+                },
+                "required": ["id"]
+            }
+        }
+    ])
+}
+
+/// How a tool call is executed.
+#[derive(Debug, PartialEq)]
+enum Invocation {
+    /// `rtk <args>`
+    Rtk(Vec<String>),
+    /// `sh -c <command>` (already rewritten to rtk)
+    Shell(String),
+}
+
+fn call_tool(params: &Value, verbose: u8) -> std::result::Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+    let args = params.get("arguments").cloned().unwrap_or(json!({}));
+
+    let invocation = build_invocation(name, &args).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+    let cwd = args.get("cwd").and_then(|c| c.as_str()).map(PathBuf::from);
+
+    if verbose > 0 {
+        eprintln!("rtk mcp: {} -> {:?}", name, invocation);
+    }
+
+    // Execution failures are tool errors, not protocol errors
+    Ok(match execute(&invocation, cwd) {
+        Ok(output) => output.into_result(),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": format!("Error: {:#}", e) }],
+            "isError": true
+        }),
+    })
+}
+
+fn build_invocation(name: &str, args: &Value) -> Result<Invocation> {
+    let str_arg = |key: &str| args.get(key).and_then(|v| v.as_str());
+    let required = |key: &str| {
+        str_arg(key)
+            .filter(|v| !v.trim().is_empty())
+            .ok_or_else(|| anyhow!("'{}' is required", key))
+    };
+
+    let rtk_args: Vec<String> = match name {
+        "run_command" => return command_invocation(required("command")?),
+        "read_file" => {
+            let mut a = vec!["read".to_string(), required("path")?.to_string()];
+            if let Some(level) = str_arg("level") {
+                a.extend(["--level".to_string(), level.to_string()]);
+            }
+            if let Some(n) = args.get("max_lines").and_then(|v| v.as_u64()) {
+                a.extend(["--max-lines".to_string(), n.to_string()]);
+            }
+            if args.get("line_numbers").and_then(|v| v.as_bool()) == Some(true) {
+                a.push("--line-numbers".to_string());
+            }
+            a
+        }
+        "grep" => {
+            let mut a = vec!["grep".to_string(), required("pattern")?.to_string()];
+            a.push(str_arg("path").unwrap_or(".").to_string());
+            if let Some(ft) = str_arg("file_type") {
+                a.extend(["--file-type".to_string(), ft.to_string()]);
+            }
+            if let Some(n) = args.get("max").and_then(|v| v.as_u64()) {
+                a.extend(["--max".to_string(), n.to_string()]);
+            }
+            a
+        }
+        "git_status" => vec!["git".to_string(), "status".to_string()],
+        "git_diff" => {
+            let mut a = vec!["git".to_string(), "diff".to_string()];
+            if let Some(extra) = args.get("args").and_then(|v| v.as_array()) {
+                a.extend(extra.iter().filter_map(|v| v.as_str()).map(String::from));
+            }
+            a
+        }
+        "test" => vec!["test".to_string(), required("command")?.to_string()],
+        "tee_show" => {
+            let mut a = vec!["tee".to_string(), "show".to_string()];
+            a.push(required("id")?.to_string());
+            if let Some(lines) = str_arg("lines") {
+                a.extend(["--lines".to_string(), lines.to_string()]);
+            }
+            if let Some(grep) = str_arg("grep") {
+                a.extend(["--grep".to_string(), grep.to_string()]);
+            }
+            a
+        }
+        _ => bail!("unknown tool '{}'", name),
+    };
+
+    Ok(Invocation::Rtk(rtk_args))
+}
+
+/// Apply learned corrections, then rewrite the shell command to rtk when
+/// supported, else run it through `rtk proxy` so it is still tracked (and user
+/// filters apply). Corrections are checked here because `rtk proxy sh -c` only
+/// sees `sh`.
+fn command_invocation(command: &str) -> Result<Invocation> {
+    let command = match corrections::check_command(command) {
+        Checked::Rejected(reason) => bail!(reason),
+        Checked::Rewritten(fixed, _) => fixed,
+        Checked::Unchanged => command.to_string(),
+    };
+    Ok(
+        match rewrite_cmd::rewrite_command(&command, &shell_rtk_exe(), HookMode::Flex) {
+            RewriteResult::Rewritten(rewritten) => Invocation::Shell(rewritten),
+            RewriteResult::AlreadyRtk => Invocation::Shell(command),
+            // Heredoc or no rtk filter (rewrite_command never rejects)
+            _ => Invocation::Rtk(vec![
+                "proxy".to_string(),
+                "sh".to_string(),
+                "-c".to_string(),
+                command,
+            ]),
+        },
+    )
+}
+
+fn rtk_exe() -> String {
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "rtk".to_string())
+}
+
+/// `rtk_exe` quoted for `sh -c`: the binary may live under a path with spaces
+/// (`~/Library/Application Support/...`)
+fn shell_rtk_exe() -> String {
+    crate::init::shell_escape_for_hook(&rtk_exe())
+}
+
+/// Captured result of one tool call.
+#[derive(Debug)]
+struct ToolOutput {
+    text: String,
+    exit_code: i32,
+    tee_id: Option<String>,
+}
+
+impl ToolOutput {
+    fn from_streams(stdout: &str, stderr: &str, exit_code: i32) -> Self {
+        let mut tee_id = None;
+        let mut lines = Vec::new();
+        for line in stdout.lines() {
+            match crate::tee::parse_hint(line) {
+                Some((_, id)) => tee_id = id.or(tee_id),
+                None => lines.push(line),
+            }
+        }
+
+        let mut text = lines.join("\n").trim_end().to_string();
+        if !stderr.trim().is_empty() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(stderr.trim_end());
+        }
+        if let Some(id) = &tee_id {
+            text.push_str(&format!("\n[full output saved: tee_show id=\"{}\"]", id));
+        }
+
+        Self {
+            text,
+            exit_code,
+            tee_id,
+        }
+    }
+
+    fn into_result(self) -> Value {
+        json!({
+            "content": [{ "type": "text", "text": self.text }],
+            "structuredContent": { "exit_code": self.exit_code, "tee_id": self.tee_id },
+            "isError": self.exit_code != 0
+        })
+    }
+}
+
+fn execute(invocation: &Invocation, cwd: Option<PathBuf>) -> Result<ToolOutput> {
+    let mut cmd = match invocation {
+        Invocation::Rtk(args) => {
+            let mut cmd = Command::new(rtk_exe());
+            cmd.args(args);
+            cmd
+        }
+        Invocation::Shell(command) => {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", command]);
+            cmd
+        }
+    };
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+
+    let output = cmd
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run rtk")?;
+
+    Ok(ToolOutput::from_streams(
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+        crate::exec::exit_code(&output.status),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize_and_list() {
+        let init = handle_message(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#,
+            0,
+        )
+        .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(init["result"]["serverInfo"]["name"], "rtk");
+
+        let list = handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#, 0).unwrap();
+        let names: Vec<&str> = list["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"run_command"));
+        assert!(names.contains(&"git_diff"));
+        assert!(names.contains(&"tee_show"));
+    }
+
+    #[test]
+    fn test_notifications_and_errors() {
+        assert!(handle_message(
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            0
+        )
+        .is_none());
+
+        let unknown = handle_message(r#"{"jsonrpc":"2.0","id":3,"method":"nope"}"#, 0).unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let garbage = handle_message("{not json", 0).unwrap();
+        assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+
+        let bad_tool = handle_message(
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"read_file","arguments":{}}}"#,
+            0,
+        )
+        .unwrap();
+        assert_eq!(bad_tool["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_build_invocation() {
+        assert_eq!(
+            build_invocation(
+                "read_file",
+                &json!({"path": "src/main.rs", "level": "aggressive", "max_lines": 50})
+            )
+            .unwrap(),
+            Invocation::Rtk(
+                [
+                    "read",
+                    "src/main.rs",
+                    "--level",
+                    "aggressive",
+                    "--max-lines",
+                    "50"
+                ]
+                .map(String::from)
+                .to_vec()
+            )
+        );
+        assert_eq!(
+            build_invocation("git_diff", &json!({"args": ["--stat", "HEAD~1"]})).unwrap(),
+            Invocation::Rtk(
+                ["git", "diff", "--stat", "HEAD~1"]
+                    .map(String::from)
+                    .to_vec()
+            )
+        );
+        assert_eq!(
+            build_invocation("test", &json!({"command": "cargo test"})).unwrap(),
+            Invocation::Rtk(["test", "cargo test"].map(String::from).to_vec())
+        );
+        assert!(build_invocation("rm_rf", &json!({})).is_err());
+
+        // Unsupported commands still go through rtk (proxy) for tracking
+        assert_eq!(
+            build_invocation("run_command", &json!({"command": "echo hi"})).unwrap(),
+            Invocation::Rtk(["proxy", "sh", "-c", "echo hi"].map(String::from).to_vec())
+        );
+        match build_invocation("run_command", &json!({"command": "git status"})).unwrap() {
+            Invocation::Shell(cmd) => assert!(cmd.ends_with(" git status"), "got: {}", cmd),
+            other => panic!("expected rewrite, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rewrite_runs_from_exe_path_with_spaces() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("Application Support").join("rtk");
+        std::fs::create_dir_all(exe.parent().unwrap()).unwrap();
+        std::fs::write(&exe, "#!/bin/sh\necho \"rtk $*\"\n").unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let quoted = crate::init::shell_escape_for_hook(&exe.to_string_lossy());
+        let RewriteResult::Rewritten(command) =
+            rewrite_cmd::rewrite_command("git status", &quoted, HookMode::Flex)
+        else {
+            panic!("expected a rewrite");
+        };
+        let output = execute(&Invocation::Shell(command), None).unwrap();
+        assert_eq!(output.exit_code, 0, "{}", output.text);
+        assert_eq!(output.text.trim(), "rtk git status");
+    }
+
+    #[test]
+    fn test_tool_output_extracts_tee_id() {
+        let output = ToolOutput::from_streams(
+            "FAILURES (1):\n1. tests::bad\n[full output: ~/.local/share/rtk/tee/a1b2c3.log | rtk tee show a1b2c3]\n",
+            "",
+            101,
+        );
+        assert_eq!(output.tee_id.as_deref(), Some("a1b2c3"));
+        assert!(!output.text.contains("rtk tee show"));
+        assert!(output.text.contains("tee_show id=\"a1b2c3\""));
+
+        let result = output.into_result();
+        assert_eq!(result["isError"], true);
+        assert_eq!(result["structuredContent"]["exit_code"], 101);
+    }
+}
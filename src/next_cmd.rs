@@ -1,10 +1,11 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::{strip_ansi, truncate};
 use anyhow::{Context, Result};
 use regex::Regex;
 use std::process::Command;
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Try next directly first, fallback to npx if not found
@@ -44,18 +45,14 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track("next build", "rtk next build", &raw, &filtered);
+    crate::preserve::track(&timer, "next build", "rtk next build", &raw, &filtered);
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Filter Next.js build output - extract routes, bundles, warnings
-fn filter_next_build(output: &str) -> String {
+pub(crate) fn filter_next_build(output: &str) -> String {
     lazy_static::lazy_static! {
         // Route line pattern: ○ /dashboard    1.2 kB  132 kB
         static ref ROUTE_PATTERN: Regex = Regex::new(
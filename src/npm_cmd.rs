@@ -1,8 +1,9 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
 
-pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
+pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("npm");
@@ -28,22 +29,19 @@ pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
     let filtered = filter_npm_output(&raw);
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("npm run {}", args.join(" ")),
         &format!("rtk npm run {}", args.join(" ")),
         &raw,
         &filtered,
     );
 
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Filter npm run output - strip boilerplate, progress bars, npm WARN
-fn filter_npm_output(output: &str) -> String {
+pub(crate) fn filter_npm_output(output: &str) -> String {
     let mut result = Vec::new();
 
     for line in output.lines() {
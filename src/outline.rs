@@ -0,0 +1,683 @@
+//! Signature-only outlines and single-symbol extraction backed by tree-sitter.
+//!
+//! Unlike the regex filters in `filter.rs`, the grammars understand nested
+//! braces, string literals and Python indentation, so line ranges are exact.
+
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// One outline entry (module, type, impl, function, method...)
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub kind: &'static str,
+    /// Qualified name, outermost first (`["Foo", "bar"]`)
+    pub path: Vec<String>,
+    pub signature: String,
+    /// First line of the item itself (1-based)
+    pub start_line: usize,
+    pub end_line: usize,
+    /// First line including attached doc comments, attributes and decorators
+    pub doc_line: usize,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grammar {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl Grammar {
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        Some(match ext.as_str() {
+            "rs" => Grammar::Rust,
+            "py" | "pyw" | "pyi" => Grammar::Python,
+            "js" | "mjs" | "cjs" | "jsx" => Grammar::JavaScript,
+            "ts" | "mts" | "cts" => Grammar::TypeScript,
+            "tsx" => Grammar::Tsx,
+            "go" => Grammar::Go,
+            _ => return None,
+        })
+    }
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Grammar::Python => tree_sitter_python::LANGUAGE.into(),
+            Grammar::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Grammar::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Grammar::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Grammar::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    fn separator(self) -> &'static str {
+        match self {
+            Grammar::Rust => "::",
+            _ => ".",
+        }
+    }
+}
+
+/// An item recognised in the syntax tree, before it becomes a `Symbol`
+struct Item<'t> {
+    kind: &'static str,
+    name: String,
+    /// Outer node: its extent is the item's line range
+    node: Node<'t>,
+    /// Where the signature text starts (skips decorators)
+    sig_start: usize,
+    body: Option<Node<'t>>,
+    /// Signature computed by the classifier instead of sliced from source
+    signature: Option<String>,
+    /// Qualifier for items declared outside their type (Go methods)
+    owner: Option<String>,
+    /// Recurse into the body for members
+    nested: bool,
+}
+
+impl<'t> Item<'t> {
+    fn new(kind: &'static str, name: String, node: Node<'t>) -> Self {
+        Item {
+            kind,
+            name,
+            node,
+            sig_start: node.start_byte(),
+            body: node.child_by_field_name("body"),
+            signature: None,
+            owner: None,
+            nested: false,
+        }
+    }
+
+    fn nested(mut self) -> Self {
+        self.nested = true;
+        self
+    }
+}
+
+/// Whether outlines are available for this file type
+pub fn is_supported(path: &Path) -> bool {
+    Grammar::from_path(path).is_some()
+}
+
+/// Parse `content` and list its symbols in source order (depth-first)
+pub fn symbols(path: &Path, content: &str) -> Result<Vec<Symbol>> {
+    let grammar = Grammar::from_path(path)
+        .ok_or_else(|| anyhow!("Outline not supported for {}", path.display()))?;
+
+    let mut parser = Parser::new();
+    parser.set_language(&grammar.language())?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| anyhow!("Failed to parse {}", path.display()))?;
+
+    let mut out = Vec::new();
+    collect(grammar, tree.root_node(), content, &[], 0, &mut out);
+    Ok(out)
+}
+
+fn collect(
+    grammar: Grammar,
+    scope: Node,
+    src: &str,
+    parent: &[String],
+    depth: usize,
+    out: &mut Vec<Symbol>,
+) {
+    let mut cursor = scope.walk();
+    for child in scope.named_children(&mut cursor) {
+        for item in classify(grammar, child, src) {
+            let mut path = parent.to_vec();
+            if let Some(owner) = &item.owner {
+                path.push(owner.clone());
+            }
+            path.push(item.name.clone());
+
+            let signature = item
+                .signature
+                .clone()
+                .unwrap_or_else(|| signature_text(src, &item));
+            out.push(Symbol {
+                kind: item.kind,
+                path: path.clone(),
+                signature,
+                start_line: item.node.start_position().row + 1,
+                end_line: item.node.end_position().row + 1,
+                doc_line: leading_docs_row(item.node) + 1,
+                depth,
+            });
+
+            if item.nested {
+                if let Some(body) = item.body {
+                    collect(grammar, body, src, &path, depth + 1, out);
+                }
+            }
+        }
+    }
+}
+
+fn classify<'t>(grammar: Grammar, node: Node<'t>, src: &str) -> Vec<Item<'t>> {
+    match grammar {
+        Grammar::Rust => rust_item(node, src).into_iter().collect(),
+        Grammar::Python => python_item(node, src).into_iter().collect(),
+        Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx => js_items(node, src),
+        Grammar::Go => go_items(node, src),
+    }
+}
+
+fn rust_item<'t>(node: Node<'t>, src: &str) -> Option<Item<'t>> {
+    let kind = match node.kind() {
+        "function_item" | "function_signature_item" => "fn",
+        "struct_item" => "struct",
+        "enum_item" => "enum",
+        "union_item" => "union",
+        "trait_item" => "trait",
+        "impl_item" => "impl",
+        "mod_item" => "mod",
+        "type_item" => "type",
+        "const_item" => "const",
+        "static_item" => "static",
+        "macro_definition" => "macro",
+        _ => return None,
+    };
+    // `impl<T> Display for Foo<T>` is addressed as `Foo`
+    let name_field = if kind == "impl" { "type" } else { "name" };
+    let name = base_type_name(&field_text(node, name_field, src)?);
+    let item = Item::new(kind, name, node);
+    Some(if matches!(kind, "impl" | "trait" | "mod") {
+        item.nested()
+    } else {
+        item
+    })
+}
+
+fn python_item<'t>(node: Node<'t>, src: &str) -> Option<Item<'t>> {
+    match node.kind() {
+        "function_definition" => Some(Item::new("def", field_text(node, "name", src)?, node)),
+        "class_definition" => {
+            Some(Item::new("class", field_text(node, "name", src)?, node).nested())
+        }
+        "decorated_definition" => {
+            let inner = node.child_by_field_name("definition")?;
+            let mut item = python_item(inner, src)?;
+            item.node = node;
+            item.sig_start = inner.start_byte();
+            Some(item)
+        }
+        _ => None,
+    }
+}
+
+fn js_items<'t>(node: Node<'t>, src: &str) -> Vec<Item<'t>> {
+    let simple = |kind: &'static str| {
+        field_text(node, "name", src)
+            .map(|name| Item::new(kind, name, node))
+            .into_iter()
+            .collect()
+    };
+    match node.kind() {
+        "function_declaration" | "generator_function_declaration" | "function_signature" => {
+            simple("function")
+        }
+        "class_declaration" | "abstract_class_declaration" | "class" => {
+            simple("class").into_iter().map(Item::nested).collect()
+        }
+        "method_definition" | "method_signature" | "abstract_method_signature" => simple("method"),
+        "interface_declaration" => simple("interface"),
+        "type_alias_declaration" => {
+            let mut items: Vec<Item> = simple("type");
+            // The `value` of a type alias is not a body; keep the first line
+            items.iter_mut().for_each(|i| i.body = None);
+            items
+        }
+        "enum_declaration" => simple("enum"),
+        "internal_module" | "module" => simple("namespace").into_iter().map(Item::nested).collect(),
+        "export_statement" => {
+            let Some(decl) = node.child_by_field_name("declaration") else {
+                return Vec::new();
+            };
+            js_items(decl, src)
+                .into_iter()
+                .map(|mut item| {
+                    item.node = node;
+                    item.sig_start = node.start_byte();
+                    item
+                })
+                .collect()
+        }
+        // const handler = async (req) => { ... }
+        "lexical_declaration" | "variable_declaration" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .filter(|d| d.kind() == "variable_declarator")
+                .filter_map(|decl| {
+                    let value = decl.child_by_field_name("value")?;
+                    if !matches!(
+                        value.kind(),
+                        "arrow_function" | "function_expression" | "function" | "class"
+                    ) {
+                        return None;
+                    }
+                    let kind = if value.kind() == "class" {
+                        "class"
+                    } else {
+                        "function"
+                    };
+                    let mut item = Item::new(kind, field_text(decl, "name", src)?, node);
+                    item.body = value.child_by_field_name("body");
+                    item.nested = kind == "class";
+                    Some(item)
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn go_items<'t>(node: Node<'t>, src: &str) -> Vec<Item<'t>> {
+    match node.kind() {
+        "function_declaration" => field_text(node, "name", src)
+            .map(|name| Item::new("func", name, node))
+            .into_iter()
+            .collect(),
+        "method_declaration" => {
+            let Some(name) = field_text(node, "name", src) else {
+                return Vec::new();
+            };
+            let mut item = Item::new("func", name, node);
+            item.owner = node
+                .child_by_field_name("receiver")
+                .and_then(|r| go_receiver_type(r, src));
+            vec![item]
+        }
+        "type_declaration" => {
+            let mut cursor = node.walk();
+            let specs: Vec<Node> = node
+                .named_children(&mut cursor)
+                .filter(|s| matches!(s.kind(), "type_spec" | "type_alias"))
+                .collect();
+            let single = specs.len() == 1;
+            specs
+                .into_iter()
+                .filter_map(|spec| {
+                    let name = field_text(spec, "name", src)?;
+                    let ty = spec.child_by_field_name("type")?;
+                    let shape = match ty.kind() {
+                        "struct_type" => "struct".to_string(),
+                        "interface_type" => "interface".to_string(),
+                        _ => first_line(node_text(ty, src)),
+                    };
+                    let eq = if spec.kind() == "type_alias" {
+                        "= "
+                    } else {
+                        ""
+                    };
+                    let mut item =
+                        Item::new("type", name.clone(), if single { node } else { spec });
+                    item.signature = Some(format!("type {} {}{}", name, eq, shape));
+                    Some(item)
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `(s *Server)` / `(l List[T])` -> `Server` / `List`
+fn go_receiver_type(receiver: Node, src: &str) -> Option<String> {
+    let mut cursor = receiver.walk();
+    let param = receiver
+        .named_children(&mut cursor)
+        .find(|p| p.kind() == "parameter_declaration")?;
+    let ty = node_text(param.child_by_field_name("type")?, src);
+    Some(base_type_name(ty.trim_start_matches('*')))
+}
+
+/// Strip references, generics and module paths: `&'a mod::Foo<T>` -> `Foo`
+fn base_type_name(text: &str) -> String {
+    let text = text.trim_start_matches('&').trim_start();
+    let text = match text.strip_prefix('\'') {
+        Some(rest) => rest.split_once(' ').map_or(rest, |(_, t)| t),
+        None => text,
+    };
+    let text = text.trim_start_matches("mut ").trim_start_matches("dyn ");
+    let base = text.split(['<', '[']).next().unwrap_or(text);
+    base.rsplit("::").next().unwrap_or(base).trim().to_string()
+}
+
+/// Source from the item's start up to its body, whitespace collapsed
+fn signature_text(src: &str, item: &Item) -> String {
+    let end = item.body.map_or(item.node.end_byte(), |b| b.start_byte());
+    let raw = &src[item.sig_start..end.max(item.sig_start)];
+    let raw = if item.body.is_some() {
+        raw.to_string()
+    } else {
+        first_line(raw)
+    };
+    // Multi-line parameter lists: `( a: A, b: B, )` -> `(a: A, b: B)`
+    let collapsed = raw
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(", )", ")")
+        .replace(" )", ")");
+    collapsed
+        .trim_end_matches(['{', ':', ';', '=', ','])
+        .trim_end()
+        .to_string()
+}
+
+/// Walk back over doc comments, attributes and decorators directly above `node`
+fn leading_docs_row(node: Node) -> usize {
+    let mut row = node.start_position().row;
+    let mut prev = node.prev_sibling();
+    while let Some(sibling) = prev {
+        let kind = sibling.kind();
+        let attached =
+            kind.contains("comment") || kind.contains("attribute") || kind == "decorator";
+        if !attached || sibling.end_position().row + 1 < row {
+            break;
+        }
+        row = sibling.start_position().row;
+        prev = sibling.prev_sibling();
+    }
+    row
+}
+
+fn field_text(node: Node, field: &str, src: &str) -> Option<String> {
+    node.child_by_field_name(field)
+        .map(|n| node_text(n, src).to_string())
+}
+
+fn node_text<'s>(node: Node, src: &'s str) -> &'s str {
+    &src[node.byte_range()]
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or("").to_string()
+}
+
+/// Render an outline: line range, then the signature indented by nesting depth
+pub fn format_outline(symbols: &[Symbol]) -> String {
+    let range = |s: &Symbol| {
+        if s.start_line == s.end_line {
+            s.start_line.to_string()
+        } else {
+            format!("{}-{}", s.start_line, s.end_line)
+        }
+    };
+    let width = symbols.iter().map(|s| range(s).len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for s in symbols {
+        let range = range(s);
+        out.push_str(&format!(
+            "{:<width$}  {}{}\n",
+            range,
+            "  ".repeat(s.depth),
+            s.signature,
+            width = width
+        ));
+    }
+    out
+}
+
+/// Find symbols matching `query` (`Foo::bar`, `Foo.bar` or just `bar`).
+/// Exact qualified paths win; otherwise any symbol whose path ends with the query.
+pub fn find<'a>(symbols: &'a [Symbol], query: &str) -> Vec<&'a Symbol> {
+    let wanted: Vec<&str> = query
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|p| !p.is_empty())
+        .collect();
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let matches = |path: &[String]| path.iter().map(String::as_str).eq(wanted.iter().copied());
+    let exact: Vec<&Symbol> = symbols.iter().filter(|s| matches(&s.path)).collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    symbols
+        .iter()
+        .filter(|s| s.path.len() > wanted.len() && matches(&s.path[s.path.len() - wanted.len()..]))
+        .collect()
+}
+
+/// Qualified name with the file's separator (`Foo::bar` in Rust, `Foo.bar` elsewhere)
+pub fn display_path(path: &Path, symbol: &Symbol) -> String {
+    let sep = Grammar::from_path(path).map_or("::", Grammar::separator);
+    symbol.path.join(sep)
+}
+
+/// Source lines of a symbol, including its doc comments and attributes
+pub fn symbol_source<'c>(content: &'c str, symbol: &Symbol) -> Vec<&'c str> {
+    content
+        .lines()
+        .skip(symbol.doc_line - 1)
+        .take(symbol.end_line + 1 - symbol.doc_line)
+        .collect()
+}
+
+/// Error for an unknown symbol, listing what the file does contain
+pub fn not_found(path: &Path, query: &str, symbols: &[Symbol]) -> anyhow::Error {
+    let mut known: Vec<String> = symbols
+        .iter()
+        .filter(|s| s.depth <= 1)
+        .map(|s| display_path(path, s))
+        .collect();
+    known.dedup();
+    let more = known.len().saturating_sub(15);
+    known.truncate(15);
+    let suffix = if more > 0 {
+        format!(" (+{} more)", more)
+    } else {
+        String::new()
+    };
+    anyhow!(
+        "Symbol '{}' not found in {}. Available: {}{}",
+        query,
+        path.display(),
+        known.join(", "),
+        suffix
+    )
+}
+
+/// Fails early for stdin and unsupported extensions
+pub fn ensure_supported(path: &Path) -> Result<()> {
+    if !is_supported(path) {
+        bail!(
+            "Outline not supported for {} (supported: .rs, .py, .js, .jsx, .ts, .tsx, .go)",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(name: &str, src: &str) -> Vec<Symbol> {
+        symbols(Path::new(name), src).unwrap()
+    }
+
+    fn signatures(symbols: &[Symbol]) -> Vec<(usize, String)> {
+        symbols
+            .iter()
+            .map(|s| (s.depth, s.signature.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_outline_nested_braces_and_strings() {
+        let src = r#"/// Config docs
+#[derive(Debug)]
+pub struct Config {
+    name: String,
+}
+
+impl Config {
+    pub fn new(name: &str) -> Self {
+        let s = "not a } brace // nor a comment";
+        if true { { } }
+        Config { name: s.to_string() }
+    }
+
+    fn helper(&self) {}
+}
+
+mod inner {
+    pub trait Greet {
+        fn greet(&self) -> String;
+    }
+}
+"#;
+        let syms = outline("lib.rs", src);
+        assert_eq!(
+            signatures(&syms),
+            vec![
+                (0, "pub struct Config".to_string()),
+                (0, "impl Config".to_string()),
+                (1, "pub fn new(name: &str) -> Self".to_string()),
+                (1, "fn helper(&self)".to_string()),
+                (0, "mod inner".to_string()),
+                (1, "pub trait Greet".to_string()),
+                (2, "fn greet(&self) -> String".to_string()),
+            ]
+        );
+        let new = &syms[2];
+        assert_eq!((new.start_line, new.end_line), (8, 12));
+        assert_eq!(syms[0].doc_line, 1);
+        assert_eq!(syms[0].start_line, 3);
+    }
+
+    #[test]
+    fn test_python_outline_decorators_and_indentation() {
+        let src = r#"import os
+
+class Service:
+    """Docstring with def fake(): inside"""
+
+    @staticmethod
+    def create(path: str) -> "Service":
+        return Service()
+
+    async def run(self):
+        def inner():
+            pass
+        return inner
+
+def main():
+    pass
+"#;
+        let syms = outline("app.py", src);
+        assert_eq!(
+            signatures(&syms),
+            vec![
+                (0, "class Service".to_string()),
+                (1, "def create(path: str) -> \"Service\"".to_string()),
+                (1, "async def run(self)".to_string()),
+                (0, "def main()".to_string()),
+            ]
+        );
+        assert_eq!(syms[1].start_line, 6);
+        assert_eq!(syms[1].path, vec!["Service", "create"]);
+    }
+
+    #[test]
+    fn test_typescript_outline_exports_and_arrows() {
+        let src = r#"export interface Props {
+  name: string;
+}
+
+export class Widget extends Base {
+  render(): string {
+    return `}${this.name}`;
+  }
+}
+
+export const handler = async (req: Request): Promise<void> => {
+  await req.json();
+};
+
+function helper<T>(x: T): T { return x; }
+"#;
+        let syms = outline("widget.ts", src);
+        assert_eq!(
+            signatures(&syms),
+            vec![
+                (0, "export interface Props".to_string()),
+                (0, "export class Widget extends Base".to_string()),
+                (1, "render(): string".to_string()),
+                (
+                    0,
+                    "export const handler = async (req: Request): Promise<void> =>".to_string()
+                ),
+                (0, "function helper<T>(x: T): T".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_go_outline_methods_and_types() {
+        let src = r#"package main
+
+type Server struct {
+	addr string
+}
+
+func (s *Server) Start(port int) error {
+	return nil
+}
+
+func main() {}
+"#;
+        let syms = outline("main.go", src);
+        assert_eq!(
+            signatures(&syms),
+            vec![
+                (0, "type Server struct".to_string()),
+                (0, "func (s *Server) Start(port int) error".to_string()),
+                (0, "func main()".to_string()),
+            ]
+        );
+        assert_eq!(syms[1].path, vec!["Server", "Start"]);
+    }
+
+    #[test]
+    fn test_find_symbol() {
+        let src = "struct Foo;\nimpl Foo {\n    /// Does bar\n    fn bar(&self) {\n        todo!()\n    }\n}\n";
+        let syms = outline("x.rs", src);
+
+        let found = find(&syms, "Foo::bar");
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            symbol_source(src, found[0]),
+            vec![
+                "    /// Does bar",
+                "    fn bar(&self) {",
+                "        todo!()",
+                "    }"
+            ]
+        );
+        assert_eq!(find(&syms, "bar").len(), 1);
+        assert_eq!(find(&syms, "Foo").len(), 2); // struct + impl
+        assert!(find(&syms, "baz").is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_extension() {
+        assert!(!is_supported(Path::new("notes.txt")));
+        assert!(symbols(Path::new("notes.txt"), "hi").is_err());
+        assert!(ensure_supported(Path::new("-")).is_err());
+    }
+}
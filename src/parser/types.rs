@@ -40,6 +40,9 @@ pub struct LintIssue {
     pub severity: LintSeverity,
     pub rule_id: String,
     pub message: String,
+    /// Full diagnostic as the tool renders it (rustc), when available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -75,6 +78,12 @@ pub struct BuildOutput {
     pub errors: usize,
     pub bundles: Vec<BundleInfo>,
     pub routes: Vec<RouteInfo>,
+    /// Compilation units built (cargo crates, etc.)
+    #[serde(default)]
+    pub compiled_units: usize,
+    /// Compiler errors and warnings, in emission order
+    #[serde(default)]
+    pub diagnostics: Vec<LintIssue>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -11,7 +12,7 @@ struct Package {
     latest_version: Option<String>,
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Auto-detect uv vs pip
@@ -25,7 +26,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     // Detect subcommand
     let subcommand = args.first().map(|s| s.as_str()).unwrap_or("");
 
-    let (cmd_str, filtered) = match subcommand {
+    let (cmd_str, filtered, result) = match subcommand {
         "list" => run_list(base_cmd, &args[1..], verbose)?,
         "outdated" => run_outdated(base_cmd, &args[1..], verbose)?,
         "install" | "uninstall" | "show" => {
@@ -40,17 +41,19 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         }
     };
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("{} {}", base_cmd, args.join(" ")),
         &format!("rtk {} {}", base_cmd, args.join(" ")),
         &cmd_str,
         &filtered,
+        result.exit_code,
     );
 
-    Ok(())
+    Ok(result)
 }
 
-fn run_list(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, String)> {
+fn run_list(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, String, ExecResult)> {
     let mut cmd = Command::new(base_cmd);
 
     if base_cmd == "uv" {
@@ -77,15 +80,18 @@ fn run_list(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, Str
 
     let filtered = filter_pip_list(&stdout);
     println!("{}", filtered);
-
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        eprint!("{}", stderr);
     }
 
-    Ok((raw, filtered))
+    Ok((raw, filtered, ExecResult::from_status(&output.status)))
 }
 
-fn run_outdated(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, String)> {
+fn run_outdated(
+    base_cmd: &str,
+    args: &[String],
+    verbose: u8,
+) -> Result<(String, String, ExecResult)> {
     let mut cmd = Command::new(base_cmd);
 
     if base_cmd == "uv" {
@@ -112,15 +118,18 @@ fn run_outdated(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String,
 
     let filtered = filter_pip_outdated(&stdout);
     println!("{}", filtered);
-
     if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
+        eprint!("{}", stderr);
     }
 
-    Ok((raw, filtered))
+    Ok((raw, filtered, ExecResult::from_status(&output.status)))
 }
 
-fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(String, String)> {
+fn run_passthrough(
+    base_cmd: &str,
+    args: &[String],
+    verbose: u8,
+) -> Result<(String, String, ExecResult)> {
     let mut cmd = Command::new(base_cmd);
 
     if base_cmd == "uv" {
@@ -146,11 +155,7 @@ fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(Stri
     print!("{}", stdout);
     eprint!("{}", stderr);
 
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok((raw.clone(), raw))
+    Ok((raw.clone(), raw, ExecResult::from_status(&output.status)))
 }
 
 /// Check if a command exists in PATH
@@ -166,7 +171,7 @@ fn which_command(cmd: &str) -> Option<String> {
 }
 
 /// Filter pip list JSON output
-fn filter_pip_list(output: &str) -> String {
+pub(crate) fn filter_pip_list(output: &str) -> String {
     let packages: Vec<Package> = match serde_json::from_str(output) {
         Ok(p) => p,
         Err(e) => {
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::{package_manager_exec, strip_ansi};
 use anyhow::{Context, Result};
@@ -218,7 +219,17 @@ fn extract_failures_regex(output: &str) -> Vec<TestFailure> {
     failures
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+/// Compact summary of raw `playwright --reporter=json` output (used by `rtk bench`).
+pub(crate) fn filter_playwright_output(raw: &str) -> String {
+    match PlaywrightParser::parse(raw) {
+        ParseResult::Full(data) | ParseResult::Degraded(data, _) => {
+            data.format(FormatMode::from_verbosity(0))
+        }
+        ParseResult::Passthrough(raw) => raw,
+    }
+}
+
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = package_manager_exec("playwright");
@@ -244,6 +255,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     // Parse output using PlaywrightParser
     let parse_result = PlaywrightParser::parse(&stdout);
+    crate::json_output::report_parsed("TestResult", &parse_result);
     let mode = FormatMode::from_verbosity(verbose);
 
     let filtered = match parse_result {
@@ -267,7 +279,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("playwright {}", args.join(" ")),
         &format!("rtk playwright {}", args.join(" ")),
         &raw,
@@ -275,11 +288,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use serde::Deserialize;
@@ -283,7 +284,7 @@ pub enum PnpmCommand {
     Install { packages: Vec<String> },
 }
 
-pub fn run(cmd: PnpmCommand, args: &[String], verbose: u8) -> Result<()> {
+pub fn run(cmd: PnpmCommand, args: &[String], verbose: u8) -> Result<ExecResult> {
     match cmd {
         PnpmCommand::List { depth } => run_list(depth, args, verbose),
         PnpmCommand::Outdated => run_outdated(args, verbose),
@@ -291,7 +292,7 @@ pub fn run(cmd: PnpmCommand, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
-fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
+fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("pnpm");
@@ -306,14 +307,15 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
     let output = cmd.output().context("Failed to run pnpm list")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("pnpm list failed: {}", stderr);
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     // Parse output using PnpmListParser
     let parse_result = PnpmListParser::parse(&stdout);
+    crate::json_output::report_parsed("DependencyState", &parse_result);
     let mode = FormatMode::from_verbosity(verbose);
 
     let filtered = match parse_result {
@@ -337,17 +339,18 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("pnpm list --depth={}", depth),
         &format!("rtk pnpm list --depth={}", depth),
         &stdout,
         &filtered,
     );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
+fn run_outdated(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = Command::new("pnpm");
@@ -366,6 +369,7 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
 
     // Parse output using PnpmOutdatedParser
     let parse_result = PnpmOutdatedParser::parse(&stdout);
+    crate::json_output::report_parsed("DependencyState", &parse_result);
     let mode = FormatMode::from_verbosity(verbose);
 
     let filtered = match parse_result {
@@ -393,12 +397,19 @@ fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
         println!("{}", filtered);
     }
 
-    timer.track("pnpm outdated", "rtk pnpm outdated", &combined, &filtered);
+    crate::preserve::track(
+        &timer,
+        "pnpm outdated",
+        "rtk pnpm outdated",
+        &combined,
+        &filtered,
+    );
 
-    Ok(())
+    // pnpm exits 1 when something is outdated
+    Ok(ExecResult::from_status(&output.status))
 }
 
-fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()> {
+fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Validate package names to prevent command injection
@@ -431,7 +442,8 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
     let stderr = String::from_utf8_lossy(&output.stderr);
 
     if !output.status.success() {
-        anyhow::bail!("pnpm install failed: {}", stderr);
+        eprint!("{}", stderr);
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let combined = format!("{}{}", stdout, stderr);
@@ -439,18 +451,19 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
 
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("pnpm install {}", packages.join(" ")),
         &format!("rtk pnpm install {}", packages.join(" ")),
         &combined,
         &filtered,
     );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// Filter pnpm install output - remove progress bars, keep summary
-fn filter_pnpm_install(output: &str) -> String {
+pub(crate) fn filter_pnpm_install(output: &str) -> String {
     let mut result = Vec::new();
     let mut saw_progress = false;
 
@@ -489,7 +502,7 @@ fn filter_pnpm_install(output: &str) -> String {
 }
 
 /// Runs an unsupported pnpm subcommand by passing it through directly
-pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
+pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -506,10 +519,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
         &format!("rtk pnpm {} (passthrough)", args_str),
     );
 
-    if !status.success() {
-        std::process::exit(status.code().unwrap_or(1));
-    }
-    Ok(())
+    Ok(ExecResult::from_status(&status))
 }
 
 #[cfg(test)]
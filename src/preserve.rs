@@ -0,0 +1,313 @@
+//! Preserve-errors safety net for command filters.
+//!
+//! Filters summarize aggressively and can drop the one line that said why a
+//! command failed. Command modules record their run through [`track`] /
+//! [`track_with_exit`] instead of `TimedExecution` directly: the raw output is
+//! scanned for error signatures (`error[`, `Traceback`, `panicked at`, `FAILED`, `fatal:`);
+//! any the filtered output doesn't mention are appended under a
+//! `[rtk: preserved]` section. When a command exits non-zero without a known
+//! signature, the tail of the raw output (where modules put stderr) is kept
+//! instead. File-content views (read, grep, git diff/show/blame) keep plain
+//! `TimedExecution::track`: error-looking lines there are just text. Disable
+//! with `[filters] preserve_errors = false` or `RTK_FILTERS_PRESERVE_ERRORS=0`.
+
+use crate::tracking::TimedExecution;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+pub const PRESERVED_HEADER: &str = "[rtk: preserved]";
+
+/// Lines appended per command; the full output stays in the tee file.
+const MAX_PRESERVED_LINES: usize = 20;
+/// Tail lines kept for a failed command without a known signature.
+const FAILURE_TAIL_LINES: usize = 5;
+/// Filters truncate long lines, so only this much of a message must survive.
+const KEY_CHARS: usize = 40;
+const MAX_LINE_CHARS: usize = 240;
+
+lazy_static! {
+    static ref RUST_ERROR: Regex = Regex::new(r"error\[(\w+)\]:?\s*(.*)$").unwrap();
+    static ref PANIC: Regex = Regex::new(r"panicked at\s*(.*?):?\s*$").unwrap();
+    static ref FATAL: Regex = Regex::new(r"^\s*fatal:\s*(.+)$").unwrap();
+    static ref TRACEBACK: Regex = Regex::new(r"^\s*Traceback \(most recent call last\)").unwrap();
+    static ref FAILED: Regex = Regex::new(r"\bFAILED\b").unwrap();
+    static ref FAILED_TEST: Regex =
+        Regex::new(r"^\s*(?:test (\S+) \.\.\. FAILED|FAILED (\S+))").unwrap();
+}
+
+/// A raw line the filtered output must account for.
+#[derive(Debug, PartialEq)]
+struct Signature {
+    /// Lines appended when the filter dropped it
+    lines: Vec<String>,
+    /// The filter kept it if any key appears in its output
+    keys: Vec<String>,
+}
+
+impl Signature {
+    fn new(line: &str, key: &str) -> Self {
+        Signature {
+            lines: vec![line.trim().to_string()],
+            keys: vec![key_of(key)],
+        }
+    }
+
+    fn kept_in(&self, filtered: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|k| !k.is_empty() && filtered.contains(k.as_str()))
+    }
+}
+
+fn key_of(text: &str) -> String {
+    text.trim()
+        .trim_matches(|c| c == '\'' || c == '"')
+        .chars()
+        .take(KEY_CHARS)
+        .collect()
+}
+
+/// Error signatures in the raw output, in order.
+fn signatures(raw: &str) -> Vec<Signature> {
+    let lines: Vec<&str> = raw.lines().collect();
+    let mut found = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if TRACEBACK.is_match(line) {
+            // Keep the innermost frame and the exception line that ends the block
+            let end = lines[i + 1..]
+                .iter()
+                .position(|l| !l.is_empty() && !l.starts_with(char::is_whitespace))
+                .map(|p| i + 1 + p);
+            if let Some(end) = end {
+                let frame = lines[i + 1..end]
+                    .iter()
+                    .rev()
+                    .find(|l| l.trim_start().starts_with("File "));
+                let mut sig = Signature::new(lines[end], lines[end]);
+                if let Some(frame) = frame {
+                    sig.lines.insert(0, frame.trim().to_string());
+                }
+                found.push(sig);
+                i = end + 1;
+                continue;
+            }
+        } else if let Some(caps) = RUST_ERROR.captures(line) {
+            let message = if caps[2].trim().is_empty() {
+                &caps[1]
+            } else {
+                &caps[2]
+            };
+            found.push(Signature::new(line, message));
+        } else if let Some(caps) = PANIC.captures(line) {
+            found.push(Signature::new(line, &caps[1]));
+        } else if let Some(caps) = FATAL.captures(line) {
+            found.push(Signature::new(line, &caps[1]));
+        } else if let Some(caps) = FAILED_TEST.captures(line) {
+            let name = caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str());
+            found.push(Signature::new(line, name));
+        } else if FAILED.is_match(line) {
+            // Summary lines ("test result: FAILED.") only need the failure stated
+            let mut sig = Signature::new(line, line);
+            sig.keys.extend(["FAIL".to_string(), "fail".to_string()]);
+            found.push(sig);
+        }
+        i += 1;
+    }
+    found
+}
+
+/// Lines the filter dropped, or `None` when nothing important is missing.
+pub fn missing_lines(raw: &str, filtered: &str, exit_code: Option<i32>) -> Option<Vec<String>> {
+    if raw == filtered {
+        return None;
+    }
+
+    let found = signatures(raw);
+    let mut missing: Vec<String> = Vec::new();
+    if found.is_empty() {
+        if exit_code.is_some_and(|c| c != 0) {
+            let tail: Vec<&str> = raw
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect();
+            let start = tail.len().saturating_sub(FAILURE_TAIL_LINES);
+            missing.extend(
+                tail[start..]
+                    .iter()
+                    .filter(|l| !filtered.contains(key_of(l).as_str()))
+                    .map(|l| l.to_string()),
+            );
+        }
+    } else {
+        for sig in found.iter().filter(|s| !s.kept_in(filtered)) {
+            for line in &sig.lines {
+                if !missing.contains(line) {
+                    missing.push(line.clone());
+                }
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        return None;
+    }
+    let dropped = missing.len().saturating_sub(MAX_PRESERVED_LINES);
+    missing.truncate(MAX_PRESERVED_LINES);
+    for line in &mut missing {
+        if line.chars().count() > MAX_LINE_CHARS {
+            *line = format!(
+                "{}...",
+                line.chars().take(MAX_LINE_CHARS).collect::<String>()
+            );
+        }
+    }
+    if dropped > 0 {
+        missing.push(format!("... +{} more", dropped));
+    }
+    Some(missing)
+}
+
+/// The `[rtk: preserved]` section for a filtered run, if anything was dropped.
+pub fn preserved_section(raw: &str, filtered: &str, exit_code: Option<i32>) -> Option<String> {
+    let lines = missing_lines(raw, filtered, exit_code)?;
+    Some(format!("{}\n{}", PRESERVED_HEADER, lines.join("\n")))
+}
+
+/// Print any preserved section after a command's filtered output (and its tee
+/// hint), then record the run with the section counted as output.
+pub fn track(timer: &TimedExecution, original_cmd: &str, rtk_cmd: &str, raw: &str, filtered: &str) {
+    match emit(raw, filtered, None) {
+        Some(section) => timer.track(
+            original_cmd,
+            rtk_cmd,
+            raw,
+            &format!("{}\n{}", filtered, section),
+        ),
+        None => timer.track(original_cmd, rtk_cmd, raw, filtered),
+    }
+}
+
+/// [`track`] for commands whose exit code is known; a non-zero code without a
+/// known signature keeps the tail of the raw output.
+pub fn track_with_exit(
+    timer: &TimedExecution,
+    original_cmd: &str,
+    rtk_cmd: &str,
+    raw: &str,
+    filtered: &str,
+    exit_code: i32,
+) {
+    match emit(raw, filtered, Some(exit_code)) {
+        Some(section) => timer.track_with_exit(
+            original_cmd,
+            rtk_cmd,
+            raw,
+            &format!("{}\n{}", filtered, section),
+            exit_code,
+        ),
+        None => timer.track_with_exit(original_cmd, rtk_cmd, raw, filtered, exit_code),
+    }
+}
+
+/// Print the preserved section after the filtered output; returns what was printed.
+///
+/// JSON output goes to stderr so it stays parseable.
+fn emit(raw: &str, filtered: &str, exit_code: Option<i32>) -> Option<String> {
+    if raw == filtered {
+        return None;
+    }
+    let enabled = crate::config::Config::load()
+        .map(|c| c.filters.preserve_errors)
+        .unwrap_or(true);
+    if !enabled {
+        return None;
+    }
+
+    let section = preserved_section(raw, filtered, exit_code)?;
+    if serde_json::from_str::<serde_json::Value>(filtered.trim()).is_ok() {
+        eprintln!("{}", section);
+    } else {
+        println!("{}", section);
+    }
+    Some(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_rust_error_is_preserved() {
+        let raw =
+            "   Compiling foo v0.1.0\nerror[E0308]: mismatched types\n  --> src/main.rs:4:5\n";
+        let lines = missing_lines(raw, "build failed", Some(101)).unwrap();
+        assert_eq!(lines, vec!["error[E0308]: mismatched types"]);
+
+        // A filter that reformatted but kept the message is left alone
+        assert!(missing_lines(raw, "src/main.rs:4 mismatched types", Some(101)).is_none());
+    }
+
+    #[test]
+    fn test_traceback_keeps_frame_and_exception() {
+        let raw = "Traceback (most recent call last):\n  File \"app.py\", line 3, in <module>\n    main()\n  File \"app.py\", line 9, in main\n    raise ValueError(\"bad\")\nValueError: bad\nmore output\n";
+        let lines = missing_lines(raw, "", None).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "File \"app.py\", line 9, in main".to_string(),
+                "ValueError: bad".to_string()
+            ]
+        );
+        assert!(missing_lines(raw, "ValueError: bad", None).is_none());
+    }
+
+    #[test]
+    fn test_failed_tests_and_summary() {
+        let raw = "test a::ok ... ok\ntest a::broken ... FAILED\ntest result: FAILED. 1 passed; 1 failed\n";
+        // Summary says it failed but the test name is gone
+        let lines = missing_lines(raw, "1 passed, 1 failed", Some(101)).unwrap();
+        assert_eq!(lines, vec!["test a::broken ... FAILED"]);
+        assert!(missing_lines(raw, "FAILED: a::broken", Some(101)).is_none());
+    }
+
+    #[test]
+    fn test_fatal_and_panic() {
+        let raw = "fatal: not a git repository\nthread 'main' panicked at src/lib.rs:10:5:\n";
+        let lines = missing_lines(raw, "ok", Some(128)).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(missing_lines(raw, "not a git repository\nsrc/lib.rs:10:5", Some(128)).is_none());
+    }
+
+    #[test]
+    fn test_nonzero_exit_keeps_tail() {
+        let raw = "fetching\nresolving\nnpm ERR! code ENOENT\nnpm ERR! missing script: build\n";
+        let lines = missing_lines(raw, "npm: 2 lines", Some(1)).unwrap();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[3], "npm ERR! missing script: build");
+
+        // Success without signatures: nothing to preserve
+        assert!(missing_lines(raw, "npm: 2 lines", Some(0)).is_none());
+        assert!(missing_lines(raw, "npm: 2 lines", None).is_none());
+    }
+
+    #[test]
+    fn test_unfiltered_and_capped() {
+        let raw = "fatal: x";
+        assert!(missing_lines(raw, raw, Some(1)).is_none());
+
+        let many: String = (0..30).map(|i| format!("fatal: failure {}\n", i)).collect();
+        let lines = missing_lines(&many, "", Some(1)).unwrap();
+        assert_eq!(lines.len(), MAX_PRESERVED_LINES + 1);
+        assert_eq!(lines.last().unwrap(), "... +10 more");
+    }
+
+    #[test]
+    fn test_preserved_section_header() {
+        let section = preserved_section("fatal: gone", "", None).unwrap();
+        assert_eq!(section, "[rtk: preserved]\nfatal: gone");
+    }
+}
@@ -1,8 +1,9 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::package_manager_exec;
 use anyhow::{Context, Result};
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = package_manager_exec("prettier");
@@ -28,7 +29,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("prettier {}", args.join(" ")),
         &format!("rtk prettier {}", args.join(" ")),
         &raw,
@@ -36,11 +38,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Filter Prettier output - show only files that need formatting
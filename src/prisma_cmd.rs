@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -16,7 +17,7 @@ pub enum MigrateSubcommand {
     Deploy,
 }
 
-pub fn run(cmd: PrismaCommand, args: &[String], verbose: u8) -> Result<()> {
+pub fn run(cmd: PrismaCommand, args: &[String], verbose: u8) -> Result<ExecResult> {
     match cmd {
         PrismaCommand::Generate => run_generate(args, verbose),
         PrismaCommand::Migrate { subcommand } => run_migrate(subcommand, args, verbose),
@@ -41,7 +42,7 @@ fn create_prisma_command() -> Command {
     }
 }
 
-fn run_generate(args: &[String], verbose: u8) -> Result<()> {
+fn run_generate(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = create_prisma_command();
@@ -60,8 +61,8 @@ fn run_generate(args: &[String], verbose: u8) -> Result<()> {
         .context("Failed to run prisma generate (try: npm install -g prisma)")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma generate failed: {}", stderr);
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -71,12 +72,18 @@ fn run_generate(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track("prisma generate", "rtk prisma generate", &raw, &filtered);
+    crate::preserve::track(
+        &timer,
+        "prisma generate",
+        "rtk prisma generate",
+        &raw,
+        &filtered,
+    );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> Result<()> {
+fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = create_prisma_command();
@@ -111,8 +118,8 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
     let output = cmd.output().context("Failed to run prisma migrate")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma migrate failed: {}", stderr);
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -127,12 +134,18 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
 
     println!("{}", filtered);
 
-    timer.track(cmd_name, &format!("rtk {}", cmd_name), &raw, &filtered);
+    crate::preserve::track(
+        &timer,
+        cmd_name,
+        &format!("rtk {}", cmd_name),
+        &raw,
+        &filtered,
+    );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
-fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
+fn run_db_push(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = create_prisma_command();
@@ -149,8 +162,8 @@ fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
     let output = cmd.output().context("Failed to run prisma db push")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma db push failed: {}", stderr);
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -160,9 +173,15 @@ fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track("prisma db push", "rtk prisma db push", &raw, &filtered);
+    crate::preserve::track(
+        &timer,
+        "prisma db push",
+        "rtk prisma db push",
+        &raw,
+        &filtered,
+    );
 
-    Ok(())
+    Ok(ExecResult::SUCCESS)
 }
 
 /// Filter prisma generate output - strip ASCII art, extract counts
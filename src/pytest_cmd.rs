@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -11,7 +12,7 @@ enum ParseState {
     Summary,
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Try to detect pytest command (could be "pytest", "python -m pytest", etc.)
@@ -43,20 +44,16 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("Running: pytest --tb=short -q {}", args.join(" "));
     }
 
-    let output = cmd
-        .output()
+    let output = crate::stream::run_streaming(&mut cmd, live_pytest_failure)
         .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = &output.stdout;
+    let stderr = &output.stderr;
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let filtered = filter_pytest_output(&stdout);
+    let filtered = output.dedupe(&filter_pytest_output(stdout));
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = output.exit_code;
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "pytest", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -68,19 +65,31 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         eprintln!("{}", stderr.trim());
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("pytest {}", args.join(" ")),
         &format!("rtk pytest {}", args.join(" ")),
         &raw,
-        &filtered,
+        &output.printed(&filtered),
+        exit_code,
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
+    Ok(ExecResult::code(exit_code))
+}
+
+/// Collection errors, and failing tests as soon as pytest names them
+/// (`path::test FAILED [ 40%]` in `-v` runs; `-q` names them only at the end)
+fn live_pytest_failure(line: &str) -> Option<String> {
+    let title = line.trim_matches(|c| c == '_' || c == ' ');
+    if title.starts_with("ERROR collecting") {
+        return Some(title.to_string());
     }
 
-    Ok(())
+    let result = line.split(" [").next().unwrap_or(line).trim_end();
+    let (test, status) = result.rsplit_once(' ')?;
+    (test.contains("::") && !test.contains(' ') && matches!(status, "FAILED" | "ERROR"))
+        .then(|| format!("{} {}", status, test))
 }
 
 /// Check if a command exists in PATH
@@ -96,7 +105,7 @@ fn which_command(cmd: &str) -> Option<String> {
 }
 
 /// Parse pytest output using state machine
-fn filter_pytest_output(output: &str) -> String {
+pub(crate) fn filter_pytest_output(output: &str) -> String {
     let mut state = ParseState::Header;
     let mut test_files: Vec<String> = Vec::new();
     let mut failures: Vec<String> = Vec::new();
@@ -290,6 +299,26 @@ fn parse_summary_line(summary: &str) -> (usize, usize, usize) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_live_pytest_failure() {
+        assert_eq!(
+            live_pytest_failure("_____ ERROR collecting tests/test_a.py _____").as_deref(),
+            Some("ERROR collecting tests/test_a.py")
+        );
+        assert_eq!(
+            live_pytest_failure("tests/test_a.py::test_add FAILED                    [ 50%]")
+                .as_deref(),
+            Some("FAILED tests/test_a.py::test_add")
+        );
+        assert_eq!(
+            live_pytest_failure("tests/test_a.py::TestX::test_y[1-2] ERROR").as_deref(),
+            Some("ERROR tests/test_a.py::TestX::test_y[1-2]")
+        );
+        assert!(live_pytest_failure("tests/test_a.py::test_ok PASSED [ 25%]").is_none());
+        assert!(live_pytest_failure("FAILED tests/test_a.py::test_add - assert 1 == 2").is_none());
+        assert!(live_pytest_failure("..F.").is_none());
+    }
+
     #[test]
     fn test_filter_pytest_all_pass() {
         let output = r#"=== test session starts ===
@@ -1,39 +1,267 @@
 use crate::filter::{self, FilterLevel, Language};
+use crate::outline;
+use crate::tokenizer::Tokenizer;
 use crate::tracking;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub fn run(
-    file: &Path,
-    level: FilterLevel,
-    max_lines: Option<usize>,
-    line_numbers: bool,
-    verbose: u8,
-) -> Result<()> {
+/// Below this many tokens a file is skipped rather than cut to a few lines
+const MIN_SECTION_TOKENS: usize = 40;
+
+/// Inclusive 1-based line range from `file:START-END` or `file:LINE±N`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl std::fmt::Display for LineRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// One `rtk read` argument: a path (`-` for stdin) and an optional line range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadTarget {
+    pub path: PathBuf,
+    pub range: Option<LineRange>,
+}
+
+impl ReadTarget {
+    /// Parse `file`, `file:40`, `file:10-20`, `file:50±5` (or `file:50+-5`).
+    /// A path that exists as written is never split, so `a:1` stays a file name.
+    pub fn parse(arg: &str) -> Result<Self> {
+        lazy_static::lazy_static! {
+            static ref RANGE: Regex =
+                Regex::new(r"^(.+):(\d+)(?:-(\d+)|(?:±|\+-)(\d+))?$").unwrap();
+        }
+
+        let whole = || ReadTarget {
+            path: PathBuf::from(arg),
+            range: None,
+        };
+        if Path::new(arg).exists() {
+            return Ok(whole());
+        }
+        let Some(caps) = RANGE.captures(arg) else {
+            return Ok(whole());
+        };
+
+        let line: usize = caps[2].parse()?;
+        let (start, end) = if let Some(end) = caps.get(3) {
+            (line, end.as_str().parse()?)
+        } else if let Some(ctx) = caps.get(4) {
+            let ctx: usize = ctx.as_str().parse()?;
+            (line.saturating_sub(ctx).max(1), line + ctx)
+        } else {
+            (line, line)
+        };
+        if start == 0 || end < start {
+            bail!("Invalid line range in '{}' (lines start at 1)", arg);
+        }
+        Ok(ReadTarget {
+            path: PathBuf::from(&caps[1]),
+            range: Some(LineRange { start, end }),
+        })
+    }
+
+    fn is_stdin(&self) -> bool {
+        self.path == Path::new("-")
+    }
+
+    fn label(&self) -> String {
+        let name = if self.is_stdin() {
+            "stdin".to_string()
+        } else {
+            self.path.display().to_string()
+        };
+        match self.range {
+            Some(range) => format!("{}:{}", name, range),
+            None => name,
+        }
+    }
+}
+
+/// Parse read arguments and expand globs (`'src/**/*.rs'`) the shell left quoted
+pub fn expand_targets(args: &[String]) -> Result<Vec<ReadTarget>> {
+    let mut targets = Vec::new();
+    for arg in args {
+        let target = ReadTarget::parse(arg)?;
+        let pattern = target.path.to_string_lossy().to_string();
+        if target.path.exists() || !pattern.contains(['*', '?', '[']) {
+            targets.push(target);
+            continue;
+        }
+
+        let mut matches: Vec<PathBuf> = glob::glob(&pattern)
+            .with_context(|| format!("Invalid glob pattern: {}", pattern))?
+            .filter_map(|p| p.ok())
+            .filter(|p| p.is_file())
+            .collect();
+        if matches.is_empty() {
+            bail!("No files match pattern: {}", pattern);
+        }
+        matches.sort();
+        targets.extend(matches.into_iter().map(|path| ReadTarget {
+            path,
+            range: target.range,
+        }));
+    }
+    if targets.iter().filter(|t| t.is_stdin()).count() > 1 {
+        bail!("stdin (-) can only be read once");
+    }
+    Ok(targets)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReadOptions {
+    pub level: FilterLevel,
+    pub max_lines: Option<usize>,
+    pub line_numbers: bool,
+    /// Total token budget shared by all files
+    pub budget: Option<usize>,
+}
+
+/// One file's worth of output
+struct Section {
+    target: ReadTarget,
+    lang: Language,
+    /// What plain `cat` (or `sed -n` for a range) would have printed
+    raw: String,
+    text: String,
+    /// Line number of the first line of `text`
+    first_line: usize,
+    note: Option<String>,
+}
+
+/// Read one or more files (or stdin) with per-file headers
+pub fn run(targets: &[ReadTarget], opts: &ReadOptions, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
-    if verbose > 0 {
-        eprintln!("Reading: {} (filter: {})", file.display(), level);
+    let mut sections = Vec::with_capacity(targets.len());
+    for target in targets {
+        if verbose > 0 {
+            eprintln!("Reading: {} (filter: {})", target.label(), opts.level);
+        }
+        sections.push(load_section(target, opts, verbose)?);
+    }
+    if let Some(budget) = opts.budget {
+        apply_budget(&mut sections, budget, Tokenizer::from_config(), verbose);
+    }
+
+    let multi = sections.len() > 1;
+    let mut blocks = Vec::with_capacity(sections.len());
+    for section in &sections {
+        let mut block = String::new();
+        if multi {
+            block.push_str(&format!("==> {} <==\n", section.target.label()));
+        }
+        if let Some(note) = &section.note {
+            block.push_str(&format!("({})\n", note));
+        }
+        if opts.line_numbers {
+            block.push_str(&format_with_line_numbers(&section.text, section.first_line));
+        } else {
+            block.push_str(&section.text);
+        }
+        blocks.push(block.trim_end().to_string());
     }
+    let rtk_output = blocks.join("\n\n");
+    println!("{}", rtk_output);
+
+    let names: Vec<String> = sections
+        .iter()
+        .map(|s| s.target.path.display().to_string())
+        .collect();
+    let rtk_cmd = if sections.iter().any(|s| s.target.range.is_some()) || multi {
+        "rtk read (ranges)"
+    } else {
+        "rtk read"
+    };
+    timer.track(
+        &format!("cat {}", names.join(" ")),
+        rtk_cmd,
+        &tracked_input(&sections),
+        &rtk_output,
+    );
+    Ok(())
+}
+
+/// Tracked input: what the plain commands would have printed for all sections
+fn tracked_input(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|s| s.raw.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    // Read file content
-    let content = fs::read_to_string(file)
-        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+/// Same as `run` for a single `-` target
+pub fn run_stdin(range: Option<LineRange>, opts: &ReadOptions, verbose: u8) -> Result<()> {
+    let target = ReadTarget {
+        path: PathBuf::from("-"),
+        range,
+    };
+    run(&[target], opts, verbose)
+}
+
+fn load_section(target: &ReadTarget, opts: &ReadOptions, verbose: u8) -> Result<Section> {
+    let content = if target.is_stdin() {
+        use std::io::{self, Read as IoRead};
+        let mut content = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut content)
+            .context("Failed to read from stdin")?;
+        content
+    } else {
+        fs::read_to_string(&target.path)
+            .with_context(|| format!("Failed to read file: {}", target.path.display()))?
+    };
 
-    // Detect language from extension
-    let lang = file
+    // Detect language from extension (stdin has none)
+    let lang = target
+        .path
         .extension()
         .and_then(|e| e.to_str())
         .map(Language::from_extension)
         .unwrap_or(Language::Unknown);
-
     if verbose > 1 {
         eprintln!("Detected language: {:?}", lang);
     }
 
+    // Ranges are shown verbatim so line numbers match the file
+    if let Some(range) = target.range {
+        let total = content.lines().count();
+        if range.start > total {
+            bail!(
+                "{}: line {} is past the end of the file ({} lines)",
+                target.label(),
+                range.start,
+                total
+            );
+        }
+        let text = content
+            .lines()
+            .skip(range.start - 1)
+            .take(range.end - range.start + 1)
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Ok(Section {
+            target: target.clone(),
+            lang,
+            raw: text.clone(),
+            text,
+            first_line: range.start,
+            note: None,
+        });
+    }
+
     // Apply filter
-    let filter = filter::get_filter(level);
+    let filter = filter::get_filter(opts.level);
     let mut filtered = filter.filter(&content, &lang);
 
     if verbose > 0 {
@@ -51,93 +279,195 @@ pub fn run(
     }
 
     // Apply smart truncation if max_lines is set
-    if let Some(max) = max_lines {
+    if let Some(max) = opts.max_lines {
         filtered = filter::smart_truncate(&filtered, max, &lang);
     }
 
-    let rtk_output = if line_numbers {
-        format_with_line_numbers(&filtered)
-    } else {
-        filtered.clone()
+    Ok(Section {
+        target: target.clone(),
+        lang,
+        raw: content,
+        text: filtered,
+        first_line: 1,
+        note: None,
+    })
+}
+
+/// Share `budget` tokens across sections. Explicit line ranges are served
+/// first, then whole files in argument order; a file that no longer fits is
+/// cut with `smart_truncate`, and once the budget is spent the rest are skipped.
+/// Tokens are counted with the configured `tracking.tokenizer`.
+fn apply_budget(sections: &mut [Section], budget: usize, tokenizer: Tokenizer, verbose: u8) {
+    let mut order: Vec<usize> = (0..sections.len()).collect();
+    order.sort_by_key(|&i| (sections[i].target.range.is_none(), i));
+
+    let mut remaining = budget;
+    for i in order {
+        let section = &mut sections[i];
+        let tokens = tokenizer.count(&section.text);
+        if tokens <= remaining {
+            remaining -= tokens;
+            continue;
+        }
+
+        if remaining < MIN_SECTION_TOKENS {
+            section.note = Some(format!(
+                "skipped: budget exhausted, {} lines",
+                section.text.lines().count()
+            ));
+            section.text.clear();
+            remaining = 0;
+            continue;
+        }
+
+        let lines = section.text.lines().count();
+        let max_lines = (lines * remaining / tokens).max(2);
+        if section.target.range.is_some() {
+            // Keep line numbers aligned: plain cut instead of smart_truncate
+            let kept: Vec<&str> = section.text.lines().take(max_lines).collect();
+            section.note = Some(format!("budget: showing {} of {} lines", kept.len(), lines));
+            section.text = kept.join("\n");
+        } else {
+            section.text = filter::smart_truncate(&section.text, max_lines, &section.lang);
+        }
+        if verbose > 0 {
+            eprintln!(
+                "Budget: {} cut to {} lines ({} tokens left)",
+                section.target.label(),
+                max_lines,
+                remaining
+            );
+        }
+        remaining = remaining.saturating_sub(tokenizer.count(&section.text));
+    }
+}
+
+/// The file `--outline` / `--symbol` work on: exactly one, without a line range
+pub fn outline_target(targets: &[ReadTarget]) -> Result<&Path> {
+    let [target] = targets else {
+        bail!("--outline and --symbol take a single file");
     };
+    if let Some(range) = target.range {
+        bail!(
+            "--outline and --symbol read the whole file; drop ':{}' from {}",
+            range,
+            target.path.display()
+        );
+    }
+    Ok(&target.path)
+}
+
+/// Signature-only view: modules, types and functions with their line ranges
+pub fn run_outline(file: &Path, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let content = read_outline_source(file)?;
+    let rtk_output = render_outline(file, &content, verbose)?;
     println!("{}", rtk_output);
     timer.track(
         &format!("cat {}", file.display()),
-        "rtk read",
+        "rtk read --outline",
         &content,
         &rtk_output,
     );
     Ok(())
 }
 
-pub fn run_stdin(
-    level: FilterLevel,
-    max_lines: Option<usize>,
-    line_numbers: bool,
-    verbose: u8,
-) -> Result<()> {
-    use std::io::{self, Read as IoRead};
-
+/// Source of a single item (`Foo::bar`, `Foo.bar` or `bar`), docs included
+pub fn run_symbol(file: &Path, query: &str, line_numbers: bool, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
+    let content = read_outline_source(file)?;
+    let rtk_output = render_symbol(file, &content, query, line_numbers, verbose)?;
+    println!("{}", rtk_output);
+    timer.track(
+        &format!("cat {}", file.display()),
+        &format!("rtk read --symbol {}", query),
+        &content,
+        &rtk_output,
+    );
+    Ok(())
+}
 
+fn read_outline_source(file: &Path) -> Result<String> {
+    outline::ensure_supported(file)?;
+    fs::read_to_string(file).with_context(|| format!("Failed to read file: {}", file.display()))
+}
+
+fn render_outline(file: &Path, content: &str, verbose: u8) -> Result<String> {
+    let symbols = outline::symbols(file, content)?;
     if verbose > 0 {
-        eprintln!("Reading from stdin (filter: {})", level);
+        eprintln!(
+            "Outline: {} symbols in {} lines",
+            symbols.len(),
+            content.lines().count()
+        );
     }
 
-    // Read from stdin
-    let mut content = String::new();
-    io::stdin()
-        .lock()
-        .read_to_string(&mut content)
-        .context("Failed to read from stdin")?;
-
-    // No file extension, so use Unknown language
-    let lang = Language::Unknown;
+    Ok(if symbols.is_empty() {
+        format!("{}: no symbols found", file.display())
+    } else {
+        outline::format_outline(&symbols).trim_end().to_string()
+    })
+}
 
-    if verbose > 1 {
-        eprintln!("Language: {:?} (stdin has no extension)", lang);
+fn render_symbol(
+    file: &Path,
+    content: &str,
+    query: &str,
+    line_numbers: bool,
+    verbose: u8,
+) -> Result<String> {
+    let symbols = outline::symbols(file, content)?;
+    let found = outline::find(&symbols, query);
+    if found.is_empty() {
+        return Err(outline::not_found(file, query, &symbols));
     }
 
-    // Apply filter
-    let filter = filter::get_filter(level);
-    let mut filtered = filter.filter(&content, &lang);
-
     if verbose > 0 {
-        let original_lines = content.lines().count();
-        let filtered_lines = filtered.lines().count();
-        let reduction = if original_lines > 0 {
-            ((original_lines - filtered_lines) as f64 / original_lines as f64) * 100.0
-        } else {
-            0.0
-        };
-        eprintln!(
-            "Lines: {} -> {} ({:.1}% reduction)",
-            original_lines, filtered_lines, reduction
-        );
+        eprintln!("Symbol '{}': {} match(es)", query, found.len());
     }
 
-    // Apply smart truncation if max_lines is set
-    if let Some(max) = max_lines {
-        filtered = filter::smart_truncate(&filtered, max, &lang);
+    let mut sections = Vec::new();
+    for symbol in found {
+        let lines = outline::symbol_source(content, symbol);
+        let body = if line_numbers {
+            let width = symbol.end_line.to_string().len();
+            lines
+                .iter()
+                .enumerate()
+                .map(|(i, l)| format!("{:>width$} │ {}", symbol.doc_line + i, l, width = width))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            lines.join("\n")
+        };
+        sections.push(format!(
+            "{}:{}-{} ({} {})\n{}",
+            file.display(),
+            symbol.doc_line,
+            symbol.end_line,
+            symbol.kind,
+            outline::display_path(file, symbol),
+            body
+        ));
     }
 
-    let rtk_output = if line_numbers {
-        format_with_line_numbers(&filtered)
-    } else {
-        filtered.clone()
-    };
-    println!("{}", rtk_output);
-
-    timer.track("cat - (stdin)", "rtk read -", &content, &rtk_output);
-    Ok(())
+    Ok(sections.join("\n\n"))
 }
 
-fn format_with_line_numbers(content: &str) -> String {
+fn format_with_line_numbers(content: &str, first_line: usize) -> String {
     let lines: Vec<&str> = content.lines().collect();
-    let width = lines.len().to_string().len();
+    let width = (first_line + lines.len())
+        .saturating_sub(1)
+        .to_string()
+        .len();
     let mut out = String::new();
     for (i, line) in lines.iter().enumerate() {
-        out.push_str(&format!("{:>width$} │ {}\n", i + 1, line, width = width));
+        out.push_str(&format!(
+            "{:>width$} │ {}\n",
+            first_line + i,
+            line,
+            width = width
+        ));
     }
     out
 }
@@ -160,7 +490,211 @@ fn main() {{
         )?;
 
         // Just verify it doesn't panic
-        run(file.path(), FilterLevel::Minimal, None, false, 0)?;
+        let target = ReadTarget::parse(&file.path().to_string_lossy())?;
+        run(&[target], &options(None), 0)?;
+        Ok(())
+    }
+
+    fn options(budget: Option<usize>) -> ReadOptions {
+        ReadOptions {
+            level: FilterLevel::None,
+            max_lines: None,
+            line_numbers: false,
+            budget,
+        }
+    }
+
+    fn target(path: &str, range: Option<(usize, usize)>) -> ReadTarget {
+        ReadTarget {
+            path: PathBuf::from(path),
+            range: range.map(|(start, end)| LineRange { start, end }),
+        }
+    }
+
+    #[test]
+    fn test_parse_read_target() {
+        assert_eq!(
+            ReadTarget::parse("src/x.rs").unwrap(),
+            target("src/x.rs", None)
+        );
+        assert_eq!(
+            ReadTarget::parse("src/x.rs:10-20").unwrap(),
+            target("src/x.rs", Some((10, 20)))
+        );
+        assert_eq!(
+            ReadTarget::parse("src/x.rs:50±5").unwrap(),
+            target("src/x.rs", Some((45, 55)))
+        );
+        assert_eq!(
+            ReadTarget::parse("src/x.rs:3+-5").unwrap(),
+            target("src/x.rs", Some((1, 8)))
+        );
+        assert_eq!(
+            ReadTarget::parse("src/x.rs:7").unwrap(),
+            target("src/x.rs", Some((7, 7)))
+        );
+        assert_eq!(
+            ReadTarget::parse("-:2-4").unwrap(),
+            target("-", Some((2, 4)))
+        );
+        assert!(ReadTarget::parse("src/x.rs:20-10").is_err());
+        assert!(ReadTarget::parse("src/x.rs:0").is_err());
+    }
+
+    #[test]
+    fn test_load_section_range() -> Result<()> {
+        let mut file = NamedTempFile::with_suffix(".rs")?;
+        writeln!(file, "// one\ntwo\nthree\nfour")?;
+        let path = file.path().to_string_lossy().to_string();
+
+        let section = load_section(&target(&path, Some((2, 3))), &options(None), 0)?;
+        assert_eq!(section.text, "two\nthree");
+        assert_eq!(section.first_line, 2);
+        assert!(load_section(&target(&path, Some((9, 10))), &options(None), 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_read_tracks_only_selected_lines() -> Result<()> {
+        let mut file = NamedTempFile::with_suffix(".rs")?;
+        let lines: Vec<String> = (1..=200)
+            .map(|i| format!("let value_{} = compute_something({});", i, i))
+            .collect();
+        writeln!(file, "{}", lines.join("\n"))?;
+        let path = file.path().to_string_lossy().to_string();
+
+        let section = load_section(&target(&path, Some((10, 20))), &options(None), 0)?;
+        let input = tracked_input(&[section]);
+        assert_eq!(
+            tracking::estimate_tokens(&input),
+            tracking::estimate_tokens(&lines[9..20].join("\n"))
+        );
+        assert!(
+            tracking::estimate_tokens(&input) < tracking::estimate_tokens(&lines.join("\n")) / 10
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand_targets_glob() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("b.rs"), "b")?;
+        fs::write(dir.path().join("a.rs"), "a")?;
+        fs::write(dir.path().join("c.txt"), "c")?;
+
+        let pattern = format!("{}/*.rs:1-2", dir.path().display());
+        let targets = expand_targets(&[pattern])?;
+        assert_eq!(targets.len(), 2);
+        assert!(targets[0].path.ends_with("a.rs"));
+        assert_eq!(targets[1].range, Some(LineRange { start: 1, end: 2 }));
+
+        let missing = format!("{}/*.go", dir.path().display());
+        assert!(expand_targets(&[missing]).is_err());
+        assert!(expand_targets(&["-".to_string(), "-".to_string()]).is_err());
+        Ok(())
+    }
+
+    fn section(path: &str, range: Option<(usize, usize)>, lines: usize) -> Section {
+        let text = (1..=lines)
+            .map(|i| format!("let value_{} = compute_something({});", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Section {
+            target: target(path, range),
+            lang: Language::Rust,
+            raw: text.clone(),
+            text,
+            first_line: 1,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_budget_priority() {
+        // ~10 tokens per line
+        let mut sections = vec![
+            section("a.rs", None, 50),
+            section("b.rs", Some((1, 20)), 20),
+            section("c.rs", None, 50),
+        ];
+        apply_budget(&mut sections, 300, Tokenizer::Heuristic, 0);
+
+        // The ranged file is served first and kept whole
+        assert_eq!(sections[1].text.lines().count(), 20);
+        assert!(sections[1].note.is_none());
+        // The first whole file is cut to what is left
+        assert!(sections[0].text.contains("more lines"));
+        assert!(tracking::estimate_tokens(&sections[0].text) <= 115);
+        // Everything shown fits the budget
+        let used: usize = sections
+            .iter()
+            .map(|s| tracking::estimate_tokens(&s.text))
+            .sum();
+        assert!(used <= 300);
+
+        // A budget the ranged file alone uses up skips the rest
+        let mut sections = vec![
+            section("a.rs", None, 50),
+            section("b.rs", Some((1, 20)), 20),
+        ];
+        apply_budget(&mut sections, 200, Tokenizer::Heuristic, 0);
+        assert!(sections[0].text.is_empty());
+        assert!(sections[0]
+            .note
+            .as_deref()
+            .unwrap()
+            .contains("budget exhausted"));
+    }
+
+    #[test]
+    fn test_apply_budget_uses_tokenizer() {
+        let mut sections = vec![section("a.rs", None, 50)];
+        let tokens = Tokenizer::Cl100k.count(&sections[0].text);
+        apply_budget(&mut sections, 200, Tokenizer::Cl100k, 0);
+        assert!(tokens > 200);
+        assert!(Tokenizer::Cl100k.count(&sections[0].text) <= 200);
+    }
+
+    #[test]
+    fn test_format_with_line_numbers_offset() {
+        assert_eq!(format_with_line_numbers("a\nb", 9), " 9 │ a\n10 │ b\n");
+    }
+
+    #[test]
+    fn test_read_outline_and_symbol() -> Result<()> {
+        let mut file = NamedTempFile::with_suffix(".py")?;
+        writeln!(file, "class A:\n    def go(self):\n        return 1")?;
+        let path = file.path();
+        let content = read_outline_source(path)?;
+
+        let outline = render_outline(path, &content, 0)?;
+        assert!(outline.contains("class A"), "{}", outline);
+        assert!(outline.contains("go"), "{}", outline);
+        assert!(!outline.contains("return 1"));
+
+        let symbol = render_symbol(path, &content, "A.go", true, 0)?;
+        let (header, body) = symbol.split_once('\n').unwrap();
+        assert!(header.ends_with(":2-3 (def A.go)"), "{}", header);
+        assert_eq!(body, "2 │     def go(self):\n3 │         return 1");
+
+        assert!(render_symbol(path, &content, "missing", false, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_outline_target_rejects_ranges() -> Result<()> {
+        let file = NamedTempFile::with_suffix(".py")?;
+        let path = file.path().to_string_lossy().to_string();
+
+        let whole = vec![target(&path, None)];
+        assert_eq!(outline_target(&whole)?, file.path());
+
+        let ranged = vec![target(&path, Some((2, 3)))];
+        let err = outline_target(&ranged).unwrap_err().to_string();
+        assert!(err.contains("drop ':2-3'"), "{}", err);
+
+        let two = vec![target(&path, None), target(&path, None)];
+        assert!(outline_target(&two).is_err());
         Ok(())
     }
 
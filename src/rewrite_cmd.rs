@@ -0,0 +1,699 @@
+//! Native Claude Code PreToolUse rewrite hook.
+//!
+//! Reads the hook JSON on stdin and answers with an `updatedInput` that routes
+//! the Bash command through rtk. Matching is delegated to `discover::registry`
+//! (`split_command_chain` + `classify_command`) so the hook and `rtk discover`
+//! never disagree about what rtk supports.
+
+use crate::discover::registry::{
+    classify_command, split_command_chain, split_command_prefix, Classification,
+};
+use crate::exec::ExecResult;
+use crate::hook_audit_cmd;
+use crate::learn::corrections::{self, Checked};
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+
+lazy_static! {
+    static ref HEAD_N: Regex = Regex::new(r"^head\s+-(\d+)\s+(.+)$").unwrap();
+    static ref HEAD_LINES: Regex = Regex::new(r"^head\s+--lines=(\d+)\s+(.+)$").unwrap();
+}
+
+/// Which segments of a command chain get rewritten (`RTK_HOOK_MODE`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookMode {
+    /// Rewrite every segment of `a && b ; c` (default)
+    Flex,
+    /// Rewrite only the first segment
+    Strict,
+}
+
+impl HookMode {
+    pub fn from_env() -> Self {
+        match std::env::var("RTK_HOOK_MODE").as_deref() {
+            Ok("strict") => HookMode::Strict,
+            _ => HookMode::Flex,
+        }
+    }
+}
+
+/// Outcome of rewriting a full command line.
+#[derive(Debug, PartialEq)]
+pub enum RewriteResult {
+    Rewritten(String),
+    AlreadyRtk,
+    Heredoc,
+    NoMatch,
+    /// Blocked by a learned `reject` correction
+    Rejected(String),
+}
+
+impl RewriteResult {
+    /// Action label written to the hook audit log.
+    fn audit_action(&self) -> &'static str {
+        match self {
+            RewriteResult::Rewritten(_) => "rewrite",
+            RewriteResult::AlreadyRtk => "skip:already_rtk",
+            RewriteResult::Heredoc => "skip:heredoc",
+            RewriteResult::NoMatch => "skip:no_match",
+            RewriteResult::Rejected(_) => "deny:correction",
+        }
+    }
+}
+
+/// `rtk rewrite [command...]`: rewrite a command given on the CLI, or act as the hook.
+pub fn run(command: &[String], verbose: u8) -> Result<ExecResult> {
+    if command.is_empty() {
+        run_pretooluse(verbose)?;
+        return Ok(ExecResult::SUCCESS);
+    }
+
+    let cmd = command.join(" ");
+    let checked = corrections::check_command(&cmd);
+    match rewrite_corrected(&cmd, checked, &rtk_bin(), HookMode::from_env()) {
+        RewriteResult::Rewritten(rewritten) => {
+            println!("{}", rewritten);
+            Ok(ExecResult::SUCCESS)
+        }
+        RewriteResult::Rejected(reason) => {
+            eprintln!("{}", reason);
+            Ok(ExecResult::code(2))
+        }
+        other => {
+            if verbose > 0 {
+                eprintln!("No rewrite ({})", other.audit_action());
+            }
+            Ok(ExecResult::code(1))
+        }
+    }
+}
+
+/// `rtk hook pretooluse`: read PreToolUse JSON on stdin, print the hook response.
+/// Always fails open: malformed input or a non-matching command produces no output.
+pub fn run_pretooluse(verbose: u8) -> Result<()> {
+    let mut input = String::new();
+    if std::io::stdin().read_to_string(&mut input).is_err() {
+        return Ok(());
+    }
+
+    let payload: Value = match serde_json::from_str(&input) {
+        Ok(v) => v,
+        Err(e) => {
+            if verbose > 0 {
+                eprintln!("rtk hook: invalid PreToolUse JSON: {}", e);
+            }
+            return Ok(());
+        }
+    };
+
+    let cmd = match payload
+        .get("tool_input")
+        .and_then(|t| t.get("command"))
+        .and_then(Value::as_str)
+    {
+        Some(c) if !c.is_empty() => c,
+        _ => return Ok(()),
+    };
+
+    // Tag rewritten commands with the agent session so `rtk gain --session` can filter.
+    let rtk = match hook_session_id(&payload) {
+        Some(id) => format!("RTK_SESSION_ID={} {}", id, rtk_bin()),
+        None => rtk_bin(),
+    };
+    let checked = corrections::check_command(cmd);
+    let result = rewrite_corrected(cmd, checked, &rtk, HookMode::from_env());
+    audit_log(&result, cmd);
+
+    let response = match result {
+        RewriteResult::Rewritten(rewritten) => {
+            build_hook_response(&payload["tool_input"], &rewritten)
+        }
+        RewriteResult::Rejected(reason) => build_deny_response(&reason),
+        _ => return Ok(()),
+    };
+    println!("{}", serde_json::to_string_pretty(&response)?);
+
+    Ok(())
+}
+
+/// Apply a learned-corrections verdict, then the usual rtk rewrite. A corrected
+/// command is returned even when rtk has no filter for it.
+fn rewrite_corrected(cmd: &str, checked: Checked, rtk: &str, mode: HookMode) -> RewriteResult {
+    let fixed = match checked {
+        Checked::Rejected(reason) => return RewriteResult::Rejected(reason),
+        Checked::Rewritten(fixed, _) => fixed,
+        Checked::Unchanged => return rewrite_command(cmd, rtk, mode),
+    };
+    match rewrite_command(&fixed, rtk, mode) {
+        RewriteResult::Rewritten(r) => RewriteResult::Rewritten(r),
+        _ => RewriteResult::Rewritten(fixed),
+    }
+}
+
+/// `session_id` from the hook payload, if it is safe to inline in a shell command.
+fn hook_session_id(payload: &Value) -> Option<&str> {
+    payload
+        .get("session_id")
+        .and_then(Value::as_str)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= 128
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// Build the hook answer, preserving every original `tool_input` field except `command`.
+fn build_hook_response(tool_input: &Value, rewritten: &str) -> Value {
+    let mut updated = tool_input.clone();
+    if let Some(obj) = updated.as_object_mut() {
+        obj.insert("command".to_string(), Value::String(rewritten.to_string()));
+    }
+
+    json!({
+        "hookSpecificOutput": {
+            "hookEventName": "PreToolUse",
+            "permissionDecision": "allow",
+            "permissionDecisionReason": "RTK auto-rewrite",
+            "updatedInput": updated
+        }
+    })
+}
+
+/// Deny the tool call, telling the agent the right form.
+fn build_deny_response(reason: &str) -> Value {
+    json!({
+        "hookSpecificOutput": {
+            "hookEventName": "PreToolUse",
+            "permissionDecision": "deny",
+            "permissionDecisionReason": reason
+        }
+    })
+}
+
+/// Binary used in rewritten commands: `$RTK_BIN` when set, else plain `rtk` from PATH.
+fn rtk_bin() -> String {
+    std::env::var("RTK_BIN")
+        .ok()
+        .filter(|bin| !bin.is_empty())
+        .unwrap_or_else(|| "rtk".to_string())
+}
+
+/// Append "timestamp | action | original | rewritten" when `RTK_HOOK_AUDIT=1`.
+fn audit_log(result: &RewriteResult, original: &str) {
+    if std::env::var("RTK_HOOK_AUDIT").as_deref() != Ok("1") {
+        return;
+    }
+
+    let rewritten = match result {
+        RewriteResult::Rewritten(r) => r.as_str(),
+        _ => "-",
+    };
+    let line = format!(
+        "{} | {} | {} | {}\n",
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+        result.audit_action(),
+        original.replace('\n', "\\n"),
+        rewritten.replace('\n', "\\n")
+    );
+
+    let path = hook_audit_cmd::default_log_path();
+    let _ = append_line(&path, &line);
+}
+
+fn append_line(path: &std::path::Path, line: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Rewrite a full command line (possibly a `&&`/`||`/`;` chain) to use rtk.
+pub fn rewrite_command(cmd: &str, rtk: &str, mode: HookMode) -> RewriteResult {
+    let trimmed = cmd.trim();
+    if is_rtk_command(trimmed) {
+        return RewriteResult::AlreadyRtk;
+    }
+    if cmd.contains("<<") {
+        return RewriteResult::Heredoc;
+    }
+    // Command substitution and unbalanced quotes are ambiguous for lightweight
+    // splitting: fail open to preserve exact shell behavior.
+    if cmd.contains("$(") || cmd.contains('`') || !quotes_balanced(cmd) {
+        return RewriteResult::NoMatch;
+    }
+
+    let mut rebuilt = String::with_capacity(cmd.len() + 16);
+    let mut cursor = 0;
+    let mut changed = false;
+
+    for (i, segment) in split_command_chain(cmd).into_iter().enumerate() {
+        if mode == HookMode::Strict && i > 0 {
+            break;
+        }
+
+        // Segments borrow from `cmd`, so their offsets locate them in the original.
+        let start = segment.as_ptr() as usize - cmd.as_ptr() as usize;
+        let end = start + segment.len();
+
+        // A pipe after the segment means its output feeds another command: keep it raw.
+        let after = cmd[end..].trim_start();
+        let piped = after.starts_with('|') && !after.starts_with("||");
+        if piped || has_unquoted_redirect(segment) {
+            continue;
+        }
+
+        if let Some(rewritten) = rewrite_segment(segment, rtk) {
+            rebuilt.push_str(&cmd[cursor..start]);
+            rebuilt.push_str(&rewritten);
+            cursor = end;
+            changed = true;
+        }
+    }
+
+    if !changed {
+        return RewriteResult::NoMatch;
+    }
+
+    rebuilt.push_str(&cmd[cursor..]);
+    RewriteResult::Rewritten(rebuilt)
+}
+
+/// Rewrite one chain segment, keeping env/wrapper/runner prefixes in place.
+fn rewrite_segment(segment: &str, rtk: &str) -> Option<String> {
+    let (prefix, core) = split_command_prefix(segment);
+    if core.is_empty() || is_rtk_command(core) {
+        return None;
+    }
+
+    match classify_command(core) {
+        Classification::Supported { rtk_equivalent, .. } => {
+            rtk_form(core, rtk_equivalent, rtk).map(|r| format!("{}{}", prefix, r))
+        }
+        _ => None,
+    }
+}
+
+/// Translate a supported raw command into the concrete rtk invocation.
+/// Classification says rtk handles the command; this decides the exact argv.
+fn rtk_form(core: &str, rtk_equivalent: &str, rtk: &str) -> Option<String> {
+    if rtk_equivalent.starts_with("rtk proxy ") {
+        return Some(format!("{} proxy {}", rtk, core));
+    }
+
+    match rtk_equivalent {
+        "rtk read" => {
+            if let Some(rest) = after_word(core, "cat").filter(|r| !r.trim().is_empty()) {
+                return Some(format!("{} read{}", rtk, rest));
+            }
+            let caps = HEAD_N
+                .captures(core)
+                .or_else(|| HEAD_LINES.captures(core))?;
+            Some(format!(
+                "{} read {} --max-lines {}",
+                rtk, &caps[2], &caps[1]
+            ))
+        }
+        "rtk grep" => {
+            let rest = after_word(core, "rg").or_else(|| after_word(core, "grep"))?;
+            Some(format!("{} grep{}", rtk, rest))
+        }
+        "rtk npm" => {
+            let rest = after_words(core, &["npm", "run"]).or_else(|| after_word(core, "npm"))?;
+            (!rest.trim().is_empty()).then(|| format!("{} npm{}", rtk, rest))
+        }
+        "rtk vitest" => {
+            let tool = strip_js_runner(core);
+            let rest = after_word(tool, "vitest").or_else(|| {
+                // `pnpm test` runs the project's vitest script; bare `test` is a shell builtin.
+                (tool.len() != core.len())
+                    .then(|| after_word(tool, "test"))
+                    .flatten()
+            })?;
+            let rest = after_word(rest.trim_start(), "run").unwrap_or(rest);
+            let rest = rest.trim_start();
+            if rest.is_empty() {
+                Some(format!("{} vitest run", rtk))
+            } else {
+                Some(format!("{} vitest run {}", rtk, rest))
+            }
+        }
+        "rtk tsc" => {
+            let tool = strip_js_runner(core);
+            let rest = after_word(tool, "tsc").or_else(|| after_word(tool, "vue-tsc"))?;
+            Some(format!("{} tsc{}", rtk, rest))
+        }
+        "rtk lint" => {
+            let tool = strip_js_runner(core);
+            let rest = after_word(tool, "eslint").or_else(|| after_word(tool, "lint"))?;
+            Some(format!("{} lint{}", rtk, rest))
+        }
+        "rtk next" => {
+            let rest = after_words(strip_js_runner(core), &["next", "build"])?;
+            Some(format!("{} next{}", rtk, rest))
+        }
+        "rtk prettier" | "rtk playwright" | "rtk prisma" => {
+            let name = &rtk_equivalent[4..];
+            let rest = after_word(strip_js_runner(core), name)?;
+            Some(format!("{} {}{}", rtk, name, rest))
+        }
+        "rtk pytest" | "rtk ruff" => {
+            let name = &rtk_equivalent[4..];
+            let tool = after_words(core, &["python", "-m"])
+                .or_else(|| after_words(core, &["python3", "-m"]))
+                .map(str::trim_start)
+                .unwrap_or(core);
+            let rest = after_word(tool, name)?;
+            Some(format!("{} {}{}", rtk, name, rest))
+        }
+        "rtk pip" => {
+            let tool = after_word(core, "uv").map(str::trim_start).unwrap_or(core);
+            let rest = after_word(tool, "pip")?;
+            Some(format!("{} pip{}", rtk, rest))
+        }
+        // Generic npx stays raw: `rtk npx` only improves the tools routed above.
+        "rtk npx" => None,
+        _ => Some(format!("{} {}", rtk, core)),
+    }
+}
+
+/// Drop a leading `npx`/`pnpm` so `npx tsc` and `tsc` rewrite the same way.
+fn strip_js_runner(cmd: &str) -> &str {
+    after_word(cmd, "npx")
+        .or_else(|| after_word(cmd, "pnpm"))
+        .map(str::trim_start)
+        .unwrap_or(cmd)
+}
+
+/// Remainder after `word` (leading whitespace kept), if `cmd` starts with that whole word.
+fn after_word<'a>(cmd: &'a str, word: &str) -> Option<&'a str> {
+    let rest = cmd.strip_prefix(word)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(rest)
+}
+
+/// Like `after_word` for a sequence of words separated by whitespace.
+fn after_words<'a>(cmd: &'a str, words: &[&str]) -> Option<&'a str> {
+    let mut rest = cmd;
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            rest = rest.trim_start();
+        }
+        rest = after_word(rest, word)?;
+    }
+    Some(rest)
+}
+
+fn is_rtk_command(cmd: &str) -> bool {
+    let first = cmd.split_whitespace().next().unwrap_or("");
+    first == "rtk" || first.ends_with("/rtk")
+}
+
+/// True if the segment has `<` or `>` outside quotes (redirections must keep raw output).
+fn has_unquoted_redirect(segment: &str) -> bool {
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+    for c in segment.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if !in_single => escaped = true,
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            '<' | '>' if !in_single && !in_double => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn quotes_balanced(cmd: &str) -> bool {
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+    for c in cmd.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if !in_single => escaped = true,
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            _ => {}
+        }
+    }
+    !in_single && !in_double
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rw(cmd: &str) -> Option<String> {
+        match rewrite_command(cmd, "rtk", HookMode::Flex) {
+            RewriteResult::Rewritten(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    fn assert_rewrite(cmd: &str, expected: &str) {
+        assert_eq!(rw(cmd).as_deref(), Some(expected), "input: {cmd}");
+    }
+
+    fn assert_no_rewrite(cmd: &str) {
+        assert_eq!(rw(cmd), None, "input: {cmd}");
+    }
+
+    #[test]
+    fn test_rewrite_basic_patterns() {
+        assert_rewrite("git status", "rtk git status");
+        assert_rewrite("git log --oneline -10", "rtk git log --oneline -10");
+        assert_rewrite("gh pr list", "rtk gh pr list");
+        assert_rewrite("gh release list", "rtk gh release list");
+        assert_rewrite("cargo test", "rtk cargo test");
+        assert_rewrite("cargo nextest run", "rtk cargo nextest run");
+        assert_rewrite("ls -la", "rtk ls -la");
+        assert_rewrite("tree src/", "rtk tree src/");
+        assert_rewrite("find . -name '*.ts'", "rtk find . -name '*.ts'");
+        assert_rewrite(
+            "curl -s https://example.com",
+            "rtk curl -s https://example.com",
+        );
+        assert_rewrite(
+            "kubectl apply -f deploy.yaml",
+            "rtk kubectl apply -f deploy.yaml",
+        );
+        assert_rewrite("go test ./...", "rtk go test ./...");
+        assert_rewrite("golangci-lint run", "rtk golangci-lint run");
+    }
+
+    #[test]
+    fn test_rewrite_renamed_tools() {
+        assert_rewrite("cat package.json", "rtk read package.json");
+        assert_rewrite(
+            "head -20 src/main.rs",
+            "rtk read src/main.rs --max-lines 20",
+        );
+        assert_rewrite("head --lines=5 a.txt", "rtk read a.txt --max-lines 5");
+        assert_rewrite("grep -rn pattern src/", "rtk grep -rn pattern src/");
+        assert_rewrite("rg pattern src/", "rtk grep pattern src/");
+        assert_rewrite("npm run build", "rtk npm build");
+        assert_rewrite("npm test", "rtk npm test");
+        assert_rewrite("vue-tsc -b", "rtk tsc -b");
+        assert_rewrite("npx vue-tsc --noEmit", "rtk tsc --noEmit");
+        assert_rewrite("npx prisma migrate", "rtk prisma migrate");
+        assert_rewrite("npx playwright test", "rtk playwright test");
+        assert_rewrite("python3 script.py", "rtk proxy python3 script.py");
+        assert_rewrite("python -m pytest -q", "rtk pytest -q");
+        assert_rewrite("uv pip list", "rtk pip list");
+    }
+
+    #[test]
+    fn test_rewrite_vitest_no_double_run() {
+        assert_rewrite("vitest", "rtk vitest run");
+        assert_rewrite("vitest run", "rtk vitest run");
+        assert_rewrite(
+            "vitest run --reporter=verbose",
+            "rtk vitest run --reporter=verbose",
+        );
+        assert_rewrite("npx vitest run", "rtk vitest run");
+        assert_rewrite("pnpm vitest run --coverage", "rtk vitest run --coverage");
+        assert_rewrite("pnpm test", "rtk vitest run");
+    }
+
+    #[test]
+    fn test_rewrite_preserves_prefixes() {
+        assert_rewrite("GIT_PAGER=cat git status", "GIT_PAGER=cat rtk git status");
+        assert_rewrite(
+            "NODE_ENV=test CI=1 npx vitest run",
+            "NODE_ENV=test CI=1 rtk vitest run",
+        );
+        assert_rewrite(
+            "NODE_ENV=test npm run test:e2e",
+            "NODE_ENV=test rtk npm test:e2e",
+        );
+        assert_rewrite("uv run pytest tests/ -q", "uv run rtk pytest tests/ -q");
+        assert_rewrite(
+            "uv run python -m pytest tests/ -q",
+            "uv run rtk pytest tests/ -q",
+        );
+        assert_no_rewrite("uv run rtk git status");
+    }
+
+    #[test]
+    fn test_rewrite_chain_flex_and_strict() {
+        assert_rewrite(
+            "git add . && git commit -m 'a && b' ; ls",
+            "rtk git add . && rtk git commit -m 'a && b' ; rtk ls",
+        );
+        assert_eq!(
+            rewrite_command("git status && git diff", "rtk", HookMode::Strict),
+            RewriteResult::Rewritten("rtk git status && git diff".to_string())
+        );
+        assert_rewrite("cd /tmp && git status", "cd /tmp && rtk git status");
+    }
+
+    #[test]
+    fn test_rewrite_skips() {
+        assert_eq!(
+            rewrite_command("rtk git status", "rtk", HookMode::Flex),
+            RewriteResult::AlreadyRtk
+        );
+        assert_eq!(
+            rewrite_command("cat <<'EOF'\nhello\nEOF", "rtk", HookMode::Flex),
+            RewriteResult::Heredoc
+        );
+        assert_no_rewrite("echo hello world");
+        assert_no_rewrite("mkdir -p foo/bar");
+        assert_no_rewrite("python -c \"print('hello')\"");
+        assert_no_rewrite("uv run python -c \"print('hello')\"");
+        assert_no_rewrite("node -e 'console.log(1)'");
+        assert_no_rewrite("tail -f app.log");
+        assert_no_rewrite("npx cowsay hi");
+    }
+
+    #[test]
+    fn test_rewrite_keeps_io_operators_raw() {
+        assert_no_rewrite("uv run pytest tests/x.py --collect-only 2>&1");
+        assert_no_rewrite("uv run python -m pytest tests/x.py -v 2>&1 | tail -40");
+        assert_no_rewrite("git status > /tmp/git-status.txt");
+        assert_no_rewrite("git log | head");
+        assert_no_rewrite("echo $(git status)");
+        assert_no_rewrite("echo `git status`");
+        assert_rewrite("git log --grep='a>b'", "rtk git log --grep='a>b'");
+        assert_rewrite("git status || git diff", "rtk git status || rtk git diff");
+    }
+
+    #[test]
+    fn test_rewrite_agrees_with_discover() {
+        // Anything the hook rewrites must be classified Supported by discover.
+        for cmd in [
+            "git status",
+            "cargo clippy",
+            "npx tsc",
+            "pytest",
+            "docker ps",
+        ] {
+            assert!(rw(cmd).is_some(), "{cmd} should rewrite");
+            assert!(matches!(
+                classify_command(cmd),
+                Classification::Supported { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_hook_session_id_sanitized() {
+        let ok = json!({"session_id": "abc-123_x"});
+        assert_eq!(hook_session_id(&ok), Some("abc-123_x"));
+        let unsafe_id = json!({"session_id": "x; rm -rf /"});
+        assert_eq!(hook_session_id(&unsafe_id), None);
+        assert_eq!(hook_session_id(&json!({})), None);
+    }
+
+    #[test]
+    fn test_session_prefix_keeps_rewrite_idempotent() {
+        let rtk = "RTK_SESSION_ID=s1 rtk";
+        let first = rewrite_command("git status", rtk, HookMode::Flex);
+        assert_eq!(
+            first,
+            RewriteResult::Rewritten("RTK_SESSION_ID=s1 rtk git status".to_string())
+        );
+        assert_eq!(
+            rewrite_command("RTK_SESSION_ID=s1 rtk git status", rtk, HookMode::Flex),
+            RewriteResult::NoMatch
+        );
+    }
+
+    #[test]
+    fn test_build_hook_response_preserves_fields() {
+        let input = json!({"command": "git status", "description": "check", "timeout": 5});
+        let out = build_hook_response(&input, "rtk git status");
+        let updated = &out["hookSpecificOutput"]["updatedInput"];
+        assert_eq!(updated["command"], "rtk git status");
+        assert_eq!(updated["description"], "check");
+        assert_eq!(updated["timeout"], 5);
+        assert_eq!(out["hookSpecificOutput"]["permissionDecision"], "allow");
+    }
+
+    #[test]
+    fn test_audit_actions() {
+        assert_eq!(RewriteResult::AlreadyRtk.audit_action(), "skip:already_rtk");
+        assert_eq!(RewriteResult::Heredoc.audit_action(), "skip:heredoc");
+        assert_eq!(RewriteResult::NoMatch.audit_action(), "skip:no_match");
+        assert_eq!(
+            RewriteResult::Rewritten(String::new()).audit_action(),
+            "rewrite"
+        );
+        assert_eq!(
+            RewriteResult::Rejected(String::new()).audit_action(),
+            "deny:correction"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_corrected() {
+        let fixed = Checked::Rewritten("git status --short".to_string(), vec![]);
+        assert_eq!(
+            rewrite_corrected("git status --shrt", fixed, "rtk", HookMode::Flex),
+            RewriteResult::Rewritten("rtk git status --short".to_string())
+        );
+
+        // No rtk filter: the corrected command still goes through
+        let fixed = Checked::Rewritten("mkdir -p foo".to_string(), vec![]);
+        assert_eq!(
+            rewrite_corrected("mkdir -pp foo", fixed, "rtk", HookMode::Flex),
+            RewriteResult::Rewritten("mkdir -p foo".to_string())
+        );
+
+        let rejected = Checked::Rejected("use x".to_string());
+        assert_eq!(
+            rewrite_corrected("git status", rejected, "rtk", HookMode::Flex),
+            RewriteResult::Rejected("use x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_deny_response() {
+        let out = build_deny_response("use `git commit --amend`");
+        assert_eq!(out["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(
+            out["hookSpecificOutput"]["permissionDecisionReason"],
+            "use `git commit --amend`"
+        );
+        assert!(out["hookSpecificOutput"].get("updatedInput").is_none());
+    }
+}
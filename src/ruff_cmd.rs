@@ -1,3 +1,5 @@
+use crate::exec::ExecResult;
+use crate::parser::{LintIssue, LintSeverity};
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -7,9 +9,7 @@ use std::process::Command;
 
 #[derive(Debug, Deserialize)]
 struct RuffLocation {
-    #[allow(dead_code)]
     row: usize,
-    #[allow(dead_code)]
     column: usize,
 }
 
@@ -22,9 +22,7 @@ struct RuffFix {
 #[derive(Debug, Deserialize)]
 struct RuffDiagnostic {
     code: String,
-    #[allow(dead_code)]
     message: String,
-    #[allow(dead_code)]
     location: RuffLocation,
     #[allow(dead_code)]
     end_location: Option<RuffLocation>,
@@ -32,7 +30,7 @@ struct RuffDiagnostic {
     fix: Option<RuffFix>,
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Detect subcommand: check, format, or version
@@ -100,7 +98,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     println!("{}", filtered);
 
-    timer.track(
+    crate::preserve::track(
+        &timer,
         &format!("ruff {}", args.join(" ")),
         &format!("rtk ruff {}", args.join(" ")),
         &raw,
@@ -108,11 +107,26 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
+    Ok(ExecResult::from_status(&output.status))
+}
 
-    Ok(())
+/// Parse ruff check JSON into lint issues (None if the output isn't ruff JSON)
+pub(crate) fn parse_ruff_check_issues(output: &str) -> Option<Vec<LintIssue>> {
+    let diagnostics: Vec<RuffDiagnostic> = serde_json::from_str(output).ok()?;
+    Some(
+        diagnostics
+            .into_iter()
+            .map(|d| LintIssue {
+                file_path: d.filename,
+                line: d.location.row,
+                column: d.location.column,
+                severity: LintSeverity::Error,
+                rule_id: d.code,
+                message: d.message,
+                rendered: None,
+            })
+            .collect(),
+    )
 }
 
 /// Filter ruff check JSON output - group by rule and file
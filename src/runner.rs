@@ -1,10 +1,11 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use regex::Regex;
 use std::process::{Command, Stdio};
 
 /// Run a command and filter output to show only errors/warnings
-pub fn run_err(command: &str, verbose: u8) -> Result<()> {
+pub fn run_err(command: &str, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -29,17 +30,29 @@ pub fn run_err(command: &str, verbose: u8) -> Result<()> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
-    let filtered = filter_errors(&raw);
+    let exit_code = crate::exec::exit_code(&output.status);
+
+    // A user-defined [[filters]] rule replaces the generic error filter
+    let user_rule = crate::user_filter::find_filter(command);
+    if verbose > 0 {
+        if let Some(rule) = &user_rule {
+            eprintln!("Applying filter: {}", rule.name);
+        }
+    }
+    let filtered = match &user_rule {
+        Some(rule) => rule.apply(command, &raw, exit_code).unwrap_or_else(|e| {
+            eprintln!("rtk: filter '{}' failed: {:#}", rule.name, e);
+            filter_errors(&raw)
+        }),
+        None => filter_errors(&raw),
+    };
     let mut rtk = String::new();
 
     if filtered.is_empty() {
         if output.status.success() {
             rtk.push_str("✅ Command completed successfully (no errors)");
         } else {
-            rtk.push_str(&format!(
-                "❌ Command failed (exit code: {:?})\n",
-                output.status.code()
-            ));
+            rtk.push_str(&format!("❌ Command failed (exit code: {})\n", exit_code));
             let lines: Vec<&str> = raw.lines().collect();
             for line in lines.iter().rev().take(10).rev() {
                 rtk.push_str(&format!("  {}\n", line));
@@ -49,61 +62,67 @@ pub fn run_err(command: &str, verbose: u8) -> Result<()> {
         rtk.push_str(&filtered);
     }
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "err", exit_code) {
         println!("{}\n{}", rtk, hint);
     } else {
         println!("{}", rtk);
     }
-    timer.track(command, "rtk run-err", &raw, &rtk);
-    Ok(())
+    crate::preserve::track_with_exit(&timer, command, "rtk run-err", &raw, &rtk, exit_code);
+    Ok(ExecResult::code(exit_code))
 }
 
 /// Run tests and show only failures
-pub fn run_test(command: &str, verbose: u8) -> Result<()> {
+pub fn run_test(command: &str, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("Running tests: {}", command);
     }
 
-    let output = if cfg!(target_os = "windows") {
-        Command::new("cmd")
-            .args(["/C", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command]);
+        c
     } else {
-        Command::new("sh")
-            .args(["-c", command])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-    }
-    .context("Failed to execute test command")?;
+        let mut c = Command::new("sh");
+        c.args(["-c", command]);
+        c
+    };
+    let output = crate::stream::run_streaming(&mut cmd, live_test_failure)
+        .context("Failed to execute test command")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
-
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let summary = extract_test_summary(&raw, command);
+    let raw = format!("{}\n{}", output.stdout, output.stderr);
+    let exit_code = output.exit_code;
+    let summary = output.dedupe(&extract_test_summary(&raw, command));
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "test", exit_code) {
         println!("{}\n{}", summary, hint);
     } else {
         println!("{}", summary);
     }
-    timer.track(command, "rtk run-test", &raw, &summary);
-    Ok(())
+    crate::preserve::track_with_exit(
+        &timer,
+        command,
+        "rtk run-test",
+        &raw,
+        &output.printed(&summary),
+        exit_code,
+    );
+    Ok(ExecResult::code(exit_code))
 }
 
-fn filter_errors(output: &str) -> String {
+/// Failure lines worth printing while the suite is still running
+/// (cargo, go, jest/vitest, pytest).
+fn live_test_failure(line: &str) -> Option<String> {
+    lazy_static::lazy_static! {
+        static ref LIVE_FAILURE: Regex = Regex::new(
+            r"^\s*(test \S+ \.\.\. FAILED|--- FAIL: \S+.*|FAIL\s+\S+.*|FAILED \S+.*|[✕×] .+)$"
+        )
+        .unwrap();
+    }
+    LIVE_FAILURE.is_match(line).then(|| line.trim().to_string())
+}
+
+pub(crate) fn filter_errors(output: &str) -> String {
     lazy_static::lazy_static! {
         static ref ERROR_PATTERNS: Vec<Regex> = vec![
             // Generic errors
@@ -160,7 +179,7 @@ fn filter_errors(output: &str) -> String {
     result.join("\n")
 }
 
-fn extract_test_summary(output: &str, command: &str) -> String {
+pub(crate) fn extract_test_summary(output: &str, command: &str) -> String {
     let mut result = Vec::new();
     let lines: Vec<&str> = output.lines().collect();
 
@@ -268,4 +287,19 @@ mod tests {
         assert!(filtered.contains("error"));
         assert!(!filtered.contains("info"));
     }
+
+    #[test]
+    fn test_live_test_failure() {
+        assert_eq!(
+            live_test_failure("test tests::it_works ... FAILED").as_deref(),
+            Some("test tests::it_works ... FAILED")
+        );
+        assert_eq!(
+            live_test_failure("    --- FAIL: TestAdd (0.00s)").as_deref(),
+            Some("--- FAIL: TestAdd (0.00s)")
+        );
+        assert!(live_test_failure("FAILED tests/test_a.py::test_x - assert 1 == 2").is_some());
+        assert!(live_test_failure("test tests::it_works ... ok").is_none());
+        assert!(live_test_failure("ok  \texample.com/pkg\t0.01s").is_none());
+    }
 }
@@ -0,0 +1,850 @@
+//! `rtk git diff --semantic`: a map of what changed where, instead of the
+//! first N hunks.
+//!
+//! Changes are grouped per file and per enclosing function/impl block. The
+//! enclosing symbol comes from the hunk header (git's funcname context) and
+//! from declarations inside the hunk, recognized per `filter::Language`.
+//! Pure renames, blocks moved between places, whitespace-only changes and
+//! lock/generated files collapse to one line each. `--only path::symbol`
+//! prints the full hunks of one group.
+
+use crate::filter::Language;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Removed runs shorter than this (non-blank lines) are not reported as moves
+const MIN_MOVED_LINES: usize = 3;
+/// Skip move detection on huge diffs (quadratic in the number of runs)
+const MAX_MOVE_SCAN_LINES: usize = 20_000;
+const MAX_GROUPS_PER_FILE: usize = 12;
+const MAX_SYMBOL_WIDTH: usize = 40;
+const TOP_LEVEL: &str = "(top level)";
+
+const LOCK_FILES: &[&str] = &[
+    "Cargo.lock",
+    "Gemfile.lock",
+    "Pipfile.lock",
+    "bun.lockb",
+    "composer.lock",
+    "flake.lock",
+    "go.sum",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "uv.lock",
+    "yarn.lock",
+];
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".min.js", ".min.css", ".js.map", ".css.map", ".pb.go", "_pb2.py", ".g.dart",
+];
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "DO NOT EDIT",
+    "Code generated",
+    "auto-generated",
+    "autogenerated",
+];
+
+lazy_static! {
+    static ref RUST_ITEM: Regex = Regex::new(
+        r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:async|const|unsafe|extern(?:\s+"[^"]*")?)\s+)*(fn|struct|enum|trait|mod|union)\s+([A-Za-z_]\w*)"#
+    )
+    .unwrap();
+    static ref RUST_IMPL: Regex =
+        Regex::new(r"^\s*(?:unsafe\s+)?impl(?:<[^{]*?>)?\s+([^{]+?)\s*(?:where\b.*)?\{?\s*$").unwrap();
+    static ref PY_DEF: Regex = Regex::new(r"^\s*(?:async\s+)?(def|class)\s+([A-Za-z_]\w*)").unwrap();
+    static ref JS_FUNCTION: Regex = Regex::new(
+        r"^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\*?\s+([A-Za-z_$][\w$]*)"
+    )
+    .unwrap();
+    static ref JS_TYPE: Regex = Regex::new(
+        r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(class|interface|enum|type)\s+([A-Za-z_$][\w$]*)"
+    )
+    .unwrap();
+    static ref JS_CONST_FN: Regex = Regex::new(
+        r"^\s*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*(?::[^=]+)?=>|[A-Za-z_$][\w$]*\s*=>)"
+    )
+    .unwrap();
+    static ref JS_METHOD: Regex = Regex::new(
+        r"^\s+(?:(?:public|private|protected|static|async|readonly|override|get|set)\s+)*([A-Za-z_$][\w$]*)\s*\([^)]*\)\s*(?::\s*[^={]+)?\{\s*$"
+    )
+    .unwrap();
+    static ref GO_FUNC: Regex =
+        Regex::new(r"^func\s+(?:\(\s*\w*\s*\*?(\w+)[^)]*\)\s*)?([A-Za-z_]\w*)").unwrap();
+    static ref GO_TYPE: Regex = Regex::new(r"^type\s+([A-Za-z_]\w*)").unwrap();
+    static ref C_TYPE: Regex = Regex::new(
+        r"^\s*(?:(?:public|private|protected|static|final|abstract|sealed)\s+)*(class|struct|interface|enum|namespace|record)\s+([A-Za-z_]\w*)"
+    )
+    .unwrap();
+    static ref C_FUNCTION: Regex = Regex::new(
+        r"^\s*(?:[\w:<>,\[\]]+[\s*&]+)+([A-Za-z_~][\w:~]*)\s*\([^;]*$"
+    )
+    .unwrap();
+    static ref RUBY_DEF: Regex =
+        Regex::new(r"^\s*(def|class|module)\s+([\w.:]+[?!=]?)").unwrap();
+    static ref SHELL_FUNCTION: Regex =
+        Regex::new(r"^\s*(?:function\s+([\w-]+)|([A-Za-z_][\w-]*)\s*\(\))").unwrap();
+}
+
+/// Control-flow keywords the C-like function pattern must not report
+const NOT_FUNCTIONS: &[&str] = &[
+    "if", "for", "while", "switch", "catch", "return", "sizeof", "else", "do", "new",
+];
+
+/// One file section of a unified diff.
+#[derive(Debug, Default)]
+pub(crate) struct FileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    /// `similarity index` of a rename
+    pub similarity: Option<u8>,
+    pub new_file: bool,
+    pub deleted: bool,
+    pub binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Hunk {
+    pub header: String,
+    /// Body lines with their ` `/`+`/`-` prefix
+    pub lines: Vec<String>,
+}
+
+impl FileDiff {
+    fn counts(&self) -> (usize, usize) {
+        let lines = self.hunks.iter().flat_map(|h| &h.lines);
+        let (mut added, mut removed) = (0, 0);
+        for line in lines {
+            match line.as_bytes().first() {
+                Some(b'+') => added += 1,
+                Some(b'-') => removed += 1,
+                _ => {}
+            }
+        }
+        (added, removed)
+    }
+
+    fn language(&self) -> Language {
+        Path::new(&self.path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map_or(Language::Unknown, Language::from_extension)
+    }
+
+    fn declared_symbol(&self, line: &str) -> Option<String> {
+        if self.path.ends_with(".md") || self.path.ends_with(".markdown") {
+            return markdown_heading(line);
+        }
+        declared_symbol(self.language(), line)
+    }
+
+    fn is_lock_file(&self) -> bool {
+        let name = self.path.rsplit('/').next().unwrap_or(&self.path);
+        LOCK_FILES.contains(&name)
+    }
+
+    fn is_generated(&self) -> bool {
+        if GENERATED_SUFFIXES.iter().any(|s| self.path.ends_with(s))
+            || self
+                .path
+                .split('/')
+                .any(|c| c == "__generated__" || c == "generated")
+        {
+            return true;
+        }
+        // Generators stamp a header comment in the first lines of the file
+        self.hunks
+            .first()
+            .is_some_and(|h| h.header.starts_with("@@ -0,0 +1") || h.header.starts_with("@@ -1,"))
+            && self.hunks[0]
+                .lines
+                .iter()
+                .take(5)
+                .any(|l| GENERATED_MARKERS.iter().any(|m| l.contains(m)))
+    }
+
+    fn pure_rename(&self) -> bool {
+        self.old_path.is_some() && self.hunks.is_empty() && !self.binary
+    }
+
+    fn display_path(&self) -> String {
+        match &self.old_path {
+            Some(old) => format!("{} → {}", old, self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Split `git diff` output into files and hunks.
+pub(crate) fn parse(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut in_hunk = false;
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            in_hunk = false;
+            let path = rest.split(" b/").last().unwrap_or(rest).to_string();
+            files.push(FileDiff {
+                path,
+                ..FileDiff::default()
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            in_hunk = true;
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+        } else if in_hunk {
+            if line.starts_with(['+', '-', ' ']) || line.is_empty() {
+                if let Some(hunk) = file.hunks.last_mut() {
+                    // Some tools strip the space of empty context lines
+                    hunk.lines.push(if line.is_empty() {
+                        " ".to_string()
+                    } else {
+                        line.to_string()
+                    });
+                }
+            }
+        } else if let Some(old) = line.strip_prefix("rename from ") {
+            file.old_path = Some(old.to_string());
+        } else if let Some(new) = line.strip_prefix("rename to ") {
+            file.path = new.to_string();
+        } else if let Some(pct) = line.strip_prefix("similarity index ") {
+            file.similarity = pct.trim_end_matches('%').parse().ok();
+        } else if line.starts_with("new file mode") {
+            file.new_file = true;
+        } else if line.starts_with("deleted file mode") {
+            file.deleted = true;
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        }
+    }
+    files
+}
+
+/// The symbol a source line declares ("fn run", "impl Foo", "class Bar").
+fn declared_symbol(lang: Language, line: &str) -> Option<String> {
+    let kind_name = |re: &Regex| re.captures(line).map(|c| format!("{} {}", &c[1], &c[2]));
+    match lang {
+        Language::Rust => kind_name(&RUST_ITEM).or_else(|| {
+            RUST_IMPL.captures(line).map(|c| {
+                let target = c[1].split_whitespace().collect::<Vec<_>>().join(" ");
+                format!("impl {}", target)
+            })
+        }),
+        Language::Python => kind_name(&PY_DEF),
+        Language::JavaScript | Language::TypeScript => JS_FUNCTION
+            .captures(line)
+            .map(|c| format!("function {}", &c[1]))
+            .or_else(|| kind_name(&JS_TYPE))
+            .or_else(|| {
+                JS_CONST_FN
+                    .captures(line)
+                    .map(|c| format!("const {}", &c[1]))
+            })
+            .or_else(|| {
+                JS_METHOD
+                    .captures(line)
+                    .filter(|c| !NOT_FUNCTIONS.contains(&&c[1]))
+                    .map(|c| format!("{}()", &c[1]))
+            }),
+        Language::Go => GO_FUNC
+            .captures(line)
+            .map(|c| match c.get(1) {
+                Some(recv) => format!("func {}.{}", recv.as_str(), &c[2]),
+                None => format!("func {}", &c[2]),
+            })
+            .or_else(|| GO_TYPE.captures(line).map(|c| format!("type {}", &c[1]))),
+        Language::C | Language::Cpp | Language::Java => kind_name(&C_TYPE).or_else(|| {
+            C_FUNCTION
+                .captures(line)
+                .filter(|c| !NOT_FUNCTIONS.contains(&&c[1]))
+                .map(|c| format!("{}()", &c[1]))
+        }),
+        Language::Ruby => kind_name(&RUBY_DEF),
+        Language::Shell => SHELL_FUNCTION.captures(line).map(|c| {
+            let name = c.get(1).or(c.get(2)).map_or("", |m| m.as_str());
+            format!("{}()", name)
+        }),
+        Language::Unknown => None,
+    }
+}
+
+/// Markdown section a line opens ("## Install").
+fn markdown_heading(line: &str) -> Option<String> {
+    let title = line.trim_start_matches('#');
+    if title.len() == line.len() || !title.starts_with(' ') {
+        return None;
+    }
+    let level = &line[..line.len() - title.len()];
+    Some(format!(
+        "{} {}",
+        level,
+        truncate(title.trim(), MAX_SYMBOL_WIDTH)
+    ))
+}
+
+/// Symbol from git's funcname context after the second `@@`. Without a
+/// diff driver git picks any unindented line, so only declarations count.
+fn header_symbol(file: &FileDiff, header: &str) -> Option<String> {
+    let context = header.splitn(3, "@@").nth(2)?.trim();
+    file.declared_symbol(context)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max - 3).collect::<String>())
+    }
+}
+
+/// Enclosing symbol of every line of every hunk: the header's, then the
+/// latest declaration seen inside the hunk.
+fn line_symbols(file: &FileDiff) -> Vec<Vec<String>> {
+    file.hunks
+        .iter()
+        .map(|hunk| {
+            let mut current =
+                header_symbol(file, &hunk.header).unwrap_or_else(|| TOP_LEVEL.to_string());
+            hunk.lines
+                .iter()
+                .map(|line| {
+                    if let Some(symbol) = file.declared_symbol(&line[1..]) {
+                        current = symbol;
+                    }
+                    current.clone()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Line address: (file, hunk, line)
+type LineRef = (usize, usize, usize);
+
+/// A block removed in one place and added verbatim in another.
+#[derive(Debug, PartialEq)]
+struct Move {
+    lines: usize,
+    from: String,
+    to: String,
+}
+
+/// Contiguous `+` or `-` lines of one hunk, blank lines left out of `text`.
+struct Run {
+    file: usize,
+    hunk: usize,
+    /// (line index, trimmed text) of the non-blank lines
+    text: Vec<(usize, String)>,
+    /// Every line index, blank ones included
+    span: (usize, usize),
+}
+
+fn runs(files: &[FileDiff], prefix: char) -> Vec<Run> {
+    let mut runs = Vec::new();
+    for (f, file) in files.iter().enumerate() {
+        for (h, hunk) in file.hunks.iter().enumerate() {
+            let mut current: Option<Run> = None;
+            for (i, line) in hunk.lines.iter().enumerate() {
+                if line.starts_with(prefix) {
+                    let run = current.get_or_insert(Run {
+                        file: f,
+                        hunk: h,
+                        text: Vec::new(),
+                        span: (i, i),
+                    });
+                    run.span.1 = i;
+                    let trimmed = line[1..].trim();
+                    if !trimmed.is_empty() {
+                        run.text.push((i, trimmed.to_string()));
+                    }
+                } else if let Some(run) = current.take() {
+                    runs.push(run);
+                }
+            }
+            runs.extend(current);
+        }
+    }
+    runs
+}
+
+/// Find blocks removed in one place and re-added elsewhere; returns the
+/// moved lines (both sides) and one entry per move.
+fn detect_moves(files: &[FileDiff], symbols: &[Vec<Vec<String>>]) -> (HashSet<LineRef>, Vec<Move>) {
+    let mut moved = HashSet::new();
+    let mut moves = Vec::new();
+
+    let changed: usize = files.iter().map(|f| f.counts()).map(|(a, r)| a + r).sum();
+    if changed > MAX_MOVE_SCAN_LINES {
+        return (moved, moves);
+    }
+
+    let label = |f: usize, h: usize, i: usize| format!("{}::{}", files[f].path, symbols[f][h][i]);
+    let added = runs(files, '+');
+    for removed in runs(files, '-') {
+        let needle: Vec<&str> = removed.text.iter().map(|(_, t)| t.as_str()).collect();
+        if needle.len() < MIN_MOVED_LINES {
+            continue;
+        }
+        for run in &added {
+            let hay: Vec<&str> = run.text.iter().map(|(_, t)| t.as_str()).collect();
+            let Some(pos) = hay
+                .windows(needle.len())
+                .position(|w| w == needle.as_slice())
+            else {
+                continue;
+            };
+            let (first, last) = (run.text[pos].0, run.text[pos + needle.len() - 1].0);
+            if (first..=last).any(|i| moved.contains(&(run.file, run.hunk, i))) {
+                continue;
+            }
+            moved.extend((first..=last).map(|i| (run.file, run.hunk, i)));
+            moved
+                .extend((removed.span.0..=removed.span.1).map(|i| (removed.file, removed.hunk, i)));
+            moves.push(Move {
+                lines: needle.len(),
+                from: label(removed.file, removed.hunk, removed.text[0].0),
+                to: label(run.file, run.hunk, first),
+            });
+            break;
+        }
+    }
+    (moved, moves)
+}
+
+/// Changes to one symbol of one file.
+#[derive(Debug, Default)]
+struct Group {
+    symbol: String,
+    added: usize,
+    removed: usize,
+    hunks: Vec<usize>,
+    /// Changed text with all whitespace removed, to spot reformatting
+    squashed_added: String,
+    squashed_removed: String,
+}
+
+impl Group {
+    fn whitespace_only(&self) -> bool {
+        self.added + self.removed > 0 && self.squashed_added == self.squashed_removed
+    }
+}
+
+fn squash(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars().filter(|c| !c.is_whitespace())
+}
+
+fn groups(
+    file_idx: usize,
+    file: &FileDiff,
+    symbols: &[Vec<String>],
+    moved: &HashSet<LineRef>,
+) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (h, hunk) in file.hunks.iter().enumerate() {
+        for (i, line) in hunk.lines.iter().enumerate() {
+            let is_added = line.starts_with('+');
+            if !(is_added || line.starts_with('-')) || moved.contains(&(file_idx, h, i)) {
+                continue;
+            }
+            let symbol = &symbols[h][i];
+            let group = match groups.iter().position(|g| &g.symbol == symbol) {
+                Some(pos) => &mut groups[pos],
+                None => {
+                    groups.push(Group {
+                        symbol: symbol.clone(),
+                        ..Group::default()
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+            if group.hunks.last() != Some(&h) {
+                group.hunks.push(h);
+            }
+            if is_added {
+                group.added += 1;
+                group.squashed_added.extend(squash(&line[1..]));
+            } else {
+                group.removed += 1;
+                group.squashed_removed.extend(squash(&line[1..]));
+            }
+        }
+    }
+    groups
+}
+
+fn counts(added: usize, removed: usize) -> String {
+    format!("+{} -{}", added, removed)
+}
+
+/// The grouped overview printed by `rtk git diff --semantic`.
+pub(crate) fn summarize(diff: &str) -> String {
+    let files = parse(diff);
+    if files.is_empty() {
+        return String::new();
+    }
+    let symbols: Vec<_> = files.iter().map(line_symbols).collect();
+    let (moved, moves) = detect_moves(&files, &symbols);
+
+    let mut out: Vec<String> = Vec::new();
+    let mut total_groups = 0;
+    let mut example: Option<String> = None;
+
+    for (f, file) in files.iter().enumerate() {
+        let (added, removed) = file.counts();
+        let path = file.display_path();
+
+        if file.binary {
+            out.push(format!("📄 {}  binary", path));
+            continue;
+        }
+        if file.pure_rename() {
+            out.push(format!("📄 {}  renamed", path));
+            continue;
+        }
+        if file.is_lock_file() {
+            out.push(format!("📄 {}  lock file {}", path, counts(added, removed)));
+            continue;
+        }
+        if file.is_generated() {
+            out.push(format!("📄 {}  generated {}", path, counts(added, removed)));
+            continue;
+        }
+
+        let groups = groups(f, file, &symbols[f], &moved);
+        let mut label = counts(added, removed);
+        if file.new_file {
+            label = format!("new file {}", label);
+        } else if file.deleted {
+            label = format!("deleted {}", label);
+        } else if file.old_path.is_some() {
+            label = format!("renamed {}", label);
+        }
+        let has_moves = moved.iter().any(|&(mf, _, _)| mf == f);
+        if groups.iter().all(Group::whitespace_only) {
+            // Blank lines left around a moved block don't count as changes
+            if has_moves {
+                out.push(format!("📄 {}  {} (moves only)", path, label));
+                continue;
+            }
+            if !groups.is_empty() {
+                out.push(format!("📄 {}  whitespace only {}", path, label));
+                continue;
+            }
+        }
+        out.push(format!("📄 {}  {}", path, label));
+        if file.deleted {
+            continue;
+        }
+
+        let width = groups
+            .iter()
+            .map(|g| g.symbol.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_SYMBOL_WIDTH);
+        for group in groups.iter().take(MAX_GROUPS_PER_FILE) {
+            let mut line = format!(
+                "  {:<width$}  {}",
+                truncate(&group.symbol, MAX_SYMBOL_WIDTH),
+                counts(group.added, group.removed),
+                width = width
+            );
+            if group.whitespace_only() {
+                line.push_str(" (whitespace only)");
+            } else if example.is_none()
+                && group.symbol != TOP_LEVEL
+                && !symbol_name(&group.symbol).contains(char::is_whitespace)
+            {
+                example = Some(format!("{}::{}", file.path, symbol_name(&group.symbol)));
+            }
+            out.push(line);
+        }
+        if groups.len() > MAX_GROUPS_PER_FILE {
+            out.push(format!(
+                "  ... +{} more groups",
+                groups.len() - MAX_GROUPS_PER_FILE
+            ));
+        }
+        total_groups += groups.len();
+    }
+
+    for m in &moves {
+        out.push(format!("↔ moved {} lines: {} → {}", m.lines, m.from, m.to));
+    }
+
+    out.push(String::new());
+    out.push(format!(
+        "{} files, {} groups. Expand one: rtk git diff --semantic --only {}",
+        files.len(),
+        total_groups,
+        example.as_deref().unwrap_or("<path>::<symbol>")
+    ));
+    out.join("\n")
+}
+
+/// Bare name of a group symbol: "fn run" → "run", "impl Foo" → "Foo".
+fn symbol_name(symbol: &str) -> &str {
+    if symbol == TOP_LEVEL {
+        return "top";
+    }
+    symbol
+        .split_once(' ')
+        .map_or(symbol, |(_, name)| name)
+        .trim_end_matches("()")
+}
+
+fn symbol_matches(symbol: &str, wanted: &str) -> bool {
+    symbol == wanted || symbol_name(symbol) == wanted
+}
+
+fn path_matches(path: &str, wanted: &str) -> bool {
+    path == wanted || path.ends_with(&format!("/{}", wanted))
+}
+
+/// Full hunks for `--only path` or `--only path::symbol`.
+pub(crate) fn expand(diff: &str, spec: &str) -> anyhow::Result<String> {
+    let files = parse(diff);
+
+    // The path may itself contain "::" (rare), so try every split point
+    let mut targets: Vec<(usize, Option<&str>)> = Vec::new();
+    for (f, file) in files.iter().enumerate() {
+        if path_matches(&file.path, spec) {
+            targets.push((f, None));
+            continue;
+        }
+        let split = spec
+            .match_indices("::")
+            .map(|(i, _)| (&spec[..i], &spec[i + 2..]))
+            .find(|(path, _)| path_matches(&file.path, path));
+        if let Some((_, symbol)) = split {
+            targets.push((f, Some(symbol)));
+        }
+    }
+    if targets.is_empty() {
+        anyhow::bail!("No changed file matches '{}'", spec);
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    for (f, symbol) in targets {
+        let file = &files[f];
+        let hunks: Vec<usize> = match symbol {
+            None => (0..file.hunks.len()).collect(),
+            Some(wanted) => {
+                let symbols = line_symbols(file);
+                let groups = groups(f, file, &symbols, &HashSet::new());
+                let Some(group) = groups.iter().find(|g| symbol_matches(&g.symbol, wanted)) else {
+                    let mut known: Vec<&str> =
+                        groups.iter().map(|g| symbol_name(&g.symbol)).collect();
+                    known.dedup();
+                    anyhow::bail!(
+                        "No change group '{}' in {} (groups: {})",
+                        wanted,
+                        file.path,
+                        known.join(", ")
+                    );
+                };
+                group.hunks.clone()
+            }
+        };
+
+        out.push(match symbol {
+            Some(wanted) => format!("📄 {} :: {}", file.display_path(), wanted),
+            None => format!("📄 {}", file.display_path()),
+        });
+        for h in hunks {
+            out.push(file.hunks[h].header.clone());
+            out.extend(file.hunks[h].lines.iter().cloned());
+        }
+    }
+    Ok(out.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUST_DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,6 +10,7 @@ impl Parser {
+     fn parse(&self) -> usize {
+-        self.count
++        self.count + 1
+     }
+
+     fn reset(&mut self) {
++        self.count = 0;
+@@ -40,3 +41,4 @@ fn helper() {
+     let x = 1;
++    let y = 2;
+ }
+";
+
+    #[test]
+    fn test_groups_by_enclosing_symbol() {
+        let summary = summarize(RUST_DIFF);
+        assert!(summary.contains("📄 src/lib.rs  +3 -1"), "{}", summary);
+        assert!(summary.contains("  fn parse   +1 -1"), "{}", summary);
+        assert!(summary.contains("  fn reset   +1 -0"), "{}", summary);
+        assert!(summary.contains("  fn helper  +1 -0"), "{}", summary);
+        assert!(summary.contains("--only src/lib.rs::parse"));
+    }
+
+    #[test]
+    fn test_declared_symbols_per_language() {
+        let cases = [
+            (Language::Rust, "pub(crate) async fn run(x: u8) {", "fn run"),
+            (
+                Language::Rust,
+                "impl<T: Debug> Display for Wrapper<T> {",
+                "impl Display for Wrapper<T>",
+            ),
+            (Language::Python, "    async def fetch(self):", "def fetch"),
+            (
+                Language::TypeScript,
+                "export const load = async (id: string) => {",
+                "const load",
+            ),
+            (Language::TypeScript, "  render(): void {", "render()"),
+            (
+                Language::Go,
+                "func (s *Server) Start() error {",
+                "func Server.Start",
+            ),
+            (Language::Java, "public class Main {", "class Main"),
+            (
+                Language::C,
+                "static int parse_args(int argc, char **argv)",
+                "parse_args()",
+            ),
+            (Language::Ruby, "  def valid?", "def valid?"),
+            (Language::Shell, "cleanup() {", "cleanup()"),
+        ];
+        for (lang, line, expected) in cases {
+            assert_eq!(
+                declared_symbol(lang, line).as_deref(),
+                Some(expected),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(declared_symbol(Language::C, "    if (x) {"), None);
+        assert_eq!(declared_symbol(Language::Unknown, "fn run() {"), None);
+        assert_eq!(
+            markdown_heading("## Install").as_deref(),
+            Some("## Install")
+        );
+        assert_eq!(markdown_heading("#include <stdio.h>"), None);
+    }
+
+    #[test]
+    fn test_one_liners() {
+        let diff = "diff --git a/old.rs b/new.rs
+similarity index 100%
+rename from old.rs
+rename to new.rs
+diff --git a/Cargo.lock b/Cargo.lock
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -1,3 +1,3 @@
+-version = \"1.0.0\"
++version = \"1.0.1\"
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+diff --git a/fmt.py b/fmt.py
+--- a/fmt.py
++++ b/fmt.py
+@@ -1,2 +1,2 @@ def main():
+-x=foo( 1,2 )
++x = foo(1, 2)
+diff --git a/api.pb.go b/api.pb.go
+--- a/api.pb.go
++++ b/api.pb.go
+@@ -5,1 +5,1 @@
+-var a = 1
++var a = 2
+";
+        let summary = summarize(diff);
+        assert!(
+            summary.contains("📄 old.rs → new.rs  renamed\n"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("📄 Cargo.lock  lock file +1 -1"));
+        assert!(summary.contains("📄 logo.png  binary"));
+        assert!(summary.contains("📄 fmt.py  whitespace only +1 -1"));
+        assert!(summary.contains("📄 api.pb.go  generated +1 -1"));
+    }
+
+    #[test]
+    fn test_moved_block() {
+        let diff = "diff --git a/a.py b/a.py
+--- a/a.py
++++ b/a.py
+@@ -1,6 +1,1 @@
+ import os
+-def helper(x):
+-    y = x * 2
+-    z = y + 1
+-    return z
+-
+diff --git a/b.py b/b.py
+--- a/b.py
++++ b/b.py
+@@ -1,1 +1,6 @@
+ import sys
++
++def helper(x):
++    y = x * 2
++    z = y + 1
++    return z
+";
+        let summary = summarize(diff);
+        assert!(
+            summary.contains("↔ moved 4 lines: a.py::def helper → b.py::def helper"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("📄 a.py  +0 -5 (moves only)"),
+            "{}",
+            summary
+        );
+        // The blank line added before the block doesn't make b.py a change
+        assert!(
+            summary.contains("📄 b.py  +5 -0 (moves only)"),
+            "{}",
+            summary
+        );
+        assert!(summary.ends_with(
+            "2 files, 0 groups. Expand one: rtk git diff --semantic --only <path>::<symbol>"
+        ));
+    }
+
+    #[test]
+    fn test_expand_only_group() {
+        let out = expand(RUST_DIFF, "lib.rs::reset").unwrap();
+        assert!(out.starts_with("📄 src/lib.rs :: reset\n@@ -10,6 +10,7 @@ impl Parser {"));
+        assert!(out.contains("+        self.count = 0;"));
+        assert!(!out.contains("fn helper"));
+
+        let whole = expand(RUST_DIFF, "src/lib.rs").unwrap();
+        assert_eq!(whole.lines().filter(|l| l.starts_with("@@")).count(), 2);
+
+        let err = expand(RUST_DIFF, "src/lib.rs::missing").unwrap_err();
+        assert!(err.to_string().contains("groups: parse, reset, helper"));
+        assert!(expand(RUST_DIFF, "other.rs").is_err());
+    }
+}
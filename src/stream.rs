@@ -0,0 +1,360 @@
+//! Streaming executor for long-running commands.
+//!
+//! Reads the child's stdout/stderr line by line while it runs, passes each line
+//! to an incremental filter that can print it right away (failures, compile
+//! errors), and buffers everything so the usual end-of-run filter still builds
+//! the compact summary. The child runs in its own process group; SIGINT,
+//! SIGTERM, SIGHUP and job control (SIGTSTP/SIGCONT) are forwarded to it, so
+//! an interrupted run still ends with a summary of what completed.
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+
+/// Exit code reported when the child was stopped by SIGINT.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Stdout,
+    Stderr,
+}
+
+/// Everything a streamed run produced.
+#[derive(Debug, Default)]
+pub struct StreamedOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+    /// The run was cut short by SIGINT
+    pub interrupted: bool,
+    /// Lines already printed by the live filter
+    pub live: Vec<String>,
+}
+
+impl StreamedOutput {
+    /// Drop summary lines that were already printed live.
+    pub fn dedupe(&self, summary: &str) -> String {
+        if self.live.is_empty() {
+            return summary.to_string();
+        }
+        summary
+            .lines()
+            .filter(|line| !self.live.iter().any(|live| live == line.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Everything rtk printed for this run: live lines, then the summary.
+    pub fn printed(&self, summary: &str) -> String {
+        if self.live.is_empty() {
+            summary.to_string()
+        } else {
+            format!("{}\n{}", self.live.join("\n"), summary)
+        }
+    }
+}
+
+/// Run `cmd`, calling `live` on every output line as it arrives. Lines for
+/// which `live` returns text are printed immediately.
+pub fn run_streaming<F>(cmd: &mut Command, mut live: F) -> Result<StreamedOutput>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Own process group: terminal signals reach only rtk, which forwards each once
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(cmd, 0);
+
+    // Handlers go in before the spawn so no signal can kill rtk and orphan the child
+    let signals = signals::install();
+    let mut child = cmd.spawn().context("Failed to spawn command")?;
+    signals.publish(child.id());
+
+    let (tx, rx) = mpsc::channel();
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|s| spawn_reader(s, Source::Stdout, tx.clone())),
+        child
+            .stderr
+            .take()
+            .map(|s| spawn_reader(s, Source::Stderr, tx.clone())),
+    ];
+    drop(tx);
+
+    let mut out = StreamedOutput::default();
+    for (source, line) in rx {
+        if let Some(text) = live(&line) {
+            println!("{}", text);
+            out.live.push(text);
+        }
+        let buf = match source {
+            Source::Stdout => &mut out.stdout,
+            Source::Stderr => &mut out.stderr,
+        };
+        buf.push_str(&line);
+        buf.push('\n');
+    }
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+
+    let status = child.wait().context("Failed to wait for command")?;
+    out.interrupted = signals::interrupted();
+    if out.interrupted {
+        eprintln!("rtk: interrupted, summarizing partial output");
+    }
+    out.exit_code = match status.code() {
+        Some(code) => code,
+        None if out.interrupted => INTERRUPTED_EXIT_CODE,
+        None => crate::exec::exit_code(&status),
+    };
+
+    Ok(out)
+}
+
+fn spawn_reader<R: Read + Send + 'static>(
+    stream: R,
+    source: Source,
+    tx: mpsc::Sender<(Source, String)>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if tx.send((source, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, Ordering};
+
+    /// Signals relayed to the child's process group.
+    const FORWARDED: [libc::c_int; 5] = [
+        libc::SIGINT,
+        libc::SIGTERM,
+        libc::SIGHUP,
+        libc::SIGTSTP,
+        libc::SIGCONT,
+    ];
+
+    /// Signals a forwarded run must not swallow if the child never started.
+    const TERMINATING: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+
+    static CHILD_PGID: AtomicI32 = AtomicI32::new(0);
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+    /// Signals received before the child's pid was published, one bit per `FORWARDED` entry
+    static PENDING: AtomicU32 = AtomicU32::new(0);
+
+    fn bit(signal: libc::c_int) -> u32 {
+        FORWARDED
+            .iter()
+            .position(|s| *s == signal)
+            .map_or(0, |i| 1 << i)
+    }
+
+    /// Send the pending signals to the child's group. Whoever swaps a bit out
+    /// (handler or `publish`) forwards it, so each signal goes out exactly once.
+    fn flush_pending(pgid: libc::pid_t) {
+        let pending = PENDING.swap(0, Ordering::SeqCst);
+        for signal in FORWARDED.iter().filter(|s| pending & bit(**s) != 0) {
+            unsafe {
+                libc::kill(-pgid, *signal);
+            }
+        }
+    }
+
+    extern "C" fn forward(signal: libc::c_int) {
+        // Only async-signal-safe calls here
+        if signal == libc::SIGINT {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+        }
+        let pgid = CHILD_PGID.load(Ordering::SeqCst);
+        if pgid > 0 {
+            unsafe {
+                libc::kill(-pgid, signal);
+            }
+        } else {
+            PENDING.fetch_or(bit(signal), Ordering::SeqCst);
+            // The pid may have been published between the load and the store
+            let pgid = CHILD_PGID.load(Ordering::SeqCst);
+            if pgid > 0 {
+                flush_pending(pgid);
+            }
+        }
+        if signal == libc::SIGTSTP {
+            // Suspend rtk too, as the default action would; SIGCONT resumes both
+            unsafe {
+                libc::kill(libc::getpid(), libc::SIGSTOP);
+            }
+        }
+    }
+
+    /// Restores the previous signal dispositions on drop.
+    pub struct Guard {
+        previous: [libc::sighandler_t; FORWARDED.len()],
+    }
+
+    /// Catch the forwarded signals until the guard drops. Signals that arrive
+    /// before `publish` are held and sent to the child once it exists.
+    pub fn install() -> Guard {
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        CHILD_PGID.store(0, Ordering::SeqCst);
+        PENDING.store(0, Ordering::SeqCst);
+        let handler: extern "C" fn(libc::c_int) = forward;
+        let previous =
+            FORWARDED.map(|signal| unsafe { libc::signal(signal, handler as libc::sighandler_t) });
+        Guard { previous }
+    }
+
+    impl Guard {
+        /// Start forwarding to the child's process group, including anything pending.
+        pub fn publish(&self, pid: u32) {
+            let pgid = pid as libc::pid_t;
+            CHILD_PGID.store(pgid, Ordering::SeqCst);
+            flush_pending(pgid);
+        }
+    }
+
+    pub fn interrupted() -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            for (signal, previous) in FORWARDED.iter().zip(self.previous) {
+                unsafe {
+                    libc::signal(*signal, previous);
+                }
+            }
+            CHILD_PGID.store(0, Ordering::SeqCst);
+            // The child never started: act on a held SIGINT/SIGTERM/SIGHUP ourselves
+            let pending = PENDING.swap(0, Ordering::SeqCst);
+            for signal in TERMINATING.iter().filter(|s| pending & bit(**s) != 0) {
+                unsafe {
+                    libc::raise(*signal);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod signals {
+    // The console delivers Ctrl-C to the whole process group already
+    pub struct Guard;
+
+    pub fn install() -> Guard {
+        Guard
+    }
+
+    impl Guard {
+        pub fn publish(&self, _pid: u32) {}
+    }
+
+    pub fn interrupted() -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Signal handlers are process-wide: one streamed run at a time
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_run_streaming_captures_and_filters() {
+        let _serial = SERIAL.lock().unwrap();
+        let mut cmd = Command::new("sh");
+        cmd.args([
+            "-c",
+            "echo ok one; echo 'FAIL two' >&2; echo ok three; exit 3",
+        ]);
+        let out = run_streaming(&mut cmd, |line| {
+            line.starts_with("FAIL").then(|| line.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(out.stdout, "ok one\nok three\n");
+        assert_eq!(out.stderr, "FAIL two\n");
+        assert_eq!(out.live, vec!["FAIL two"]);
+        assert_eq!(out.exit_code, 3);
+        assert_eq!(out.printed("summary"), "FAIL two\nsummary");
+        assert_eq!(out.dedupe("2 failed\n  FAIL two\ndone"), "2 failed\ndone");
+    }
+
+    #[test]
+    fn test_run_streaming_forwards_sigterm() {
+        let _serial = SERIAL.lock().unwrap();
+        // rtk itself gets SIGTERM; the child (in its own group) must see it too
+        let mut cmd = Command::new("sh");
+        cmd.args([
+            "-c",
+            "trap 'echo got TERM; exit 7' TERM; kill -TERM $PPID; sleep 5 & wait",
+        ]);
+        let out = run_streaming(&mut cmd, |_| None).unwrap();
+        assert_eq!(out.stdout, "got TERM\n");
+        assert_eq!(out.exit_code, 7);
+    }
+
+    #[test]
+    fn test_signal_before_publish_reaches_child() {
+        let _serial = SERIAL.lock().unwrap();
+        let guard = signals::install();
+        // Arrives while the child is being spawned: held, not fatal to rtk
+        unsafe {
+            libc::raise(libc::SIGTERM);
+        }
+
+        let mut cmd = Command::new("sh");
+        cmd.args([
+            "-c",
+            "trap 'echo got TERM; exit 7' TERM; echo ready; sleep 5 & wait",
+        ])
+        .stdout(Stdio::piped());
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        let mut child = cmd.spawn().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        assert_eq!(line, "ready\n");
+
+        guard.publish(child.id());
+        line.clear();
+        stdout.read_line(&mut line).unwrap();
+        assert_eq!(line, "got TERM\n");
+        assert_eq!(child.wait().unwrap().code(), Some(7));
+        drop(guard);
+    }
+
+    #[test]
+    fn test_run_streaming_signal_exit_code() {
+        let _serial = SERIAL.lock().unwrap();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "kill -TERM $$"]);
+        let out = run_streaming(&mut cmd, |_| None).unwrap();
+        assert_eq!(out.exit_code, 128 + 15);
+        assert!(!out.interrupted);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -5,7 +6,7 @@ use regex::Regex;
 use std::process::{Command, Stdio};
 
 /// Run a command and provide a heuristic summary
-pub fn run(command: &str, verbose: u8) -> Result<()> {
+pub fn run(command: &str, verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -33,8 +34,8 @@ pub fn run(command: &str, verbose: u8) -> Result<()> {
 
     let summary = summarize_output(&raw, command, output.status.success());
     println!("{}", summary);
-    timer.track(command, "rtk summary", &raw, &summary);
-    Ok(())
+    crate::preserve::track(&timer, command, "rtk summary", &raw, &summary);
+    Ok(ExecResult::from_status(&output.status))
 }
 
 fn summarize_output(output: &str, command: &str, success: bool) -> String {
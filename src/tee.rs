@@ -1,5 +1,10 @@
 use crate::config::Config;
-use std::path::PathBuf;
+use anyhow::{bail, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 
 /// Minimum output size to tee (smaller outputs don't need recovery)
 const MIN_TEE_SIZE: usize = 500;
@@ -47,26 +52,131 @@ fn get_tee_dir(config: &Config) -> Option<PathBuf> {
     dirs::data_local_dir().map(|d| d.join("rtk").join("tee"))
 }
 
-/// Rotate old tee files: keep only the last `max_files`, delete oldest.
-fn cleanup_old_files(dir: &std::path::Path, max_files: usize) {
-    let mut entries: Vec<_> = std::fs::read_dir(dir)
+/// Tee directory from the effective config (used by readers of saved output).
+pub fn tee_dir() -> Option<PathBuf> {
+    let config = Config::load().ok()?;
+    get_tee_dir(&config)
+}
+
+/// Metadata for one saved output, stored next to it as `<id>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeeCapture {
+    /// Short id used by `rtk tee show <id>`
+    pub id: String,
+    /// Command slug the output came from (e.g. "cargo_test")
+    pub command: String,
+    /// Exit code of the command (None for captures from older rtk versions)
+    pub exit_code: Option<i32>,
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    /// Size of the saved file in bytes
+    pub bytes: u64,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl TeeCapture {
+    /// Read metadata for a `.log` file; older epoch-prefixed files get it from their name.
+    fn from_log(path: &Path) -> Option<Self> {
+        let stem = path.file_stem()?.to_string_lossy().to_string();
+        let bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+
+        if let Some(mut capture) = std::fs::read_to_string(path.with_extension("json"))
+            .ok()
+            .and_then(|json| serde_json::from_str::<TeeCapture>(&json).ok())
+        {
+            capture.bytes = bytes;
+            capture.path = path.to_path_buf();
+            return Some(capture);
+        }
+
+        let (epoch, command) = stem.split_once('_').unwrap_or(("", stem.as_str()));
+        Some(TeeCapture {
+            id: stem.clone(),
+            command: command.to_string(),
+            exit_code: None,
+            timestamp: epoch.parse().unwrap_or(0),
+            bytes,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+/// All captures in `dir`, newest first.
+pub fn list_captures(dir: &Path) -> Vec<TeeCapture> {
+    let mut captures: Vec<(std::time::SystemTime, TeeCapture)> = log_files(dir)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, TeeCapture::from_log(&path)?))
+        })
+        .collect();
+    captures.sort_by(|(a_time, a), (b_time, b)| {
+        b_time
+            .cmp(a_time)
+            .then_with(|| b.path.file_name().cmp(&a.path.file_name()))
+    });
+    captures.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Resolve a capture by exact id or unique id prefix.
+pub fn find_capture(dir: &Path, id: &str) -> Result<TeeCapture> {
+    let captures = list_captures(dir);
+    if let Some(exact) = captures.iter().find(|c| c.id == id) {
+        return Ok(exact.clone());
+    }
+    let matches: Vec<&TeeCapture> = captures.iter().filter(|c| c.id.starts_with(id)).collect();
+    match matches.as_slice() {
+        [only] => Ok((*only).clone()),
+        [] => bail!("No tee capture with id '{}' (see: rtk tee list)", id),
+        _ => bail!(
+            "Ambiguous tee id '{}': {}",
+            id,
+            matches
+                .iter()
+                .map(|c| c.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
         .ok()
         .into_iter()
         .flatten()
         .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
-        .collect();
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+        .collect()
+}
 
-    if entries.len() <= max_files {
-        return;
+/// Short id (6 hex chars) not yet used in `dir`.
+fn new_capture_id(dir: &Path, slug: &str, raw: &str) -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let mut attempt = 0u32;
+    loop {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (nanos, std::process::id(), slug, raw.len(), attempt).hash(&mut hasher);
+        let id = format!("{:06x}", hasher.finish() & 0xff_ffff);
+        if !dir.join(format!("{}.log", id)).exists() {
+            return id;
+        }
+        attempt += 1;
     }
+}
 
-    // Sort by filename (which starts with epoch timestamp = chronological)
-    entries.sort_by_key(|e| e.file_name());
-
-    let to_remove = entries.len() - max_files;
-    for entry in entries.iter().take(to_remove) {
-        let _ = std::fs::remove_file(entry.path());
+/// Rotate old tee files: keep only the last `max_files`, delete oldest.
+fn cleanup_old_files(dir: &Path, max_files: usize) {
+    let captures = list_captures(dir);
+    for capture in captures.iter().skip(max_files) {
+        let _ = std::fs::remove_file(&capture.path);
+        let _ = std::fs::remove_file(capture.path.with_extension("json"));
     }
 }
 
@@ -104,10 +214,11 @@ fn should_tee(
 fn write_tee_file(
     raw: &str,
     command_slug: &str,
-    tee_dir: &std::path::Path,
+    exit_code: i32,
+    tee_dir: &Path,
     max_file_size: usize,
     max_files: usize,
-) -> Option<PathBuf> {
+) -> Option<TeeCapture> {
     std::fs::create_dir_all(tee_dir).ok()?;
 
     let slug = sanitize_slug(command_slug);
@@ -115,8 +226,8 @@ fn write_tee_file(
         .duration_since(std::time::UNIX_EPOCH)
         .ok()?
         .as_secs();
-    let filename = format!("{}_{}.log", epoch, slug);
-    let filepath = tee_dir.join(filename);
+    let id = new_capture_id(tee_dir, &slug, raw);
+    let filepath = tee_dir.join(format!("{}.log", id));
 
     // Truncate at max_file_size
     let content = if raw.len() > max_file_size {
@@ -129,17 +240,29 @@ fn write_tee_file(
         raw.to_string()
     };
 
-    std::fs::write(&filepath, content).ok()?;
+    std::fs::write(&filepath, &content).ok()?;
+
+    let capture = TeeCapture {
+        id,
+        command: slug,
+        exit_code: Some(exit_code),
+        timestamp: epoch as i64,
+        bytes: content.len() as u64,
+        path: filepath,
+    };
+    if let Ok(json) = serde_json::to_string(&capture) {
+        let _ = std::fs::write(capture.path.with_extension("json"), json);
+    }
 
     // Rotate old files
     cleanup_old_files(tee_dir, max_files);
 
-    Some(filepath)
+    Some(capture)
 }
 
 /// Write raw output to tee file if conditions are met.
-/// Returns file path on success, None if skipped/failed.
-pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<PathBuf> {
+/// Returns the capture on success, None if skipped/failed.
+pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<TeeCapture> {
     // Check RTK_TEE=0 env override (disable)
     if std::env::var("RTK_TEE").ok().as_deref() == Some("0") {
         return None;
@@ -153,14 +276,15 @@ pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<PathBuf>
     write_tee_file(
         raw,
         command_slug,
+        exit_code,
         &tee_dir,
         config.tee.max_file_size,
         config.tee.max_files,
     )
 }
 
-/// Format the hint line with ~ shorthand for home directory.
-fn format_hint(path: &std::path::Path) -> String {
+/// Format the hint line with ~ shorthand for home directory and the retrieval command.
+fn format_hint(path: &Path, id: &str) -> String {
     let display = if let Some(home) = dirs::home_dir() {
         if let Ok(relative) = path.strip_prefix(&home) {
             format!("~/{}", relative.display())
@@ -171,14 +295,29 @@ fn format_hint(path: &std::path::Path) -> String {
         path.display().to_string()
     };
 
-    format!("[full output: {}]", display)
+    format!("[full output: {} | rtk tee show {}]", display, id)
+}
+
+lazy_static! {
+    static ref HINT_RE: Regex =
+        Regex::new(r"^\[full output: (.+?)(?: \| rtk tee show (\S+))?\]$").unwrap();
+}
+
+/// Parse a hint line from `format_hint` into (path, capture id).
+/// Legacy hints without the `rtk tee show` suffix have no id.
+pub fn parse_hint(line: &str) -> Option<(String, Option<String>)> {
+    let caps = HINT_RE.captures(line.trim())?;
+    Some((
+        caps[1].to_string(),
+        caps.get(2).map(|m| m.as_str().to_string()),
+    ))
 }
 
 /// Convenience: tee + format hint in one call.
 /// Returns hint string if file was written, None if skipped.
 pub fn tee_and_hint(raw: &str, command_slug: &str, exit_code: i32) -> Option<String> {
-    let path = tee_raw(raw, command_slug, exit_code)?;
-    Some(format_hint(&path))
+    let capture = tee_raw(raw, command_slug, exit_code)?;
+    Some(format_hint(&capture.path, &capture.id))
 }
 
 /// TeeMode controls when tee writes files.
@@ -289,15 +428,17 @@ mod tests {
         let result = write_tee_file(
             &content,
             "cargo_test",
+            1,
             tmpdir.path(),
             DEFAULT_MAX_FILE_SIZE,
             20,
         );
         assert!(result.is_some());
 
-        let path = result.unwrap();
-        assert!(path.exists());
-        let written = fs::read_to_string(&path).unwrap();
+        let capture = result.unwrap();
+        assert_eq!(capture.id.len(), 6);
+        assert!(capture.path.exists());
+        let written = fs::read_to_string(&capture.path).unwrap();
         assert!(written.contains("error: test failed"));
     }
 
@@ -306,11 +447,11 @@ mod tests {
         let tmpdir = tempfile::tempdir().unwrap();
         let big_output = "x".repeat(2000);
         // Set max_file_size to 1000 bytes
-        let result = write_tee_file(&big_output, "test", tmpdir.path(), 1000, 20);
+        let result = write_tee_file(&big_output, "test", 1, tmpdir.path(), 1000, 20);
         assert!(result.is_some());
 
-        let path = result.unwrap();
-        let content = fs::read_to_string(&path).unwrap();
+        let capture = result.unwrap();
+        let content = fs::read_to_string(&capture.path).unwrap();
         assert!(content.contains("--- truncated at 1000 bytes ---"));
         assert!(content.len() < 2000);
     }
@@ -345,11 +486,60 @@ mod tests {
 
     #[test]
     fn test_format_hint() {
-        let path = PathBuf::from("/tmp/rtk/tee/123_cargo_test.log");
-        let hint = format_hint(&path);
+        let path = PathBuf::from("/tmp/rtk/tee/a1b2c3.log");
+        let hint = format_hint(&path, "a1b2c3");
         assert!(hint.starts_with("[full output: "));
         assert!(hint.ends_with(']'));
-        assert!(hint.contains("123_cargo_test.log"));
+        assert!(hint.contains("a1b2c3.log"));
+        assert!(hint.contains("rtk tee show a1b2c3"));
+
+        let (parsed_path, id) = parse_hint(&hint).unwrap();
+        assert!(parsed_path.ends_with("a1b2c3.log"));
+        assert_eq!(id.as_deref(), Some("a1b2c3"));
+        assert_eq!(
+            parse_hint("[full output: ~/tee/1700000000_go.log]"),
+            Some(("~/tee/1700000000_go.log".to_string(), None))
+        );
+        assert!(parse_hint("ok").is_none());
+    }
+
+    #[test]
+    fn test_capture_metadata_and_lookup() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let dir = tmpdir.path();
+        // Capture written by an older rtk (no metadata sidecar)
+        fs::write(dir.join("1700000000_go_test.log"), "legacy").unwrap();
+        let capture = write_tee_file("boom", "cargo test", 101, dir, 1000, 20).unwrap();
+
+        let captures = list_captures(dir);
+        assert_eq!(captures.len(), 2);
+        let found = find_capture(dir, &capture.id[..4]).unwrap();
+        assert_eq!(found.command, "cargo_test");
+        assert_eq!(found.exit_code, Some(101));
+        assert_eq!(found.bytes, 4);
+
+        let legacy = find_capture(dir, "1700000000_go_test").unwrap();
+        assert_eq!(legacy.command, "go_test");
+        assert_eq!(legacy.timestamp, 1_700_000_000);
+        assert!(legacy.exit_code.is_none());
+
+        assert!(find_capture(dir, "zzz").is_err());
+    }
+
+    #[test]
+    fn test_cleanup_removes_metadata() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let dir = tmpdir.path();
+        for _ in 0..3 {
+            write_tee_file("out", "t", 1, dir, 1000, 2).unwrap();
+        }
+        assert_eq!(list_captures(dir).len(), 2);
+        let sidecars = fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            .count();
+        assert_eq!(sidecars, 2);
     }
 
     #[test]
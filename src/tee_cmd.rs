@@ -0,0 +1,287 @@
+//! `rtk tee list/show/last/grep`: retrieve slices of raw output saved by tee,
+//! so the agent doesn't have to `cat` a full capture back into context.
+
+use crate::tee::{self, TeeCapture};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::path::PathBuf;
+
+/// Matches printed per capture by `rtk tee grep`.
+const MAX_MATCHES_PER_CAPTURE: usize = 20;
+/// Matches printed across all captures by `rtk tee grep`.
+const MAX_MATCHES_TOTAL: usize = 100;
+
+fn capture_dir() -> Result<PathBuf> {
+    tee::tee_dir().context("Cannot determine tee directory")
+}
+
+/// List recent captures, newest first.
+pub fn run_list(limit: usize, verbose: u8) -> Result<()> {
+    let dir = capture_dir()?;
+    let captures = tee::list_captures(&dir);
+
+    if captures.is_empty() {
+        println!("No tee captures in {}", dir.display());
+        return Ok(());
+    }
+    if verbose > 0 {
+        eprintln!("Tee dir: {}", dir.display());
+    }
+
+    println!(
+        "{:<8} {:>8} {:>5} {:>7}  COMMAND",
+        "ID", "AGE", "EXIT", "SIZE"
+    );
+    for capture in captures.iter().take(limit) {
+        println!(
+            "{:<8} {:>8} {:>5} {:>7}  {}",
+            capture.id,
+            format_age(capture.timestamp),
+            capture
+                .exit_code
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            format_size(capture.bytes),
+            capture.command
+        );
+    }
+    if captures.len() > limit {
+        println!("... +{} more", captures.len() - limit);
+    }
+
+    Ok(())
+}
+
+/// Print a capture (or a slice of it) by id or id prefix.
+pub fn run_show(id: &str, lines: Option<&str>, grep: Option<&str>, verbose: u8) -> Result<()> {
+    let capture = tee::find_capture(&capture_dir()?, id)?;
+    print_capture(&capture, lines, grep, verbose)
+}
+
+/// Print the most recent capture.
+pub fn run_last(lines: Option<&str>, grep: Option<&str>, verbose: u8) -> Result<()> {
+    let dir = capture_dir()?;
+    let Some(capture) = tee::list_captures(&dir).into_iter().next() else {
+        bail!("No tee captures in {}", dir.display());
+    };
+    print_capture(&capture, lines, grep, verbose)
+}
+
+/// Search all captures (or one) for a regex, printing `id:line: text`.
+pub fn run_grep(pattern: &str, id: Option<&str>, verbose: u8) -> Result<()> {
+    let re = Regex::new(pattern).with_context(|| format!("Invalid regex '{}'", pattern))?;
+    let dir = capture_dir()?;
+    let captures = match id {
+        Some(id) => vec![tee::find_capture(&dir, id)?],
+        None => tee::list_captures(&dir),
+    };
+
+    let mut contents = Vec::with_capacity(captures.len());
+    for capture in &captures {
+        contents.push((capture.id.as_str(), read_capture(capture)?));
+    }
+    let (out, hits) = grep_captures(&re, &contents);
+
+    if verbose > 0 {
+        eprintln!("{} matches in {} captures", hits, captures.len());
+    }
+    if hits == 0 {
+        println!("No matches for '{}'", pattern);
+    } else {
+        print!("{}", out);
+    }
+    Ok(())
+}
+
+/// Render `id:line: text` matches, capped per capture and in total.
+/// Returns the rendered text and the total number of matches.
+fn grep_captures(re: &Regex, captures: &[(&str, String)]) -> (String, usize) {
+    let mut out = String::new();
+    let mut hits = 0;
+    let mut shown = 0;
+
+    for (id, content) in captures {
+        let matches: Vec<(usize, &str)> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| re.is_match(line))
+            .collect();
+        hits += matches.len();
+
+        let room = MAX_MATCHES_PER_CAPTURE.min(MAX_MATCHES_TOTAL - shown);
+        for (n, line) in matches.iter().take(room) {
+            out.push_str(&format!("{}:{}: {}\n", id, n + 1, line));
+        }
+        shown += matches.len().min(room);
+        if matches.len() > room && room == MAX_MATCHES_PER_CAPTURE {
+            out.push_str(&format!(
+                "{}: ... +{} more (rtk tee show {} --grep ...)\n",
+                id,
+                matches.len() - room,
+                id
+            ));
+        }
+    }
+
+    if hits > shown && shown == MAX_MATCHES_TOTAL {
+        out.push_str(&format!(
+            "... +{} more matches (narrow with --id or a stricter pattern)\n",
+            hits - shown
+        ));
+    }
+    (out, hits)
+}
+
+fn print_capture(
+    capture: &TeeCapture,
+    lines: Option<&str>,
+    grep: Option<&str>,
+    verbose: u8,
+) -> Result<()> {
+    if verbose > 0 {
+        eprintln!("{} ({})", capture.id, capture.path.display());
+    }
+    let content = read_capture(capture)?;
+    let range = lines.map(parse_line_range).transpose()?;
+    let re = grep
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid regex '{}'", p)))
+        .transpose()?;
+
+    print!("{}", slice_output(&content, range, re.as_ref()));
+    Ok(())
+}
+
+fn read_capture(capture: &TeeCapture) -> Result<String> {
+    let bytes = std::fs::read(&capture.path)
+        .with_context(|| format!("Failed to read {}", capture.path.display()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Parse `a:b`, `a:` or `:b` (1-based, inclusive) into a line range.
+fn parse_line_range(spec: &str) -> Result<(usize, usize)> {
+    let (start, end) = spec
+        .split_once(':')
+        .with_context(|| format!("Invalid --lines '{}' (expected a:b)", spec))?;
+    let parse = |s: &str, default: usize| -> Result<usize> {
+        if s.trim().is_empty() {
+            Ok(default)
+        } else {
+            s.trim()
+                .parse()
+                .with_context(|| format!("Invalid line number '{}'", s))
+        }
+    };
+    let start = parse(start, 1)?.max(1);
+    let end = parse(end, usize::MAX)?;
+    if end < start {
+        bail!("Invalid --lines '{}': end before start", spec);
+    }
+    Ok((start, end))
+}
+
+/// Apply the line range, then the grep filter. Grep results keep line numbers.
+fn slice_output(content: &str, range: Option<(usize, usize)>, re: Option<&Regex>) -> String {
+    let (start, end) = range.unwrap_or((1, usize::MAX));
+    let mut out = String::new();
+    for (n, line) in content.lines().enumerate() {
+        let lineno = n + 1;
+        if lineno < start {
+            continue;
+        }
+        if lineno > end {
+            break;
+        }
+        match re {
+            Some(re) if !re.is_match(line) => {}
+            Some(_) => out.push_str(&format!("{}: {}\n", lineno, line)),
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn format_age(timestamp: i64) -> String {
+    let secs = (chrono::Utc::now().timestamp() - timestamp).max(0);
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{}B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1}K", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1}M", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("3:5").unwrap(), (3, 5));
+        assert_eq!(parse_line_range("10:").unwrap(), (10, usize::MAX));
+        assert_eq!(parse_line_range(":4").unwrap(), (1, 4));
+        assert!(parse_line_range("5:3").is_err());
+        assert!(parse_line_range("7").is_err());
+    }
+
+    #[test]
+    fn test_slice_output_range_and_grep() {
+        let content = "a\nerror one\nb\nerror two\nc";
+        assert_eq!(slice_output(content, Some((2, 3)), None), "error one\nb\n");
+
+        let re = Regex::new("error").unwrap();
+        assert_eq!(
+            slice_output(content, None, Some(&re)),
+            "2: error one\n4: error two\n"
+        );
+        assert_eq!(
+            slice_output(content, Some((3, 5)), Some(&re)),
+            "4: error two\n"
+        );
+    }
+
+    #[test]
+    fn test_grep_captures_caps_matches() {
+        let re = Regex::new("err").unwrap();
+        let many = (1..=30).map(|i| format!("err {}", i)).collect::<Vec<_>>();
+        let captures = vec![("a1", many.join("\n")), ("b2", "ok\nerr x".to_string())];
+
+        let (out, hits) = grep_captures(&re, &captures);
+        assert_eq!(hits, 31);
+        assert!(out.starts_with("a1:1: err 1\n"));
+        assert!(out.contains("a1:20: err 20\n"));
+        assert!(!out.contains("a1:21:"));
+        assert!(out.contains("a1: ... +10 more"));
+        assert!(out.ends_with("b2:2: err x\n"));
+
+        let captures: Vec<(&str, String)> = (0..6).map(|_| ("c", many.join("\n"))).collect();
+        let (out, hits) = grep_captures(&re, &captures);
+        assert_eq!(hits, 180);
+        assert_eq!(
+            out.lines()
+                .filter(|l| l.starts_with("c:") && !l.contains("more"))
+                .count(),
+            100
+        );
+        assert!(out.ends_with("... +80 more matches (narrow with --id or a stricter pattern)\n"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(2048), "2.0K");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0M");
+    }
+}
@@ -0,0 +1,120 @@
+//! Token counting for savings tracking.
+//!
+//! `heuristic` is the historical ~4 chars/token estimate. `cl100k` and `o200k`
+//! run a real BPE encoder whose vocabularies are embedded in the binary, so
+//! counting never touches the network. Selected via `tracking.tokenizer`.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tokenizer {
+    /// ceil(bytes / 4), fast but inaccurate on whitespace-heavy or dense output
+    #[default]
+    Heuristic,
+    /// cl100k_base BPE (GPT-4 / GPT-3.5 family)
+    Cl100k,
+    /// o200k_base BPE (GPT-4o family)
+    O200k,
+}
+
+impl Tokenizer {
+    pub const BPE: [Tokenizer; 2] = [Tokenizer::Cl100k, Tokenizer::O200k];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tokenizer::Heuristic => "heuristic",
+            Tokenizer::Cl100k => "cl100k",
+            Tokenizer::O200k => "o200k",
+        }
+    }
+
+    /// Tokenizer selected in the effective config (heuristic if unset or unreadable).
+    pub fn from_config() -> Self {
+        Config::load()
+            .map(|c| c.tracking.tokenizer)
+            .unwrap_or_default()
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        match self {
+            Tokenizer::Heuristic => crate::tracking::estimate_tokens(text),
+            Tokenizer::Cl100k => tiktoken_rs::cl100k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+            Tokenizer::O200k => tiktoken_rs::o200k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+        }
+    }
+}
+
+/// Heuristic vs. real token counts for one text sample.
+#[derive(Debug, Clone, Serialize)]
+pub struct Calibration {
+    pub label: String,
+    pub bytes: usize,
+    pub heuristic: usize,
+    pub actual: usize,
+}
+
+impl Calibration {
+    pub fn measure(label: &str, text: &str, tokenizer: Tokenizer) -> Self {
+        Self {
+            label: label.to_string(),
+            bytes: text.len(),
+            heuristic: Tokenizer::Heuristic.count(text),
+            actual: tokenizer.count(text),
+        }
+    }
+
+    /// Signed heuristic error relative to the real count (+ = overestimate).
+    pub fn error_pct(&self) -> f64 {
+        error_pct(self.heuristic, self.actual)
+    }
+}
+
+pub fn error_pct(heuristic: usize, actual: usize) -> f64 {
+    if actual == 0 {
+        return 0.0;
+    }
+    (heuristic as f64 - actual as f64) / actual as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_matches_estimate() {
+        assert_eq!(Tokenizer::Heuristic.count(""), 0);
+        assert_eq!(Tokenizer::Heuristic.count("hello world"), 3);
+    }
+
+    #[test]
+    fn test_bpe_counts() {
+        assert_eq!(Tokenizer::Cl100k.count(""), 0);
+        assert_eq!(Tokenizer::Cl100k.count("hello world"), 2);
+        assert_eq!(Tokenizer::O200k.count("hello world"), 2);
+    }
+
+    #[test]
+    fn test_heuristic_overstates_whitespace() {
+        let padded = format!("{}x", " ".repeat(400));
+        let cal = Calibration::measure("pad", &padded, Tokenizer::Cl100k);
+        assert!(cal.actual < cal.heuristic);
+        assert!(cal.error_pct() > 0.0);
+    }
+
+    #[test]
+    fn test_config_name_roundtrip() {
+        for t in Tokenizer::BPE {
+            let parsed: Tokenizer = serde_json::from_str(&format!("\"{}\"", t.name())).unwrap();
+            assert_eq!(parsed, t);
+        }
+    }
+}
@@ -29,9 +29,10 @@
 //!
 //! See [docs/tracking.md](../docs/tracking.md) for full documentation.
 
+use crate::tokenizer::Tokenizer;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, Connection};
 use serde::Serialize;
 use std::ffi::OsString;
 use std::fs;
@@ -42,7 +43,8 @@ use std::time::Instant;
 const HISTORY_DAYS: i64 = 90;
 const TRACKING_DB_FILE: &str = "history.db";
 const REDACTED_VALUE: &str = "<redacted>";
-type CommandStats = (String, usize, usize, f64, u64);
+/// Per-command totals: (cmd, count, saved, avg_pct, avg_time_ms)
+pub type CommandStats = (String, usize, usize, f64, u64);
 
 /// Main tracking interface for recording and querying command history.
 ///
@@ -63,7 +65,7 @@ type CommandStats = (String, usize, usize, f64, u64);
 /// use rtk::tracking::Tracker;
 ///
 /// let tracker = Tracker::new()?;
-/// tracker.record("ls -la", "rtk ls", 1000, 200, 50)?;
+/// tracker.record("ls -la", "rtk ls", 1000, 200, 50, Some(0))?;
 ///
 /// let summary = tracker.get_summary()?;
 /// println!("Total saved: {} tokens", summary.total_saved);
@@ -71,6 +73,54 @@ type CommandStats = (String, usize, usize, f64, u64);
 /// ```
 pub struct Tracker {
     conn: Option<Connection>,
+    filter: GainFilter,
+}
+
+/// Restricts [`Tracker`] queries to one project and/or agent session.
+#[derive(Debug, Default, Clone)]
+pub struct GainFilter {
+    /// Project root as recorded (see [`project_root`])
+    pub project: Option<String>,
+    /// Session id as recorded from `RTK_SESSION_ID`
+    pub session: Option<String>,
+}
+
+impl GainFilter {
+    /// SQL `WHERE` fragment (leading space, or empty) and its positional args.
+    fn where_clause(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut args = Vec::new();
+        for (column, value) in [
+            ("project_path", &self.project),
+            ("session_id", &self.session),
+        ] {
+            if let Some(value) = value {
+                args.push(value.clone());
+                conditions.push(format!("{} = ?{}", column, args.len()));
+            }
+        }
+        if conditions.is_empty() {
+            (String::new(), args)
+        } else {
+            (format!(" WHERE {}", conditions.join(" AND ")), args)
+        }
+    }
+}
+
+/// Dimension used by [`Tracker::get_by_group`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GainGroup {
+    Project,
+    Session,
+}
+
+impl GainGroup {
+    fn column(&self) -> &'static str {
+        match self {
+            GainGroup::Project => "project_path",
+            GainGroup::Session => "session_id",
+        }
+    }
 }
 
 /// Individual command record from tracking history.
@@ -178,6 +228,30 @@ pub struct WeekStats {
     pub avg_time_ms: u64,
 }
 
+/// Statistics for one project or session.
+///
+/// Serializable to JSON for export via `rtk gain --by project --format json`.
+/// Records from before these dimensions existed are grouped under `(unknown)`.
+#[derive(Debug, Serialize)]
+pub struct GroupStats {
+    /// Project root or session id
+    pub key: String,
+    /// Number of commands executed
+    pub commands: usize,
+    /// Total input tokens
+    pub input_tokens: usize,
+    /// Total output tokens
+    pub output_tokens: usize,
+    /// Total tokens saved
+    pub saved_tokens: usize,
+    /// Savings percentage
+    pub savings_pct: f64,
+    /// Total execution time (milliseconds)
+    pub total_time_ms: u64,
+    /// Average execution time per command (milliseconds)
+    pub avg_time_ms: u64,
+}
+
 /// Monthly statistics for token savings and execution metrics.
 ///
 /// Serializable to JSON for export via `rtk gain --monthly --format json`.
@@ -226,15 +300,21 @@ impl Tracker {
     /// ```
     pub fn new() -> Result<Self> {
         if !is_tracking_enabled()? {
-            return Ok(Self { conn: None });
+            return Ok(Self {
+                conn: None,
+                filter: GainFilter::default(),
+            });
         }
 
-        let db_path = get_db_path()?;
+        Self::open(&get_db_path()?)
+    }
+
+    fn open(db_path: &Path) -> Result<Self> {
         if let Some(parent) = db_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(&db_path)?;
+        let conn = Connection::open(db_path)?;
         conn.execute(
             "CREATE TABLE IF NOT EXISTS commands (
                 id INTEGER PRIMARY KEY,
@@ -260,9 +340,36 @@ impl Tracker {
             [],
         );
 
-        enforce_local_db_permissions(&db_path)?;
+        // Migration: project/session dimensions (NULL for older records)
+        for column in [
+            "project_path TEXT",
+            "session_id TEXT",
+            "exit_code INTEGER",
+            "rtk_version TEXT",
+        ] {
+            let _ = conn.execute(&format!("ALTER TABLE commands ADD COLUMN {}", column), []);
+        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project ON commands(project_path)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session ON commands(session_id)",
+            [],
+        )?;
+
+        enforce_local_db_permissions(db_path)?;
 
-        Ok(Self { conn: Some(conn) })
+        Ok(Self {
+            conn: Some(conn),
+            filter: GainFilter::default(),
+        })
+    }
+
+    /// Restrict all subsequent queries to `filter`.
+    pub fn with_filter(mut self, filter: GainFilter) -> Self {
+        self.filter = filter;
+        self
     }
 
     /// Record a command execution with token counts and timing.
@@ -277,6 +384,9 @@ impl Tracker {
     /// - `input_tokens`: Estimated tokens from standard command output
     /// - `output_tokens`: Actual tokens from RTK output
     /// - `exec_time_ms`: Execution time in milliseconds
+    /// - `exit_code`: Exit code of the underlying command, if known
+    ///
+    /// The current project root, `RTK_SESSION_ID` and rtk version are recorded too.
     ///
     /// # Examples
     ///
@@ -284,7 +394,7 @@ impl Tracker {
     /// use rtk::tracking::Tracker;
     ///
     /// let tracker = Tracker::new()?;
-    /// tracker.record("ls -la", "rtk ls", 1000, 200, 50)?;
+    /// tracker.record("ls -la", "rtk ls", 1000, 200, 50, Some(0))?;
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn record(
@@ -294,6 +404,7 @@ impl Tracker {
         input_tokens: usize,
         output_tokens: usize,
         exec_time_ms: u64,
+        exit_code: Option<i32>,
     ) -> Result<()> {
         let conn = match &self.conn {
             Some(conn) => conn,
@@ -311,8 +422,8 @@ impl Tracker {
         let rtk_cmd = sanitize_command_for_tracking(rtk_cmd);
 
         conn.execute(
-            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct, exec_time_ms, project_path, session_id, exit_code, rtk_version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 Utc::now().to_rfc3339(),
                 original_cmd,
@@ -321,15 +432,40 @@ impl Tracker {
                 output_tokens as i64,
                 saved as i64,
                 pct,
-                exec_time_ms as i64
+                exec_time_ms as i64,
+                current_project(),
+                current_session(),
+                exit_code,
+                env!("CARGO_PKG_VERSION")
             ],
         )?;
 
-        let _ = conn;
+        crate::dedup::report_row(conn.last_insert_rowid());
         self.cleanup_old()?;
         Ok(())
     }
 
+    /// Rewrite a recorded command's output after dedup replaced it with a
+    /// shorter "unchanged" line or diff, so the extra savings are counted.
+    pub fn apply_dedup(&self, id: i64, output_tokens: usize) -> Result<()> {
+        let conn = match &self.conn {
+            Some(conn) => conn,
+            None => return Ok(()),
+        };
+
+        conn.execute(
+            "UPDATE commands SET
+                output_tokens = MIN(?1, input_tokens),
+                saved_tokens = MAX(input_tokens - ?1, 0),
+                savings_pct = CASE WHEN input_tokens > 0
+                    THEN MAX(input_tokens - ?1, 0) * 100.0 / input_tokens ELSE 0 END,
+                rtk_cmd = rtk_cmd || ' (dedup)'
+             WHERE id = ?2",
+            params![output_tokens as i64, id],
+        )?;
+        Ok(())
+    }
+
     fn cleanup_old(&self) -> Result<()> {
         let conn = match &self.conn {
             Some(conn) => conn,
@@ -384,11 +520,14 @@ impl Tracker {
             });
         };
 
-        let mut stmt = conn.prepare(
-            "SELECT input_tokens, output_tokens, saved_tokens, exec_time_ms FROM commands",
-        )?;
+        let (where_sql, args) = self.filter.where_clause();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT input_tokens, output_tokens, saved_tokens, exec_time_ms FROM commands{}",
+            where_sql
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             Ok((
                 row.get::<_, i64>(0)? as usize,
                 row.get::<_, i64>(1)? as usize,
@@ -435,19 +574,43 @@ impl Tracker {
     }
 
     fn get_by_command(&self) -> Result<Vec<CommandStats>> {
+        self.query_command_stats("", "SUM(saved_tokens) DESC", 10)
+    }
+
+    /// Commands with the lowest average savings, worst first.
+    ///
+    /// Passthrough runs record no tokens and are left out, so this lists
+    /// commands whose filters ran but saved little.
+    pub fn get_worst_commands(&self, limit: usize) -> Result<Vec<CommandStats>> {
+        self.query_command_stats(
+            " HAVING SUM(input_tokens) > 0",
+            "AVG(savings_pct) ASC, COUNT(*) DESC",
+            limit,
+        )
+    }
+
+    fn query_command_stats(
+        &self,
+        having: &str,
+        order_by: &str,
+        limit: usize,
+    ) -> Result<Vec<CommandStats>> {
         let Some(conn) = self.conn.as_ref() else {
             return Ok(Vec::new());
         };
 
-        let mut stmt = conn.prepare(
-            "SELECT rtk_cmd, COUNT(*), SUM(saved_tokens), AVG(savings_pct), AVG(exec_time_ms)
-             FROM commands
-             GROUP BY rtk_cmd
-             ORDER BY SUM(saved_tokens) DESC
-             LIMIT 10",
-        )?;
+        let (where_sql, args) = self.filter.where_clause();
 
-        let rows = stmt.query_map([], |row| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT rtk_cmd, COUNT(*), SUM(saved_tokens), AVG(savings_pct), AVG(exec_time_ms)
+             FROM commands{}
+             GROUP BY rtk_cmd{}
+             ORDER BY {}
+             LIMIT {}",
+            where_sql, having, order_by, limit
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, i64>(1)? as usize,
@@ -465,15 +628,18 @@ impl Tracker {
             return Ok(Vec::new());
         };
 
-        let mut stmt = conn.prepare(
+        let (where_sql, args) = self.filter.where_clause();
+
+        let mut stmt = conn.prepare(&format!(
             "SELECT DATE(timestamp), SUM(saved_tokens)
-             FROM commands
+             FROM commands{}
              GROUP BY DATE(timestamp)
              ORDER BY DATE(timestamp) DESC
              LIMIT 30",
-        )?;
+            where_sql
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
         })?;
 
@@ -505,7 +671,9 @@ impl Tracker {
             return Ok(Vec::new());
         };
 
-        let mut stmt = conn.prepare(
+        let (where_sql, args) = self.filter.where_clause();
+
+        let mut stmt = conn.prepare(&format!(
             "SELECT
                 DATE(timestamp) as date,
                 COUNT(*) as commands,
@@ -513,12 +681,13 @@ impl Tracker {
                 SUM(output_tokens) as output,
                 SUM(saved_tokens) as saved,
                 SUM(exec_time_ms) as total_time
-             FROM commands
+             FROM commands{}
              GROUP BY DATE(timestamp)
              ORDER BY DATE(timestamp) DESC",
-        )?;
+            where_sql
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             let input = row.get::<_, i64>(2)? as usize;
             let saved = row.get::<_, i64>(4)? as usize;
             let commands = row.get::<_, i64>(1)? as usize;
@@ -574,7 +743,9 @@ impl Tracker {
             return Ok(Vec::new());
         };
 
-        let mut stmt = conn.prepare(
+        let (where_sql, args) = self.filter.where_clause();
+
+        let mut stmt = conn.prepare(&format!(
             "SELECT
                 DATE(timestamp, 'weekday 0', '-6 days') as week_start,
                 DATE(timestamp, 'weekday 0') as week_end,
@@ -583,12 +754,13 @@ impl Tracker {
                 SUM(output_tokens) as output,
                 SUM(saved_tokens) as saved,
                 SUM(exec_time_ms) as total_time
-             FROM commands
+             FROM commands{}
              GROUP BY week_start
              ORDER BY week_start DESC",
-        )?;
+            where_sql
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             let input = row.get::<_, i64>(3)? as usize;
             let saved = row.get::<_, i64>(5)? as usize;
             let commands = row.get::<_, i64>(2)? as usize;
@@ -645,7 +817,9 @@ impl Tracker {
             return Ok(Vec::new());
         };
 
-        let mut stmt = conn.prepare(
+        let (where_sql, args) = self.filter.where_clause();
+
+        let mut stmt = conn.prepare(&format!(
             "SELECT
                 strftime('%Y-%m', timestamp) as month,
                 COUNT(*) as commands,
@@ -653,12 +827,13 @@ impl Tracker {
                 SUM(output_tokens) as output,
                 SUM(saved_tokens) as saved,
                 SUM(exec_time_ms) as total_time
-             FROM commands
+             FROM commands{}
              GROUP BY month
              ORDER BY month DESC",
-        )?;
+            where_sql
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             let input = row.get::<_, i64>(2)? as usize;
             let saved = row.get::<_, i64>(4)? as usize;
             let commands = row.get::<_, i64>(1)? as usize;
@@ -691,6 +866,59 @@ impl Tracker {
         Ok(result)
     }
 
+    /// Get statistics grouped by project or session, most tokens saved first.
+    pub fn get_by_group(&self, group: GainGroup) -> Result<Vec<GroupStats>> {
+        let Some(conn) = self.conn.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let (where_sql, args) = self.filter.where_clause();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT
+                COALESCE({col}, '(unknown)') as key,
+                COUNT(*) as commands,
+                SUM(input_tokens) as input,
+                SUM(output_tokens) as output,
+                SUM(saved_tokens) as saved,
+                SUM(exec_time_ms) as total_time
+             FROM commands{where_sql}
+             GROUP BY key
+             ORDER BY saved DESC",
+            col = group.column(),
+            where_sql = where_sql
+        ))?;
+
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
+            let input = row.get::<_, i64>(2)? as usize;
+            let saved = row.get::<_, i64>(4)? as usize;
+            let commands = row.get::<_, i64>(1)? as usize;
+            let total_time = row.get::<_, i64>(5)? as u64;
+            let savings_pct = if input > 0 {
+                (saved as f64 / input as f64) * 100.0
+            } else {
+                0.0
+            };
+            let avg_time_ms = if commands > 0 {
+                total_time / commands as u64
+            } else {
+                0
+            };
+
+            Ok(GroupStats {
+                key: row.get(0)?,
+                commands,
+                input_tokens: input,
+                output_tokens: row.get::<_, i64>(3)? as usize,
+                saved_tokens: saved,
+                savings_pct,
+                total_time_ms: total_time,
+                avg_time_ms,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     /// Get recent command history.
     ///
     /// Returns up to `limit` most recent command records, ordered by timestamp (newest first).
@@ -717,14 +945,17 @@ impl Tracker {
             return Ok(Vec::new());
         };
 
-        let mut stmt = conn.prepare(
+        let (where_sql, args) = self.filter.where_clause();
+
+        let mut stmt = conn.prepare(&format!(
             "SELECT timestamp, rtk_cmd, saved_tokens, savings_pct
-             FROM commands
+             FROM commands{}
              ORDER BY timestamp DESC
-             LIMIT ?1",
-        )?;
+             LIMIT {}",
+            where_sql, limit
+        ))?;
 
-        let rows = stmt.query_map(params![limit as i64], |row| {
+        let rows = stmt.query_map(params_from_iter(&args), |row| {
             Ok(CommandRecord {
                 timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
                     .map(|dt| dt.with_timezone(&Utc))
@@ -739,7 +970,31 @@ impl Tracker {
     }
 }
 
-fn get_db_path() -> Result<PathBuf> {
+/// Git root of `start` (nearest ancestor containing `.git`), else `start` itself.
+pub fn project_root(start: &Path) -> PathBuf {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(start)
+        .to_path_buf()
+}
+
+/// Project recorded for commands run from the current directory.
+pub fn current_project() -> Option<String> {
+    let cwd = std::env::current_dir().ok()?;
+    Some(project_root(&cwd).to_string_lossy().into_owned())
+}
+
+/// Agent session id, set by `rtk hook pretooluse` via `RTK_SESSION_ID`.
+pub fn current_session() -> Option<String> {
+    std::env::var("RTK_SESSION_ID")
+        .ok()
+        .filter(|id| !id.trim().is_empty())
+}
+
+/// Tracking database location (`RTK_DB_PATH`, then `tracking.database_path`),
+/// kept under the rtk data directory. Other rtk databases live next to it.
+pub(crate) fn get_db_path() -> Result<PathBuf> {
     let data_dir = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
     let data_root = data_dir.join("rtk");
 
@@ -788,7 +1043,8 @@ fn has_parent_dir_component(path: &Path) -> bool {
         .any(|c| matches!(c, Component::ParentDir))
 }
 
-fn enforce_local_db_permissions(path: &Path) -> Result<()> {
+/// Restrict a local database to the current user (0600 on unix).
+pub(crate) fn enforce_local_db_permissions(path: &Path) -> Result<()> {
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -828,7 +1084,7 @@ fn is_tracking_enabled() -> Result<bool> {
         .unwrap_or(false))
 }
 
-fn parse_bool_env(value: &str) -> Option<bool> {
+pub fn parse_bool_env(value: &str) -> Option<bool> {
     match value.to_ascii_lowercase().as_str() {
         "1" | "true" | "yes" | "on" | "enable" | "enabled" => Some(true),
         "0" | "false" | "no" | "off" | "disable" | "disabled" => Some(false),
@@ -838,8 +1094,9 @@ fn parse_bool_env(value: &str) -> Option<bool> {
 
 /// Estimate token count from text using ~4 chars = 1 token heuristic.
 ///
-/// This is a fast approximation suitable for tracking purposes.
-/// For precise counts, integrate with your LLM's tokenizer API.
+/// This is a fast approximation. Tracking counts through the tokenizer
+/// selected by `tracking.tokenizer` (see [`crate::tokenizer`]), which defaults
+/// to this heuristic.
 ///
 /// # Formula
 ///
@@ -907,7 +1164,7 @@ impl TimedExecution {
     ///
     /// Records the command execution with:
     /// - Elapsed time since [`start`](Self::start)
-    /// - Token counts estimated from input/output strings
+    /// - Token counts from the configured tokenizer
     /// - Calculated savings metrics
     ///
     /// # Arguments
@@ -928,9 +1185,33 @@ impl TimedExecution {
     /// timer.track("ls -la", "rtk ls", input, output);
     /// ```
     pub fn track(&self, original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
+        self.record_tokens(original_cmd, rtk_cmd, input, output, None);
+    }
+
+    /// Like [`track`](Self::track), also recording the underlying command's exit code.
+    pub fn track_with_exit(
+        &self,
+        original_cmd: &str,
+        rtk_cmd: &str,
+        input: &str,
+        output: &str,
+        exit_code: i32,
+    ) {
+        self.record_tokens(original_cmd, rtk_cmd, input, output, Some(exit_code));
+    }
+
+    fn record_tokens(
+        &self,
+        original_cmd: &str,
+        rtk_cmd: &str,
+        input: &str,
+        output: &str,
+        exit_code: Option<i32>,
+    ) {
         let elapsed_ms = self.start.elapsed().as_millis() as u64;
-        let input_tokens = estimate_tokens(input);
-        let output_tokens = estimate_tokens(output);
+        let tokenizer = Tokenizer::from_config();
+        let input_tokens = tokenizer.count(input);
+        let output_tokens = tokenizer.count(output);
 
         if let Ok(tracker) = Tracker::new() {
             let _ = tracker.record(
@@ -939,6 +1220,7 @@ impl TimedExecution {
                 input_tokens,
                 output_tokens,
                 elapsed_ms,
+                exit_code,
             );
         }
     }
@@ -967,7 +1249,7 @@ impl TimedExecution {
         let elapsed_ms = self.start.elapsed().as_millis() as u64;
         // input_tokens=0, output_tokens=0 won't dilute savings statistics
         if let Ok(tracker) = Tracker::new() {
-            let _ = tracker.record(original_cmd, rtk_cmd, 0, 0, elapsed_ms);
+            let _ = tracker.record(original_cmd, rtk_cmd, 0, 0, elapsed_ms, None);
         }
     }
 }
@@ -1021,11 +1303,12 @@ pub fn args_display(args: &[OsString]) -> String {
 #[deprecated(note = "Use TimedExecution instead")]
 #[allow(dead_code)]
 pub fn track(original_cmd: &str, rtk_cmd: &str, input: &str, output: &str) {
-    let input_tokens = estimate_tokens(input);
-    let output_tokens = estimate_tokens(output);
+    let tokenizer = Tokenizer::from_config();
+    let input_tokens = tokenizer.count(input);
+    let output_tokens = tokenizer.count(output);
 
     if let Ok(tracker) = Tracker::new() {
-        let _ = tracker.record(original_cmd, rtk_cmd, input_tokens, output_tokens, 0);
+        let _ = tracker.record(original_cmd, rtk_cmd, input_tokens, output_tokens, 0, None);
     }
 }
 
@@ -1297,6 +1580,91 @@ mod tests {
         LOCK.get_or_init(|| Mutex::new(()))
     }
 
+    #[test]
+    fn test_gain_filter_where_clause() {
+        assert_eq!(
+            GainFilter::default().where_clause(),
+            (String::new(), vec![])
+        );
+        let filter = GainFilter {
+            project: Some("/repo".to_string()),
+            session: Some("s1".to_string()),
+        };
+        let (sql, args) = filter.where_clause();
+        assert_eq!(sql, " WHERE project_path = ?1 AND session_id = ?2");
+        assert_eq!(args, vec!["/repo", "s1"]);
+    }
+
+    #[test]
+    fn test_project_root_finds_git_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let nested = temp.path().join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(temp.path().join(".git")).unwrap();
+        assert_eq!(project_root(&nested), temp.path());
+    }
+
+    #[test]
+    fn test_dimensions_migration_and_filters() {
+        let temp = tempfile::tempdir().unwrap();
+        let db = temp.path().join("old.db");
+
+        // Database created before the project/session columns existed
+        let conn = Connection::open(&db).unwrap();
+        conn.execute(
+            "CREATE TABLE commands (
+                id INTEGER PRIMARY KEY,
+                timestamp TEXT NOT NULL,
+                original_cmd TEXT NOT NULL,
+                rtk_cmd TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                saved_tokens INTEGER NOT NULL,
+                savings_pct REAL NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO commands (timestamp, original_cmd, rtk_cmd, input_tokens, output_tokens, saved_tokens, savings_pct)
+             VALUES (?1, 'ls', 'rtk ls', 100, 10, 90, 90.0)",
+            params![Utc::now().to_rfc3339()],
+        )
+        .unwrap();
+        drop(conn);
+
+        let tracker = Tracker::open(&db).unwrap();
+        tracker
+            .record("git status", "rtk git status", 200, 20, 5, Some(1))
+            .unwrap();
+
+        let groups = tracker.get_by_group(GainGroup::Project).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert!(groups
+            .iter()
+            .any(|g| g.key == "(unknown)" && g.commands == 1));
+
+        let scoped = Tracker::open(&db).unwrap().with_filter(GainFilter {
+            project: current_project(),
+            session: None,
+        });
+        let summary = scoped.get_summary().unwrap();
+        assert_eq!(summary.total_commands, 1);
+        assert_eq!(summary.total_saved, 180);
+        assert_eq!(scoped.get_recent(10).unwrap().len(), 1);
+
+        let conn = Connection::open(&db).unwrap();
+        let (exit_code, version): (i32, String) = conn
+            .query_row(
+                "SELECT exit_code, rtk_version FROM commands WHERE exit_code IS NOT NULL",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(exit_code, 1);
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+    }
+
     // 1. estimate_tokens — verify ~4 chars/token ratio
     #[test]
     fn test_estimate_tokens() {
@@ -1327,7 +1695,7 @@ mod tests {
         let test_cmd = format!("rtk git status test_{}", std::process::id());
 
         tracker
-            .record("git status", &test_cmd, 100, 20, 50)
+            .record("git status", &test_cmd, 100, 20, 50, Some(0))
             .expect("Failed to record");
 
         let recent = tracker.get_recent(10).expect("Failed to get recent");
@@ -1342,6 +1710,41 @@ mod tests {
         assert_eq!(test_record.savings_pct, 80.0);
     }
 
+    #[test]
+    fn test_apply_dedup_updates_savings() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = Tracker::open(&dir.path().join("history.db")).unwrap();
+        tracker
+            .record("git status", "rtk git status", 100, 40, 5, Some(0))
+            .unwrap();
+        let id = tracker.conn.as_ref().unwrap().last_insert_rowid();
+
+        tracker.apply_dedup(id, 10).unwrap();
+
+        let recent = tracker.get_recent(1).unwrap();
+        assert_eq!(recent[0].rtk_cmd, "rtk git status (dedup)");
+        assert_eq!(recent[0].saved_tokens, 90);
+        assert_eq!(recent[0].savings_pct, 90.0);
+    }
+
+    #[test]
+    fn test_get_worst_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = Tracker::open(&dir.path().join("history.db")).unwrap();
+        tracker.record("ls", "rtk ls", 100, 90, 5, Some(0)).unwrap();
+        tracker
+            .record("git status", "rtk git status", 100, 20, 5, Some(0))
+            .unwrap();
+        tracker
+            .record("docker run", "rtk docker (passthrough)", 0, 0, 5, Some(0))
+            .unwrap();
+
+        let worst = tracker.get_worst_commands(10).unwrap();
+        let names: Vec<&str> = worst.iter().map(|c| c.0.as_str()).collect();
+        assert_eq!(names, vec!["rtk ls", "rtk git status"]);
+        assert_eq!(worst[0].3, 10.0);
+    }
+
     // 4. track_passthrough doesn't dilute stats (input=0, output=0)
     #[test]
     fn test_track_passthrough_no_dilution() {
@@ -1354,12 +1757,12 @@ mod tests {
 
         // Record one real command with 80% savings
         tracker
-            .record("cmd1", &cmd1, 1000, 200, 10)
+            .record("cmd1", &cmd1, 1000, 200, 10, Some(0))
             .expect("Failed to record cmd1");
 
         // Record passthrough (0, 0)
         tracker
-            .record("cmd2", &cmd2, 0, 0, 5)
+            .record("cmd2", &cmd2, 0, 0, 5, None)
             .expect("Failed to record passthrough");
 
         // Verify both records exist in recent history
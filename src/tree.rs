@@ -6,6 +6,7 @@
 //! Token optimization: automatically excludes noise directories via -I pattern
 //! unless -a flag is present (respecting user intent).
 
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
@@ -40,7 +41,7 @@ const NOISE_DIRS: &[&str] = &[
     ".eggs",
 ];
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Check if tree is installed
@@ -77,7 +78,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         eprint!("{}", stderr);
-        std::process::exit(output.status.code().unwrap_or(1));
+        return Ok(ExecResult::from_status(&output.status));
     }
 
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
@@ -97,9 +98,9 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     }
 
     print!("{}", filtered);
-    timer.track("tree", "rtk tree", &raw, &filtered);
+    crate::preserve::track(&timer, "tree", "rtk tree", &raw, &filtered);
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 fn filter_tree_output(raw: &str) -> String {
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
@@ -5,7 +6,7 @@ use regex::Regex;
 use std::collections::HashMap;
 use std::process::Command;
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+pub fn run(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     // Try tsc directly first, fallback to npx if not found
@@ -41,26 +42,28 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
 
     let filtered = filter_tsc_output(&raw);
 
-    let exit_code = output.status.code().unwrap_or(1);
+    let exit_code = crate::exec::exit_code(&output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "tsc", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track(
+    crate::preserve::track_with_exit(
+        &timer,
         &format!("tsc {}", args.join(" ")),
         &format!("rtk tsc {}", args.join(" ")),
         &raw,
         &filtered,
+        exit_code,
     );
 
     // Preserve tsc exit code for CI/CD compatibility
-    std::process::exit(exit_code);
+    Ok(ExecResult::code(exit_code))
 }
 
 /// Filter TypeScript compiler output - group errors by file, show every error
-fn filter_tsc_output(output: &str) -> String {
+pub(crate) fn filter_tsc_output(output: &str) -> String {
     lazy_static::lazy_static! {
         // Pattern: src/file.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'.
         static ref TSC_ERROR: Regex = Regex::new(
@@ -154,7 +157,7 @@ fn filter_tsc_output(output: &str) -> String {
 
     // Files sorted by error count (most errors first)
     let mut files_sorted: Vec<_> = by_file.iter().collect();
-    files_sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    files_sorted.sort_by_key(|b| std::cmp::Reverse(b.1.len()));
 
     // Show every error per file — no limits
     for (file, file_errors) in &files_sorted {
@@ -0,0 +1,435 @@
+//! User-defined declarative filters for arbitrary commands.
+//!
+//! Rules come from `.rtk/filters.toml` (nearest one walking up from the CWD)
+//! and from the global `config.toml`. Project rules are tried first; the first
+//! rule whose `match` regex hits the command wins. Rules with an invalid regex
+//! are reported on stderr and skipped when they are loaded.
+//!
+//! In `.rtk/filters.toml` each rule is a `[[filters]]` table:
+//!
+//! ```toml
+//! [[filters]]
+//! name = "deploy"
+//! match = "^mycli deploy"
+//! strip = ["^DEBUG", "^\\s*$"]
+//! dedupe = true
+//! head = 40
+//! summary = "{command}: {kept_lines}/{total_lines} lines (exit {exit_code})\n{output}"
+//! ```
+//!
+//! In `config.toml` the same keys go under `[[filters.rules]]`:
+//!
+//! ```toml
+//! [[filters.rules]]
+//! name = "terraform"
+//! match = "^terraform plan"
+//! keep = ["^\\s*[-+~]", "^Plan:"]
+//! ```
+//!
+//! Steps always run in this order: strip, keep, dedupe, collapse_runs, head/tail, summary.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Project-level filter file, relative to a directory on the CWD's ancestor chain.
+const PROJECT_FILTERS_FILE: &str = ".rtk/filters.toml";
+
+/// A single declarative filter rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserFilter {
+    /// Label shown in verbose output
+    pub name: String,
+    /// Regex matched against the full command line
+    #[serde(rename = "match")]
+    pub match_command: String,
+    /// Drop lines matching any of these regexes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strip: Vec<String>,
+    /// Keep only lines matching at least one of these regexes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keep: Vec<String>,
+    /// Drop repeated lines, keeping the first occurrence
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dedupe: bool,
+    /// Collapse consecutive identical lines into `[×N] line`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapse_runs: bool,
+    /// Keep only the first N lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<usize>,
+    /// Keep only the last N lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail: Option<usize>,
+    /// Replace output with a template: {command} {exit_code} {total_lines} {kept_lines} {output}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Shape of `.rtk/filters.toml`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProjectFilters {
+    #[serde(default)]
+    filters: Vec<UserFilter>,
+}
+
+/// Load all valid rules: project file first, then the global config.
+pub fn load_filters() -> Vec<UserFilter> {
+    let mut rules = Vec::new();
+
+    if let Some(path) = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| find_project_filters(&cwd))
+    {
+        match load_project_filters(&path) {
+            Ok(project) => rules.extend(project),
+            Err(e) => eprintln!("rtk: ignoring {}: {:#}", path.display(), e),
+        }
+    }
+
+    if let Ok(config) = Config::load() {
+        rules.extend(config.filters.rules);
+    }
+
+    valid_rules(rules)
+}
+
+/// Drop rules with an invalid regex, reporting each on stderr.
+fn valid_rules(rules: Vec<UserFilter>) -> Vec<UserFilter> {
+    rules
+        .into_iter()
+        .filter(|rule| match rule.validate() {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!("rtk: skipping filter: {:#}", e);
+                false
+            }
+        })
+        .collect()
+}
+
+/// Walk up from `start` to find the nearest `.rtk/filters.toml`.
+fn find_project_filters(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_FILTERS_FILE))
+        .find(|p| p.is_file())
+}
+
+fn load_project_filters(path: &Path) -> Result<Vec<UserFilter>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed: ProjectFilters =
+        toml::from_str(&content).with_context(|| format!("Invalid TOML in {}", path.display()))?;
+    Ok(parsed.filters)
+}
+
+/// Rules rendered as `[[filters]]` tables.
+pub fn to_project_toml(rules: &[UserFilter]) -> String {
+    let wrapper = ProjectFilters {
+        filters: rules.to_vec(),
+    };
+    toml::to_string_pretty(&wrapper).unwrap_or_default()
+}
+
+/// Append rules to the nearest `.rtk/filters.toml` above `start` (created in
+/// `start` if there is none), skipping names already defined there.
+/// Returns the file and the number of rules added.
+pub fn append_project_filters(start: &Path, rules: &[UserFilter]) -> Result<(PathBuf, usize)> {
+    let path = find_project_filters(start).unwrap_or_else(|| start.join(PROJECT_FILTERS_FILE));
+    let existing = if path.is_file() {
+        load_project_filters(&path)?
+    } else {
+        Vec::new()
+    };
+
+    let new: Vec<UserFilter> = rules
+        .iter()
+        .filter(|r| !existing.iter().any(|e| e.name == r.name))
+        .cloned()
+        .collect();
+    if new.is_empty() {
+        return Ok((path, 0));
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut content = std::fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with("\n\n") {
+        content.push_str(if content.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        });
+    }
+    content.push_str(&to_project_toml(&new));
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok((path, new.len()))
+}
+
+/// Find the first rule matching `command`.
+pub fn find_filter(command: &str) -> Option<UserFilter> {
+    select_filter(load_filters(), command)
+}
+
+fn select_filter(rules: Vec<UserFilter>, command: &str) -> Option<UserFilter> {
+    rules
+        .into_iter()
+        .find(|rule| Regex::new(&rule.match_command).is_ok_and(|re| re.is_match(command)))
+}
+
+fn compile_all(patterns: &[String], rule: &str) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| {
+            Regex::new(p).with_context(|| format!("Invalid regex '{}' in filter '{}'", p, rule))
+        })
+        .collect()
+}
+
+impl UserFilter {
+    /// Compile every regex in the rule, returning the `match` regex.
+    pub fn validate(&self) -> Result<Regex> {
+        compile_all(&self.strip, &self.name)?;
+        compile_all(&self.keep, &self.name)?;
+        Regex::new(&self.match_command).with_context(|| {
+            format!(
+                "Invalid regex '{}' in filter '{}'",
+                self.match_command, self.name
+            )
+        })
+    }
+
+    /// Apply the rule's steps to raw command output.
+    pub fn apply(&self, command: &str, raw: &str, exit_code: i32) -> Result<String> {
+        let strip = compile_all(&self.strip, &self.name)?;
+        let keep = compile_all(&self.keep, &self.name)?;
+
+        let total_lines = raw.lines().count();
+        let mut lines: Vec<String> = raw
+            .lines()
+            .filter(|l| !strip.iter().any(|re| re.is_match(l)))
+            .filter(|l| keep.is_empty() || keep.iter().any(|re| re.is_match(l)))
+            .map(str::to_string)
+            .collect();
+
+        if self.dedupe {
+            let mut seen = HashSet::new();
+            lines.retain(|l| seen.insert(l.clone()));
+        }
+
+        if self.collapse_runs {
+            lines = collapse_runs(lines);
+        }
+
+        let kept_lines = lines.len();
+        let output = head_tail(&lines, self.head, self.tail);
+
+        match &self.summary {
+            Some(template) => Ok(template
+                .replace("{command}", command)
+                .replace("{exit_code}", &exit_code.to_string())
+                .replace("{total_lines}", &total_lines.to_string())
+                .replace("{kept_lines}", &kept_lines.to_string())
+                .replace("{output}", &output)),
+            None => Ok(output),
+        }
+    }
+}
+
+fn collapse_runs(lines: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::with_capacity(lines.len());
+    let mut iter = lines.into_iter().peekable();
+
+    while let Some(line) = iter.next() {
+        let mut count = 1;
+        while iter.peek() == Some(&line) {
+            iter.next();
+            count += 1;
+        }
+        if count > 1 {
+            result.push(format!("[×{}] {}", count, line));
+        } else {
+            result.push(line);
+        }
+    }
+
+    result
+}
+
+fn head_tail(lines: &[String], head: Option<usize>, tail: Option<usize>) -> String {
+    let total = lines.len();
+    let head_n = head.unwrap_or(0);
+    let tail_n = tail.unwrap_or(0);
+
+    if (head.is_none() && tail.is_none()) || head_n + tail_n >= total {
+        return lines.join("\n");
+    }
+
+    let mut out: Vec<String> = lines[..head_n].to_vec();
+    let omitted = total - head_n - tail_n;
+    if tail.is_some() {
+        out.push(format!("... {} lines omitted", omitted));
+        out.extend_from_slice(&lines[total - tail_n..]);
+    } else {
+        out.push(format!("... +{} more lines", omitted));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(toml_src: &str) -> UserFilter {
+        let parsed: ProjectFilters = toml::from_str(toml_src).unwrap();
+        parsed.filters.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_parse_project_filters() {
+        let f = rule(
+            r#"
+            [[filters]]
+            name = "deploy"
+            match = "^mycli deploy"
+            strip = ["^DEBUG"]
+            head = 5
+            "#,
+        );
+        assert_eq!(f.name, "deploy");
+        assert_eq!(f.match_command, "^mycli deploy");
+        assert_eq!(f.head, Some(5));
+        assert!(!f.dedupe);
+    }
+
+    #[test]
+    fn test_strip_and_keep() {
+        let f = rule(
+            r#"
+            [[filters]]
+            name = "t"
+            match = "."
+            strip = ["^DEBUG"]
+            keep = ["ok|fail"]
+            "#,
+        );
+        let raw = "DEBUG ok\nstep ok\nnoise\nstep fail";
+        assert_eq!(f.apply("x", raw, 0).unwrap(), "step ok\nstep fail");
+    }
+
+    #[test]
+    fn test_dedupe_and_collapse() {
+        let mut f = rule("[[filters]]\nname = \"t\"\nmatch = \".\"\ncollapse_runs = true");
+        assert_eq!(f.apply("x", "a\na\na\nb\na", 0).unwrap(), "[×3] a\nb\na");
+
+        f.collapse_runs = false;
+        f.dedupe = true;
+        assert_eq!(f.apply("x", "a\nb\na\nc", 0).unwrap(), "a\nb\nc");
+    }
+
+    #[test]
+    fn test_head_tail() {
+        let lines: Vec<String> = (1..=10).map(|i| i.to_string()).collect();
+        assert_eq!(head_tail(&lines, Some(2), None), "1\n2\n... +8 more lines");
+        assert_eq!(
+            head_tail(&lines, None, Some(2)),
+            "... 8 lines omitted\n9\n10"
+        );
+        assert_eq!(
+            head_tail(&lines, Some(1), Some(1)),
+            "1\n... 8 lines omitted\n10"
+        );
+        assert_eq!(head_tail(&lines, Some(20), None), lines.join("\n"));
+    }
+
+    #[test]
+    fn test_summary_template() {
+        let f = rule(
+            r#"
+            [[filters]]
+            name = "t"
+            match = "."
+            keep = ["ERR"]
+            summary = "{command} exit={exit_code} {kept_lines}/{total_lines}\n{output}"
+            "#,
+        );
+        let out = f.apply("mycli run", "a\nERR x\nb", 2).unwrap();
+        assert_eq!(out, "mycli run exit=2 1/3\nERR x");
+    }
+
+    #[test]
+    fn test_select_filter_first_match_wins() {
+        let rules = vec![
+            rule("[[filters]]\nname = \"bad\"\nmatch = \"(\""),
+            rule("[[filters]]\nname = \"a\"\nmatch = \"^mycli\""),
+            rule("[[filters]]\nname = \"b\"\nmatch = \"deploy\""),
+        ];
+        let found = select_filter(valid_rules(rules), "mycli deploy").unwrap();
+        assert_eq!(found.name, "a");
+    }
+
+    #[test]
+    fn test_valid_rules_drops_invalid_step_regex() {
+        let rules = vec![
+            rule("[[filters]]\nname = \"bad\"\nmatch = \"^mycli\"\nstrip = [\"(\"]"),
+            rule("[[filters]]\nname = \"good\"\nmatch = \"^mycli\""),
+        ];
+        let rules = valid_rules(rules);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(select_filter(rules, "mycli deploy").unwrap().name, "good");
+    }
+
+    #[test]
+    fn test_invalid_step_regex_is_error() {
+        let f = rule("[[filters]]\nname = \"t\"\nmatch = \".\"\nstrip = [\"(\"]");
+        assert!(f.apply("x", "line", 0).is_err());
+    }
+
+    #[test]
+    fn test_find_project_filters_walks_up() {
+        let temp = tempfile::tempdir().unwrap();
+        let nested = temp.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(temp.path().join(".rtk")).unwrap();
+        std::fs::write(
+            temp.path().join(PROJECT_FILTERS_FILE),
+            "[[filters]]\nname = \"p\"\nmatch = \".\"",
+        )
+        .unwrap();
+
+        let found = find_project_filters(&nested).unwrap();
+        assert_eq!(found, temp.path().join(PROJECT_FILTERS_FILE));
+        assert_eq!(load_project_filters(&found).unwrap()[0].name, "p");
+    }
+
+    #[test]
+    fn test_append_project_filters_skips_existing() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join(".rtk")).unwrap();
+        std::fs::write(
+            temp.path().join(PROJECT_FILTERS_FILE),
+            "[[filters]]\nname = \"p\"\nmatch = \".\"\n",
+        )
+        .unwrap();
+
+        let rules = vec![
+            rule("[[filters]]\nname = \"p\"\nmatch = \"x\""),
+            rule("[[filters]]\nname = \"q\"\nmatch = \"^q\"\ndedupe = true"),
+        ];
+        let (path, added) = append_project_filters(temp.path(), &rules).unwrap();
+        assert_eq!(path, temp.path().join(PROJECT_FILTERS_FILE));
+        assert_eq!(added, 1);
+
+        let loaded = load_project_filters(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].name, "q");
+        assert!(loaded[1].dedupe);
+        assert_eq!(append_project_filters(temp.path(), &rules).unwrap().1, 0);
+    }
+}
@@ -75,7 +75,7 @@ pub fn execute_command(cmd: &str, args: &[&str]) -> Result<(String, String, i32)
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let exit_code = output.status.code().unwrap_or(-1);
+    let exit_code = crate::exec::exit_code(&output.status);
 
     Ok((stdout, stderr, exit_code))
 }
@@ -1,3 +1,4 @@
+use crate::exec::ExecResult;
 use anyhow::{Context, Result};
 use regex::Regex;
 use serde::Deserialize;
@@ -213,13 +214,23 @@ pub enum VitestCommand {
     Run,
 }
 
-pub fn run(cmd: VitestCommand, args: &[String], verbose: u8) -> Result<()> {
+/// Compact summary of raw `vitest run --reporter=json` output (used by `rtk bench`).
+pub(crate) fn filter_vitest_output(raw: &str) -> String {
+    match VitestParser::parse(raw) {
+        ParseResult::Full(data) | ParseResult::Degraded(data, _) => {
+            data.format(FormatMode::from_verbosity(0))
+        }
+        ParseResult::Passthrough(raw) => raw,
+    }
+}
+
+pub fn run(cmd: VitestCommand, args: &[String], verbose: u8) -> Result<ExecResult> {
     match cmd {
         VitestCommand::Run => run_vitest(args, verbose),
     }
 }
 
-fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
+fn run_vitest(args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     let mut cmd = package_manager_exec("vitest");
@@ -239,6 +250,7 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
 
     // Parse output using VitestParser
     let parse_result = VitestParser::parse(&stdout);
+    crate::json_output::report_parsed("TestResult", &parse_result);
     let mode = FormatMode::from_verbosity(verbose);
 
     let filtered = match parse_result {
@@ -260,17 +272,24 @@ fn run_vitest(args: &[String], verbose: u8) -> Result<()> {
         }
     };
 
-    let exit_code = output.status.code().unwrap_or(1);
+    let exit_code = crate::exec::exit_code(&output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&combined, "vitest_run", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
         println!("{}", filtered);
     }
 
-    timer.track("vitest run", "rtk vitest run", &combined, &filtered);
+    crate::preserve::track_with_exit(
+        &timer,
+        "vitest run",
+        "rtk vitest run",
+        &combined,
+        &filtered,
+        exit_code,
+    );
 
     // Propagate original exit code
-    std::process::exit(exit_code)
+    Ok(ExecResult::code(exit_code))
 }
 
 #[cfg(test)]
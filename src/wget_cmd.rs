@@ -1,9 +1,10 @@
+use crate::exec::ExecResult;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::process::Command;
 
 /// Compact wget - strips progress bars, shows only result
-pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
+pub fn run(url: &str, args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -39,19 +40,32 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
             format_size(size)
         );
         println!("{}", msg);
-        timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
+        crate::preserve::track(
+            &timer,
+            &format!("wget {}", url),
+            "rtk wget",
+            &raw_output,
+            &msg,
+        );
     } else {
         let error = parse_error(&stderr, &stdout);
         let msg = format!("⬇️ {} FAILED: {}", compact_url(url), error);
-        println!("{}", msg);
-        timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
+        eprintln!("{}", msg);
+        crate::preserve::track_with_exit(
+            &timer,
+            &format!("wget {}", url),
+            "rtk wget",
+            &raw_output,
+            &msg,
+            crate::exec::exit_code(&output.status),
+        );
     }
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 /// Run wget and output to stdout (for piping)
-pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
+pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<ExecResult> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
@@ -95,7 +109,8 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
             }
         }
         print!("{}", rtk_output);
-        timer.track(
+        crate::preserve::track(
+            &timer,
             &format!("wget -O - {}", url),
             "rtk wget -o",
             &raw_output,
@@ -105,11 +120,18 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let error = parse_error(&stderr, "");
         let msg = format!("⬇️ {} FAILED: {}", compact_url(url), error);
-        println!("{}", msg);
-        timer.track(&format!("wget -O - {}", url), "rtk wget -o", &stderr, &msg);
+        eprintln!("{}", msg);
+        crate::preserve::track_with_exit(
+            &timer,
+            &format!("wget -O - {}", url),
+            "rtk wget -o",
+            &stderr,
+            &msg,
+            crate::exec::exit_code(&output.status),
+        );
     }
 
-    Ok(())
+    Ok(ExecResult::from_status(&output.status))
 }
 
 fn extract_filename_from_output(stderr: &str, url: &str, args: &[String]) -> String {
@@ -0,0 +1,261 @@
+//! Exit-code pass-through: every wrapper must exit with its child's code.
+//!
+//! Each test runs the real `rtk` binary against fake tools (shell scripts on a
+//! private `PATH`) that print to both streams and exit with `FAKE_EXIT`, or
+//! kill themselves with `FAKE_SIGNAL`.
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const FAKE_TOOL: &str = r#"#!/bin/sh
+echo "$(basename "$0") stdout line"
+echo "$(basename "$0"): fake failure" >&2
+if [ -n "$FAKE_SIGNAL" ]; then
+    kill -"$FAKE_SIGNAL" $$
+fi
+exit "${FAKE_EXIT:-0}"
+"#;
+
+const FAKE_TOOLS: &[&str] = &[
+    "cargo",
+    "curl",
+    "docker",
+    "eslint",
+    "gh",
+    "git",
+    "go",
+    "golangci-lint",
+    "kubectl",
+    "ls",
+    "next",
+    "npm",
+    "npx",
+    "playwright",
+    "pnpm",
+    "prettier",
+    "prisma",
+    "pytest",
+    "rg",
+    "ruff",
+    "tool",
+    "tree",
+    "tsc",
+    "uv",
+    "vitest",
+    "wc",
+    "wget",
+];
+
+/// Every wrapper that runs a child, as `rtk` arguments.
+const WRAPPERS: &[&[&str]] = &[
+    &["cargo", "build"],
+    &["cargo", "check"],
+    &["cargo", "clippy"],
+    &["cargo", "install", "foo"],
+    &["cargo", "nextest", "run"],
+    &["cargo", "test"],
+    &["cargo", "fmt"],
+    &["curl", "http://localhost"],
+    &["docker", "compose", "build"],
+    &["docker", "compose", "logs"],
+    &["docker", "compose", "ps"],
+    &["docker", "compose", "up"],
+    &["docker", "images"],
+    &["docker", "logs", "web"],
+    &["docker", "ps"],
+    &["docker", "run", "alpine"],
+    &["err", "tool"],
+    &["format"],
+    &["gh", "api", "user"],
+    &["gh", "issue", "list"],
+    &["gh", "pr", "checks", "1"],
+    &["gh", "pr", "list"],
+    &["gh", "pr", "view", "1"],
+    &["gh", "repo", "view"],
+    &["gh", "run", "list"],
+    &["git", "add", "."],
+    &["git", "branch"],
+    &["git", "cherry-pick", "abc1234"],
+    &["git", "commit", "-m", "msg"],
+    &["git", "diff"],
+    &["git", "fetch"],
+    &["git", "log"],
+    &["git", "merge", "feature"],
+    &["git", "pull"],
+    &["git", "push"],
+    &["git", "rebase", "main"],
+    &["git", "show"],
+    &["git", "stash", "list"],
+    &["git", "status"],
+    &["git", "tag"],
+    &["git", "worktree", "list"],
+    &["go", "build"],
+    &["go", "mod", "tidy"],
+    &["go", "test"],
+    &["go", "vet"],
+    &["golangci-lint", "run"],
+    &["grep", "needle", "."],
+    &["kubectl", "logs", "web"],
+    &["kubectl", "pods"],
+    &["kubectl", "services"],
+    &["lint"],
+    &["ls"],
+    &["next"],
+    &["npm", "run", "build"],
+    &["npx", "cowsay"],
+    &["npx", "prisma", "studio"],
+    &["pip", "install", "foo"],
+    &["pip", "list"],
+    &["pip", "outdated"],
+    &["playwright", "test"],
+    &["pnpm", "list"],
+    &["pnpm", "outdated"],
+    &["pnpm", "why", "foo"],
+    &["prettier", "--check", "."],
+    &["prisma", "generate"],
+    &["prisma", "migrate", "status"],
+    &["proxy", "tool"],
+    &["pytest"],
+    &["ruff", "check"],
+    &["summary", "tool"],
+    &["test", "tool"],
+    &["tree"],
+    &["tsc"],
+    &["vitest", "run"],
+    &["wc", "Cargo.toml"],
+    &["wget", "http://localhost/file"],
+];
+
+struct Sandbox {
+    dir: TempDir,
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        let dir = TempDir::new().unwrap();
+        let bin = dir.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+        for tool in FAKE_TOOLS {
+            let path = bin.join(tool);
+            std::fs::write(&path, FAKE_TOOL).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        std::fs::create_dir(dir.path().join("home")).unwrap();
+        Sandbox { dir }
+    }
+
+    fn rtk(&self, args: &[&str], env: &[(&str, &str)]) -> Output {
+        let root = self.dir.path();
+        let path = format!(
+            "{}:{}",
+            root.join("bin").display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rtk"));
+        cmd.args(args)
+            .current_dir(Path::new(env!("CARGO_MANIFEST_DIR")))
+            .env("PATH", path)
+            .env("HOME", root.join("home"))
+            .env("XDG_CONFIG_HOME", root.join("home/.config"))
+            .env("XDG_DATA_HOME", root.join("home/.local/share"))
+            .env("RTK_DB_PATH", root.join("history.db"))
+            .env("RTK_TEE", "0")
+            .env_remove("RTK_SESSION_ID")
+            .env_remove("RUST_BACKTRACE");
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+        cmd.output().unwrap()
+    }
+}
+
+fn describe(args: &[&str], output: &Output) -> String {
+    format!(
+        "rtk {}\nstdout: {}\nstderr: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn test_wrappers_pass_through_exit_code() {
+    let sandbox = Sandbox::new();
+    for args in WRAPPERS {
+        let output = sandbox.rtk(args, &[("FAKE_EXIT", "3")]);
+        assert_eq!(output.status.code(), Some(3), "{}", describe(args, &output));
+    }
+}
+
+#[test]
+fn test_wrappers_exit_zero_on_success() {
+    let sandbox = Sandbox::new();
+    // gh filters parse JSON, which the fake tool doesn't print
+    for args in WRAPPERS.iter().filter(|a| a[0] != "gh" || a[1] == "api") {
+        let output = sandbox.rtk(args, &[("FAKE_EXIT", "0")]);
+        assert_eq!(output.status.code(), Some(0), "{}", describe(args, &output));
+    }
+}
+
+#[test]
+fn test_signal_maps_to_shell_exit_code() {
+    let sandbox = Sandbox::new();
+    for args in [
+        &["cargo", "build"][..],
+        &["git", "status"],
+        &["gh", "pr", "list"],
+        &["proxy", "tool"],
+        &["summary", "tool"],
+        &["test", "tool"],
+    ] {
+        let output = sandbox.rtk(args, &[("FAKE_SIGNAL", "TERM")]);
+        assert_eq!(
+            output.status.code(),
+            Some(143),
+            "{}",
+            describe(args, &output)
+        );
+    }
+}
+
+#[test]
+fn test_passthrough_keeps_stderr_on_stderr() {
+    let sandbox = Sandbox::new();
+    for args in [
+        &["cargo", "fmt"][..],
+        &["docker", "ps"],
+        &["git", "tag"],
+        &["go", "mod", "tidy"],
+        &["pnpm", "why", "foo"],
+        &["proxy", "tool"],
+    ] {
+        let output = sandbox.rtk(args, &[("FAKE_EXIT", "3")]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("fake failure"),
+            "{}",
+            describe(args, &output)
+        );
+        assert!(
+            !stdout.contains("fake failure"),
+            "{}",
+            describe(args, &output)
+        );
+    }
+}
+
+#[test]
+fn test_commands_without_child_exit_zero() {
+    let sandbox = Sandbox::new();
+    let output = sandbox.rtk(&["log", "Cargo.toml"], &[]);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        describe(&["log"], &output)
+    );
+}